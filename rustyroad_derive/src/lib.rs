@@ -0,0 +1,317 @@
+//! # Rusty Road derive
+//! The `#[derive(Model)]` and `#[derive(Validate)]` macros of Rusty Road, use them through
+//! `rustyroad::Model` and `rustyroad::Validate`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit, Meta};
+
+/// ## Name: Model
+/// ### Description: Implements `rustyroad::Model` for a struct with named fields
+/// Every field is a column of the table, named like the field or by `#[sqlx(rename = "...")]`.
+/// The table is `#[table_name = "..."]`, or the struct name in snake case, and the primary key
+/// is the field marked `#[primary_key]`, or `id`. Rows are read with the `sqlx::FromRow` impl of
+/// each database the fields decode on, and fail to decode on the others. The generated code names
+/// sqlx through `rustyroad::sqlx`, so the impl must come from the same sqlx version as rustyroad.
+///
+/// ### Example:
+/// ```ignore
+/// use rustyroad::Model;
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model)]
+/// #[table_name = "posts"]
+/// pub struct Post {
+///     #[primary_key]
+///     pub id: Option<i64>,
+///     pub title: String,
+/// }
+/// ```
+#[proc_macro_derive(Model, attributes(table_name, primary_key))]
+pub fn derive_model(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_model(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_model(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Model)] needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "#[derive(Model)] can only be used on structs",
+            ))
+        }
+    };
+
+    let mut table_name = snake_case(&input.ident.to_string());
+    for attr in &input.attrs {
+        if attr.path().is_ident("table_name") {
+            table_name = string_value(&attr.meta)?;
+        }
+    }
+
+    let mut primary_key = None;
+    for field in fields {
+        if field.attrs.iter().any(|attr| attr.path().is_ident("primary_key")) {
+            if primary_key.is_some() {
+                return Err(Error::new_spanned(
+                    field,
+                    "only one field can be the #[primary_key]",
+                ));
+            }
+            primary_key = field.ident.clone();
+        }
+    }
+    let primary_key = match primary_key {
+        Some(primary_key) => primary_key,
+        None => fields
+            .iter()
+            .filter_map(|field| field.ident.clone())
+            .find(|ident| ident == "id")
+            .ok_or_else(|| {
+                Error::new(
+                    Span::call_site(),
+                    "#[derive(Model)] needs an `id` field or a field marked #[primary_key]",
+                )
+            })?,
+    };
+
+    let mut columns = Vec::new();
+    let mut values = Vec::new();
+    let mut primary_key_column = String::new();
+    for field in fields {
+        let Some(ident) = &field.ident else { continue };
+        let column = column_name(field)?;
+        if *ident == primary_key {
+            primary_key_column = column.clone();
+        } else {
+            values.push(quote! {
+                (#column, ::rustyroad::database::SqlValue::from(::std::clone::Clone::clone(&self.#ident)))
+            });
+        }
+        columns.push(column);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rustyroad::database::Model for #ident #type_generics #where_clause {
+            const TABLE_NAME: &'static str = #table_name;
+            const PRIMARY_KEY: &'static str = #primary_key_column;
+            const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+            fn primary_key_value(&self) -> ::rustyroad::database::SqlValue {
+                ::rustyroad::database::SqlValue::from(::std::clone::Clone::clone(&self.#primary_key))
+            }
+
+            fn column_values(&self) -> ::std::vec::Vec<(&'static str, ::rustyroad::database::SqlValue)> {
+                ::std::vec![#(#values),*]
+            }
+
+            fn from_pg_row(row: &::rustyroad::database::PgRow) -> ::std::result::Result<Self, ::rustyroad::sqlx::Error> {
+                use ::rustyroad::database::{DecodeUnsupported as _, DecodeWithFromRow as _};
+                (&&::rustyroad::database::RowDecoder::<Self, ::rustyroad::database::PgRow>::new()).decode(row)
+            }
+
+            fn from_mysql_row(row: &::rustyroad::database::MySqlRow) -> ::std::result::Result<Self, ::rustyroad::sqlx::Error> {
+                use ::rustyroad::database::{DecodeUnsupported as _, DecodeWithFromRow as _};
+                (&&::rustyroad::database::RowDecoder::<Self, ::rustyroad::database::MySqlRow>::new()).decode(row)
+            }
+
+            fn from_sqlite_row(row: &::rustyroad::database::SqliteRow) -> ::std::result::Result<Self, ::rustyroad::sqlx::Error> {
+                use ::rustyroad::database::{DecodeUnsupported as _, DecodeWithFromRow as _};
+                (&&::rustyroad::database::RowDecoder::<Self, ::rustyroad::database::SqliteRow>::new()).decode(row)
+            }
+        }
+    })
+}
+
+/// ## Name: Validate
+/// ### Description: Implements `rustyroad::database::Validate` from the `#[validate(...)]` attributes
+/// A field takes `presence`, `length(min = .., max = ..)`, `format = "<regex>"`,
+/// `range(min = .., max = ..)`, `unique` and `custom = "<fn(&T) -> Result<(), String>>"`, the
+/// struct takes `custom = "<fn(&Self, &mut ValidationErrors)>"`. The errors are keyed by column.
+///
+/// ### Example:
+/// ```ignore
+/// use rustyroad::{Model, Validate};
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model, Validate)]
+/// #[table_name = "posts"]
+/// pub struct Post {
+///     pub id: Option<i64>,
+///     #[validate(presence, length(max = 120))]
+///     pub title: String,
+///     #[validate(format = "^[a-z0-9-]+$", unique)]
+///     pub slug: String,
+/// }
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_validate(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_validate(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Validate)] needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "#[derive(Validate)] can only be used on structs",
+            ))
+        }
+    };
+
+    let mut rules = Vec::new();
+    let mut unique_columns = Vec::new();
+    for field in fields {
+        let Some(ident) = &field.ident else { continue };
+        let column = column_name(field)?;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("presence") {
+                    rules.push(quote! { errors.presence(#column, &self.#ident); });
+                } else if meta.path.is_ident("unique") {
+                    unique_columns.push(column.clone());
+                } else if meta.path.is_ident("length") {
+                    let (min, max) = bounds(&meta, |value| quote! { #value })?;
+                    rules.push(quote! { errors.length(#column, &self.#ident, #min, #max); });
+                } else if meta.path.is_ident("range") {
+                    let (min, max) = bounds(&meta, |value| quote! { (#value) as f64 })?;
+                    rules.push(quote! { errors.range(#column, &self.#ident, #min, #max); });
+                } else if meta.path.is_ident("format") {
+                    let pattern: syn::LitStr = meta.value()?.parse()?;
+                    if let Err(why) = regex::Regex::new(&pattern.value()) {
+                        return Err(Error::new_spanned(pattern, format!("invalid format: {}", why)));
+                    }
+                    rules.push(quote! { errors.format(#column, &self.#ident, #pattern); });
+                } else if meta.path.is_ident("custom") {
+                    let function: syn::ExprPath = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+                    rules.push(quote! { errors.custom(#column, #function(&self.#ident)); });
+                } else {
+                    return Err(meta.error("expected presence, length, format, range, unique or custom"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("custom") {
+                let function: syn::ExprPath = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+                rules.push(quote! { #function(self, errors); });
+                Ok(())
+            } else {
+                Err(meta.error("expected custom = \"<function>\" on the struct"))
+            }
+        })?;
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rustyroad::database::Validate for #ident #type_generics #where_clause {
+            const UNIQUE_COLUMNS: &'static [&'static str] = &[#(#unique_columns),*];
+
+            #[allow(unused_variables)]
+            fn validate_fields(&self, errors: &mut ::rustyroad::database::ValidationErrors) {
+                #(#rules)*
+            }
+        }
+    })
+}
+
+/// Reads the `min = ..` and `max = ..` of `length(...)` and `range(...)` as `Option`s of the
+/// converted values.
+fn bounds(
+    meta: &syn::meta::ParseNestedMeta,
+    convert: impl Fn(Expr) -> proc_macro2::TokenStream,
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream), Error> {
+    let mut min = quote! { ::std::option::Option::None };
+    let mut max = quote! { ::std::option::Option::None };
+    meta.parse_nested_meta(|bound| {
+        let value = convert(bound.value()?.parse()?);
+        if bound.path.is_ident("min") {
+            min = quote! { ::std::option::Option::Some(#value) };
+        } else if bound.path.is_ident("max") {
+            max = quote! { ::std::option::Option::Some(#value) };
+        } else {
+            return Err(bound.error("expected min or max"));
+        }
+        Ok(())
+    })?;
+    Ok((min, max))
+}
+
+/// Reads the `"..."` of `#[name = "..."]`.
+fn string_value(meta: &Meta) -> Result<String, Error> {
+    if let Meta::NameValue(name_value) = meta {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) = &name_value.value
+        {
+            return Ok(value.value());
+        }
+    }
+    Err(Error::new_spanned(meta, "expected #[table_name = \"...\"]"))
+}
+
+/// The column of a field: its `#[sqlx(rename = "...")]`, or its name without the `r#` of raw
+/// identifiers like `r#type`.
+fn column_name(field: &syn::Field) -> Result<String, Error> {
+    let mut rename = None;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("sqlx")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                rename = Some(value.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                // Other sqlx options are sqlx's business
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|_| Ok(()))?;
+            }
+            Ok(())
+        })?;
+    }
+    let name = field.ident.as_ref().map(ToString::to_string).unwrap_or_default();
+    Ok(rename.unwrap_or_else(|| name.strip_prefix("r#").map(str::to_string).unwrap_or(name)))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, character) in name.chars().enumerate() {
+        if character.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(character.to_lowercase());
+        } else {
+            snake.push(character);
+        }
+    }
+    snake
+}
@@ -0,0 +1,344 @@
+use std::future::Future;
+
+use sqlx::types::Uuid;
+use sqlx::Row;
+
+use super::database_error::DatabaseError;
+use super::model::Model;
+use super::query_builder::{MySqlRow, Order, PgRow, Query, QueryPool, RowReader, SqlValue, SqliteRow};
+
+/// ## Name: Associations
+/// ### Description: Reads the rows a model is associated with, implemented for every [`Model`]
+/// - `belongs_to`: the row a foreign key column of this row points to, e.g. the author of a post
+/// - `has_many` / `has_one`: the rows of another table whose foreign key points to this row
+/// - `many_to_many`: the rows linked to this row through a join table, see `attach` and `detach`
+///
+/// The `preload_*` functions read the associated rows of many rows with a single query, to
+/// avoid a query per row (N+1). They return one entry per row, in the order of the rows.
+/// Models generated by `rustyroad generate model` call these from a function per association,
+/// e.g. `post.author(&pool)` and `Post::preload_author(&pool, &posts)`.
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::{Associations, PoolConnection};
+/// use rustyroad::Model;
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model)]
+/// #[table_name = "users"]
+/// pub struct User {
+///     pub id: Option<i64>,
+///     pub name: String,
+/// }
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model)]
+/// #[table_name = "posts"]
+/// pub struct Post {
+///     pub id: Option<i64>,
+///     pub title: String,
+///     pub author_id: i64,
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// sqlx::raw_sql(
+///     "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+///      CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, author_id INTEGER NOT NULL REFERENCES users (id));",
+/// )
+/// .execute(&pool)
+/// .await
+/// .unwrap();
+/// let pool = PoolConnection::Sqlite(pool);
+///
+/// let ada = User { id: None, name: "Ada".to_string() }.create(&pool).await.unwrap();
+/// let post = Post { id: None, title: "Hello".to_string(), author_id: 1 }.create(&pool).await.unwrap();
+///
+/// let author: Option<User> = post.belongs_to(&pool, "author_id").await.unwrap();
+/// assert_eq!(author.unwrap().name, "Ada");
+/// let posts: Vec<Post> = ada.has_many(&pool, "author_id").await.unwrap();
+/// assert_eq!(posts.len(), 1);
+///
+/// // One query for the authors of every post
+/// let authors: Vec<Option<User>> = Post::preload_belongs_to(&pool, &posts, "author_id").await.unwrap();
+/// assert_eq!(authors[0].as_ref().unwrap().name, "Ada");
+/// # });
+/// ```
+pub trait Associations: Model {
+    /// The value of a column of this row, the primary key included.
+    fn column_value(&self, column: &str) -> SqlValue {
+        if column == Self::PRIMARY_KEY {
+            return self.primary_key_value();
+        }
+        self.column_values()
+            .into_iter()
+            .find(|(name, _)| *name == column)
+            .map(|(_, value)| value)
+            .unwrap_or(SqlValue::Null)
+    }
+
+    /// Returns the row the foreign key column of this row points to, `None` when it's `NULL`.
+    fn belongs_to<T, P>(&self, pool: &P, foreign_key: &str) -> impl Future<Output = Result<Option<T>, DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let id = self.column_value(foreign_key);
+        async move {
+            if id == SqlValue::Null {
+                return Ok(None);
+            }
+            T::find(pool, id).await
+        }
+    }
+
+    /// Returns the rows of `T` whose foreign key points to this row, ordered by primary key.
+    fn has_many<T, P>(&self, pool: &P, foreign_key: &str) -> impl Future<Output = Result<Vec<T>, DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let query = T::query()
+            .where_eq(&qualified::<T>(foreign_key), self.primary_key_value())
+            .order_by(&qualified::<T>(T::PRIMARY_KEY), Order::Asc);
+        async move { query.read_all(pool, T::row_reader()).await }
+    }
+
+    /// Returns the row of `T` whose foreign key points to this row, if any.
+    fn has_one<T, P>(&self, pool: &P, foreign_key: &str) -> impl Future<Output = Result<Option<T>, DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let query = T::query()
+            .where_eq(&qualified::<T>(foreign_key), self.primary_key_value())
+            .order_by(&qualified::<T>(T::PRIMARY_KEY), Order::Asc)
+            .limit(1);
+        async move { query.read_optional(pool, T::row_reader()).await }
+    }
+
+    /// Returns the rows of `T` linked to this row through the join table, ordered by primary key.
+    /// `foreign_key` is the column of the join table that points to this row, and
+    /// `association_foreign_key` the one that points to `T`.
+    fn many_to_many<T, P>(
+        &self,
+        pool: &P,
+        join_table: &str,
+        foreign_key: &str,
+        association_foreign_key: &str,
+    ) -> impl Future<Output = Result<Vec<T>, DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let query = join_query::<T>(join_table, association_foreign_key)
+            .where_eq(&format!("{}.{}", join_table, foreign_key), self.primary_key_value());
+        async move { query.read_all(pool, T::row_reader()).await }
+    }
+
+    /// Links this row to `other` by inserting a row into the join table.
+    fn attach<T, P>(
+        &self,
+        pool: &P,
+        other: &T,
+        join_table: &str,
+        foreign_key: &str,
+        association_foreign_key: &str,
+    ) -> impl Future<Output = Result<(), DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let insert = Query::insert(join_table)
+            .value(foreign_key, self.primary_key_value())
+            .value(association_foreign_key, other.primary_key_value());
+        async move {
+            insert.execute(pool).await?;
+            Ok(())
+        }
+    }
+
+    /// Removes the link between this row and `other`, returns whether it existed.
+    fn detach<T, P>(
+        &self,
+        pool: &P,
+        other: &T,
+        join_table: &str,
+        foreign_key: &str,
+        association_foreign_key: &str,
+    ) -> impl Future<Output = Result<bool, DatabaseError>> + Send
+    where
+        T: Model,
+        P: QueryPool + Sync,
+    {
+        let delete = Query::delete(join_table)
+            .where_eq(foreign_key, self.primary_key_value())
+            .where_eq(association_foreign_key, other.primary_key_value());
+        async move { Ok(delete.execute(pool).await?.rows_affected > 0) }
+    }
+
+    /// Reads what [`Associations::belongs_to`] returns for every row, with one query.
+    fn preload_belongs_to<T, P>(
+        pool: &P,
+        rows: &[Self],
+        foreign_key: &str,
+    ) -> impl Future<Output = Result<Vec<Option<T>>, DatabaseError>> + Send
+    where
+        T: Model + Clone,
+        P: QueryPool + Sync,
+    {
+        let keys: Vec<SqlValue> = rows.iter().map(|row| row.column_value(foreign_key)).collect();
+        let query = T::query().where_in(&qualified::<T>(T::PRIMARY_KEY), distinct(&keys));
+        async move {
+            let parents = query.read_all(pool, T::row_reader()).await?;
+            Ok(keys
+                .iter()
+                .map(|key| parents.iter().find(|parent| parent.primary_key_value() == *key).cloned())
+                .collect())
+        }
+    }
+
+    /// Reads what [`Associations::has_many`] returns for every row, with one query.
+    fn preload_has_many<T, P>(
+        pool: &P,
+        rows: &[Self],
+        foreign_key: &str,
+    ) -> impl Future<Output = Result<Vec<Vec<T>>, DatabaseError>> + Send
+    where
+        T: Model + Associations + Clone,
+        P: QueryPool + Sync,
+    {
+        let ids: Vec<SqlValue> = rows.iter().map(Model::primary_key_value).collect();
+        let query = T::query()
+            .where_in(&qualified::<T>(foreign_key), distinct(&ids))
+            .order_by(&qualified::<T>(T::PRIMARY_KEY), Order::Asc);
+        let foreign_key = foreign_key.to_string();
+        async move {
+            let children = query.read_all(pool, T::row_reader()).await?;
+            Ok(ids
+                .iter()
+                .map(|id| {
+                    children
+                        .iter()
+                        .filter(|child| child.column_value(&foreign_key) == *id)
+                        .cloned()
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    /// Reads what [`Associations::has_one`] returns for every row, with one query.
+    fn preload_has_one<T, P>(
+        pool: &P,
+        rows: &[Self],
+        foreign_key: &str,
+    ) -> impl Future<Output = Result<Vec<Option<T>>, DatabaseError>> + Send
+    where
+        T: Model + Associations + Clone,
+        P: QueryPool + Sync,
+    {
+        let children = Self::preload_has_many::<T, P>(pool, rows, foreign_key);
+        async move {
+            Ok(children
+                .await?
+                .into_iter()
+                .map(|children| children.into_iter().next())
+                .collect())
+        }
+    }
+
+    /// Reads what [`Associations::many_to_many`] returns for every row, with one query.
+    fn preload_many_to_many<T, P>(
+        pool: &P,
+        rows: &[Self],
+        join_table: &str,
+        foreign_key: &str,
+        association_foreign_key: &str,
+    ) -> impl Future<Output = Result<Vec<Vec<T>>, DatabaseError>> + Send
+    where
+        T: Model + Clone,
+        P: QueryPool + Sync,
+    {
+        let ids: Vec<SqlValue> = rows.iter().map(Model::primary_key_value).collect();
+        // The column of the join table that points to the row comes last, see `KeyedRow`
+        let join_column = format!("{}.{}", join_table, foreign_key);
+        let query = join_query::<T>(join_table, association_foreign_key)
+            .columns([join_column.clone()])
+            .where_in(&join_column, distinct(&ids));
+        async move {
+            let linked = query.read_all(pool, KeyedRow::<T>::reader()).await?;
+            Ok(ids
+                .iter()
+                .map(|id| {
+                    linked
+                        .iter()
+                        .filter(|linked| linked.key == *id)
+                        .map(|linked| linked.row.clone())
+                        .collect()
+                })
+                .collect())
+        }
+    }
+}
+
+impl<M: Model> Associations for M {}
+
+/// `table.column` of the table of `T`.
+fn qualified<T: Model>(column: &str) -> String {
+    format!("{}.{}", T::TABLE_NAME, column)
+}
+
+/// Selects the rows of `T` joined to the join table, ordered by primary key.
+fn join_query<T: Model>(join_table: &str, association_foreign_key: &str) -> Query {
+    T::query()
+        .join(
+            join_table,
+            &format!("{}.{}", join_table, association_foreign_key),
+            &qualified::<T>(T::PRIMARY_KEY),
+        )
+        .order_by(&qualified::<T>(T::PRIMARY_KEY), Order::Asc)
+}
+
+/// The values without `NULL` and repeats, to look up with `IN (...)`.
+fn distinct(values: &[SqlValue]) -> Vec<SqlValue> {
+    let mut distinct: Vec<SqlValue> = Vec::new();
+    for value in values {
+        if *value != SqlValue::Null && !distinct.contains(value) {
+            distinct.push(value.clone());
+        }
+    }
+    distinct
+}
+
+/// A row of `T` with the value of the last column of the query, which `T` doesn't read.
+struct KeyedRow<T> {
+    key: SqlValue,
+    row: T,
+}
+
+impl<T: Model> KeyedRow<T> {
+    fn reader() -> RowReader<Self> {
+        RowReader::new(
+            |row| Ok(KeyedRow { key: pg_key(row)?, row: T::from_pg_row(row)? }),
+            |row| Ok(KeyedRow { key: mysql_key(row)?, row: T::from_mysql_row(row)? }),
+            |row| Ok(KeyedRow { key: sqlite_key(row)?, row: T::from_sqlite_row(row)? }),
+        )
+    }
+}
+
+/// Reads the key of a [`KeyedRow`] as the first of these types the column decodes into.
+macro_rules! key_reader {
+    ($name:ident, $row:ty $(, $key_type:ty)*) => {
+        fn $name(row: &$row) -> Result<SqlValue, sqlx::Error> {
+            let index = row.len() - 1;
+            row.try_get::<i64, _>(index)
+                .map(SqlValue::from)
+                .or_else(|_| row.try_get::<i32, _>(index).map(SqlValue::from))
+                $(.or_else(|_| row.try_get::<$key_type, _>(index).map(SqlValue::from)))*
+                .or_else(|_| row.try_get::<String, _>(index).map(SqlValue::from))
+        }
+    };
+}
+
+key_reader!(pg_key, PgRow, Uuid);
+key_reader!(mysql_key, MySqlRow, u32);
+key_reader!(sqlite_key, SqliteRow);
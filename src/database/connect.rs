@@ -12,12 +12,9 @@ use super::Database;
 /// let pool: sqlx::PgPool = get_pg_pool(&database);
 /// ```
 pub async fn get_pg_pool(database: &Database) -> Result<sqlx::PgPool, sqlx::Error> {
-    let database_url = format!(
-        "postgres://{}:{}@{}:{}/{}",
-        database.username, database.password, database.host, database.port, database.name
-    );
+    let options = database.pg_connect_options(&database.name)?;
 
-    let db_pool = sqlx::PgPool::connect(&database_url).await?;
+    let db_pool = database.pool.pool_options().connect_with(options).await?;
     Ok(db_pool)
 }
 
@@ -33,12 +30,9 @@ pub async fn get_pg_pool(database: &Database) -> Result<sqlx::PgPool, sqlx::Erro
 /// let pool: sqlx::MySqlPool = get_mysql_pool(&database);
 /// ```
 pub async fn get_mysql_pool(database: &Database) -> Result<sqlx::MySqlPool, sqlx::Error> {
-    let database_url = format!(
-        "mysql://{}:{}@{}:{}/{}",
-        database.username, database.password, database.host, database.port, database.name
-    );
+    let options = database.mysql_connect_options(Some(&database.name))?;
 
-    let db_pool = sqlx::MySqlPool::connect(&database_url).await?;
+    let db_pool = database.pool.pool_options().connect_with(options).await?;
     Ok(db_pool)
 }
 
@@ -56,6 +50,6 @@ pub async fn get_mysql_pool(database: &Database) -> Result<sqlx::MySqlPool, sqlx
 pub async fn get_sqlite_pool(database: &Database) -> Result<sqlx::SqlitePool, sqlx::Error> {
     let database_url = format!("{}.db", database.name);
 
-    let db_pool = sqlx::SqlitePool::connect(&database_url).await?;
+    let db_pool = database.pool.pool_options().connect(&database_url).await?;
     Ok(db_pool)
 }
@@ -1,14 +1,19 @@
-use crate::database::{get_mysql_pool, get_pg_pool, get_sqlite_pool};
-use sqlx::mysql::{MySqlConnectOptions, MySqlPool};
-use sqlx::postgres::{PgConnectOptions, PgPool};
-use sqlx::sqlite::SqlitePool;
-use std::error::Error;
+use crate::database::create_database;
+use sqlx::mysql::{MySql, MySqlConnectOptions, MySqlPool};
+use sqlx::postgres::{PgConnectOptions, PgPool, Postgres};
+use sqlx::sqlite::{Sqlite, SqlitePool};
 use std::fs;
+use std::io::ErrorKind;
+use std::str::FromStr;
 use std::sync::Arc;
 use toml::Value;
-use crate::writers::create_database_if_not_exists;
+use url::Url;
 
+use super::database_error::DatabaseError;
 use super::databasetype::DatabaseType;
+use super::database_url::{interpolate_env_vars, DATABASE_URL_VARIABLE};
+use super::environment::Environment;
+use super::pool::PoolSettings;
 
 #[derive(Debug, Clone)]
 pub struct Database {
@@ -18,6 +23,10 @@ pub struct Database {
     pub host: String,
     pub port: u16,
     pub database_type: DatabaseType,
+    pub pool: PoolSettings,
+    /// The URL the database was read from, see [`Database::from_url`]. Its options, e.g.
+    /// `sslmode` or `authSource`, are used to connect.
+    pub url: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,9 +34,15 @@ pub enum DatabaseConnection {
     Pg(Arc<PgPool>),
     MySql(Arc<MySqlPool>),
     Sqlite(Arc<SqlitePool>),
+    Mongo(mongodb::Database),
 }
 
 impl Database {
+    /// ## Name: new
+    /// ### Description: Builds the settings of a database with the default pool settings
+    /// #### Parameters:
+    /// - database_type: [`&str`] - `postgres`, `mysql`, `sqlite` or `mongo`
+    /// - Returns: [`Result<Database, DatabaseError>`] - a [`DatabaseError::UnsupportedDriver`] error for any other database type
     pub fn new(
         name: String,
         username: String,
@@ -35,145 +50,348 @@ impl Database {
         host: String,
         port: u16,
         database_type: &str,
-    ) -> Database {
-        Database {
+    ) -> Result<Database, DatabaseError> {
+        let database_type = match database_type {
+            "postgres" => DatabaseType::Postgres,
+            "mysql" => DatabaseType::Mysql,
+            "sqlite" => DatabaseType::Sqlite,
+            "mongo" | "mongodb" => DatabaseType::Mongo,
+            _ => return Err(DatabaseError::UnsupportedDriver(database_type.to_string())),
+        };
+        Ok(Database {
             name,
             username,
             password,
             host,
             port,
-            database_type: match database_type {
-                "postgres" => DatabaseType::Postgres,
-                "mysql" => DatabaseType::Mysql,
-                "sqlite" => DatabaseType::Sqlite,
-                _ => DatabaseType::Mysql,
-            },
-        }
+            database_type,
+            pool: PoolSettings::default(),
+            url: None,
+        })
+    }
+
+    /// ## Name: pg_connect_options
+    /// ### Description: The options of a connection to the Postgres server, with the options of [`Database::url`]
+    /// #### Parameters:
+    /// - database: [`&str`] - the database to connect to
+    /// - Returns: [`Result<PgConnectOptions, sqlx::Error>`]
+    pub fn pg_connect_options(&self, database: &str) -> Result<PgConnectOptions, sqlx::Error> {
+        let options = match self.server_url()? {
+            Some(url) => PgConnectOptions::from_str(&url)?,
+            None => PgConnectOptions::new(),
+        };
+        Ok(options
+            .username(&self.username)
+            .password(&self.password)
+            .database(database)
+            .host(&self.host)
+            .port(self.port))
+    }
+
+    /// ## Name: mysql_connect_options
+    /// ### Description: The options of a connection to the MySQL server, with the options of [`Database::url`]
+    /// #### Parameters:
+    /// - database: [`Option<&str>`] - the database to select, `None` for none
+    /// - Returns: [`Result<MySqlConnectOptions, sqlx::Error>`]
+    pub fn mysql_connect_options(&self, database: Option<&str>) -> Result<MySqlConnectOptions, sqlx::Error> {
+        let options = match self.server_url()? {
+            Some(url) => MySqlConnectOptions::from_str(&url)?,
+            None => MySqlConnectOptions::new(),
+        };
+        let options = options
+            .username(&self.username)
+            .password(&self.password)
+            .host(&self.host)
+            .port(self.port);
+        Ok(match database {
+            Some(database) => options.database(database),
+            None => options,
+        })
+    }
+
+    /// [`Database::url`] without its database, which the connect options select.
+    fn server_url(&self) -> Result<Option<String>, sqlx::Error> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+        let mut url = Url::parse(url).map_err(|why| sqlx::Error::Configuration(why.into()))?;
+        url.set_path("");
+        Ok(Some(url.to_string()))
     }
 
-    pub async fn create_database_connection(
-        &self,
-    ) -> Result<DatabaseConnection, Box<dyn Error + Send>> {
+    /// ## Name: create_database_connection
+    /// ### Description: Opens a connection pool to the database
+    /// A Postgres database that doesn't exist yet is created first. MongoDB creates databases
+    /// on first write, so the server is only pinged.
+    /// #### Returns:
+    /// - [`Result<DatabaseConnection, DatabaseError>`]
+    pub async fn create_database_connection(&self) -> Result<DatabaseConnection, DatabaseError> {
         match &self.database_type {
             DatabaseType::Mysql => {
-                let options = MySqlConnectOptions::new()
-                    .username(&self.username)
-                    .password(&self.password)
-                    .database(&self.name)
-                    .host(&self.host)
-                    .port(self.port);
-                let pool = MySqlPool::connect_with(options)
-                    .await
-                    .unwrap_or_else(|_| panic!("Failed to create connection pool."));
+                let options = self.mysql_connect_options(Some(&self.name))?;
+                let pool = self.pool.pool_options::<MySql>().connect_with(options).await?;
                 Ok(DatabaseConnection::MySql(Arc::new(pool)))
             }
             DatabaseType::Sqlite => {
-                let pool = SqlitePool::connect(&format!("{}.db", self.name))
-                    .await
-                    .unwrap_or_else(|_| panic!("Could not connect to the SQLite database."));
+                let pool = self
+                    .pool
+                    .pool_options::<Sqlite>()
+                    .connect(&format!("{}.db", self.name))
+                    .await?;
                 Ok(DatabaseConnection::Sqlite(Arc::new(pool)))
             }
             DatabaseType::Postgres => {
-
-                let database: Database = Database::get_database_from_rustyroad_toml().unwrap();
-                let admin_database_url = format!(
-                    "postgres://{}:{}@{}:{}/postgres",
-                    database.username,
-                    database.password,
-                    database.host,
-                    database.port,
-                );
-                create_database_if_not_exists(
-                    admin_database_url.as_str(), database).await.unwrap_or_else(|_| panic!("Failed to create database"));
-
-
-                let options = PgConnectOptions::new()
-                    .username(&self.username)
-                    .password(&self.password)
-                    .database(&self.name)
-                    .host(&self.host)
-                    .port(self.port);
-                let pool = PgPool::connect_with(options)
-                    .await
-                    .unwrap_or_else(|_| panic!("Failed to create connection pool."));
+                let options = self.pg_connect_options(&self.name)?;
+                let pool_options = self.pool.pool_options::<Postgres>();
+                let pool = match pool_options.clone().connect_with(options.clone()).await {
+                    Ok(pool) => pool,
+                    // 3D000: the database doesn't exist yet
+                    Err(sqlx::Error::Database(why)) if why.code().as_deref() == Some("3D000") => {
+                        create_database(self).await?;
+                        pool_options.connect_with(options).await?
+                    }
+                    Err(why) => return Err(why.into()),
+                };
 
                 Ok(DatabaseConnection::Pg(Arc::new(pool)))
             }
-            DatabaseType::Mongo => todo!(),
+            DatabaseType::Mongo => Ok(DatabaseConnection::Mongo(
+                self.create_mongo_connection().await?,
+            )),
         }
     }
 
-    pub fn get_database_from_rustyroad_toml() -> Result<Database, std::io::Error> {
-        let file = fs::read_to_string("rustyroad.toml")
-            .unwrap_or_else(|_| panic!("Error: Could not find rustyroad.toml"));
-        let toml: Value = toml::from_str(&file).unwrap();
-        let database_table = toml["database"].as_table().unwrap();
-        Ok(Database::new(
-            database_table["database_name"]
-                .as_str()
-                .unwrap()
-                .to_string(),
-            database_table["database_user"]
-                .as_str()
-                .unwrap()
-                .to_string(),
-            database_table["database_password"]
-                .as_str()
-                .unwrap()
-                .to_string(),
-            database_table["database_host"]
-                .as_str()
-                .unwrap()
-                .to_string(),
-            database_table["database_port"]
-                .as_str()
-                .unwrap()
-                .parse::<u16>()
-                .unwrap(),
-            database_table["database_type"].as_str().unwrap(),
-        ))
+    /// ## Name: get_database_from_rustyroad_toml
+    /// ### Description: Reads the database of the current environment from the rustyroad.toml of the current directory
+    /// The environment comes from `RUSTYROAD_ENV`, see [`Environment::current`], and `DATABASE_URL`
+    /// is used as described in [`Database::get_database_for_environment`].
+    pub fn get_database_from_rustyroad_toml() -> Result<Database, DatabaseError> {
+        let environment = Environment::current().map_err(DatabaseError::Parse)?;
+        Database::get_database_for_environment(environment)
+    }
+
+    /// ## Name: get_database_for_environment
+    /// ### Description: Reads the database of an environment from the rustyroad.toml of the current directory
+    /// The database comes from, in order of precedence:
+    /// 1. the environment's table of rustyroad.toml over the shared `[database]` settings, where
+    ///    `${NAME}` reads a variable, e.g. `database_url = "${DATABASE_URL}"`;
+    /// 2. `DATABASE_URL`, only when there is no rustyroad.toml.
+    ///
+    /// A `DATABASE_URL` the settings don't reference is ignored, which is reported on stderr.
+    /// #### Parameters:
+    /// - environment: [`Environment`] - the environment whose database is read
+    /// - Returns: [`Result<Database, DatabaseError>`]
+    pub fn get_database_for_environment(environment: Environment) -> Result<Database, DatabaseError> {
+        let database_url = database_url_variable();
+        let file = match fs::read_to_string("rustyroad.toml") {
+            Ok(file) => file,
+            Err(why) if why.kind() == ErrorKind::NotFound => {
+                return match database_url {
+                    Some(database_url) => Database::from_url(&database_url),
+                    None => Err(DatabaseError::ConfigMissing(
+                        "there is no rustyroad.toml in the current directory".to_string(),
+                    )),
+                };
+            }
+            Err(why) => {
+                return Err(DatabaseError::Parse(format!("could not read rustyroad.toml: {}", why)))
+            }
+        };
+
+        let settings = environment_settings(&file, environment)?;
+        if database_url.is_some() && !references_database_url(&settings) {
+            eprintln!(
+                "Warning: {} is ignored, the {} database comes from rustyroad.toml",
+                DATABASE_URL_VARIABLE, environment
+            );
+        }
+        Database::from_settings(&settings, environment)
+    }
+
+    /// ## Name: from_rustyroad_toml
+    /// ### Description: Reads the database of an environment from the contents of a rustyroad.toml
+    /// Settings in `[database]` apply to every environment, and the `[database.development]`,
+    /// `[database.test]` and `[database.production]` tables override them. Once any of those
+    /// tables exists, the selected environment must have one; a rustyroad.toml without them
+    /// uses `[database]` in every environment.
+    ///
+    /// Settings may use environment variables as `${NAME}` or `${NAME:-fallback}`, so secrets
+    /// don't have to be committed. A `database_url` setting replaces the other settings.
+    /// #### Parameters:
+    /// - contents: [`&str`] - the contents of a rustyroad.toml
+    /// - environment: [`Environment`] - the environment whose database is read
+    /// - Returns: [`Result<Database, DatabaseError>`]
+    /// ### Example:
+    /// ```rust
+    /// use rustyroad::database::{Database, Environment};
+    ///
+    /// let toml = r#"
+    /// [database]
+    /// database_user = "postgres"
+    /// database_password = "postgres"
+    /// database_host = "localhost"
+    /// database_port = "5432"
+    /// database_type = "postgres"
+    ///
+    /// [database.development]
+    /// database_name = "blog"
+    ///
+    /// [database.test]
+    /// database_name = "blog_test"
+    /// "#;
+    /// let database = Database::from_rustyroad_toml(toml, Environment::Test).unwrap();
+    /// assert_eq!(database.name, "blog_test");
+    /// assert!(Database::from_rustyroad_toml(toml, Environment::Production).is_err());
+    /// ```
+    pub fn from_rustyroad_toml(contents: &str, environment: Environment) -> Result<Database, DatabaseError> {
+        Database::from_settings(&environment_settings(contents, environment)?, environment)
+    }
+
+    /// Builds the database from the merged settings of an environment, see [`environment_settings`].
+    fn from_settings(
+        settings: &toml::map::Map<String, Value>,
+        environment: Environment,
+    ) -> Result<Database, DatabaseError> {
+        let setting = |key: &str| -> Result<String, DatabaseError> {
+            match settings.get(key) {
+                Some(Value::String(value)) => interpolate_env_vars(value),
+                Some(Value::Integer(value)) => Ok(value.to_string()),
+                Some(_) => Err(DatabaseError::Parse(format!("{} must be a string", key))),
+                None => Err(DatabaseError::ConfigMissing(format!(
+                    "rustyroad.toml has no {} for the {} database",
+                    key, environment
+                ))),
+            }
+        };
+        let pool = PoolSettings::from_settings(|key| {
+            if settings.contains_key(key) {
+                setting(key).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+        if settings.contains_key("database_url") {
+            let mut database = Database::from_url(&setting("database_url")?)?;
+            database.pool = pool;
+            return Ok(database);
+        }
+
+        let port = setting("database_port")?
+            .parse::<u16>()
+            .map_err(|why| DatabaseError::Parse(format!("invalid database_port: {}", why)))?;
+
+        let mut database = Database::new(
+            setting("database_name")?,
+            setting("database_user")?,
+            setting("database_password")?,
+            setting("database_host")?,
+            port,
+            &setting("database_type")?,
+        )?;
+        database.pool = pool;
+        Ok(database)
     }
 
     /// # Name: get_db_pool
     /// Description: Returns a database connection pool based on the database type.
+    /// The pool is the one of [`Database::shared_pool`], so calling this per request doesn't reconnect.
     ///
     /// # Arguments
     /// * `database` - Database struct
     ///
     /// # Returns
-    /// * `DatabaseConnection` - Database connection pool
+    /// * `PoolConnection` - Database connection pool
     ///
     /// # Example
-    /// ```
+    /// ```rust,no_run
     /// use rustyroad::database::Database;
-    /// let database = Database::get_database_from_rustyroad_toml().unwrap();
-    /// let db_pool = database.get_db_pool(database).unwrap();
+    ///
+    /// # async fn example() -> Result<(), rustyroad::database::DatabaseError> {
+    /// let database = Database::get_database_from_rustyroad_toml()?;
+    /// let db_pool = Database::get_db_pool(database).await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn get_db_pool(database: Database) -> Result<PoolConnection, Box<dyn Error + Send>> {
-        match database.database_type {
-            DatabaseType::Mysql => {
-                let pool = get_mysql_pool(&database)
-                    .await
-                    .expect("Error getting mysql pool");
-                Ok(PoolConnection::MySql(pool))
-            }
-            DatabaseType::Sqlite => {
-                let pool = get_sqlite_pool(&database)
-                    .await
-                    .expect("Error getting sqlite pool");
-                Ok(PoolConnection::Sqlite(pool))
-            }
-            DatabaseType::Postgres => {
-                let pool = get_pg_pool(&database).await.expect("Error getting pg pool");
-                Ok(PoolConnection::Pg(pool))
-            }
-            DatabaseType::Mongo => todo!(),
+    pub async fn get_db_pool(database: Database) -> Result<PoolConnection, DatabaseError> {
+        match database.shared_pool().await? {
+            DatabaseConnection::Pg(pool) => Ok(PoolConnection::Pg((*pool).clone())),
+            DatabaseConnection::MySql(pool) => Ok(PoolConnection::MySql((*pool).clone())),
+            DatabaseConnection::Sqlite(pool) => Ok(PoolConnection::Sqlite((*pool).clone())),
+            DatabaseConnection::Mongo(database) => Ok(PoolConnection::Mongo(database)),
         }
     }
 }
 
+/// Merges the shared `[database]` settings with the ones of the environment's table.
+fn environment_settings(
+    contents: &str,
+    environment: Environment,
+) -> Result<toml::map::Map<String, Value>, DatabaseError> {
+    let toml: Value = toml::from_str(contents)
+        .map_err(|why| DatabaseError::Parse(format!("could not parse rustyroad.toml: {}", why)))?;
+    let database_table = toml
+        .get("database")
+        .and_then(|database| database.as_table())
+        .ok_or_else(|| {
+            DatabaseError::ConfigMissing("rustyroad.toml has no [database] table".to_string())
+        })?;
+
+    // Shared settings first, then the ones of the environment
+    let mut settings: toml::map::Map<String, Value> = database_table
+        .iter()
+        .filter(|(_, value)| !value.is_table())
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let has_environments = Environment::ALL
+        .iter()
+        .any(|environment| environment_table(database_table, *environment).is_some());
+    if has_environments {
+        let environment_settings = environment_table(database_table, environment).ok_or_else(|| {
+            DatabaseError::ConfigMissing(format!(
+                "rustyroad.toml has no [database.{}] table",
+                environment
+            ))
+        })?;
+        settings.extend(environment_settings.clone());
+    }
+
+    Ok(settings)
+}
+
+/// Whether any of the settings uses `${DATABASE_URL}`, with or without a fallback.
+fn references_database_url(settings: &toml::map::Map<String, Value>) -> bool {
+    let variable = format!("${{{}", DATABASE_URL_VARIABLE);
+    settings.values().any(|value| {
+        value.as_str().is_some_and(|value| {
+            value.match_indices(&variable).any(|(index, _)| {
+                let rest = &value[index + variable.len()..];
+                rest.starts_with('}') || rest.starts_with(":-")
+            })
+        })
+    })
+}
+
+/// Returns `DATABASE_URL`, when it is set and not empty.
+fn database_url_variable() -> Option<String> {
+    std::env::var(DATABASE_URL_VARIABLE)
+        .ok()
+        .map(|database_url| database_url.trim().to_string())
+        .filter(|database_url| !database_url.is_empty())
+}
+
+/// Returns the `[database.<environment>]` table, by its long or short name.
+fn environment_table(
+    database_table: &toml::map::Map<String, Value>,
+    environment: Environment,
+) -> Option<&toml::map::Map<String, Value>> {
+    [environment.to_string().as_str(), environment.short_name()]
+        .iter()
+        .find_map(|name| database_table.get(*name).and_then(|table| table.as_table()))
+}
+
 pub enum PoolConnection {
     Pg(sqlx::PgPool),
     MySql(sqlx::MySqlPool),
     Sqlite(sqlx::SqlitePool),
+    Mongo(mongodb::Database),
 }
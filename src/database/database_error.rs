@@ -0,0 +1,127 @@
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+use super::validations::ValidationErrors;
+
+/// ## Name: DatabaseError
+/// ### Description: Why the database of a project couldn't be configured or reached
+/// Returned instead of panicking, so generated applications can report the failure, or retry
+/// when [`DatabaseError::is_transient`] says the database may come back.
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// rustyroad.toml, or a table or setting it needs, doesn't exist.
+    ConfigMissing(String),
+    /// rustyroad.toml, a setting in it or a database URL is malformed.
+    Parse(String),
+    /// The database server couldn't be reached.
+    ConnectionRefused(String),
+    /// The database server rejected the user name or password.
+    AuthFailed(String),
+    /// The database type has no driver, e.g. `cassandra`.
+    UnsupportedDriver(String),
+    /// The file of a SQLite database couldn't be written or removed.
+    Io(io::Error),
+    /// Any other error reported by the driver.
+    Sql(sqlx::Error),
+    /// Any other error reported by the MongoDB driver.
+    Mongo(mongodb::error::Error),
+    /// A query built with [`Query`](super::Query) can't run on the database, e.g. `RETURNING` on MySQL.
+    Query(String),
+    /// The row breaks the rules of its model, see [`Validate`](super::Validate).
+    Validation(ValidationErrors),
+}
+
+impl DatabaseError {
+    /// Whether the same call may succeed later, e.g. once the database server restarted.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DatabaseError::ConnectionRefused(_))
+    }
+}
+
+impl Display for DatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigMissing(message) => write!(f, "Missing database configuration: {}", message),
+            Self::Parse(message) => write!(f, "Invalid database configuration: {}", message),
+            Self::ConnectionRefused(message) => {
+                write!(f, "Couldn't connect to the database: {}", message)
+            }
+            Self::AuthFailed(message) => {
+                write!(f, "The database rejected the credentials: {}", message)
+            }
+            Self::UnsupportedDriver(database_type) => write!(
+                f,
+                "The {} database type isn't supported, use postgres, mysql, sqlite or mongo",
+                database_type
+            ),
+            Self::Io(err) => write!(f, "Database file error: {}", err),
+            Self::Sql(err) => write!(f, "SQL error: {}", err),
+            Self::Mongo(err) => write!(f, "MongoDB error: {}", err),
+            Self::Query(message) => write!(f, "Invalid query: {}", message),
+            Self::Validation(errors) => write!(f, "Validation failed: {}", errors),
+        }
+    }
+}
+
+impl StdError for DatabaseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Sql(err) => Some(err),
+            Self::Mongo(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for DatabaseError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut => {
+                Self::ConnectionRefused(err.to_string())
+            }
+            sqlx::Error::Configuration(_) => Self::Parse(err.to_string()),
+            // 28000 and 28P01 are the SQLSTATEs of a rejected login on Postgres and MySQL
+            sqlx::Error::Database(database_error)
+                if matches!(database_error.code().as_deref(), Some("28000") | Some("28P01")) =>
+            {
+                Self::AuthFailed(database_error.message().to_string())
+            }
+            _ => Self::Sql(err),
+        }
+    }
+}
+
+impl From<mongodb::error::Error> for DatabaseError {
+    fn from(err: mongodb::error::Error) -> Self {
+        use mongodb::error::ErrorKind;
+
+        match err.kind.as_ref() {
+            ErrorKind::ServerSelection { .. }
+            | ErrorKind::Io(_)
+            | ErrorKind::DnsResolve { .. }
+            | ErrorKind::ConnectionPoolCleared { .. } => Self::ConnectionRefused(err.to_string()),
+            ErrorKind::Authentication { .. } => Self::AuthFailed(err.to_string()),
+            ErrorKind::InvalidArgument { .. } => Self::Parse(err.to_string()),
+            _ => Self::Mongo(err),
+        }
+    }
+}
+
+/// Lets functions that return [`std::io::Error`] use `?` on the database layer.
+impl From<DatabaseError> for io::Error {
+    fn from(err: DatabaseError) -> Self {
+        let kind = match &err {
+            DatabaseError::ConfigMissing(_) => io::ErrorKind::NotFound,
+            DatabaseError::Parse(_) => io::ErrorKind::InvalidData,
+            DatabaseError::ConnectionRefused(_) => io::ErrorKind::ConnectionRefused,
+            DatabaseError::AuthFailed(_) => io::ErrorKind::PermissionDenied,
+            DatabaseError::UnsupportedDriver(_) => io::ErrorKind::Unsupported,
+            DatabaseError::Query(_) | DatabaseError::Validation(_) => io::ErrorKind::InvalidInput,
+            DatabaseError::Io(err) => err.kind(),
+            DatabaseError::Sql(_) | DatabaseError::Mongo(_) => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, err)
+    }
+}
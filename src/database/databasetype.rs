@@ -1,7 +1,7 @@
 use strum::IntoEnumIterator;
 
 use super::{category::DataTypeCategory, types_for_database::TypesForDatabase};
-use crate::database::{MySqlTypes, PostgresTypes};
+use crate::database::{MySqlTypes, PostgresTypes, SqliteTypes};
 use std::fmt::{self, Display, Formatter};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DatabaseType {
@@ -142,6 +142,53 @@ impl DatabaseTypeTrait for MySqlDatabaseType {
     }
 }
 
+impl DatabaseTypeTrait for SqliteDatabaseType {
+    type DatabaseType = SqliteTypes;
+    type DataType = TypesForDatabase;
+    type DataTypeCategory = DataTypeCategory;
+
+    /// # Name: get_database_types
+    /// ## Description
+    /// This function returns the SQLite types for the given data type category, in the order they're offered.
+    /// The types of `data_types_for_category` are used when it has the category, otherwise the
+    /// defaults of the category, see `DataTypeCategory::get_data_types_from_data_type_category`.
+    /// ## Parameters
+    /// * `data_types_for_category` - A reference to the data types for the given data type category.
+    /// * `data_type_category` - A reference to the data type category.
+    /// ## Returns
+    /// A vector with one `TypesForDatabase`, whose SQLite map holds the types of the category.
+    /// ## Example
+    /// ```
+    /// use rustyroad::database::{DatabaseTypeTrait, SqliteDatabaseType, SqliteTypes, types_for_database::TypesForDatabase, category::DataTypeCategory};
+    /// let sqlite_database_type = SqliteDatabaseType;
+    /// let data_types_for_category = TypesForDatabase::new();
+    ///
+    /// let database_types = sqlite_database_type.get_database_types(&data_types_for_category, &DataTypeCategory::DateTime);
+    /// assert_eq!(database_types.len(), 1);
+    /// assert_eq!(
+    ///     database_types[0].sqlite.types["DateTime"],
+    ///     vec![SqliteTypes::DateTime, SqliteTypes::Date, SqliteTypes::Time]
+    /// );
+    /// ```
+    fn get_database_types(&self, data_types_for_category: &Self::DataType, data_type_category: &Self::DataTypeCategory) -> Vec<TypesForDatabase> {
+        let category = data_type_category.to_string();
+        let data_types = match data_types_for_category.sqlite.types.get(&category) {
+            Some(data_types) => data_types.clone(),
+            None => data_type_category
+                .get_data_types_from_data_type_category(DatabaseType::Sqlite)
+                .sqlite
+                .types
+                .remove(&category)
+                .unwrap_or_default(),
+        };
+
+        let mut types_for_database = TypesForDatabase::new();
+        types_for_database.add_sqlite_type(category, data_types).expect("Failed to add sqlite type");
+
+        vec![types_for_database]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
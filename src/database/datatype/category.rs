@@ -1,4 +1,5 @@
 use super::postgres_types::PostgresTypes;
+use super::sqlite_types::SqliteTypes;
 use super::TypesForDatabase;
 use crate::DatabaseType;
 use std::borrow::Borrow;
@@ -388,6 +389,40 @@ impl DataTypeCategory {
                     types_for_database
                 }
             },
+            // SQLite stores every value as INTEGER, REAL, TEXT, BLOB or NULL, so each category
+            // offers the declared types whose affinity keeps its values intact. Categories
+            // SQLite can't store, e.g. arrays or ranges, have no types.
+            DatabaseType::Sqlite => {
+                let types = match self {
+                    DataTypeCategory::Boolean => vec![SqliteTypes::Boolean, SqliteTypes::Integer],
+                    DataTypeCategory::Numeric => {
+                        vec![SqliteTypes::Integer, SqliteTypes::Real, SqliteTypes::Numeric]
+                    }
+                    DataTypeCategory::DateTime => {
+                        vec![SqliteTypes::DateTime, SqliteTypes::Date, SqliteTypes::Time]
+                    }
+                    DataTypeCategory::Text
+                    | DataTypeCategory::Json
+                    | DataTypeCategory::NetworkAddress => vec![SqliteTypes::Text],
+                    DataTypeCategory::UUID => vec![SqliteTypes::Text, SqliteTypes::Blob],
+                    // Whole cents don't suffer from floating point rounding
+                    DataTypeCategory::Money => vec![SqliteTypes::Integer, SqliteTypes::Numeric],
+                    DataTypeCategory::BitString => vec![SqliteTypes::Blob, SqliteTypes::Integer],
+                    DataTypeCategory::Other => vec![SqliteTypes::Blob],
+                    DataTypeCategory::Array
+                    | DataTypeCategory::Composite
+                    | DataTypeCategory::Geometric
+                    | DataTypeCategory::Interval
+                    | DataTypeCategory::Range
+                    | DataTypeCategory::Search => vec![],
+                };
+                if !types.is_empty() {
+                    types_for_database
+                        .add_sqlite_type(self.to_string(), types)
+                        .expect("Failed to add sqlite type");
+                }
+                types_for_database
+            }
             _ => types_for_database,
         }
     }
@@ -29,11 +29,94 @@ impl SqliteTypes {
                 DataTypeCategory::DateTime
             }
             SqliteTypes::Text => DataTypeCategory::Text,
-            SqliteTypes::Blob => DataTypeCategory::Other,
-            SqliteTypes::Boolean => DataTypeCategory::Other,
-            SqliteTypes::Null => todo!(),
+            SqliteTypes::Boolean => DataTypeCategory::Boolean,
+            SqliteTypes::Blob | SqliteTypes::Null => DataTypeCategory::Other,
         }
     }
+
+    /// Returns the type name written in `CREATE TABLE`, e.g. `DATETIME`.
+    pub fn sql_type(&self) -> &'static str {
+        match &self {
+            SqliteTypes::Integer => "INTEGER",
+            SqliteTypes::Real => "REAL",
+            SqliteTypes::Text => "TEXT",
+            SqliteTypes::Blob => "BLOB",
+            SqliteTypes::Numeric => "NUMERIC",
+            SqliteTypes::Date => "DATE",
+            SqliteTypes::Time => "TIME",
+            SqliteTypes::DateTime => "DATETIME",
+            SqliteTypes::Boolean => "BOOLEAN",
+            SqliteTypes::Null => "NULL",
+        }
+    }
+
+    /// Returns the affinity SQLite gives a column declared with this type,
+    /// which is one of `Integer`, `Real`, `Text`, `Blob` or `Numeric`.
+    pub fn affinity(&self) -> SqliteTypes {
+        sqlite_affinity(self.sql_type())
+    }
+
+    /// Returns the Rust type sqlx decodes a column of this type into.
+    /// Dates and times are stored as ISO-8601 text, booleans as `0` or `1`.
+    pub fn rust_type(&self) -> &'static str {
+        match &self {
+            SqliteTypes::Integer => "i64",
+            SqliteTypes::Real | SqliteTypes::Numeric => "f64",
+            SqliteTypes::Text => "String",
+            SqliteTypes::Blob | SqliteTypes::Null => "Vec<u8>",
+            SqliteTypes::Date => "NaiveDate",
+            SqliteTypes::Time => "NaiveTime",
+            SqliteTypes::DateTime => "NaiveDateTime",
+            SqliteTypes::Boolean => "bool",
+        }
+    }
+
+    /// Describes how a value of this type is stored, shown when choosing a column type.
+    pub fn storage_hint(&self) -> &'static str {
+        match &self {
+            SqliteTypes::Integer => "whole numbers up to 8 bytes",
+            SqliteTypes::Real => "8-byte floating point numbers",
+            SqliteTypes::Text => "UTF-8 text",
+            SqliteTypes::Blob => "bytes, stored exactly as given",
+            SqliteTypes::Numeric => "integers when exact, otherwise floating point numbers",
+            SqliteTypes::Date => "ISO-8601 text, e.g. 2024-01-31",
+            SqliteTypes::Time => "ISO-8601 text, e.g. 13:45:00",
+            SqliteTypes::DateTime => "ISO-8601 text, e.g. 2024-01-31 13:45:00",
+            SqliteTypes::Boolean => "0 or 1",
+            SqliteTypes::Null => "only NULL",
+        }
+    }
+}
+
+/// ## Name: sqlite_affinity
+/// ### Description: Returns the affinity SQLite gives a column declared with a type name
+/// Follows the rules of [the SQLite documentation](https://www.sqlite.org/datatype3.html#determination_of_column_affinity),
+/// in order: `INT` means `Integer`, `CHAR`, `CLOB` or `TEXT` mean `Text`, `BLOB` or no type
+/// means `Blob`, `REAL`, `FLOA` or `DOUB` mean `Real`, and anything else is `Numeric`.
+/// #### Parameters:
+/// - declared_type: [`&str`] - the type of the column in `CREATE TABLE`, e.g. `VARCHAR(255)`
+/// - Returns: [`SqliteTypes`]
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::{sqlite_affinity, SqliteTypes};
+///
+/// assert_eq!(sqlite_affinity("BIGINT"), SqliteTypes::Integer);
+/// assert_eq!(sqlite_affinity("VARCHAR(255)"), SqliteTypes::Text);
+/// assert_eq!(sqlite_affinity("DATETIME"), SqliteTypes::Numeric);
+/// ```
+pub fn sqlite_affinity(declared_type: &str) -> SqliteTypes {
+    let declared_type = declared_type.to_uppercase();
+    if declared_type.contains("INT") {
+        SqliteTypes::Integer
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|name| declared_type.contains(name)) {
+        SqliteTypes::Text
+    } else if declared_type.contains("BLOB") || declared_type.trim().is_empty() {
+        SqliteTypes::Blob
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|name| declared_type.contains(name)) {
+        SqliteTypes::Real
+    } else {
+        SqliteTypes::Numeric
+    }
 }
 
 impl Ord for SqliteTypeMap {
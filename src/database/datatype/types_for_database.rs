@@ -329,7 +329,8 @@ impl TypesForDatabase {
                     },
                 })
                 .collect(),
-            DatabaseType::Mongo => todo!(),
+            // MongoDB fields have BSON types, see ColumnSpec::bson_type
+            DatabaseType::Mongo => Vec::new(),
         };
 
         entries.sort();
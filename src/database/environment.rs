@@ -0,0 +1,81 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The variable that selects the environment a command runs in.
+pub const ENVIRONMENT_VARIABLE: &str = "RUSTYROAD_ENV";
+
+/// ## Name: Environment
+/// ### Description: The environment a project runs in
+/// Selected with the `RUSTYROAD_ENV` variable, `development` by default. Both the short
+/// (`dev`, `test`, `prod`) and the long names are accepted.
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::Environment;
+///
+/// let environment: Environment = "prod".parse().unwrap();
+/// assert_eq!(environment, Environment::Production);
+/// assert_eq!(environment.short_name(), "prod");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Environment {
+    #[default]
+    Development,
+    Test,
+    Production,
+}
+
+impl Environment {
+    /// Every environment, in the order they are usually deployed.
+    pub const ALL: [Environment; 3] = [
+        Environment::Development,
+        Environment::Test,
+        Environment::Production,
+    ];
+
+    /// ## Name: current
+    /// ### Description: Returns the environment set in `RUSTYROAD_ENV`, or development when it isn't set
+    /// #### Returns:
+    /// - [`Result<Environment, String>`] - an error when `RUSTYROAD_ENV` holds an unknown environment
+    pub fn current() -> Result<Environment, String> {
+        match std::env::var(ENVIRONMENT_VARIABLE) {
+            Ok(name) if !name.trim().is_empty() => name.parse(),
+            _ => Ok(Environment::default()),
+        }
+    }
+
+    /// Returns the short name, used for the per-environment directories, e.g. `seeders/dev`.
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Environment::Development => "dev",
+            Environment::Test => "test",
+            Environment::Production => "prod",
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let environment = match self {
+            Environment::Development => "development",
+            Environment::Test => "test",
+            Environment::Production => "production",
+        };
+        write!(f, "{}", environment)
+    }
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_lowercase().as_str() {
+            "dev" | "development" => Ok(Environment::Development),
+            "test" => Ok(Environment::Test),
+            "prod" | "production" => Ok(Environment::Production),
+            other => Err(format!(
+                "Unknown environment '{}', expected dev, test or prod",
+                other
+            )),
+        }
+    }
+}
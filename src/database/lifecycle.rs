@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::PathBuf;
+
+use sqlx::mysql::MySqlPool;
+use sqlx::postgres::PgPool;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+use crate::database::migrations::{
+    describe_database, run_all_migrations, CustomMigrationError, MigrationDirection,
+    MigrationMode, DEFAULT_MIGRATION_LOCK_TIMEOUT,
+};
+use crate::database::migrations::SCHEMA_MIGRATIONS_TABLE;
+use crate::database::{run_seeds, Database, DatabaseError, DatabaseType, Environment};
+
+/// ## Name: sqlite_database_path
+/// ### Description: Returns the file of a SQLite database, `{name}.db` relative to the project root
+pub fn sqlite_database_path(database: &Database) -> PathBuf {
+    PathBuf::from(format!("{}.db", database.name))
+}
+
+/// ## Name: database_exists
+/// ### Description: Checks whether the database configured in a [`Database`] exists on its server
+/// #### Parameters:
+/// - database: [`&Database`] - the database to look for
+/// - Returns: [`Result<bool, DatabaseError>`]
+pub async fn database_exists(database: &Database) -> Result<bool, DatabaseError> {
+    match database.database_type {
+        DatabaseType::Postgres => {
+            let pool = postgres_admin_pool(database).await?;
+            let exists: Option<i32> =
+                sqlx::query_scalar("SELECT 1 FROM pg_database WHERE datname = $1")
+                    .bind(&database.name)
+                    .fetch_optional(&pool)
+                    .await?;
+            pool.close().await;
+            Ok(exists.is_some())
+        }
+        DatabaseType::Mysql => {
+            let pool = mysql_admin_pool(database).await?;
+            let exists: Option<String> = sqlx::query_scalar(
+                "SELECT SCHEMA_NAME FROM INFORMATION_SCHEMA.SCHEMATA WHERE SCHEMA_NAME = ?",
+            )
+            .bind(&database.name)
+            .fetch_optional(&pool)
+            .await?;
+            pool.close().await;
+            Ok(exists.is_some())
+        }
+        DatabaseType::Sqlite => Ok(sqlite_database_path(database).is_file()),
+        DatabaseType::Mongo => {
+            let names = database.mongo_client().await?.list_database_names().await?;
+            Ok(names.contains(&database.name))
+        }
+    }
+}
+
+/// ## Name: create_database
+/// ### Description: Creates the database configured in a [`Database`] unless it already exists
+/// Postgres and MySQL databases are created through the server with the configured user,
+/// a SQLite database is an empty `{name}.db` file. MongoDB only keeps databases that have a
+/// collection, so the `schema_migrations` collection is created with it.
+/// #### Parameters:
+/// - database: [`&Database`] - the database to create
+/// - Returns: [`Result<bool, DatabaseError>`] - `false` when the database already existed
+pub async fn create_database(database: &Database) -> Result<bool, DatabaseError> {
+    if database_exists(database).await? {
+        return Ok(false);
+    }
+
+    match database.database_type {
+        DatabaseType::Postgres => {
+            let pool = postgres_admin_pool(database).await?;
+            sqlx::query(&format!("CREATE DATABASE {}", quote_postgres(&database.name)))
+                .execute(&pool)
+                .await?;
+            pool.close().await;
+        }
+        DatabaseType::Mysql => {
+            let pool = mysql_admin_pool(database).await?;
+            sqlx::query(&format!("CREATE DATABASE {}", quote_mysql(&database.name)))
+                .execute(&pool)
+                .await?;
+            pool.close().await;
+        }
+        DatabaseType::Sqlite => {
+            let options = SqliteConnectOptions::new()
+                .filename(sqlite_database_path(database))
+                .create_if_missing(true);
+            let pool = SqlitePool::connect_with(options).await?;
+            pool.close().await;
+        }
+        DatabaseType::Mongo => {
+            database
+                .mongo_client()
+                .await?
+                .database(&database.name)
+                .create_collection(SCHEMA_MIGRATIONS_TABLE)
+                .await?;
+        }
+    }
+    Ok(true)
+}
+
+/// ## Name: drop_database
+/// ### Description: Drops the database configured in a [`Database`] if it exists
+/// Postgres refuses to drop a database other sessions are connected to, stop the application first.
+/// #### Parameters:
+/// - database: [`&Database`] - the database to drop
+/// - Returns: [`Result<bool, DatabaseError>`] - `false` when there was no database to drop
+pub async fn drop_database(database: &Database) -> Result<bool, DatabaseError> {
+    if !database_exists(database).await? {
+        return Ok(false);
+    }
+
+    match database.database_type {
+        DatabaseType::Postgres => {
+            let pool = postgres_admin_pool(database).await?;
+            sqlx::query(&format!("DROP DATABASE {}", quote_postgres(&database.name)))
+                .execute(&pool)
+                .await?;
+            pool.close().await;
+        }
+        DatabaseType::Mysql => {
+            let pool = mysql_admin_pool(database).await?;
+            sqlx::query(&format!("DROP DATABASE {}", quote_mysql(&database.name)))
+                .execute(&pool)
+                .await?;
+            pool.close().await;
+        }
+        DatabaseType::Sqlite => {
+            let path = sqlite_database_path(database);
+            fs::remove_file(&path).map_err(DatabaseError::Io)?;
+            // The write-ahead log belongs to the database too
+            for suffix in ["-wal", "-shm"] {
+                let mut companion = path.clone().into_os_string();
+                companion.push(suffix);
+                let _ = fs::remove_file(companion);
+            }
+        }
+        DatabaseType::Mongo => {
+            database.mongo_client().await?.database(&database.name).drop().await?;
+        }
+    }
+    Ok(true)
+}
+
+/// ## Name: create_project_database
+/// ### Description: Creates the database configured in rustyroad.toml, for `rustyroad db create`
+pub async fn create_project_database() -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    if create_database(&database).await? {
+        println!("Created the {}", describe_database(&database));
+    } else {
+        println!("The {} already exists", describe_database(&database));
+    }
+    Ok(())
+}
+
+/// ## Name: drop_project_database
+/// ### Description: Drops the database configured in rustyroad.toml, for `rustyroad db drop`
+pub async fn drop_project_database() -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    if drop_database(&database).await? {
+        println!("Dropped the {}", describe_database(&database));
+    } else {
+        println!("The {} doesn't exist", describe_database(&database));
+    }
+    Ok(())
+}
+
+/// ## Name: setup_project_database
+/// ### Description: Creates the database configured in rustyroad.toml if needed, runs the pending migrations and the seeds
+/// Every step skips what was already done, so `rustyroad db setup` can be run on any checkout.
+/// #### Parameters:
+/// - environment: [`Environment`] - the environment whose seeds are run
+pub async fn setup_project_database(environment: Environment) -> Result<(), CustomMigrationError> {
+    create_project_database().await?;
+    run_all_migrations(
+        MigrationDirection::Up,
+        false,
+        MigrationMode::Execute,
+        DEFAULT_MIGRATION_LOCK_TIMEOUT,
+    )
+    .await?;
+    run_seeds(environment).await
+}
+
+/// ## Name: reset_project_database
+/// ### Description: Drops the database configured in rustyroad.toml and sets it up again from scratch
+/// #### Parameters:
+/// - environment: [`Environment`] - the environment whose seeds are run
+pub async fn reset_project_database(environment: Environment) -> Result<(), CustomMigrationError> {
+    drop_project_database().await?;
+    setup_project_database(environment).await
+}
+
+/// Connects to the `postgres` maintenance database, since a database can't create or drop itself.
+async fn postgres_admin_pool(database: &Database) -> Result<PgPool, DatabaseError> {
+    let options = database.pg_connect_options("postgres")?;
+    Ok(PgPool::connect_with(options).await?)
+}
+
+/// Connects to the MySQL server without selecting a database.
+async fn mysql_admin_pool(database: &Database) -> Result<MySqlPool, DatabaseError> {
+    let options = database.mysql_connect_options(None)?;
+    Ok(MySqlPool::connect_with(options).await?)
+}
+
+fn quote_postgres(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_mysql(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
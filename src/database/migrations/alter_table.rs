@@ -0,0 +1,487 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+
+use crate::database::{Database, DatabaseConnection, DatabaseType};
+
+use super::column_spec::{ColumnKind, ColumnSpec};
+use super::sqlite_table::{
+    load_sqlite_table_schema, sqlite_rebuild_sql, SqliteColumn, SqliteForeignKey,
+    SqliteTableSchema,
+};
+use super::{
+    connect_to_project_database, create_migration_files, next_migration_version, CustomMigrationError,
+    MIGRATIONS_DIR,
+};
+
+/// ## Name: AlterTable
+/// ### Description: A change to an existing table, turned into reversible up/down SQL
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::{AlterTable, ColumnSpec};
+/// use rustyroad::database::DatabaseType;
+///
+/// let change = AlterTable::AddColumn {
+///     table: "posts".to_string(),
+///     column: ColumnSpec::parse("subtitle:string:null").unwrap(),
+/// };
+/// let (up, down) = change.to_sql(&DatabaseType::Postgres, None).unwrap();
+/// assert_eq!(up, "ALTER TABLE posts ADD COLUMN subtitle VARCHAR(255) NULL;\n");
+/// assert_eq!(down, "ALTER TABLE posts DROP COLUMN subtitle;\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlterTable {
+    AddColumn {
+        table: String,
+        column: ColumnSpec,
+    },
+    /// The column spec is used to add the column back on rollback.
+    RemoveColumn {
+        table: String,
+        column: ColumnSpec,
+    },
+    RenameColumn {
+        table: String,
+        from: String,
+        to: String,
+    },
+    /// `from` is the current definition of the column, used on rollback.
+    ChangeColumnType {
+        table: String,
+        column: ColumnSpec,
+        from: ColumnSpec,
+    },
+    AddIndex {
+        table: String,
+        columns: Vec<String>,
+        unique: bool,
+    },
+    AddForeignKey {
+        table: String,
+        column: String,
+        references: String,
+    },
+}
+
+impl AlterTable {
+    /// Returns the table the change applies to.
+    pub fn table(&self) -> &str {
+        match self {
+            AlterTable::AddColumn { table, .. }
+            | AlterTable::RemoveColumn { table, .. }
+            | AlterTable::RenameColumn { table, .. }
+            | AlterTable::ChangeColumnType { table, .. }
+            | AlterTable::AddIndex { table, .. }
+            | AlterTable::AddForeignKey { table, .. } => table,
+        }
+    }
+
+    /// Returns the name of the generated migration, e.g. `add_subtitle_to_posts`.
+    pub fn migration_name(&self) -> String {
+        match self {
+            AlterTable::AddColumn { table, column } => format!("add_{}_to_{}", column.name, table),
+            AlterTable::RemoveColumn { table, column } => {
+                format!("remove_{}_from_{}", column.name, table)
+            }
+            AlterTable::RenameColumn { table, from, to } => {
+                format!("rename_{}_to_{}_in_{}", from, to, table)
+            }
+            AlterTable::ChangeColumnType { table, column, .. } => {
+                format!("change_{}_type_in_{}", column.name, table)
+            }
+            AlterTable::AddIndex { table, columns, .. } => {
+                format!("add_index_to_{}_on_{}", table, columns.join("_and_"))
+            }
+            AlterTable::AddForeignKey { table, column, .. } => {
+                format!("add_foreign_key_to_{}_on_{}", table, column)
+            }
+        }
+    }
+
+    /// Whether SQLite needs the table rebuilt, which requires the current table schema.
+    pub fn requires_sqlite_rebuild(&self) -> bool {
+        match self {
+            // SQLite can't drop a column with a foreign key, so rolling one back needs the rebuild too
+            AlterTable::AddColumn { column, .. } => {
+                is_constrained(column) || matches!(column.kind, ColumnKind::References(_))
+            }
+            AlterTable::RemoveColumn { .. }
+            | AlterTable::ChangeColumnType { .. }
+            | AlterTable::AddForeignKey { .. } => true,
+            AlterTable::RenameColumn { .. } | AlterTable::AddIndex { .. } => false,
+        }
+    }
+
+    /// ## Name: to_sql
+    /// ### Description: Returns the `up.sql` and `down.sql` contents of the change
+    /// #### Parameters:
+    /// - database_type: [`&DatabaseType`] - the database the SQL is written for
+    /// - sqlite_schema: [`Option<&SqliteTableSchema>`] - the current table, required when [`requires_sqlite_rebuild`](Self::requires_sqlite_rebuild) is true on SQLite
+    /// - Returns: [`Result<(String, String), std::io::Error>`]
+    pub fn to_sql(
+        &self,
+        database_type: &DatabaseType,
+        sqlite_schema: Option<&SqliteTableSchema>,
+    ) -> Result<(String, String), io::Error> {
+        validate_identifier(self.table())?;
+
+        match database_type {
+            DatabaseType::Postgres | DatabaseType::Mysql => self.alter_sql(database_type),
+            DatabaseType::Sqlite if self.requires_sqlite_rebuild() => {
+                let schema = sqlite_schema.ok_or_else(|| {
+                    io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The schema of '{}' is needed to rebuild it", self.table()),
+                    )
+                })?;
+                self.sqlite_rebuild(schema)
+            }
+            DatabaseType::Sqlite => self.alter_sql(database_type),
+            DatabaseType::Mongo => Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "MongoDB collections have no columns to alter, add a collMod command to the up.json of a new migration instead",
+            )),
+        }
+    }
+
+    /// The up/down SQL for changes the database supports with `ALTER TABLE`.
+    fn alter_sql(&self, database_type: &DatabaseType) -> Result<(String, String), io::Error> {
+        let sql = match self {
+            AlterTable::AddColumn { table, column } => (
+                add_column_sql(table, column, database_type)?,
+                drop_column_sql(table, column, database_type),
+            ),
+            AlterTable::RemoveColumn { table, column } => (
+                drop_column_sql(table, column, database_type),
+                add_column_sql(table, column, database_type)?,
+            ),
+            AlterTable::RenameColumn { table, from, to } => {
+                validate_identifier(from)?;
+                validate_identifier(to)?;
+                (
+                    format!("ALTER TABLE {} RENAME COLUMN {} TO {};\n", table, from, to),
+                    format!("ALTER TABLE {} RENAME COLUMN {} TO {};\n", table, to, from),
+                )
+            }
+            AlterTable::ChangeColumnType {
+                table,
+                column,
+                from,
+            } => (
+                change_column_type_sql(table, column, database_type)?,
+                change_column_type_sql(table, from, database_type)?,
+            ),
+            AlterTable::AddIndex {
+                table,
+                columns,
+                unique,
+            } => {
+                for column in columns {
+                    validate_identifier(column)?;
+                }
+                let index_name = index_name(table, columns);
+                let drop_index = match database_type {
+                    DatabaseType::Mysql => format!("DROP INDEX {} ON {};\n", index_name, table),
+                    _ => format!("DROP INDEX {};\n", index_name),
+                };
+                (
+                    format!(
+                        "CREATE {}INDEX {} ON {} ({});\n",
+                        if *unique { "UNIQUE " } else { "" },
+                        index_name,
+                        table,
+                        columns.join(", ")
+                    ),
+                    drop_index,
+                )
+            }
+            AlterTable::AddForeignKey {
+                table,
+                column,
+                references,
+            } => {
+                validate_identifier(column)?;
+                validate_identifier(references)?;
+                (
+                    add_foreign_key_sql(table, column, references),
+                    drop_foreign_key_sql(table, column, database_type),
+                )
+            }
+        };
+        Ok(sql)
+    }
+
+    /// The up/down SQL for changes SQLite can only make by rebuilding the table.
+    fn sqlite_rebuild(&self, schema: &SqliteTableSchema) -> Result<(String, String), io::Error> {
+        let (source, target) = match self {
+            AlterTable::AddColumn { column, .. } => {
+                ensure_column(schema, &column.name, false)?;
+                (schema.clone(), with_column_spec(schema, column)?)
+            }
+            AlterTable::RemoveColumn { column, .. } => {
+                ensure_column(schema, &column.name, true)?;
+                (schema.clone(), schema.without_column(&column.name))
+            }
+            AlterTable::ChangeColumnType { column, .. } => {
+                ensure_column(schema, &column.name, true)?;
+                let mut target = schema.clone();
+                for existing in target.columns.iter_mut() {
+                    if existing.name == column.name {
+                        existing.column_type = column.sql_type(&DatabaseType::Sqlite)?;
+                        existing.not_null = !column.nullable;
+                    }
+                }
+                (schema.clone(), target)
+            }
+            AlterTable::AddForeignKey {
+                column, references, ..
+            } => {
+                validate_identifier(references)?;
+                ensure_column(schema, column, true)?;
+                let mut target = schema.clone();
+                target.foreign_keys.push(SqliteForeignKey {
+                    columns: vec![column.clone()],
+                    references_table: references.clone(),
+                    references_columns: vec!["id".to_string()],
+                    on_update: "NO ACTION".to_string(),
+                    on_delete: "NO ACTION".to_string(),
+                });
+                (schema.clone(), target)
+            }
+            AlterTable::RenameColumn { .. } | AlterTable::AddIndex { .. } => {
+                return self.alter_sql(&DatabaseType::Sqlite)
+            }
+        };
+
+        Ok((
+            sqlite_rebuild_sql(&source, &target),
+            sqlite_rebuild_sql(&target, &source),
+        ))
+    }
+}
+
+/// ## Name: create_alter_table_migration
+/// ### Description: Writes a migration for a change to an existing table of the current project
+/// On SQLite the current table is read from the project database when the change needs a rebuild,
+/// so the migrations that create the table have to be applied first.
+/// #### Parameters:
+/// - change: [`AlterTable`] - the change to make
+/// - Returns: [`Result<String, CustomMigrationError>`] - the path of the new migration directory
+///
+/// ### Example:
+/// ```rust,no_run
+/// use rustyroad::database::migrations::{create_alter_table_migration, AlterTable};
+///
+/// # async fn example() {
+/// let change = AlterTable::RenameColumn {
+///     table: "posts".to_string(),
+///     from: "body".to_string(),
+///     to: "content".to_string(),
+/// };
+/// create_alter_table_migration(change).await.unwrap();
+/// # }
+/// ```
+pub async fn create_alter_table_migration(
+    change: AlterTable,
+) -> Result<String, CustomMigrationError> {
+    if fs::read_to_string("rustyroad.toml").is_err() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            ErrorKind::NotFound,
+            "Error reading the rustyroad.toml, please see the documentation for more information.",
+        )));
+    }
+
+    let database = Database::get_database_from_rustyroad_toml()?;
+
+    let sqlite_schema = if database.database_type == DatabaseType::Sqlite
+        && change.requires_sqlite_rebuild()
+    {
+        match connect_to_project_database().await? {
+            DatabaseConnection::Sqlite(pool) => {
+                Some(load_sqlite_table_schema(&pool, change.table()).await?)
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let (up_sql_contents, down_sql_contents) = change
+        .to_sql(&database.database_type, sqlite_schema.as_ref())
+        .map_err(CustomMigrationError::IoError)?;
+
+    fs::create_dir_all(MIGRATIONS_DIR).map_err(CustomMigrationError::IoError)?;
+    let folder_name = format!(
+        "{}/{}-{}",
+        MIGRATIONS_DIR,
+        next_migration_version(),
+        change.migration_name()
+    );
+    create_migration_files(&folder_name, &up_sql_contents, &down_sql_contents)
+        .map_err(CustomMigrationError::IoError)?;
+    println!("Created migration {}", folder_name);
+
+    Ok(folder_name)
+}
+
+/// Whether a new column has a constraint SQLite's `ADD COLUMN` can't add.
+fn is_constrained(column: &ColumnSpec) -> bool {
+    !column.nullable || column.unique
+}
+
+fn foreign_key_name(table: &str, column: &str) -> String {
+    format!("fk_{}_{}", table, column)
+}
+
+fn index_name(table: &str, columns: &[String]) -> String {
+    format!("index_{}_on_{}", table, columns.join("_and_"))
+}
+
+fn add_column_sql(
+    table: &str,
+    column: &ColumnSpec,
+    database_type: &DatabaseType,
+) -> Result<String, io::Error> {
+    let mut sql = format!(
+        "ALTER TABLE {} ADD COLUMN {}",
+        table,
+        column.column_definition(database_type)?
+    );
+
+    match (&column.kind, database_type) {
+        // SQLite only accepts the foreign key inline, on a nullable column
+        (ColumnKind::References(references), DatabaseType::Sqlite) => {
+            sql.push_str(&format!(" REFERENCES {} (id);\n", references));
+        }
+        (ColumnKind::References(references), _) => {
+            sql.push_str(";\n");
+            sql.push_str(&add_foreign_key_sql(table, &column.name, references));
+        }
+        _ => sql.push_str(";\n"),
+    }
+
+    Ok(sql)
+}
+
+fn drop_column_sql(table: &str, column: &ColumnSpec, database_type: &DatabaseType) -> String {
+    let mut sql = String::new();
+    if matches!(column.kind, ColumnKind::References(_)) && *database_type != DatabaseType::Sqlite
+    {
+        sql.push_str(&drop_foreign_key_sql(table, &column.name, database_type));
+    }
+    sql.push_str(&format!("ALTER TABLE {} DROP COLUMN {};\n", table, column.name));
+    sql
+}
+
+fn change_column_type_sql(
+    table: &str,
+    column: &ColumnSpec,
+    database_type: &DatabaseType,
+) -> Result<String, io::Error> {
+    let sql = match database_type {
+        DatabaseType::Postgres => {
+            let sql_type = column.sql_type(database_type)?;
+            let nullability = if column.nullable {
+                "DROP NOT NULL"
+            } else {
+                "SET NOT NULL"
+            };
+            format!(
+                "ALTER TABLE {table} ALTER COLUMN {column} TYPE {sql_type} USING {column}::{sql_type};\nALTER TABLE {table} ALTER COLUMN {column} {nullability};\n",
+                table = table,
+                column = column.name,
+                sql_type = sql_type,
+                nullability = nullability
+            )
+        }
+        _ => format!(
+            "ALTER TABLE {} MODIFY COLUMN {};\n",
+            table,
+            column.column_definition(database_type)?
+        ),
+    };
+    Ok(sql)
+}
+
+fn add_foreign_key_sql(table: &str, column: &str, references: &str) -> String {
+    format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} (id);\n",
+        table,
+        foreign_key_name(table, column),
+        column,
+        references
+    )
+}
+
+fn drop_foreign_key_sql(table: &str, column: &str, database_type: &DatabaseType) -> String {
+    match database_type {
+        DatabaseType::Mysql => format!(
+            "ALTER TABLE {} DROP FOREIGN KEY {};\n",
+            table,
+            foreign_key_name(table, column)
+        ),
+        _ => format!(
+            "ALTER TABLE {} DROP CONSTRAINT {};\n",
+            table,
+            foreign_key_name(table, column)
+        ),
+    }
+}
+
+/// Returns the schema with a column added from a column spec.
+fn with_column_spec(
+    schema: &SqliteTableSchema,
+    column: &ColumnSpec,
+) -> Result<SqliteTableSchema, io::Error> {
+    let mut target = schema.with_column(SqliteColumn {
+        name: column.name.clone(),
+        column_type: column.sql_type(&DatabaseType::Sqlite)?,
+        not_null: !column.nullable,
+        default_value: None,
+    });
+    if column.unique {
+        target.unique_constraints.push(vec![column.name.clone()]);
+    }
+    if let ColumnKind::References(references) = &column.kind {
+        target.foreign_keys.push(SqliteForeignKey {
+            columns: vec![column.name.clone()],
+            references_table: references.clone(),
+            references_columns: vec!["id".to_string()],
+            on_update: "NO ACTION".to_string(),
+            on_delete: "NO ACTION".to_string(),
+        });
+    }
+    Ok(target)
+}
+
+/// Checks that a column does (or doesn't) exist before the table is rebuilt around it.
+fn ensure_column(
+    schema: &SqliteTableSchema,
+    column_name: &str,
+    should_exist: bool,
+) -> Result<(), io::Error> {
+    match (schema.has_column(column_name), should_exist) {
+        (true, false) => Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("Column '{}' already exists in '{}'", column_name, schema.name),
+        )),
+        (false, true) => Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("Column '{}' doesn't exist in '{}'", column_name, schema.name),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_identifier(identifier: &str) -> Result<(), io::Error> {
+    if identifier.is_empty()
+        || !identifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' isn't a valid table or column name", identifier),
+        ));
+    }
+    Ok(())
+}
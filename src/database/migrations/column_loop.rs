@@ -2,7 +2,7 @@ use rustyline::DefaultEditor;
 use std::io::{self, Error};
 use std::ops::Deref;
 
-use crate::database::{category::DataTypeCategory, databasetype::{DatabaseType, DatabaseTypeTrait, PostgresDatabaseType}, Database, PostgresTypes, MySqlDatabaseType};
+use crate::database::{category::DataTypeCategory, databasetype::{DatabaseType, DatabaseTypeTrait, PostgresDatabaseType, SqliteDatabaseType}, Database, PostgresTypes, MySqlDatabaseType, SqliteTypes};
 
 
 pub struct MigrationAndStruct {
@@ -44,6 +44,13 @@ pub fn column_loop(num_columns: i32, migration_name: String) -> Result<Migration
         .expect("Failed to get database from rustyroad.toml");
     let database_type = database.database_type;
     println!("Database Type: {:?}", database_type);
+    // MongoDB fields have BSON types instead, see build_collection_migration
+    if database_type == DatabaseType::Mongo {
+        return Err(Error::new(
+            io::ErrorKind::Unsupported,
+            "MongoDB collections are created with `rustyroad migration generate <name> <field>:<type>`",
+        ));
+    }
     // let types_for_database = TypesForDatabase::new();
 
 
@@ -58,6 +65,17 @@ pub fn column_loop(num_columns: i32, migration_name: String) -> Result<Migration
         let mut all_available_db_types: Vec<DataTypeCategory> =
             DataTypeCategory::get_all_categories();
 
+        // SQLite can't store every category, e.g. arrays
+        if database_type == DatabaseType::Sqlite {
+            all_available_db_types.retain(|category| {
+                !category
+                    .get_data_types_from_data_type_category(DatabaseType::Sqlite)
+                    .sqlite
+                    .types
+                    .is_empty()
+            });
+        }
+
         all_available_db_types.sort();
         println!("Column Types: 1");
         for (index, column_type) in all_available_db_types.iter().enumerate() {
@@ -88,10 +106,58 @@ pub fn column_loop(num_columns: i32, migration_name: String) -> Result<Migration
                 .get_database_types(&data_types_for_category, &data_type_category),
             DatabaseType::Mysql => MySqlDatabaseType
                 .get_database_types(&data_types_for_category, &data_type_category),
-            DatabaseType::Sqlite => todo!("Implement SqliteDatabaseType.get_database_types"),
-            DatabaseType::Mongo => todo!("Implement MongoDatabaseType.get_database_types"),
+            DatabaseType::Sqlite => SqliteDatabaseType
+                .get_database_types(&data_types_for_category, data_type_category),
+            DatabaseType::Mongo => unreachable!("MongoDB returned early"),
         };
 
+        if database_type == DatabaseType::Sqlite {
+            let sqlite_types = database_types[0]
+                .sqlite
+                .types
+                .get(&data_type_category.to_string())
+                .cloned()
+                .unwrap_or_default();
+            for (index, sqlite_type) in sqlite_types.iter().enumerate() {
+                println!(
+                    "{}. {} ({} affinity, {})",
+                    index + 1,
+                    sqlite_type.sql_type(),
+                    sqlite_type.affinity().sql_type(),
+                    sqlite_type.storage_hint()
+                );
+            }
+
+            let new_type_input = rl.readline("Enter the type of the column: ").unwrap_or_else(|why| {
+                panic!("Failed to get user input: {}", why);
+            });
+            let sqlite_type = new_type_input
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| sqlite_types.get(index))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid input"))?;
+
+            let nullable_input = rl.readline("Is the column nullable? (y/n): ").unwrap();
+            let nullable = match nullable_input.trim().to_lowercase().as_str() {
+                "y" => true,
+                "n" => false,
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid input")),
+            };
+
+            let column_constraints = rl
+                .readline("Enter column constraints (e.g. PRIMARY KEY, UNIQUE, DEFAULT, CHECK, REFERENCES): ")
+                .unwrap();
+
+            let (column_definition, struct_field) =
+                sqlite_column(&column_name, sqlite_type, nullable, &column_constraints);
+            column_string.push_str(&column_definition);
+            column_string.push(',');
+            struct_fields.push_str(&struct_field);
+            continue;
+        }
+
         let database_types_hash_map = database_types
             .iter()
             .map(|x| x.clone().postgres)
@@ -159,8 +225,8 @@ pub fn column_loop(num_columns: i32, migration_name: String) -> Result<Migration
                 selected_type_vector
             }
             DatabaseType::Mysql => todo!("Implement MySqlDatabaseType column_type mapping"),
-            DatabaseType::Sqlite => todo!("Implement SqliteDatabaseType column_type mapping"),
-            DatabaseType::Mongo => todo!("Implement MongoDatabaseType column_type mapping"),
+            DatabaseType::Sqlite => unreachable!("SQLite columns are added above"),
+            DatabaseType::Mongo => unreachable!("MongoDB returned early"),
         };
 
         println!("Type You selected: {:?}", selected_type[0].clone());
@@ -254,6 +320,53 @@ pub fn column_loop(num_columns: i32, migration_name: String) -> Result<Migration
     Ok(new_migration_struct)
 }
 
+/// # Name: sqlite_column
+/// ## Description
+/// Returns the definition of a SQLite column and the field of the generated struct.
+/// The field has the Rust type sqlx decodes the column into, in an `Option` when the column is nullable.
+/// ## Parameters
+/// * `column_name` - The name of the column.
+/// * `sqlite_type` - The declared type of the column.
+/// * `nullable` - Whether the column accepts `NULL`.
+/// * `constraints` - Other constraints, e.g. `UNIQUE`, may be empty.
+/// ## Returns
+/// The column definition used inside `CREATE TABLE` and the struct field.
+/// ## Example
+/// ```
+/// use rustyroad::database::migrations::sqlite_column;
+/// use rustyroad::database::SqliteTypes;
+///
+/// let (column, field) = sqlite_column("published_at", &SqliteTypes::DateTime, true, "");
+/// assert_eq!(column, "published_at DATETIME NULL");
+/// assert_eq!(field, "    pub published_at: Option<NaiveDateTime>,\n");
+/// ```
+pub fn sqlite_column(
+    column_name: &str,
+    sqlite_type: &SqliteTypes,
+    nullable: bool,
+    constraints: &str,
+) -> (String, String) {
+    let mut column_definition = format!(
+        "{} {} {}",
+        column_name,
+        sqlite_type.sql_type(),
+        if nullable { "NULL" } else { "NOT NULL" }
+    );
+    if !constraints.trim().is_empty() {
+        column_definition.push(' ');
+        column_definition.push_str(constraints.trim());
+    }
+
+    let rust_type = if nullable {
+        format!("Option<{}>", sqlite_type.rust_type())
+    } else {
+        sqlite_type.rust_type().to_string()
+    };
+    let struct_field = format!("    pub {}: {},\n", column_name.to_lowercase(), rust_type);
+
+    (column_definition, struct_field)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
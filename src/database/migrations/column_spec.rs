@@ -0,0 +1,500 @@
+use std::io::{self, ErrorKind};
+
+use crate::database::{DatabaseType, MySqlTypes, PostgresTypes, SqliteTypes};
+use crate::helpers::helpers::{pluralize, singularize};
+
+use super::column_loop::MigrationAndStruct;
+
+/// ## Name: ColumnKind
+/// ### Description: The column types accepted in a column spec, e.g. the `text` in `title:text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnKind {
+    String,
+    Text,
+    Integer,
+    BigInt,
+    Float,
+    Decimal,
+    Boolean,
+    Date,
+    DateTime,
+    Time,
+    Uuid,
+    Json,
+    Binary,
+    /// A foreign key to the `id` column of the given table.
+    References(String),
+}
+
+/// ## Name: ColumnSpec
+/// ### Description: A column given on the command line as `name:type[:modifier...]`
+/// Columns are `NOT NULL` unless the `null` modifier is given, and `unique` adds a unique constraint.
+/// `references` takes the referenced table as its first modifier, e.g. `author_id:references:users`.
+/// #### Fields:
+/// - name: [`String`] - the name of the column
+/// - kind: [`ColumnKind`] - the type of the column
+/// - nullable: [`bool`] - whether the column accepts NULL
+/// - unique: [`bool`] - whether the column has a unique constraint
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::{ColumnKind, ColumnSpec};
+///
+/// let column = ColumnSpec::parse("author_id:references:users").unwrap();
+/// assert_eq!(column.name, "author_id");
+/// assert_eq!(column.kind, ColumnKind::References("users".to_string()));
+/// assert!(!column.nullable);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub kind: ColumnKind,
+    pub nullable: bool,
+    pub unique: bool,
+}
+
+impl ColumnSpec {
+    /// ## Name: parse
+    /// ### Description: Parses a single `name:type[:modifier...]` column spec
+    /// #### Parameters:
+    /// - spec: [`&str`] - the column spec, e.g. `title:string:unique`
+    /// - Returns: [`Result<ColumnSpec, std::io::Error>`] - an `InvalidInput` error for unknown types or modifiers
+    pub fn parse(spec: &str) -> Result<ColumnSpec, io::Error> {
+        let mut parts = spec.trim().split(':');
+        let name = parts.next().unwrap_or_default().to_string();
+        let type_name = parts.next().unwrap_or_default().to_lowercase();
+        let mut modifiers: Vec<String> = parts.map(|part| part.to_string()).collect();
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(invalid_input(format!(
+                "Invalid column name in '{}', expected name:type",
+                spec
+            )));
+        }
+
+        let kind = match type_name.as_str() {
+            "string" | "varchar" => ColumnKind::String,
+            "text" => ColumnKind::Text,
+            "integer" | "int" => ColumnKind::Integer,
+            "bigint" => ColumnKind::BigInt,
+            "float" | "double" => ColumnKind::Float,
+            "decimal" | "numeric" => ColumnKind::Decimal,
+            "boolean" | "bool" => ColumnKind::Boolean,
+            "date" => ColumnKind::Date,
+            "datetime" | "timestamp" => ColumnKind::DateTime,
+            "time" => ColumnKind::Time,
+            "uuid" => ColumnKind::Uuid,
+            "json" => ColumnKind::Json,
+            "binary" | "blob" => ColumnKind::Binary,
+            "references" | "belongs_to" => {
+                // `author_id:references` points to `authors` when no table is given
+                let table = match modifiers.first() {
+                    Some(table) if !is_modifier(table) => modifiers.remove(0),
+                    _ => pluralize(name.trim_end_matches("_id")),
+                };
+                ColumnKind::References(table)
+            }
+            "" => {
+                return Err(invalid_input(format!(
+                    "Missing column type in '{}', expected name:type",
+                    spec
+                )))
+            }
+            other => {
+                return Err(invalid_input(format!(
+                    "Unknown column type '{}' in '{}'",
+                    other, spec
+                )))
+            }
+        };
+
+        let mut column = ColumnSpec {
+            name,
+            kind,
+            nullable: false,
+            unique: false,
+        };
+
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "null" => column.nullable = true,
+                "unique" => column.unique = true,
+                other => {
+                    return Err(invalid_input(format!(
+                        "Unknown column modifier '{}' in '{}', expected null or unique",
+                        other, spec
+                    )))
+                }
+            }
+        }
+
+        Ok(column)
+    }
+
+    /// Returns the SQL type of the column for the given database.
+    pub fn sql_type(&self, database_type: &DatabaseType) -> Result<String, io::Error> {
+        let sql_type = match database_type {
+            DatabaseType::Postgres => postgres_sql_type(&self.postgres_type()),
+            DatabaseType::Mysql => mysql_sql_type(&self.mysql_type()),
+            DatabaseType::Sqlite => sqlite_sql_type(&self.sqlite_type()),
+            DatabaseType::Mongo => return Err(mongo_unsupported()),
+        };
+        Ok(sql_type)
+    }
+
+    /// Returns the `bsonType` of the field in the `$jsonSchema` validator of a MongoDB collection.
+    /// A `references` field holds the `ObjectId` of the referenced document.
+    pub fn bson_type(&self) -> &'static str {
+        match &self.kind {
+            ColumnKind::String | ColumnKind::Text | ColumnKind::Time | ColumnKind::Uuid => "string",
+            ColumnKind::Integer => "int",
+            ColumnKind::BigInt => "long",
+            ColumnKind::Float | ColumnKind::Decimal => "double",
+            ColumnKind::Boolean => "bool",
+            ColumnKind::Date | ColumnKind::DateTime => "date",
+            ColumnKind::Json => "object",
+            ColumnKind::Binary => "binData",
+            ColumnKind::References(_) => "objectId",
+        }
+    }
+
+    /// Returns the Rust type used for the column in the generated struct.
+    pub fn rust_type(&self, database_type: &DatabaseType) -> String {
+        if *database_type == DatabaseType::Mongo {
+            let rust_type = bson_rust_type(self.bson_type());
+            return if self.nullable {
+                format!("Option<{}>", rust_type)
+            } else {
+                rust_type.to_string()
+            };
+        }
+
+        let rust_type = match &self.kind {
+            ColumnKind::String | ColumnKind::Text => "String",
+            ColumnKind::Integer | ColumnKind::References(_) => "i32",
+            ColumnKind::BigInt => "i64",
+            ColumnKind::Float => "f64",
+            // The type sqlx decodes NUMERIC into, SQLite stores it as a REAL
+            ColumnKind::Decimal if *database_type == DatabaseType::Sqlite => "f64",
+            ColumnKind::Decimal => "sqlx::types::Decimal",
+            ColumnKind::Boolean => "bool",
+            ColumnKind::Date => "NaiveDate",
+            ColumnKind::DateTime => "NaiveDateTime",
+            ColumnKind::Time => "NaiveTime",
+            ColumnKind::Uuid if *database_type == DatabaseType::Postgres => "uuid::Uuid",
+            ColumnKind::Uuid => "String",
+            ColumnKind::Json => "serde_json::Value",
+            ColumnKind::Binary => "Vec<u8>",
+        };
+
+        if self.nullable {
+            format!("Option<{}>", rust_type)
+        } else {
+            rust_type.to_string()
+        }
+    }
+
+    /// Returns the column definition used inside `CREATE TABLE`, e.g. `title TEXT NOT NULL`.
+    pub fn column_definition(&self, database_type: &DatabaseType) -> Result<String, io::Error> {
+        let mut definition = format!("{} {}", self.name, self.sql_type(database_type)?);
+        definition.push_str(if self.nullable { " NULL" } else { " NOT NULL" });
+        if self.unique {
+            definition.push_str(" UNIQUE");
+        }
+        Ok(definition)
+    }
+
+    fn postgres_type(&self) -> PostgresTypes {
+        match &self.kind {
+            ColumnKind::String => PostgresTypes::VarChar,
+            ColumnKind::Text => PostgresTypes::Text,
+            ColumnKind::Integer | ColumnKind::References(_) => PostgresTypes::Integer,
+            ColumnKind::BigInt => PostgresTypes::BigInt,
+            ColumnKind::Float => PostgresTypes::DoublePrecision,
+            ColumnKind::Decimal => PostgresTypes::Numeric,
+            ColumnKind::Boolean => PostgresTypes::Boolean,
+            ColumnKind::Date => PostgresTypes::Date,
+            ColumnKind::DateTime => PostgresTypes::Timestamp,
+            ColumnKind::Time => PostgresTypes::Time,
+            ColumnKind::Uuid => PostgresTypes::Uuid,
+            ColumnKind::Json => PostgresTypes::JsonB,
+            ColumnKind::Binary => PostgresTypes::ByteA,
+        }
+    }
+
+    fn mysql_type(&self) -> MySqlTypes {
+        match &self.kind {
+            ColumnKind::String => MySqlTypes::VarChar,
+            ColumnKind::Text => MySqlTypes::Text,
+            ColumnKind::Integer | ColumnKind::References(_) => MySqlTypes::Int,
+            ColumnKind::BigInt => MySqlTypes::BigInt,
+            ColumnKind::Float => MySqlTypes::Double,
+            ColumnKind::Decimal => MySqlTypes::Decimal,
+            ColumnKind::Boolean => MySqlTypes::Boolean,
+            ColumnKind::Date => MySqlTypes::Date,
+            ColumnKind::DateTime => MySqlTypes::DateTime,
+            ColumnKind::Time => MySqlTypes::Time,
+            ColumnKind::Uuid => MySqlTypes::Char,
+            ColumnKind::Json => MySqlTypes::Json,
+            ColumnKind::Binary => MySqlTypes::Blob,
+        }
+    }
+
+    fn sqlite_type(&self) -> SqliteTypes {
+        match &self.kind {
+            ColumnKind::String | ColumnKind::Text | ColumnKind::Uuid | ColumnKind::Json => {
+                SqliteTypes::Text
+            }
+            ColumnKind::Integer | ColumnKind::BigInt | ColumnKind::References(_) => {
+                SqliteTypes::Integer
+            }
+            ColumnKind::Float => SqliteTypes::Real,
+            ColumnKind::Decimal => SqliteTypes::Numeric,
+            ColumnKind::Boolean => SqliteTypes::Boolean,
+            ColumnKind::Date => SqliteTypes::Date,
+            ColumnKind::DateTime => SqliteTypes::DateTime,
+            ColumnKind::Time => SqliteTypes::Time,
+            ColumnKind::Binary => SqliteTypes::Blob,
+        }
+    }
+}
+
+/// ## Name: parse_column_specs
+/// ### Description: Parses every column spec given on the command line
+/// #### Parameters:
+/// - specs: [`&[String]`] - the column specs, e.g. `["title:text", "author_id:references:users"]`
+/// - Returns: [`Result<Vec<ColumnSpec>, std::io::Error>`]
+pub fn parse_column_specs(specs: &[String]) -> Result<Vec<ColumnSpec>, io::Error> {
+    let columns = specs
+        .iter()
+        .map(|spec| ColumnSpec::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, column) in columns.iter().enumerate() {
+        if column.name == "id" || columns[..index].iter().any(|other| other.name == column.name) {
+            return Err(invalid_input(format!(
+                "Column '{}' is defined more than once (the id column is added automatically)",
+                column.name
+            )));
+        }
+    }
+
+    Ok(columns)
+}
+
+/// ## Name: table_name_from_migration_name
+/// ### Description: Returns the table a `create_<table>` migration creates
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::table_name_from_migration_name;
+///
+/// assert_eq!(table_name_from_migration_name("create_posts"), "posts");
+/// assert_eq!(table_name_from_migration_name("posts"), "posts");
+/// ```
+pub fn table_name_from_migration_name(migration_name: &str) -> String {
+    migration_name
+        .strip_prefix("create_")
+        .filter(|table_name| !table_name.is_empty())
+        .unwrap_or(migration_name)
+        .to_string()
+}
+
+/// ## Name: build_migration_from_columns
+/// ### Description: Builds the `up.sql` contents and the Rust struct of a table without prompting
+/// An `id` primary key is added in front of the given columns, and `references` columns get a foreign key.
+/// #### Parameters:
+/// - table_name: [`&str`] - the name of the table to create
+/// - columns: [`&[ColumnSpec]`] - the columns of the table
+/// - database_type: [`&DatabaseType`] - the database the SQL is written for
+/// - Returns: [`Result<MigrationAndStruct, std::io::Error>`]
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::{build_migration_from_columns, ColumnSpec};
+/// use rustyroad::database::DatabaseType;
+///
+/// let columns = vec![ColumnSpec::parse("title:text").unwrap()];
+/// let migration = build_migration_from_columns("posts", &columns, &DatabaseType::Sqlite).unwrap();
+/// assert!(migration.up_sql_contents.contains("title TEXT NOT NULL"));
+/// ```
+pub fn build_migration_from_columns(
+    table_name: &str,
+    columns: &[ColumnSpec],
+    database_type: &DatabaseType,
+) -> Result<MigrationAndStruct, io::Error> {
+    let primary_key = match database_type {
+        DatabaseType::Postgres => "id SERIAL PRIMARY KEY",
+        DatabaseType::Mysql => "id INT AUTO_INCREMENT PRIMARY KEY",
+        DatabaseType::Sqlite => "id INTEGER PRIMARY KEY AUTOINCREMENT",
+        DatabaseType::Mongo => return Err(mongo_unsupported()),
+    };
+
+    let mut definitions = vec![primary_key.to_string()];
+    let mut struct_fields = String::from("    pub id: i32,\n");
+
+    for column in columns {
+        definitions.push(column.column_definition(database_type)?);
+        struct_fields.push_str(&format!(
+            "    pub {}: {},\n",
+            column.name.to_lowercase(),
+            column.rust_type(database_type)
+        ));
+    }
+
+    // Foreign keys go after the columns so the same statement works on every database
+    for column in columns {
+        if let ColumnKind::References(referenced_table) = &column.kind {
+            definitions.push(format!(
+                "FOREIGN KEY ({}) REFERENCES {} (id)",
+                column.name, referenced_table
+            ));
+        }
+    }
+
+    let up_sql_contents = format!(
+        "CREATE TABLE {} (\n    {}\n);\n",
+        table_name,
+        definitions.join(",\n    ")
+    );
+
+    // Capitalize the first letter, the same way `column_loop` names its structs
+    let mut struct_name = table_name.to_string();
+    if let Some(first) = struct_name.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+
+    let rust_struct_contents = format!("pub struct {} {{\n{}}}\n", struct_name, struct_fields);
+
+    Ok(MigrationAndStruct {
+        up_sql_contents,
+        rust_struct_contents,
+    })
+}
+
+/// ## Name: join_table_name
+/// ### Description: Returns the join table of a many-to-many association, the two tables in alphabetical order
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::join_table_name;
+///
+/// assert_eq!(join_table_name("tags", "posts"), "posts_tags");
+/// ```
+pub fn join_table_name(table: &str, other_table: &str) -> String {
+    let mut tables = [table, other_table];
+    tables.sort();
+    tables.join("_")
+}
+
+/// ## Name: build_join_table_migration
+/// ### Description: Builds the `up.sql` contents of the join table of two tables
+/// The join table has a `<singular table>_id` column referencing each table, and the two columns
+/// together are its primary key so a pair of rows is only linked once.
+/// #### Parameters:
+/// - tables: [`[&str; 2]`] - the tables the association links, e.g. `["posts", "tags"]`
+/// - database_type: [`&DatabaseType`] - the database the SQL is written for
+/// - Returns: [`Result<String, std::io::Error>`] - an `Unsupported` error for MongoDB
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::build_join_table_migration;
+/// use rustyroad::database::DatabaseType;
+///
+/// let up_sql = build_join_table_migration(["tags", "posts"], &DatabaseType::Sqlite).unwrap();
+/// assert!(up_sql.starts_with("CREATE TABLE posts_tags (\n    post_id INTEGER NOT NULL,\n    tag_id INTEGER NOT NULL,\n"));
+/// assert!(up_sql.contains("PRIMARY KEY (post_id, tag_id)"));
+/// ```
+pub fn build_join_table_migration(tables: [&str; 2], database_type: &DatabaseType) -> Result<String, io::Error> {
+    if *database_type == DatabaseType::Mongo {
+        return Err(mongo_unsupported());
+    }
+    let mut tables = tables;
+    tables.sort();
+
+    let columns: Vec<ColumnSpec> = tables
+        .iter()
+        .map(|table| ColumnSpec {
+            name: format!("{}_id", singularize(table)),
+            kind: ColumnKind::References(table.to_string()),
+            nullable: false,
+            unique: false,
+        })
+        .collect();
+
+    let mut definitions = Vec::new();
+    for column in &columns {
+        definitions.push(column.column_definition(database_type)?);
+    }
+    definitions.push(format!("PRIMARY KEY ({}, {})", columns[0].name, columns[1].name));
+    for (column, table) in columns.iter().zip(tables) {
+        definitions.push(format!("FOREIGN KEY ({}) REFERENCES {} (id)", column.name, table));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {} (\n    {}\n);\n",
+        join_table_name(tables[0], tables[1]),
+        definitions.join(",\n    ")
+    ))
+}
+
+/// ## Name: bson_rust_type
+/// ### Description: Returns the Rust type that serializes to a `bsonType` of a `$jsonSchema` validator
+/// Unknown types, e.g. `array`, are read as a [`mongodb::bson::Bson`] value.
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::bson_rust_type;
+///
+/// assert_eq!(bson_rust_type("objectId"), "mongodb::bson::oid::ObjectId");
+/// assert_eq!(bson_rust_type("long"), "i64");
+/// ```
+pub fn bson_rust_type(bson_type: &str) -> &'static str {
+    match bson_type {
+        "string" => "String",
+        "int" => "i32",
+        "long" => "i64",
+        "double" => "f64",
+        "decimal" => "mongodb::bson::Decimal128",
+        "bool" => "bool",
+        "date" => "mongodb::bson::DateTime",
+        "objectId" => "mongodb::bson::oid::ObjectId",
+        "object" => "mongodb::bson::Document",
+        "binData" => "mongodb::bson::Binary",
+        "array" => "Vec<mongodb::bson::Bson>",
+        _ => "mongodb::bson::Bson",
+    }
+}
+
+/// Column modifiers that can't be mistaken for the table of a `references` column.
+fn is_modifier(part: &str) -> bool {
+    matches!(part.to_lowercase().as_str(), "null" | "unique")
+}
+
+fn postgres_sql_type(postgres_type: &PostgresTypes) -> String {
+    match postgres_type {
+        PostgresTypes::VarChar => "VARCHAR(255)".to_string(),
+        PostgresTypes::DoublePrecision => "DOUBLE PRECISION".to_string(),
+        other => format!("{:?}", other).to_uppercase(),
+    }
+}
+
+fn mysql_sql_type(mysql_type: &MySqlTypes) -> String {
+    match mysql_type {
+        MySqlTypes::VarChar => "VARCHAR(255)".to_string(),
+        MySqlTypes::Char => "CHAR(36)".to_string(),
+        MySqlTypes::Decimal => "DECIMAL(10, 2)".to_string(),
+        other => format!("{:?}", other).to_uppercase(),
+    }
+}
+
+fn sqlite_sql_type(sqlite_type: &SqliteTypes) -> String {
+    format!("{:?}", sqlite_type).to_uppercase()
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn mongo_unsupported() -> io::Error {
+    io::Error::new(
+        ErrorKind::Unsupported,
+        "MongoDB collections have no SQL types, see build_collection_migration",
+    )
+}
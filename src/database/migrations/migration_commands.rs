@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use crate::database::migrations::{
+    apply_migration, connect_to_project_database, dump_project_schema,
+    ensure_schema_migrations_table, get_applied_migrations, get_migrations,
+    run_all_migrations_with_connection, CustomMigrationError, Migration, MigrationDirection,
+    MigrationLock, MIGRATIONS_DIR,
+};
+use crate::database::{clear_seed_records, DatabaseConnection};
+
+/// ## Name: MigrationState
+/// ### Description: Whether a migration has been applied to the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    /// The migration is recorded in `schema_migrations`.
+    Applied,
+    /// The migration exists on disk but hasn't been applied yet.
+    Pending,
+    /// The migration is recorded in `schema_migrations` but its directory is gone.
+    Missing,
+}
+
+impl std::fmt::Display for MigrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Missing => "missing",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+/// ## Name: MigrationStatus
+/// ### Description: One line of the `migration status` report
+/// #### Fields:
+/// - version: [`String`] - the timestamp of the migration
+/// - name: [`String`] - the name of the migration
+/// - state: [`MigrationState`] - applied, pending or missing
+/// - applied_at: [`Option<String>`] - when the migration was applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: String,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<String>,
+}
+
+/// ## Name: get_migration_status
+/// ### Description: Compares the migrations directory with `schema_migrations`
+/// Every migration directory is listed, along with applied migrations whose directory no longer exists.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+/// - Returns: [`Result<Vec<MigrationStatus>, CustomMigrationError>`] - sorted by version
+pub async fn get_migration_status(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+) -> Result<Vec<MigrationStatus>, CustomMigrationError> {
+    let migrations = get_migrations(migrations_dir_path).map_err(CustomMigrationError::IoError)?;
+
+    ensure_schema_migrations_table(connection).await?;
+    let mut applied: HashMap<String, _> = get_applied_migrations(connection)
+        .await?
+        .into_iter()
+        .map(|applied| (applied.version.clone(), applied))
+        .collect();
+
+    let mut statuses: Vec<MigrationStatus> = migrations
+        .into_iter()
+        .map(|migration| match applied.remove(&migration.version) {
+            Some(applied) => MigrationStatus {
+                version: migration.version,
+                name: migration.name,
+                state: MigrationState::Applied,
+                applied_at: Some(applied.applied_at),
+            },
+            None => MigrationStatus {
+                version: migration.version,
+                name: migration.name,
+                state: MigrationState::Pending,
+                applied_at: None,
+            },
+        })
+        .collect();
+
+    statuses.extend(applied.into_values().map(|applied| MigrationStatus {
+        version: applied.version,
+        name: applied.name,
+        state: MigrationState::Missing,
+        applied_at: Some(applied.applied_at),
+    }));
+    statuses.sort_by(|a, b| a.version.cmp(&b.version));
+
+    Ok(statuses)
+}
+
+/// ## Name: print_migration_status
+/// ### Description: Prints the applied/pending state of every migration of the current project
+/// ### Example:
+/// ```rust,no_run
+/// use rustyroad::database::migrations::print_migration_status;
+///
+/// # async fn example() {
+/// print_migration_status().await.expect("Error reading migration status");
+/// # }
+/// ```
+pub async fn print_migration_status() -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    let statuses = get_migration_status(&connection, MIGRATIONS_DIR).await?;
+
+    if statuses.is_empty() {
+        println!("No migrations found in {}", MIGRATIONS_DIR);
+        return Ok(());
+    }
+
+    let name_width = statuses
+        .iter()
+        .map(|status| status.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+
+    println!(
+        "{:<8}  {:<14}  {:<name_width$}  Applied At",
+        "Status", "Version", "Name"
+    );
+    for status in &statuses {
+        println!(
+            "{:<8}  {:<14}  {:<name_width$}  {}",
+            status.state.to_string(),
+            status.version,
+            status.name,
+            status.applied_at.as_deref().unwrap_or("-")
+        );
+    }
+
+    let pending = statuses
+        .iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .count();
+    println!("\n{} migration(s), {} pending", statuses.len(), pending);
+
+    Ok(())
+}
+
+/// ## Name: redo_migration
+/// ### Description: Rolls back and re-applies a migration of the current project
+/// Without a name the most recently applied migration is redone.
+/// #### Parameters:
+/// - migration_name: [`Option<&str>`] - the name of the migration to redo
+/// - lock_timeout: [`Duration`] - how long to wait for another process that holds the migration lock
+pub async fn redo_migration(
+    migration_name: Option<&str>,
+    lock_timeout: Duration,
+) -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    let lock = MigrationLock::acquire(&connection, lock_timeout).await?;
+    let result = redo_migration_with_connection(&connection, MIGRATIONS_DIR, migration_name).await;
+    if result.is_ok() {
+        dump_project_schema(&connection).await;
+    }
+    if let Err(why) = lock.release().await {
+        eprintln!("Couldn't release the migration lock: {}", why);
+    }
+    result
+}
+
+/// ## Name: redo_migration_with_connection
+/// ### Description: Rolls back and re-applies a migration using an existing connection
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+/// - migration_name: [`Option<&str>`] - the name (or directory name) of the migration to redo
+pub async fn redo_migration_with_connection(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+    migration_name: Option<&str>,
+) -> Result<(), CustomMigrationError> {
+    let migration = find_applied_migration(connection, migrations_dir_path, migration_name).await?;
+
+    println!("Redoing migration: {}", migration.directory_name());
+    apply_migration(connection, &migration, MigrationDirection::Down).await?;
+    apply_migration(connection, &migration, MigrationDirection::Up).await?;
+    println!("'{}' migration redone successfully!", migration.name);
+
+    Ok(())
+}
+
+/// ## Name: reset_migrations
+/// ### Description: Rolls back every applied migration of the current project, newest first, then applies them all again
+/// #### Parameters:
+/// - lock_timeout: [`Duration`] - how long to wait for another process that holds the migration lock
+pub async fn reset_migrations(lock_timeout: Duration) -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    let lock = MigrationLock::acquire(&connection, lock_timeout).await?;
+    let result = reset_migrations_with_connection(&connection, MIGRATIONS_DIR).await;
+    if result.is_ok() {
+        dump_project_schema(&connection).await;
+    }
+    if let Err(why) = lock.release().await {
+        eprintln!("Couldn't release the migration lock: {}", why);
+    }
+    result
+}
+
+/// ## Name: reset_migrations_with_connection
+/// ### Description: Rolls back and re-applies every migration using an existing connection
+/// Rolling back removes the seeded rows too, so the seeds are forgotten and `rustyroad db seed` runs them again.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+pub async fn reset_migrations_with_connection(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+) -> Result<(), CustomMigrationError> {
+    println!("Rolling back all migrations...");
+    run_all_migrations_with_connection(connection, migrations_dir_path, MigrationDirection::Down)
+        .await?;
+    clear_seed_records(connection).await?;
+    println!("Re-applying all migrations...");
+    run_all_migrations_with_connection(connection, migrations_dir_path, MigrationDirection::Up)
+        .await
+}
+
+/// Finds the latest applied migration, optionally restricted to a given name.
+async fn find_applied_migration(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+    migration_name: Option<&str>,
+) -> Result<Migration, CustomMigrationError> {
+    let migrations = get_migrations(migrations_dir_path).map_err(CustomMigrationError::IoError)?;
+
+    ensure_schema_migrations_table(connection).await?;
+    let applied: Vec<String> = get_applied_migrations(connection)
+        .await?
+        .into_iter()
+        .map(|applied| applied.version)
+        .collect();
+
+    migrations
+        .into_iter()
+        .rev()
+        .filter(|migration| applied.contains(&migration.version))
+        .find(|migration| match migration_name {
+            Some(name) => migration.name == name || migration.directory_name() == name,
+            None => true,
+        })
+        .ok_or_else(|| {
+            let message = match migration_name {
+                Some(name) => format!("No applied migration named '{}'", name),
+                None => "No applied migrations to redo".to_string(),
+            };
+            CustomMigrationError::IoError(io::Error::new(io::ErrorKind::NotFound, message))
+        })
+}
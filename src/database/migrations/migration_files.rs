@@ -0,0 +1,264 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::MigrationDirection;
+
+/// The directory, relative to the project root, that holds every migration.
+pub const MIGRATIONS_DIR: &str = "./config/database/migrations";
+
+/// A line that opts a migration file out of the transaction it would otherwise run in.
+/// Needed for statements that can't run inside a transaction, e.g. `CREATE INDEX CONCURRENTLY`.
+pub const NO_TRANSACTION_MARKER: &str = "-- rustyroad:no-transaction";
+
+/// ## Name: Migration
+/// ### Description: A migration directory on disk, split into its version and name
+/// Migration directories are named `{timestamp}-{name}` (or `{timestamp}_{name}` for the
+/// initial migration written by `rustyroad new`). The timestamp is used as the version and
+/// decides the order in which migrations are applied.
+/// #### Fields:
+/// - version: [`String`] - the timestamp prefix of the directory
+/// - name: [`String`] - the rest of the directory name
+/// - path: [`PathBuf`] - the path to the migration directory
+/// - embedded: [`Option<EmbeddedSql>`] - the SQL compiled into the binary, read instead of the files when set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: String,
+    pub name: String,
+    pub path: PathBuf,
+    pub embedded: Option<EmbeddedSql>,
+}
+
+/// ## Name: EmbeddedSql
+/// ### Description: The `up.sql` and `down.sql` of a migration embedded with [`embed_migrations!`](crate::embed_migrations)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedSql {
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// ## Name: from_dir
+    /// ### Description: Builds a migration from the path of a migration directory
+    /// Returns `None` when the directory name doesn't start with a timestamp.
+    /// ### Example:
+    /// ```rust
+    /// use rustyroad::database::migrations::Migration;
+    ///
+    /// let migration = Migration::from_dir("config/database/migrations/20240101120000-create_posts").unwrap();
+    /// assert_eq!(migration.version, "20240101120000");
+    /// assert_eq!(migration.name, "create_posts");
+    /// ```
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Option<Migration> {
+        let path = path.as_ref();
+        let dir_name = path.file_name()?.to_str()?;
+        let split_at = dir_name.find(|c: char| !c.is_ascii_digit())?;
+        let (version, rest) = dir_name.split_at(split_at);
+        let name = rest.strip_prefix(['-', '_'])?;
+
+        if version.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(Migration {
+            version: version.to_string(),
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            embedded: None,
+        })
+    }
+
+    /// Returns the directory name, e.g. `20240101120000-create_posts`.
+    pub fn directory_name(&self) -> String {
+        self.path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{}-{}", self.version, self.name))
+    }
+
+    /// Reads the `up.sql` or `down.sql` file for the given direction.
+    /// The migrations of a MongoDB project are `up.json` and `down.json` files instead, read when there is no SQL file.
+    pub fn sql(&self, direction: MigrationDirection) -> Result<String, io::Error> {
+        if let Some(embedded) = &self.embedded {
+            return Ok(match direction {
+                MigrationDirection::Up => embedded.up.to_string(),
+                MigrationDirection::Down => embedded.down.to_string(),
+            });
+        }
+
+        let (file_name, json_file_name) = match direction {
+            MigrationDirection::Up => ("up.sql", "up.json"),
+            MigrationDirection::Down => ("down.sql", "down.json"),
+        };
+        match fs::read_to_string(self.path.join(file_name)) {
+            Err(why) if why.kind() == io::ErrorKind::NotFound && self.path.join(json_file_name).is_file() => {
+                fs::read_to_string(self.path.join(json_file_name))
+            }
+            result => result,
+        }
+    }
+
+    /// ## Name: checksum
+    /// ### Description: Returns the SHA-256 of the `up.sql` (or `up.json`) file as a hex string
+    /// The checksum is stored in `schema_migrations` when the migration is applied.
+    pub fn checksum(&self) -> Result<String, io::Error> {
+        let up_sql = self.sql(MigrationDirection::Up)?;
+        Ok(checksum_sql(&up_sql))
+    }
+}
+
+/// ## Name: runs_in_transaction
+/// ### Description: Checks whether a migration file should be wrapped in a transaction
+/// Every migration runs in a transaction unless one of its lines is [`NO_TRANSACTION_MARKER`].
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::runs_in_transaction;
+///
+/// assert!(runs_in_transaction("CREATE TABLE posts (id INTEGER PRIMARY KEY);"));
+/// assert!(!runs_in_transaction(
+///     "-- rustyroad:no-transaction\nCREATE INDEX CONCURRENTLY posts_title ON posts (title);"
+/// ));
+/// ```
+pub fn runs_in_transaction(sql: &str) -> bool {
+    !sql.lines().any(|line| line.trim() == NO_TRANSACTION_MARKER)
+}
+
+/// ## Name: split_sql_statements
+/// ### Description: Splits a migration file into its statements, to run them one at a time
+/// Statements end at a `;` outside of quotes, comments, Postgres `$tag$` strings and the
+/// `BEGIN ... END` body of a trigger. Comments before a statement and the ones on their own
+/// are left out.
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::split_sql_statements;
+///
+/// let sql = "-- rustyroad:no-transaction
+/// CREATE INDEX CONCURRENTLY index_posts_on_title ON posts (title);
+/// INSERT INTO posts (title) VALUES ('a; b');";
+/// assert_eq!(
+///     split_sql_statements(sql),
+///     [
+///         "CREATE INDEX CONCURRENTLY index_posts_on_title ON posts (title);",
+///         "INSERT INTO posts (title) VALUES ('a; b');",
+///     ]
+/// );
+/// ```
+pub fn split_sql_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    // Where the current statement starts, once it has more than whitespace and comments
+    let mut start: Option<usize> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &sql[i..];
+        let end = if rest.starts_with("--") {
+            rest.find('\n').map_or(sql.len(), |end| i + end)
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            comment.find("*/").map_or(sql.len(), |end| i + end + 4)
+        } else {
+            let end = match bytes[i] {
+                quote @ (b'\'' | b'"' | b'`') => rest[1..]
+                    .find(quote as char)
+                    .map_or(sql.len(), |end| i + end + 2),
+                b'$' => match dollar_quote_tag(rest) {
+                    Some(tag) => rest[tag.len()..]
+                        .find(tag)
+                        .map_or(sql.len(), |end| i + end + 2 * tag.len()),
+                    None => i + 1,
+                },
+                b';' => {
+                    if let Some(statement_start) = start {
+                        if !is_open_trigger(&sql[statement_start..i]) {
+                            statements.push(&sql[statement_start..=i]);
+                            start = None;
+                        }
+                    }
+                    i + 1
+                }
+                byte if byte.is_ascii_whitespace() => i + 1,
+                _ => i + rest.chars().next().map_or(1, char::len_utf8),
+            };
+            if start.is_none() && !bytes[i].is_ascii_whitespace() && bytes[i] != b';' {
+                start = Some(i);
+            }
+            end
+        };
+        i = end;
+    }
+
+    if let Some(statement_start) = start {
+        statements.push(sql[statement_start..].trim_end());
+    }
+    statements
+}
+
+/// The `$tag$` that opens a Postgres dollar-quoted string, e.g. `$$` or `$body$`.
+fn dollar_quote_tag(sql: &str) -> Option<&str> {
+    let end = sql[1..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))? + 1;
+    (sql[end..].starts_with('$') && !sql[1..end].starts_with(|c: char| c.is_ascii_digit()))
+        .then_some(&sql[..=end])
+}
+
+/// Whether the statement is a `CREATE TRIGGER` whose `BEGIN ... END` body isn't closed yet.
+fn is_open_trigger(statement: &str) -> bool {
+    let words: Vec<String> = statement
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let is_trigger = words.first().is_some_and(|word| word == "CREATE")
+        && words.iter().take(4).any(|word| word == "TRIGGER");
+    if !is_trigger {
+        return false;
+    }
+    let opened = words.iter().filter(|word| *word == "BEGIN" || *word == "CASE").count();
+    let closed = words.iter().filter(|word| *word == "END").count();
+    opened > closed
+}
+
+/// Returns the SHA-256 of a block of SQL as a hex string.
+/// Line endings are normalized first, so a checkout with CRLF line endings has the same checksum.
+pub fn checksum_sql(sql: &str) -> String {
+    format!("{:x}", Sha256::digest(sql.replace("\r\n", "\n").as_bytes()))
+}
+
+/// ## Name: get_migrations
+/// ### Description: Lists the migrations in a migrations directory, oldest first
+/// Entries that aren't directories or don't start with a timestamp are skipped.
+/// #### Parameters:
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+/// - Returns: [`Result<Vec<Migration>, std::io::Error>`]
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::{get_migrations, MIGRATIONS_DIR};
+///
+/// let migrations = get_migrations(MIGRATIONS_DIR).unwrap_or_default();
+/// for migration in migrations {
+///     println!("{} {}", migration.version, migration.name);
+/// }
+/// ```
+pub fn get_migrations(migrations_dir_path: &str) -> Result<Vec<Migration>, io::Error> {
+    let mut migrations = Vec::new();
+
+    for entry in fs::read_dir(migrations_dir_path)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        match Migration::from_dir(&path) {
+            Some(migration) => migrations.push(migration),
+            None => eprintln!(
+                "Skipping {:?}: migration directories must be named <timestamp>-<name>",
+                path
+            ),
+        }
+    }
+
+    migrations.sort_by(|a, b| a.version.cmp(&b.version).then(a.name.cmp(&b.name)));
+
+    Ok(migrations)
+}
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use mongodb::bson::{doc, Document};
+use mongodb::error::{ErrorKind, WriteFailure};
+use sha2::{Digest, Sha256};
+use sqlx::pool::PoolConnection;
+use sqlx::{MySql, Postgres, SqlitePool};
+
+use crate::database::DatabaseConnection;
+
+use super::{CustomMigrationError, MigrationError};
+
+/// How long `migration all` waits for another process to finish migrating by default.
+pub const DEFAULT_MIGRATION_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The table (or collection) that holds the migration lock on SQLite and MongoDB, which have no advisory locks.
+pub const MIGRATION_LOCK_TABLE: &str = "schema_migrations_lock";
+
+/// The prefix of the MySQL `GET_LOCK` lock name and of the text hashed into the Postgres
+/// advisory lock key, which are followed by the database name.
+const LOCK_NAME_PREFIX: &str = "rustyroad_migrations";
+
+/// MySQL rejects `GET_LOCK` names longer than 64 characters.
+const MYSQL_LOCK_NAME_MAX_LEN: usize = 64;
+
+/// How often a busy Postgres, SQLite or MongoDB lock is tried again.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// ## Name: MigrationLock
+/// ### Description: A database-level lock held while migrations run
+/// Only one process can hold it per database, so pods that start at the same time run
+/// their migrations one after the other instead of racing each other.
+/// - Postgres: a session-level advisory lock (`pg_try_advisory_lock`) keyed on the database name
+/// - MySQL: a named lock (`GET_LOCK`) that includes the database name, see [`mysql_lock_name`]
+/// - SQLite: a row in `schema_migrations_lock`
+/// - MongoDB: a document in `schema_migrations_lock`, whose `_id` can only be inserted once
+///
+/// Postgres and MySQL locks belong to a connection, so the lock keeps its own connection
+/// out of the pool until [`MigrationLock::release`] is called.
+pub struct MigrationLock {
+    holder: LockHolder,
+}
+
+enum LockHolder {
+    Pg { connection: Box<PoolConnection<Postgres>>, key: i64 },
+    MySql { connection: PoolConnection<MySql>, name: String },
+    Sqlite { pool: Arc<SqlitePool>, owner: String },
+    Mongo { database: mongodb::Database, owner: String },
+}
+
+impl MigrationLock {
+    /// ## Name: acquire
+    /// ### Description: Takes the migration lock, waiting up to `timeout` for another process to release it
+    /// #### Parameters:
+    /// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+    /// - timeout: [`Duration`] - how long to wait for the lock
+    /// - Returns: [`Result<MigrationLock, CustomMigrationError>`] - a [`CustomMigrationError::Locked`] error when the wait timed out
+    pub async fn acquire(
+        connection: &DatabaseConnection,
+        timeout: Duration,
+    ) -> Result<MigrationLock, CustomMigrationError> {
+        let started = Instant::now();
+        let mut announced = false;
+
+        let holder = match connection {
+            DatabaseConnection::Pg(pool) => {
+                let mut lock_connection = pool.acquire().await.map_err(MigrationError::from)?;
+                // Key the lock on the database name, like the MySQL lock name
+                let key: i32 = sqlx::query_scalar("SELECT hashtext($1 || current_database())")
+                    .bind(format!("{}.", LOCK_NAME_PREFIX))
+                    .fetch_one(&mut *lock_connection)
+                    .await
+                    .map_err(MigrationError::from)?;
+                let key = i64::from(key);
+                loop {
+                    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+                        .bind(key)
+                        .fetch_one(&mut *lock_connection)
+                        .await
+                        .map_err(MigrationError::from)?;
+                    if locked {
+                        break LockHolder::Pg {
+                            connection: Box::new(lock_connection),
+                            key,
+                        };
+                    }
+                    wait_for_lock(started, timeout, &mut announced, None).await?;
+                }
+            }
+            DatabaseConnection::MySql(pool) => {
+                let mut lock_connection = pool.acquire().await.map_err(MigrationError::from)?;
+                // GET_LOCK names are server-wide, so each database gets its own lock
+                let database: Option<String> = sqlx::query_scalar("SELECT DATABASE()")
+                    .fetch_one(&mut *lock_connection)
+                    .await
+                    .map_err(MigrationError::from)?;
+                let name = mysql_lock_name(database.as_deref().unwrap_or_default());
+                // GET_LOCK waits by itself and returns 0 once the timeout passed
+                let locked: Option<i64> = sqlx::query_scalar("SELECT GET_LOCK(?, ?)")
+                    .bind(&name)
+                    .bind(timeout.as_secs_f64())
+                    .fetch_one(&mut *lock_connection)
+                    .await
+                    .map_err(MigrationError::from)?;
+                if locked != Some(1) {
+                    return Err(lock_timeout_error(timeout, None));
+                }
+                LockHolder::MySql {
+                    connection: lock_connection,
+                    name,
+                }
+            }
+            DatabaseConnection::Sqlite(pool) => {
+                sqlx::query(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    locked_by TEXT NOT NULL,
+    locked_at TEXT NOT NULL
+);",
+                    MIGRATION_LOCK_TABLE
+                ))
+                .execute(pool.as_ref())
+                .await
+                .map_err(MigrationError::from)?;
+
+                let owner = lock_owner();
+                loop {
+                    let inserted = sqlx::query(&format!(
+                        "INSERT OR IGNORE INTO {} (id, locked_by, locked_at) VALUES (1, ?, ?)",
+                        MIGRATION_LOCK_TABLE
+                    ))
+                    .bind(&owner)
+                    .bind(Local::now().to_rfc3339())
+                    .execute(pool.as_ref())
+                    .await
+                    .map_err(MigrationError::from)?;
+                    if inserted.rows_affected() == 1 {
+                        break LockHolder::Sqlite {
+                            pool: pool.clone(),
+                            owner,
+                        };
+                    }
+
+                    let current: Option<(String, String)> = sqlx::query_as(&format!(
+                        "SELECT locked_by, locked_at FROM {} WHERE id = 1",
+                        MIGRATION_LOCK_TABLE
+                    ))
+                    .fetch_optional(pool.as_ref())
+                    .await
+                    .map_err(MigrationError::from)?;
+                    let current = current.map(|(locked_by, locked_at)| {
+                        format!("{} since {}", locked_by, locked_at)
+                    });
+                    wait_for_lock(started, timeout, &mut announced, current).await?;
+                }
+            }
+            DatabaseConnection::Mongo(database) => {
+                let locks = database.collection::<Document>(MIGRATION_LOCK_TABLE);
+                let owner = lock_owner();
+                loop {
+                    let inserted = locks
+                        .insert_one(doc! {
+                            "_id": 1,
+                            "locked_by": &owner,
+                            "locked_at": Local::now().to_rfc3339(),
+                        })
+                        .await;
+                    match inserted {
+                        Ok(_) => {
+                            break LockHolder::Mongo {
+                                database: database.clone(),
+                                owner,
+                            }
+                        }
+                        Err(why) if is_duplicate_key(&why) => {}
+                        Err(why) => return Err(MigrationError::from(why).into()),
+                    }
+
+                    let current = locks
+                        .find_one(doc! { "_id": 1 })
+                        .await
+                        .map_err(MigrationError::from)?
+                        .map(|lock| {
+                            format!(
+                                "{} since {}",
+                                lock.get_str("locked_by").unwrap_or_default(),
+                                lock.get_str("locked_at").unwrap_or_default()
+                            )
+                        });
+                    wait_for_lock(started, timeout, &mut announced, current).await?;
+                }
+            }
+        };
+
+        Ok(MigrationLock { holder })
+    }
+
+    /// ## Name: release
+    /// ### Description: Releases the migration lock so the next process can migrate
+    pub async fn release(self) -> Result<(), MigrationError> {
+        match self.holder {
+            LockHolder::Pg {
+                mut connection,
+                key,
+            } => {
+                sqlx::query("SELECT pg_advisory_unlock($1)")
+                    .bind(key)
+                    .execute(&mut **connection)
+                    .await?;
+            }
+            LockHolder::MySql {
+                mut connection,
+                name,
+            } => {
+                sqlx::query("SELECT RELEASE_LOCK(?)")
+                    .bind(name)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+            LockHolder::Sqlite { pool, owner } => {
+                sqlx::query(&format!(
+                    "DELETE FROM {} WHERE id = 1 AND locked_by = ?",
+                    MIGRATION_LOCK_TABLE
+                ))
+                .bind(owner)
+                .execute(pool.as_ref())
+                .await?;
+            }
+            LockHolder::Mongo { database, owner } => {
+                database
+                    .collection::<Document>(MIGRATION_LOCK_TABLE)
+                    .delete_one(doc! { "_id": 1, "locked_by": owner })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ## Name: mysql_lock_name
+/// ### Description: The name of the MySQL `GET_LOCK` lock of a database
+/// Names that would be longer than MySQL allows use a hash of the database name instead.
+/// #### Parameters:
+/// - database: [`&str`] - the name of the database
+/// - Returns: [`String`] - the lock name, for example `rustyroad_migrations.blog`
+pub fn mysql_lock_name(database: &str) -> String {
+    let name = format!("{}.{}", LOCK_NAME_PREFIX, database);
+    if name.len() <= MYSQL_LOCK_NAME_MAX_LEN {
+        return name;
+    }
+    let hash = format!("{:x}", Sha256::digest(database.as_bytes()));
+    let mut name = format!("{}.{}", LOCK_NAME_PREFIX, hash);
+    name.truncate(MYSQL_LOCK_NAME_MAX_LEN);
+    name
+}
+
+/// Whether an insert failed because the document already exists (error code 11000).
+fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == 11000
+    )
+}
+
+/// Identifies this process in the SQLite lock row or MongoDB lock document; `HOSTNAME` is the pod name on Kubernetes.
+fn lock_owner() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+    format!(
+        "pid {} on {} ({})",
+        std::process::id(),
+        host,
+        Local::now().timestamp_nanos_opt().unwrap_or_default()
+    )
+}
+
+/// Sleeps before the next attempt, or fails once the timeout has passed.
+async fn wait_for_lock(
+    started: Instant,
+    timeout: Duration,
+    announced: &mut bool,
+    holder: Option<String>,
+) -> Result<(), CustomMigrationError> {
+    if started.elapsed() >= timeout {
+        return Err(lock_timeout_error(timeout, holder));
+    }
+    if !*announced {
+        eprintln!("Another process is running migrations, waiting for it to finish...");
+        *announced = true;
+    }
+    tokio::time::sleep(LOCK_RETRY_INTERVAL.min(timeout.saturating_sub(started.elapsed()))).await;
+    Ok(())
+}
+
+fn lock_timeout_error(timeout: Duration, holder: Option<String>) -> CustomMigrationError {
+    let mut message = format!(
+        "Another process is running migrations and still holds the migration lock after {}s",
+        timeout.as_secs()
+    );
+    if let Some(holder) = holder {
+        message.push_str(&format!(
+            " (held by {}). If that process is gone, delete it from {}",
+            holder, MIGRATION_LOCK_TABLE
+        ));
+    }
+    message.push_str(". Try again later or raise --lock-timeout");
+    CustomMigrationError::Locked(message)
+}
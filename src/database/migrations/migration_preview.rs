@@ -0,0 +1,274 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::database::migrations::{
+    get_applied_migrations_if_tracked, get_migrations, runs_in_transaction,
+    supports_transactional_ddl, CustomMigrationError, Migration, MigrationDirection,
+    MigrationError, ROLLED_BACK_MIGRATIONS_TABLE, SCHEMA_MIGRATIONS_TABLE,
+};
+use crate::database::{parse_mongo_commands, Database, DatabaseConnection, DatabaseType};
+use chrono::Local;
+
+use super::schema_migrations::{
+    create_rolled_back_migrations_table_sql, create_schema_migrations_table_sql, record_rollback_sql,
+};
+
+/// ## Name: MigrationMode
+/// ### Description: Whether a migration command runs its SQL or only shows it
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MigrationMode {
+    /// Run the migrations against the database.
+    #[default]
+    Execute,
+    /// Resolve the migrations that would run and print their SQL, or write it to `output`.
+    DryRun { output: Option<PathBuf> },
+}
+
+impl MigrationMode {
+    /// Builds the mode from the `--dry-run` and `--output` flags; an output file implies a dry run.
+    pub fn from_flags(dry_run: bool, output: Option<&String>) -> MigrationMode {
+        match output {
+            Some(output) => MigrationMode::DryRun {
+                output: Some(PathBuf::from(output)),
+            },
+            None if dry_run => MigrationMode::DryRun { output: None },
+            None => MigrationMode::Execute,
+        }
+    }
+}
+
+/// ## Name: plan_migrations
+/// ### Description: Resolves the migrations a run would apply, in the order it would apply them
+/// Migrating up returns the pending migrations, oldest first. Migrating down returns the
+/// applied ones, newest first. `schema_migrations` isn't created if it doesn't exist yet.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+/// - direction: [`MigrationDirection`] - the direction to run the migrations in
+/// - Returns: [`Result<Vec<Migration>, CustomMigrationError>`]
+pub async fn plan_migrations(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+    direction: MigrationDirection,
+) -> Result<Vec<Migration>, CustomMigrationError> {
+    let migrations = get_migrations(migrations_dir_path).map_err(CustomMigrationError::IoError)?;
+    select_migrations(connection, migrations, direction).await
+}
+
+/// ## Name: select_migrations
+/// ### Description: Like [`plan_migrations`], for migrations that don't come from the migrations directory
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations: [`Vec<Migration>`] - every migration, oldest first
+/// - direction: [`MigrationDirection`] - the direction to run the migrations in
+/// - Returns: [`Result<Vec<Migration>, CustomMigrationError>`]
+pub async fn select_migrations(
+    connection: &DatabaseConnection,
+    mut migrations: Vec<Migration>,
+    direction: MigrationDirection,
+) -> Result<Vec<Migration>, CustomMigrationError> {
+    let applied: HashSet<String> = get_applied_migrations_if_tracked(connection)
+        .await?
+        .into_iter()
+        .map(|applied| applied.version)
+        .collect();
+
+    // If we're migrating down, reverse the order
+    if direction == MigrationDirection::Down {
+        migrations.reverse();
+    }
+
+    Ok(migrations
+        .into_iter()
+        .filter(|migration| match direction {
+            MigrationDirection::Up => !applied.contains(&migration.version),
+            MigrationDirection::Down => applied.contains(&migration.version),
+        })
+        .collect())
+}
+
+/// ## Name: migration_script
+/// ### Description: Renders the SQL that running the migrations would execute, as one script
+/// Each migration is wrapped in the transaction the runner would open for it, and followed by
+/// the statement that records it in (or removes it from) `schema_migrations`, so the script
+/// can be applied by hand and leaves the database as `rustyroad migration` would.
+/// On MongoDB the script is a `mongosh` script of `db.runCommand` calls instead.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool, used for the SQL dialect
+/// - target: [`&str`] - a description of the database the script is meant for
+/// - migrations: [`&[Migration]`] - the migrations, in the order they run
+/// - direction: [`MigrationDirection`] - whether to render `up.sql` or `down.sql`
+/// - Returns: [`Result<String, MigrationError>`]
+pub fn migration_script(
+    connection: &DatabaseConnection,
+    target: &str,
+    migrations: &[Migration],
+    direction: MigrationDirection,
+) -> Result<String, MigrationError> {
+    let direction_name = match direction {
+        MigrationDirection::Up => "up",
+        MigrationDirection::Down => "down",
+    };
+    if let DatabaseConnection::Mongo(_) = connection {
+        return mongo_migration_script(target, migrations, direction, direction_name);
+    }
+
+    let mut script = format!(
+        "-- Generated by rustyroad for the {}\n-- {} migration(s), direction: {}\n\n{}\n{}\n",
+        target,
+        migrations.len(),
+        direction_name,
+        create_schema_migrations_table_sql(connection),
+        create_rolled_back_migrations_table_sql(connection)
+    );
+
+    for migration in migrations {
+        let sql = migration.sql(direction)?;
+        let in_transaction = runs_in_transaction(&sql) && supports_transactional_ddl(connection);
+        let bookkeeping = match direction {
+            MigrationDirection::Up => format!(
+                "INSERT INTO {} (version, name, checksum) VALUES ('{}', '{}', '{}');",
+                SCHEMA_MIGRATIONS_TABLE,
+                quote(&migration.version),
+                quote(&migration.name),
+                quote(&migration.checksum()?)
+            ),
+            MigrationDirection::Down => format!(
+                "DELETE FROM {} WHERE version = '{}';\n{};",
+                SCHEMA_MIGRATIONS_TABLE,
+                quote(&migration.version),
+                // The statement a run executes, with the version inlined
+                record_rollback_sql(connection).replacen(
+                    if let DatabaseConnection::Pg(_) = connection { "$1" } else { "?" },
+                    &format!("'{}'", quote(&migration.version)),
+                    1
+                )
+            ),
+        };
+
+        script.push_str(&format!(
+            "\n-- {} ({})\n",
+            migration.directory_name(),
+            direction_name
+        ));
+        if in_transaction {
+            script.push_str("BEGIN;\n");
+        }
+        script.push_str(sql.trim_end());
+        script.push('\n');
+        script.push_str(&bookkeeping);
+        script.push('\n');
+        if in_transaction {
+            script.push_str("COMMIT;\n");
+        }
+    }
+
+    Ok(script)
+}
+
+fn mongo_migration_script(
+    target: &str,
+    migrations: &[Migration],
+    direction: MigrationDirection,
+    direction_name: &str,
+) -> Result<String, MigrationError> {
+    let mut script = format!(
+        "// Generated by rustyroad for the {}\n// {} migration(s), direction: {}\n",
+        target,
+        migrations.len(),
+        direction_name
+    );
+
+    for migration in migrations {
+        script.push_str(&format!(
+            "\n// {} ({})\n",
+            migration.directory_name(),
+            direction_name
+        ));
+        for command in parse_mongo_commands(&migration.sql(direction)?)? {
+            let command = serde_json::to_string(&command)
+                .map_err(|why| io::Error::new(ErrorKind::InvalidData, why))?;
+            script.push_str(&format!("db.runCommand({});\n", command));
+        }
+        let bookkeeping = match direction {
+            MigrationDirection::Up => format!(
+                "db.{}.insertOne({});",
+                SCHEMA_MIGRATIONS_TABLE,
+                serde_json::json!({
+                    "version": migration.version,
+                    "name": migration.name,
+                    "checksum": migration.checksum()?,
+                    "applied_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                })
+            ),
+            MigrationDirection::Down => format!(
+                "db.{}.deleteOne({});\ndb.{}.updateOne({}, {}, {{\"upsert\": true}});",
+                SCHEMA_MIGRATIONS_TABLE,
+                serde_json::json!({ "version": migration.version }),
+                ROLLED_BACK_MIGRATIONS_TABLE,
+                serde_json::json!({ "_id": migration.version }),
+                serde_json::json!({
+                    "$setOnInsert": {
+                        "rolled_back_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    }
+                })
+            ),
+        };
+        script.push_str(&bookkeeping);
+        script.push('\n');
+    }
+
+    Ok(script)
+}
+
+fn quote(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+/// ## Name: describe_database
+/// ### Description: Names the database a script targets, e.g. `postgres database 'app' on localhost:5432`
+pub fn describe_database(database: &Database) -> String {
+    match database.database_type {
+        DatabaseType::Sqlite => format!("sqlite database '{}'", database.name),
+        _ => format!(
+            "{} database '{}' on {}:{}",
+            database.database_type, database.name, database.host, database.port
+        ),
+    }
+}
+
+/// ## Name: preview_migrations
+/// ### Description: Prints the script of a dry run, or writes it to the output file
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations: [`&[Migration]`] - the migrations, in the order they would run
+/// - direction: [`MigrationDirection`] - the direction the migrations would run in
+/// - output: [`Option<&Path>`] - where to write the script, printed when `None`
+pub fn preview_migrations(
+    connection: &DatabaseConnection,
+    migrations: &[Migration],
+    direction: MigrationDirection,
+    output: Option<&Path>,
+) -> Result<(), CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let script = migration_script(connection, &describe_database(&database), migrations, direction)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, script).map_err(CustomMigrationError::IoError)?;
+            println!(
+                "Wrote {} migration(s) to {}, nothing was run",
+                migrations.len(),
+                path.display()
+            );
+        }
+        None => {
+            print!("{}", script);
+            println!("\n-- Dry run, nothing was run");
+        }
+    }
+
+    Ok(())
+}
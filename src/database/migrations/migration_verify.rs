@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::database::migrations::{
+    connect_to_project_database, get_applied_migrations_if_tracked, get_migrations,
+    get_rolled_back_versions, CustomMigrationError, Migration, MIGRATIONS_DIR,
+};
+use crate::database::DatabaseConnection;
+
+/// ## Name: MigrationDrift
+/// ### Description: A difference between the migrations directory and `schema_migrations`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationDrift {
+    /// The `up.sql` of an applied migration changed since it was applied.
+    Edited { version: String, name: String },
+    /// An applied migration's directory no longer exists.
+    Missing { version: String, name: String },
+    /// A pending migration that was never applied is older than the latest applied migration, so
+    /// it would run out of order. Migrations that were rolled back can be applied again.
+    OutOfOrder { version: String, name: String },
+}
+
+impl fmt::Display for MigrationDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationDrift::Edited { version, name } => write!(
+                f,
+                "{}-{} was edited after it was applied",
+                version, name
+            ),
+            MigrationDrift::Missing { version, name } => write!(
+                f,
+                "{}-{} was applied but its directory is missing",
+                version, name
+            ),
+            MigrationDrift::OutOfOrder { version, name } => write!(
+                f,
+                "{}-{} was never applied but is older than the latest applied migration",
+                version, name
+            ),
+        }
+    }
+}
+
+/// ## Name: verify_migrations
+/// ### Description: Compares the migrations directory with what `schema_migrations` recorded
+/// Applied migrations are checked against the checksum of their `up.sql`, and pending
+/// migrations that were never applied, or rolled back, against the version of the latest
+/// applied migration.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations_dir_path: [`&str`] - the path to the migrations directory
+/// - Returns: [`Result<Vec<MigrationDrift>, CustomMigrationError>`] - empty when nothing drifted, sorted by version
+pub async fn verify_migrations(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+) -> Result<Vec<MigrationDrift>, CustomMigrationError> {
+    let migrations = get_migrations(migrations_dir_path).map_err(CustomMigrationError::IoError)?;
+    find_drift(connection, migrations).await
+}
+
+/// ## Name: find_drift
+/// ### Description: Like [`verify_migrations`], for migrations that don't come from the migrations directory
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migrations: [`Vec<Migration>`] - every migration, oldest first
+/// - Returns: [`Result<Vec<MigrationDrift>, CustomMigrationError>`]
+pub async fn find_drift(
+    connection: &DatabaseConnection,
+    migrations: Vec<Migration>,
+) -> Result<Vec<MigrationDrift>, CustomMigrationError> {
+    let mut applied: HashMap<String, _> = get_applied_migrations_if_tracked(connection)
+        .await?
+        .into_iter()
+        .map(|applied| (applied.version.clone(), applied))
+        .collect();
+    let latest_applied = applied.keys().max().cloned();
+    let rolled_back: HashSet<String> = get_rolled_back_versions(connection)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut drift = Vec::new();
+    for migration in migrations {
+        match applied.remove(&migration.version) {
+            Some(recorded) => {
+                let checksum = migration.checksum().map_err(CustomMigrationError::IoError)?;
+                if checksum != recorded.checksum {
+                    drift.push(MigrationDrift::Edited {
+                        version: migration.version,
+                        name: migration.name,
+                    });
+                }
+            }
+            None => {
+                if !rolled_back.contains(&migration.version)
+                    && latest_applied
+                        .as_ref()
+                        .is_some_and(|latest| migration.version < *latest)
+                {
+                    drift.push(MigrationDrift::OutOfOrder {
+                        version: migration.version,
+                        name: migration.name,
+                    });
+                }
+            }
+        }
+    }
+
+    drift.extend(applied.into_values().map(|recorded| MigrationDrift::Missing {
+        version: recorded.version,
+        name: recorded.name,
+    }));
+    drift.sort_by(|a, b| drift_version(a).cmp(drift_version(b)));
+
+    Ok(drift)
+}
+
+fn drift_version(drift: &MigrationDrift) -> &str {
+    match drift {
+        MigrationDrift::Edited { version, .. }
+        | MigrationDrift::Missing { version, .. }
+        | MigrationDrift::OutOfOrder { version, .. } => version,
+    }
+}
+
+/// ## Name: verify_project_migrations
+/// ### Description: Checks the migrations of the current project and prints what drifted
+/// #### Returns:
+/// - [`Result<(), CustomMigrationError>`] - a [`CustomMigrationError::Drift`] error when anything drifted
+/// ### Example:
+/// ```rust,no_run
+/// use rustyroad::database::migrations::verify_project_migrations;
+///
+/// # async fn example() {
+/// if let Err(why) = verify_project_migrations().await {
+///     eprintln!("{}", why);
+/// }
+/// # }
+/// ```
+pub async fn verify_project_migrations() -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    let drift = verify_migrations(&connection, MIGRATIONS_DIR).await?;
+
+    if !drift.is_empty() {
+        return Err(CustomMigrationError::Drift(drift));
+    }
+
+    println!("Every applied migration matches its file in {}", MIGRATIONS_DIR);
+    Ok(())
+}
@@ -2,15 +2,16 @@ use chrono::Local;
 use sqlx::Executor;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::fs::{create_dir_all, DirEntry};
-use std::io::{Read, stdin};
+use std::fs::create_dir_all;
+use std::io::stdin;
 use std::path::Path;
+use std::time::Duration;
 use std::{
     fmt, fs,
     io::{self, ErrorKind},
 };
 
-use crate::database::{Database, DatabaseConnection, DatabaseType};
+use crate::database::{run_mongo_commands, Database, DatabaseConnection, DatabaseError, DatabaseType};
 use rustyline::{DefaultEditor};
 use serde::de::StdError;
 use serde_derive::Deserialize;
@@ -21,6 +22,21 @@ use crate::writers::write_to_file;
 use crate::Project;
 
 use super::column_loop::column_loop;
+use super::column_spec::{
+    build_join_table_migration, build_migration_from_columns, join_table_name, parse_column_specs,
+    table_name_from_migration_name, ColumnSpec,
+};
+use super::mongo_collection::build_collection_migration;
+use super::migration_files::{runs_in_transaction, split_sql_statements, Migration, MIGRATIONS_DIR};
+use super::migration_lock::MigrationLock;
+use super::migration_preview::{preview_migrations, MigrationMode};
+use super::migration_verify::MigrationDrift;
+use super::schema_dump::dump_project_schema;
+use super::schema_migrations::{
+    ensure_schema_migrations_table, get_applied_migrations, get_applied_migrations_if_tracked,
+    record_migration,
+    record_migration_sql, record_rollback_sql, remove_migration_record, remove_migration_record_sql,
+};
 
 const CONSTRAINTS: &[&str] = &["PRIMARY KEY", "NOT NULL", "FOREIGN KEY"];
 
@@ -114,6 +130,16 @@ pub async fn create_migration(name: &str) -> Result<String, io::Error> {
     }
  
     let table_name = name.to_string();
+
+    let database = Database::get_database_from_rustyroad_toml()?;
+    // Collections get their fields from column specs, the prompts below ask for SQL types
+    if database.database_type == DatabaseType::Mongo {
+        println!(
+            "Creating the {} collection without fields, add them with: rustyroad migration generate {} title:string",
+            table_name, name
+        );
+        return create_collection_migration(&name, &table_name, &[]);
+    }
     
     println!("Enter the number of columns");
     let mut num_input = String::new();
@@ -125,7 +151,6 @@ pub async fn create_migration(name: &str) -> Result<String, io::Error> {
 
     let mut contents = String::new();
 
-    let database = Database::get_database_from_rustyroad_toml().unwrap();
     let row_with_database_type = match database.database_type {
         DatabaseType::Postgres => "postgres::PgRow",
         DatabaseType::Sqlite => "sqlite::SqliteRow",
@@ -161,7 +186,7 @@ use sqlx::{{{}, FromRow, Row}};", row_with_database_type);
 
     let folder_name = format!(
         "config/database/migrations/{}-{}",
-        Local::now().format("%Y%m%d%H%M%S"),
+        next_migration_version(),
         name
     );
 
@@ -170,9 +195,178 @@ use sqlx::{{{}, FromRow, Row}};", row_with_database_type);
     Ok(contents)
 }
 
+/// ## Name: create_migration_from_columns
+/// ### Description: Creates a migration from column specs without prompting
+/// Used by `rustyroad generate migration create_posts title:text author_id:references:users`.
+/// A `create_<table>` migration creates `<table>`, any other name is used as the table name.
+/// #### Parameters:
+/// - name: [`&str`] - the name of the migration
+/// - column_specs: [`&[String]`] - the columns as `name:type[:modifier...]`, see [`ColumnSpec`](super::ColumnSpec)
+/// - Returns: [`Result<String, std::io::Error>`] - the Rust struct for the new table
+///
+/// ### Example:
+/// ```rust,no_run
+/// use rustyroad::database::migrations::create_migration_from_columns;
+///
+/// let columns = vec!["title:text".to_string(), "published:boolean".to_string()];
+/// create_migration_from_columns("create_posts", &columns).unwrap();
+/// ```
+pub fn create_migration_from_columns(name: &str, column_specs: &[String]) -> Result<String, io::Error> {
+    if fs::read_to_string("rustyroad.toml").is_err() {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            "Error reading the rustyroad.toml, please see the documentation for more information.",
+        ));
+    }
+
+    let columns = parse_column_specs(column_specs)?;
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let table_name = table_name_from_migration_name(name);
+    if database.database_type == DatabaseType::Mongo {
+        return create_collection_migration(name, &table_name, &columns);
+    }
+    let migration = build_migration_from_columns(&table_name, &columns, &database.database_type)?;
+
+    create_dir_all(MIGRATIONS_DIR)?;
+
+    let down_sql_contents = format!("DROP TABLE {};\n", table_name);
+
+    let folder_name = format!(
+        "{}/{}-{}",
+        MIGRATIONS_DIR,
+        next_migration_version(),
+        name
+    );
+
+    create_migration_files(&folder_name, &migration.up_sql_contents, &down_sql_contents)?;
+    println!("Created migration {}", folder_name);
+
+    Ok(migration.rust_struct_contents)
+}
+
+/// ## Name: create_join_table_migration
+/// ### Description: Creates the migration of the join table of a many-to-many association
+/// Nothing is written when a `create_<join table>` migration already exists, e.g. because the
+/// association was declared on the model of the other table first. Both tables have to be
+/// created by earlier migrations, since the join table references them.
+/// #### Parameters:
+/// - table: [`&str`] - the table of the model declaring the association, e.g. `posts`
+/// - other_table: [`&str`] - the table it's associated with, e.g. `tags`
+/// - Returns: [`Result<Option<String>, std::io::Error>`] - the folder of the new migration
+pub fn create_join_table_migration(table: &str, other_table: &str) -> Result<Option<String>, io::Error> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+    let up_sql_contents = build_join_table_migration([table, other_table], &database.database_type)?;
+    let join_table = join_table_name(table, other_table);
+    let name = format!("create_{}", join_table);
+    if find_migration_named(&name).is_some() {
+        return Ok(None);
+    }
+    if find_migration_named(&format!("create_{}", other_table)).is_none() {
+        println!(
+            "No migration creates {}, create it before running the migration of {}",
+            other_table, join_table
+        );
+    }
+
+    create_dir_all(MIGRATIONS_DIR)?;
+    let folder_name = format!(
+        "{}/{}-{}",
+        MIGRATIONS_DIR,
+        next_migration_version(),
+        name
+    );
+    let down_sql_contents = format!("DROP TABLE {};\n", join_table);
+    create_migration_files(&folder_name, &up_sql_contents, &down_sql_contents)?;
+    println!("Created migration {}", folder_name);
+
+    Ok(Some(folder_name))
+}
+
+/// ## Name: find_migration_named
+/// ### Description: Returns the folder of the latest migration with exactly this name
+/// Unlike [`find_migration_dir`], `create_posts` doesn't match `create_posts_tags`, and nothing is asked.
+/// #### Parameters:
+/// - name: [`&str`] - the name of the migration without its timestamp, e.g. `create_posts`
+/// - Returns: [`Option<String>`]
+pub fn find_migration_named(name: &str) -> Option<String> {
+    let mut folders: Vec<String> = fs::read_dir(MIGRATIONS_DIR)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|folder| folder.split_once('-').map(|(_, folder_name)| folder_name) == Some(name))
+        .collect();
+    folders.sort();
+    folders.pop().map(|folder| format!("{}/{}", MIGRATIONS_DIR, folder))
+}
+
+/// Writes the `up.json` and `down.json` of a MongoDB migration that creates a collection.
+fn create_collection_migration(
+    name: &str,
+    collection_name: &str,
+    columns: &[ColumnSpec],
+) -> Result<String, io::Error> {
+    let migration = build_collection_migration(collection_name, columns)?;
+
+    create_dir_all(MIGRATIONS_DIR)?;
+
+    let folder_name = format!(
+        "{}/{}-{}",
+        MIGRATIONS_DIR,
+        next_migration_version(),
+        name
+    );
+
+    create_mongo_migration_files(&folder_name, &migration.up_json, &migration.down_json)?;
+    println!("Created migration {}", folder_name);
+
+    Ok(migration.rust_struct_contents)
+}
+
+/// ## Name: next_migration_version
+/// ### Description: Returns the version of a new migration, the current timestamp
+/// When a migration of this second or a later one already exists, e.g. `generate model` wrote the
+/// migration of the table and the one of its join table at once, the version after the latest is
+/// used instead, so no two migrations share a version and they keep the order they were created in.
+/// - Returns: [`String`]
+pub fn next_migration_version() -> String {
+    let now = Local::now().format("%Y%m%d%H%M%S").to_string();
+    let latest = fs::read_dir(MIGRATIONS_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|folder| folder.split_once('-').and_then(|(version, _)| version.parse::<u64>().ok()))
+        .max();
+    match (latest, now.parse::<u64>()) {
+        (Some(latest), Ok(now)) if latest >= now => (latest + 1).to_string(),
+        _ => now,
+    }
+}
+
 pub fn create_migration_files(folder_name: &str, up_sql_contents: &str, down_sql_contents: &str) -> Result<(), io::Error> {
-    let up_file = format!("{}/up.sql", folder_name);
-    let down_file = format!("{}/down.sql", folder_name);
+    write_migration_files(folder_name, "sql", up_sql_contents, down_sql_contents)
+}
+
+/// ## Name: create_mongo_migration_files
+/// ### Description: Creates a migration directory with the `up.json` and `down.json` commands of a MongoDB migration
+/// #### Parameters:
+/// - folder_name: [`&str`] - the migration directory, named `<timestamp>-<name>`
+/// - up_json: [`&str`] - the JSON array of commands that applies the migration
+/// - down_json: [`&str`] - the JSON array of commands that rolls it back
+/// - Returns: [`Result<(), std::io::Error>`]
+pub fn create_mongo_migration_files(folder_name: &str, up_json: &str, down_json: &str) -> Result<(), io::Error> {
+    write_migration_files(folder_name, "json", up_json, down_json)
+}
+
+fn write_migration_files(
+    folder_name: &str,
+    extension: &str,
+    up_contents: &str,
+    down_contents: &str,
+) -> Result<(), io::Error> {
+    let up_file = format!("{}/up.{}", folder_name, extension);
+    let down_file = format!("{}/down.{}", folder_name, extension);
 
     match std::fs::create_dir(&folder_name) {
         Ok(_) => {}
@@ -188,8 +382,8 @@ pub fn create_migration_files(folder_name: &str, up_sql_contents: &str, down_sql
     create_file(&up_file)?;
     create_file(&down_file)?;
 
-    write_to_file(&up_file, up_sql_contents.as_bytes())?;
-    write_to_file(&down_file, down_sql_contents.as_bytes())?;
+    write_to_file(&up_file, up_contents.as_bytes())?;
+    write_to_file(&down_file, down_contents.as_bytes())?;
 
     Ok(())
 }
@@ -288,6 +482,12 @@ pub enum CustomMigrationError {
     MigrationError(MigrationError),
     IoError(std::io::Error),
     RunError(Box<dyn StdError + Send + Sync>),
+    /// Applied migrations no longer match the migrations directory.
+    Drift(Vec<MigrationDrift>),
+    /// Another process held the migration lock for longer than the lock timeout.
+    Locked(String),
+    /// The database couldn't be configured or reached.
+    Database(DatabaseError),
 }
 
 impl Display for CustomMigrationError {
@@ -296,6 +496,15 @@ impl Display for CustomMigrationError {
             Self::MigrationError(err) => Display::fmt(err, f),
             Self::RunError(err) => Display::fmt(err, f),
             Self::IoError(err) => Display::fmt(err, f),
+            Self::Locked(message) => write!(f, "{}", message),
+            Self::Database(err) => Display::fmt(err, f),
+            Self::Drift(drift) => {
+                writeln!(f, "The migrations directory doesn't match the database:")?;
+                for change in drift {
+                    writeln!(f, "  - {}", change)?;
+                }
+                write!(f, "Restore the migration files, or pass --force to run anyway")
+            }
         }
     }
 }
@@ -308,6 +517,12 @@ impl From<MigrationError> for CustomMigrationError {
     }
 }
 
+impl From<DatabaseError> for CustomMigrationError {
+    fn from(err: DatabaseError) -> Self {
+        Self::Database(err)
+    }
+}
+
 impl From<Box<dyn StdError + Send + Sync>> for CustomMigrationError {
     fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
         Self::RunError(err)
@@ -318,6 +533,9 @@ impl From<Box<dyn StdError + Send + Sync>> for CustomMigrationError {
 /// ### Description: Runs a migration
 /// #### Parameters:
 /// - name: [`&str`] - the name of the migration
+/// - direction: [`MigrationDirection`] - whether to apply or roll back the migration
+/// - mode: [`MigrationMode`] - whether to run the migration or only print (or write) its SQL
+/// - lock_timeout: [`Duration`] - how long to wait for another process that holds the migration lock
 /// - Returns: [`Result<(), std::io::Error>`]
 /// - if the migration was created successfully: [`Ok(())`]
 /// - if there was an error creating the migration: [`Err(std::io::Error)`]
@@ -325,70 +543,181 @@ impl From<Box<dyn StdError + Send + Sync>> for CustomMigrationError {
 /// ### Example:
 /// ```rust
 /// use rustyroad::database::MigrationDirection;
-/// use rustyroad::database::migrations::run_migration;
+/// use rustyroad::database::migrations::{run_migration, MigrationMode, DEFAULT_MIGRATION_LOCK_TIMEOUT};
 ///
-/// run_migration("create_users_table".to_string(), MigrationDirection::Up);
+/// run_migration(
+///     "create_users_table".to_string(),
+///     MigrationDirection::Up,
+///     MigrationMode::Execute,
+///     DEFAULT_MIGRATION_LOCK_TIMEOUT,
+/// );
 /// ```
 pub async fn run_migration(
     migration_name: String,
     direction: MigrationDirection,
+    mode: MigrationMode,
+    lock_timeout: Duration,
 ) -> Result<(), CustomMigrationError> {
     // get the database
-    let database: Database = Database::get_database_from_rustyroad_toml().expect("Couldn't parse the rustyroad.toml file. Please check the documentation for a proper implementation.");
+    let database: Database = Database::get_database_from_rustyroad_toml()?;
     match database.database_type {
         DatabaseType::Postgres => {
-            println!("Database Type: PostGres");
+            eprintln!("Database Type: PostGres");
         }
         DatabaseType::Mysql => {
-            println!("Database Type: MySql");
+            eprintln!("Database Type: MySql");
         }
         DatabaseType::Sqlite => {
-            println!("Database Type: Sqlite");
+            eprintln!("Database Type: Sqlite");
         }
         _ => {
-            println!("coming soon");
+            eprintln!("coming soon");
         }
     }
-    let migrations_dir_path = format!("./config/database/migrations");
+    let migrations_dir_path = MIGRATIONS_DIR.to_string();
     // find the folder that has the name of the migration in the migrations directory with the latest timestamp
     let migration_dir_selected =
-        find_migration_dir(migrations_dir_path.clone(), migration_name.clone())
-            .unwrap_or_else(|why| panic!("Couldn't find migration directory: {}", why.to_string()));
-    // Generate the path to the migrations directory at runtime
-    let migration_dir = &migration_dir_selected;
-    println!("Migration directory: {:?}", migration_dir);
-    // Get migration files from the specified directory
-    let mut migration_files: Vec<_> = fs::read_dir(migration_dir.clone())
-        .unwrap_or_else(|why| panic!("Couldn't read migrations directory: {}", why.to_string()))
-        .filter_map(Result::ok)
-        .collect();
-    // Sort the migration files based on their name (to apply them in order)
-    migration_files.sort_by_key(|entry| entry.file_name());
+        find_migration_dir(migrations_dir_path.clone(), migration_name.clone()).map_err(|why| {
+            MigrationError::Io(io::Error::new(
+                ErrorKind::NotFound,
+                format!("Couldn't find migration directory: {}", why),
+            ))
+        })?;
+    let migration = Migration::from_dir(&migration_dir_selected).ok_or_else(|| {
+        MigrationError::Io(io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "'{}' is not a valid migration directory, expected <timestamp>-<name>",
+                migration_dir_selected
+            ),
+        ))
+    })?;
 
     // Print the path to the migration directory and the migration name
-    println!("Migration directory path: {:?}", migration_dir.clone());
-    println!("Migration name: {:?}", &migration_name.clone());
+    eprintln!("Migration directory path: {:?}", migration.path);
+    eprintln!("Migration name: {:?}", &migration_name.clone());
 
     // create the connection pool
-    let connection = Database::create_database_connection(&database)
-        .await
-        .unwrap_or_else(|why| panic!("Couldn't create database connection: {}", why.to_string()));
+    let connection = Database::create_database_connection(&database).await?;
+
+    if let MigrationMode::DryRun { output } = mode {
+        let is_applied = get_applied_migrations_if_tracked(&connection)
+            .await?
+            .iter()
+            .any(|applied| applied.version == migration.version);
+        let migrations = match (direction, is_applied) {
+            (MigrationDirection::Up, false) | (MigrationDirection::Down, true) => vec![migration],
+            _ => Vec::new(),
+        };
+        return preview_migrations(&connection, &migrations, direction, output.as_deref());
+    }
+
+    let lock = MigrationLock::acquire(&connection, lock_timeout).await?;
+    let result = apply_migration(&connection, &migration, direction).await;
+    if let Ok(true) = result {
+        match direction {
+            MigrationDirection::Up => println!("Migration applied successfully"),
+            MigrationDirection::Down => println!("Migration rolled back successfully"),
+        }
+        dump_project_schema(&connection).await;
+    }
+    if let Err(why) = lock.release().await {
+        eprintln!("Couldn't release the migration lock: {}", why);
+    }
+    result?;
+    Ok(())
+}
+
+/// ## Name: apply_migration
+/// ### Description: Runs a single migration and keeps `schema_migrations` up to date
+/// Migrating up skips a migration that is already recorded as applied, and migrating down
+/// skips one that was never applied. Returns `true` when the migration was executed.
+///
+/// On Postgres and SQLite the SQL file and the `schema_migrations` update run in a single
+/// transaction, so a failing statement leaves nothing behind. MySQL commits DDL implicitly,
+/// MongoDB can't roll back collection changes, and files containing
+/// [`NO_TRANSACTION_MARKER`](super::NO_TRANSACTION_MARKER) opt out, so those run statement by statement and are only recorded once every statement succeeded.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migration: [`&Migration`] - the migration to run
+/// - direction: [`MigrationDirection`] - whether to run `up.sql` or `down.sql`
+/// - Returns: [`Result<bool, MigrationError>`]
+pub async fn apply_migration(
+    connection: &DatabaseConnection,
+    migration: &Migration,
+    direction: MigrationDirection,
+) -> Result<bool, MigrationError> {
+    ensure_schema_migrations_table(connection).await?;
+
+    let is_applied = get_applied_migrations(connection)
+        .await?
+        .iter()
+        .any(|applied| applied.version == migration.version);
 
-    execute_migration_with_connection(connection, migration_files, direction.clone())
+    match direction {
+        MigrationDirection::Up if is_applied => {
+            println!(
+                "Migration '{}' has already been applied, skipping",
+                migration.directory_name()
+            );
+            return Ok(false);
+        }
+        MigrationDirection::Down if !is_applied => {
+            println!(
+                "Migration '{}' has not been applied, nothing to roll back",
+                migration.directory_name()
+            );
+            return Ok(false);
+        }
+        _ => {}
+    }
+
+    let sql = migration.sql(direction)?;
+
+    if runs_in_transaction(&sql) && supports_transactional_ddl(connection) {
+        execute_migration_in_transaction(connection, migration, &sql, direction)
+            .await
+            .map_err(|why| {
+                eprintln!(
+                    "Migration '{}' failed and was rolled back: {}",
+                    migration.directory_name(),
+                    why
+                );
+                why
+            })?;
+        return Ok(true);
+    }
+
+    execute_migration_with_connection(connection, &sql)
         .await
-        .unwrap_or_else(|why|
-            println!("Couldn't execute migration: {}", why.to_string())
-        );
+        .map_err(|why| {
+            eprintln!(
+                "Migration '{}' failed outside of a transaction, the database may be partially migrated: {}",
+                migration.directory_name(),
+                why
+            );
+            why
+        })?;
 
     match direction {
-        MigrationDirection::Up => println!("Migration applied successfully"),
-        MigrationDirection::Down => println!("Migration rolled back successfully"),
+        MigrationDirection::Up => record_migration(connection, migration).await?,
+        MigrationDirection::Down => remove_migration_record(connection, &migration.version).await?,
+    }
+
+    Ok(true)
+}
+
+/// Whether the database can roll back schema changes made inside a transaction.
+pub(crate) fn supports_transactional_ddl(connection: &DatabaseConnection) -> bool {
+    match connection {
+        DatabaseConnection::Pg(_) | DatabaseConnection::Sqlite(_) => true,
+        DatabaseConnection::MySql(_) | DatabaseConnection::Mongo(_) => false,
     }
-    Ok(())
 }
 
 /// Create a new migration file
 /// # Arguments
+/// * `table_name` - The table the array column is added to
 /// * `up_sql_contents` - The contents of the up.sql file
 ///
 /// # Returns
@@ -406,11 +735,15 @@ pub async fn run_migration(
 /// created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 /// );
 /// ");
-/// let array = rustyroad::database::create_array(up_sql_contents, nullable);
+/// let array = rustyroad::database::create_array("users", up_sql_contents, nullable);
 /// assert!(array.is_ok());
 /// ```
 ///
-pub fn create_array(up_sql_contents: String, nullable: bool) -> Result<String, Box<dyn Error>> {
+pub fn create_array(
+    table_name: &str,
+    up_sql_contents: String,
+    nullable: bool,
+) -> Result<String, Box<dyn Error>> {
     // ask the user how many dimensions the array should have
     let dimensions = String::new();
     // Initialize the rustyline Editor with the default helper and in-memory history
@@ -454,14 +787,14 @@ pub fn create_array(up_sql_contents: String, nullable: bool) -> Result<String, B
     match nullable {
         true => {
             up_sql_contents.push_str(&format!(
-                "ALTER TABLE users ADD COLUMN {} {} ARRAY[{}] NULL;\n",
-                array_name, array_type, array_size
+                "ALTER TABLE {} ADD COLUMN {} {} ARRAY[{}] NULL;\n",
+                table_name, array_name, array_type, array_size
             ));
         }
         false => {
             up_sql_contents.push_str(&format!(
-                "ALTER TABLE users ADD COLUMN {} {} ARRAY[{}] NOT NULL;\n",
-                array_name, array_type, array_size
+                "ALTER TABLE {} ADD COLUMN {} {} ARRAY[{}] NOT NULL;\n",
+                table_name, array_name, array_type, array_size
             ));
         }
     }
@@ -488,12 +821,10 @@ pub fn find_migration_dir(
     migrations_dir_path: String,
     migration_name: String,
 ) -> Result<String, Box<dyn Error>> {
-    println!("Searching for migration directory: {}", migration_name);
+    eprintln!("Searching for migration directory: {}", migration_name);
     // Initialize the rustyline Editor with the default helper and in-memory history
-    let mut rl = DefaultEditor::new().unwrap_or_else(|why| {
-        panic!("Failed to create rustyline editor: {}", why.to_string());
-    });
-    println!("Migrations directory path: {:?}", migrations_dir_path.clone());
+    let mut rl = DefaultEditor::new()?;
+    eprintln!("Migrations directory path: {:?}", migrations_dir_path.clone());
     // get all the migration directories
     let mut migration_dirs = Vec::new();
     for entry in fs::read_dir(migrations_dir_path)? {
@@ -503,57 +834,60 @@ pub fn find_migration_dir(
             migration_dirs.push(path);
         }
     }
-    println!("Migration directories: {:?}", migration_dirs.clone());
+    eprintln!("Migration directories: {:?}", migration_dirs.clone());
 
     // filter the migration directories by the migration name
     let mut filtered_migration_dirs = Vec::new();
     for migration_dir in migration_dirs {
         let migration_dir_name = migration_dir
             .file_name()
-            .expect("Failed to get file name")
-            .to_str()
+            .and_then(|name| name.to_str())
             .ok_or("Failed to convert OsStr to str")?;
-        println!("Migration directory name: {}", migration_dir_name);
+        eprintln!("Migration directory name: {}", migration_dir_name);
         if migration_dir_name.contains(&migration_name) {
             filtered_migration_dirs.push(migration_dir);
-            println!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
+            eprintln!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
         }
     }
 
     // if there is only one migration directory with the given name, return it
     if filtered_migration_dirs.len() == 1 {
-        println!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
-        return Ok(filtered_migration_dirs[0].to_str().unwrap().to_string());
+        eprintln!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
+        return Ok(filtered_migration_dirs[0]
+            .to_str()
+            .ok_or("Failed to convert PathBuf to str")?
+            .to_string());
     }
 
     // if there are multiple migration directories with the given name, prompt the user to choose one
     if filtered_migration_dirs.len() > 1 {
         let mut migration_dir_names = Vec::new();
-        println!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
+        eprintln!("Filtered migration directories: {:?}", filtered_migration_dirs.clone());
         for migration_dir in &filtered_migration_dirs {
-            println!("Migration directory: {:?}", migration_dir.clone());
-            let migration_dir_name = migration_dir.file_name().unwrap().to_str().unwrap();
-            println!("Migration directory name: {}", migration_dir_name);
+            eprintln!("Migration directory: {:?}", migration_dir.clone());
+            let migration_dir_name = migration_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or("Failed to convert OsStr to str")?;
+            eprintln!("Migration directory name: {}", migration_dir_name);
             migration_dir_names.push(migration_dir_name);
         }
         let migration_dir_name = rl
             .readline_with_initial(
                 "Which migration do you want to execute? ",
                 (migration_dir_names[0], ""),
-            )
-            .unwrap_or_else(|why| panic!("Failed to read input: {}", why.to_string()));
+            )?;
 
-        print!("You chose: {}", migration_dir_name);
+        eprintln!("You chose: {}", migration_dir_name);
 
         for migration_dir in filtered_migration_dirs {
-            println!("Migration directory: {:?}", migration_dir.clone());
+            eprintln!("Migration directory: {:?}", migration_dir.clone());
             let migration_dir_name_from_list = migration_dir
                 .file_name()
-                .expect("Failed to get file name")
-                .to_str()
+                .and_then(|name| name.to_str())
                 .ok_or("Failed to convert OsStr to str")?;
             if migration_dir_name == migration_dir_name_from_list {
-                println!("Migration directory name: {}", migration_dir_name);
+                eprintln!("Migration directory name: {}", migration_dir_name);
                 return Ok(migration_dir
                     .to_str()
                     .ok_or("Failed to convert PathBuf to str")?
@@ -568,12 +902,25 @@ pub fn find_migration_dir(
     )))
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug)]
 pub enum MigrationError {
     Io(io::Error),
     Sql(sqlx::Error),
+    Mongo(mongodb::error::Error),
 }
 
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "IO error: {}", err),
+            Self::Sql(err) => write!(f, "SQL error: {}", err),
+            Self::Mongo(err) => write!(f, "MongoDB error: {}", err),
+        }
+    }
+}
+
+impl StdError for MigrationError {}
+
 impl From<io::Error> for MigrationError {
     fn from(err: io::Error) -> MigrationError {
         MigrationError::Io(err)
@@ -586,6 +933,23 @@ impl From<sqlx::Error> for MigrationError {
     }
 }
 
+impl From<mongodb::error::Error> for MigrationError {
+    fn from(err: mongodb::error::Error) -> MigrationError {
+        MigrationError::Mongo(err)
+    }
+}
+
+impl From<DatabaseError> for MigrationError {
+    fn from(err: DatabaseError) -> MigrationError {
+        match err {
+            DatabaseError::Sql(err) => MigrationError::Sql(err),
+            DatabaseError::Mongo(err) => MigrationError::Mongo(err),
+            DatabaseError::Io(err) => MigrationError::Io(err),
+            err => MigrationError::Io(err.into()),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Copy, Debug, Display)]
 pub enum MigrationDirection {
     Up,
@@ -593,46 +957,132 @@ pub enum MigrationDirection {
 }
 
 async fn execute_migration_with_connection(
-    connection: DatabaseConnection,
-    migration_files: Vec<DirEntry>,
-    direction: MigrationDirection,
+    connection: &DatabaseConnection,
+    sql: &str,
 ) -> Result<(), MigrationError> {
-    for entry in migration_files {
-        let path = entry.path();
-        // Ignore non-SQL files
-        if path.extension() != Some(std::ffi::OsStr::new("sql")) {
-            continue;
+    // One statement at a time, since statements like `CREATE INDEX CONCURRENTLY` can't share a
+    // query with others, on one connection, for the ones that change the session
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            let mut connection = pool.acquire().await?;
+            execute_statements::<sqlx::Postgres>(&mut connection, sql).await?;
         }
-        let mut file = fs::File::open(&path).expect("Failed to open migration file");
-        let mut sql = String::new();
-        file.read_to_string(&mut sql)
-            .expect("Failed to read migration file");
-
-        // Skip the migration if it is a down.sql file and we're migrating up, or vice versa
-        let is_down_file = path.file_stem() == Some(std::ffi::OsStr::new("down"));
-        if (direction == MigrationDirection::Up && is_down_file)
-            || (direction == MigrationDirection::Down && !is_down_file)
-        {
-            continue;
+        DatabaseConnection::MySql(pool) => {
+            let mut connection = pool.acquire().await?;
+            execute_statements::<sqlx::MySql>(&mut connection, sql).await?;
         }
-        match connection.clone() {
-            DatabaseConnection::Pg(connection) => {
-                println!("Executing query: {:?}", sql.clone().as_str());
-                //unwrap the arc
-                let rows_affected = connection.execute(sql.as_str()).await?;
-                println!("{:?} rows affected", rows_affected);
+        // A table rebuild turns the foreign keys off and runs its own BEGIN and COMMIT, so the
+        // connection is put back as it was when a statement fails
+        DatabaseConnection::Sqlite(pool) => {
+            let mut connection = pool.acquire().await?;
+            let foreign_keys: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut *connection)
+                .await?;
+            if let Err(err) = execute_statements::<sqlx::Sqlite>(&mut connection, sql).await {
+                // Fails when the statement that failed wasn't in a transaction
+                let _ = (&mut *connection).execute("ROLLBACK;").await;
+                let pragma = if foreign_keys {
+                    "PRAGMA foreign_keys = ON;"
+                } else {
+                    "PRAGMA foreign_keys = OFF;"
+                };
+                (&mut *connection).execute(pragma).await?;
+                return Err(err.into());
             }
-            DatabaseConnection::MySql(connection) => {
-                println!("Executing query: {:?}", sql);
-                let rows_affected = connection.execute(sql.as_str()).await?;
-                println!("{:?} rows affected", rows_affected);
+        }
+        // The migration files of a MongoDB project hold JSON commands instead of SQL
+        DatabaseConnection::Mongo(database) => {
+            run_mongo_commands(database, sql).await?;
+        }
+    };
+    Ok(())
+}
+
+/// Runs the statements of a migration file one at a time, stopping at the first that fails.
+async fn execute_statements<DB>(connection: &mut DB::Connection, sql: &str) -> Result<(), sqlx::Error>
+where
+    DB: sqlx::Database,
+    DB::QueryResult: std::fmt::Debug,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    for statement in split_sql_statements(sql) {
+        eprintln!("Executing query: {:?}", statement);
+        let rows_affected = connection.execute(statement).await?;
+        eprintln!("{:?} rows affected", rows_affected);
+    }
+    Ok(())
+}
+
+/// Runs the SQL of a migration and updates `schema_migrations`, and when rolling back
+/// `schema_migrations_rolled_back`, in one transaction.
+/// The transaction is rolled back when it's dropped without being committed.
+async fn execute_migration_in_transaction(
+    connection: &DatabaseConnection,
+    migration: &Migration,
+    sql: &str,
+    direction: MigrationDirection,
+) -> Result<(), MigrationError> {
+    let checksum = migration.checksum()?;
+    let record_sql = match direction {
+        MigrationDirection::Up => record_migration_sql(connection),
+        MigrationDirection::Down => remove_migration_record_sql(connection),
+    };
+
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            let mut transaction = pool.begin().await?;
+            eprintln!("Executing query in a transaction: {:?}", sql);
+            let rows_affected = (&mut *transaction).execute(sql).await?;
+            eprintln!("{:?} rows affected", rows_affected);
+            let query = match direction {
+                MigrationDirection::Up => sqlx::query(record_sql)
+                    .bind(&migration.version)
+                    .bind(&migration.name)
+                    .bind(&checksum),
+                MigrationDirection::Down => sqlx::query(record_sql).bind(&migration.version),
+            };
+            query.execute(&mut *transaction).await?;
+            if direction == MigrationDirection::Down {
+                sqlx::query(record_rollback_sql(connection))
+                    .bind(&migration.version)
+                    .execute(&mut *transaction)
+                    .await?;
             }
-            DatabaseConnection::Sqlite(connection) => {
-                println!("Executing query: {:?}", sql);
-                let rows_affected = connection.execute(sql.as_str()).await?;
-                println!("{:?} rows affected", rows_affected);
+            transaction.commit().await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            let mut transaction = pool.begin().await?;
+            eprintln!("Executing query in a transaction: {:?}", sql);
+            let rows_affected = (&mut *transaction).execute(sql).await?;
+            eprintln!("{:?} rows affected", rows_affected);
+            let query = match direction {
+                MigrationDirection::Up => sqlx::query(record_sql)
+                    .bind(&migration.version)
+                    .bind(&migration.name)
+                    .bind(&checksum),
+                MigrationDirection::Down => sqlx::query(record_sql).bind(&migration.version),
+            };
+            query.execute(&mut *transaction).await?;
+            if direction == MigrationDirection::Down {
+                sqlx::query(record_rollback_sql(connection))
+                    .bind(&migration.version)
+                    .execute(&mut *transaction)
+                    .await?;
             }
-        };
+            transaction.commit().await?;
+        }
+        DatabaseConnection::MySql(_) => {
+            return Err(MigrationError::Io(io::Error::new(
+                ErrorKind::Unsupported,
+                "MySQL can't roll back schema changes, run the migration without a transaction",
+            )));
+        }
+        DatabaseConnection::Mongo(_) => {
+            return Err(MigrationError::Io(io::Error::new(
+                ErrorKind::Unsupported,
+                "MongoDB can't roll back collection changes, run the migration without a transaction",
+            )));
+        }
     }
     Ok(())
 }
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use include_dir::Dir;
+
+use crate::database::migrations::{
+    apply_migrations, connect_to_project_database, find_drift, CustomMigrationError,
+    EmbeddedSql, Migration, MigrationDirection, MigrationLock, DEFAULT_MIGRATION_LOCK_TIMEOUT,
+};
+use crate::database::DatabaseConnection;
+
+use super::run_all_migrations::check_drift;
+
+/// ## Name: embed_migrations
+/// ### Description: Compiles the migrations of a project into the binary and returns a [`Migrator`] for them
+/// Without an argument `config/database/migrations` of the crate being compiled is embedded.
+/// A path may start with `$CARGO_MANIFEST_DIR`, or any other environment variable.
+/// ### Example:
+/// ```rust,ignore
+/// rustyroad::embed_migrations!()
+///     .run_for_project()
+///     .await
+///     .expect("Failed to run migrations");
+/// ```
+#[macro_export]
+macro_rules! embed_migrations {
+    () => {
+        $crate::embed_migrations!("$CARGO_MANIFEST_DIR/config/database/migrations")
+    };
+    // A `literal` fragment would reach `include_dir!` wrapped in a group, which it rejects
+    ($path:tt) => {{
+        use $crate::include_dir;
+        static MIGRATIONS: include_dir::Dir<'static> = include_dir::include_dir!($path);
+        $crate::database::migrations::Migrator::new(&MIGRATIONS)
+    }};
+}
+
+/// ## Name: Migrator
+/// ### Description: Runs migrations that were compiled into the binary with [`embed_migrations!`](crate::embed_migrations)
+/// Generated applications use it to migrate themselves on startup, so their containers don't
+/// need the `rustyroad` CLI. It applies the pending migrations like `rustyroad migration all`:
+/// under the migration lock, and only if the applied migrations weren't edited or deleted.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    force: bool,
+    lock_timeout: Duration,
+}
+
+impl Migrator {
+    /// ## Name: new
+    /// ### Description: Reads the migrations of an embedded migrations directory
+    /// Directories that aren't named `<timestamp>-<name>` or have no `up.sql` (or `up.json` on MongoDB) are skipped.
+    pub fn new(dir: &'static Dir<'static>) -> Migrator {
+        let mut migrations: Vec<Migration> = dir
+            .dirs()
+            .filter_map(|migration_dir| {
+                let mut migration = Migration::from_dir(migration_dir.path())?;
+                let sql = |file_name: &str| {
+                    migration_dir
+                        .get_file(migration_dir.path().join(file_name))
+                        .and_then(|file| file.contents_utf8())
+                };
+                migration.embedded = Some(EmbeddedSql {
+                    up: sql("up.sql").or_else(|| sql("up.json"))?,
+                    down: sql("down.sql").or_else(|| sql("down.json")).unwrap_or_default(),
+                });
+                Some(migration)
+            })
+            .collect();
+        migrations.sort_by(|a, b| a.version.cmp(&b.version).then(a.name.cmp(&b.name)));
+
+        Migrator {
+            migrations,
+            force: false,
+            lock_timeout: DEFAULT_MIGRATION_LOCK_TIMEOUT,
+        }
+    }
+
+    /// Runs the migrations even if applied ones were edited or deleted since.
+    pub fn force(mut self, force: bool) -> Migrator {
+        self.force = force;
+        self
+    }
+
+    /// Sets how long to wait for another process that holds the migration lock.
+    pub fn lock_timeout(mut self, lock_timeout: Duration) -> Migrator {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
+    /// Returns the embedded migrations, oldest first.
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// ## Name: run
+    /// ### Description: Applies the pending embedded migrations to a database
+    /// #### Parameters:
+    /// - connection: [`&DatabaseConnection`] - the connection pool of the database
+    /// - Returns: [`Result<(), CustomMigrationError>`]
+    pub async fn run(&self, connection: &DatabaseConnection) -> Result<(), CustomMigrationError> {
+        let lock = MigrationLock::acquire(connection, self.lock_timeout).await?;
+        let result = self.run_locked(connection).await;
+        if let Err(why) = lock.release().await {
+            eprintln!("Couldn't release the migration lock: {}", why);
+        }
+        result
+    }
+
+    async fn run_locked(&self, connection: &DatabaseConnection) -> Result<(), CustomMigrationError> {
+        check_drift(find_drift(connection, self.migrations.clone()).await?, self.force)?;
+        apply_migrations(connection, self.migrations.clone(), MigrationDirection::Up).await
+    }
+
+    /// ## Name: run_for_project
+    /// ### Description: Applies the pending embedded migrations to the database configured in rustyroad.toml
+    pub async fn run_for_project(&self) -> Result<(), CustomMigrationError> {
+        let connection = connect_to_project_database().await?;
+        self.run(&connection).await
+    }
+}
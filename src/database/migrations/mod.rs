@@ -1,9 +1,33 @@
+pub mod alter_table;
 pub mod column_loop;
 pub mod column_loop_test;
+pub mod column_spec;
+pub mod migration_commands;
+pub mod migration_files;
+pub mod migration_lock;
+pub mod migration_preview;
+pub mod migration_verify;
 pub mod migrations;
+pub mod migrator;
+pub mod mongo_collection;
 pub mod run_all_migrations;
+pub mod schema_dump;
+pub mod schema_migrations;
+pub mod sqlite_table;
 
+pub use alter_table::*;
 pub use column_loop::*;
 pub use column_loop_test::*;
+pub use column_spec::*;
+pub use migration_commands::*;
+pub use migration_files::*;
+pub use migration_lock::*;
+pub use migration_preview::*;
+pub use migration_verify::*;
 pub use migrations::*;
+pub use migrator::*;
+pub use mongo_collection::*;
 pub use run_all_migrations::*;
+pub use schema_dump::*;
+pub use schema_migrations::*;
+pub use sqlite_table::*;
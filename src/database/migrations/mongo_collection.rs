@@ -0,0 +1,137 @@
+use std::io;
+
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::database::{parse_mongo_commands, DatabaseError, DatabaseType};
+
+use super::column_spec::{ColumnKind, ColumnSpec};
+
+/// ## Name: CollectionMigration
+/// ### Description: A MongoDB migration that creates a collection, and the Rust struct of its documents
+/// #### Fields:
+/// - up_json: [`String`] - the `up.json` commands: `create` with a `$jsonSchema` validator, then `createIndexes`
+/// - down_json: [`String`] - the `down.json` commands, which drop the collection
+/// - rust_struct_contents: [`String`] - the struct of a document, with the `_id` as `id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionMigration {
+    pub up_json: String,
+    pub down_json: String,
+    pub rust_struct_contents: String,
+}
+
+/// ## Name: build_collection_migration
+/// ### Description: Builds the migration of a MongoDB collection from column specs without prompting
+/// Fields are required unless the `null` modifier is given, which also accepts `null` values.
+/// `unique` fields get a unique index and `references` fields an index, since MongoDB has no foreign keys.
+/// #### Parameters:
+/// - collection_name: [`&str`] - the name of the collection to create
+/// - columns: [`&[ColumnSpec]`] - the fields of the documents
+/// - Returns: [`Result<CollectionMigration, std::io::Error>`]
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::migrations::{build_collection_migration, ColumnSpec};
+///
+/// let columns = vec![ColumnSpec::parse("email:string:unique").unwrap()];
+/// let migration = build_collection_migration("users", &columns).unwrap();
+/// assert!(migration.up_json.contains("\"$jsonSchema\""));
+/// assert!(migration.up_json.contains("\"users_email_unique\""));
+/// assert!(migration.rust_struct_contents.contains("pub email: String,"));
+/// ```
+pub fn build_collection_migration(
+    collection_name: &str,
+    columns: &[ColumnSpec],
+) -> Result<CollectionMigration, io::Error> {
+    let mut properties = Document::new();
+    let mut required = Vec::new();
+    let mut indexes = Vec::new();
+    let mut struct_fields = String::from(
+        "    #[serde(rename = \"_id\", skip_serializing_if = \"Option::is_none\")]\n    pub id: Option<mongodb::bson::oid::ObjectId>,\n",
+    );
+
+    for column in columns {
+        let bson_type = if column.nullable {
+            Bson::Array(vec![column.bson_type().into(), "null".into()])
+        } else {
+            required.push(Bson::String(column.name.clone()));
+            column.bson_type().into()
+        };
+        properties.insert(column.name.clone(), doc! { "bsonType": bson_type });
+
+        let mut key = Document::new();
+        key.insert(column.name.clone(), 1);
+        if column.unique {
+            indexes.push(doc! {
+                "key": key,
+                "name": format!("{}_{}_unique", collection_name, column.name),
+                "unique": true,
+            });
+        } else if let ColumnKind::References(_) = column.kind {
+            indexes.push(doc! {
+                "key": key,
+                "name": format!("{}_{}_index", collection_name, column.name),
+            });
+        }
+
+        struct_fields.push_str(&format!(
+            "    pub {}: {},\n",
+            column.name.to_lowercase(),
+            column.rust_type(&DatabaseType::Mongo)
+        ));
+    }
+
+    let mut schema = doc! { "bsonType": "object" };
+    // An empty `required` array is rejected by MongoDB
+    if !required.is_empty() {
+        schema.insert("required", required);
+    }
+    schema.insert("properties", properties);
+
+    let mut up = vec![doc! {
+        "create": collection_name,
+        "validator": { "$jsonSchema": schema },
+    }];
+    if !indexes.is_empty() {
+        up.push(doc! { "createIndexes": collection_name, "indexes": indexes });
+    }
+    let down = vec![doc! { "drop": collection_name }];
+
+    // Capitalize the first letter, the same way `build_migration_from_columns` names its structs
+    let mut struct_name = collection_name.to_string();
+    if let Some(first) = struct_name.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+
+    Ok(CollectionMigration {
+        up_json: to_json(&up)?,
+        down_json: to_json(&down)?,
+        rust_struct_contents: format!("pub struct {} {{\n{}}}\n", struct_name, struct_fields),
+    })
+}
+
+/// ## Name: find_collection_schema
+/// ### Description: Finds the collection created by a MongoDB migration, and the `$jsonSchema` of its validator
+/// Returns `None` when the migration doesn't run a `create` command. A collection created
+/// without a validator has an empty schema.
+/// #### Parameters:
+/// - up_json: [`&str`] - the contents of the `up.json` of the migration
+/// - Returns: [`Result<Option<(String, Document)>, DatabaseError>`] - the collection name and its schema
+pub fn find_collection_schema(up_json: &str) -> Result<Option<(String, Document)>, DatabaseError> {
+    for command in parse_mongo_commands(up_json)? {
+        if let Ok(collection_name) = command.get_str("create") {
+            let schema = command
+                .get_document("validator")
+                .and_then(|validator| validator.get_document("$jsonSchema"))
+                .cloned()
+                .unwrap_or_default();
+            return Ok(Some((collection_name.to_string(), schema)));
+        }
+    }
+    Ok(None)
+}
+
+fn to_json(commands: &[Document]) -> Result<String, io::Error> {
+    serde_json::to_string_pretty(commands)
+        .map(|json| json + "\n")
+        .map_err(|why| io::Error::new(io::ErrorKind::InvalidData, why))
+}
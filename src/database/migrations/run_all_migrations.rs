@@ -1,54 +1,151 @@
-use std::{fs, io};
-use crate::database::migrations::{run_migration, MigrationDirection, CustomMigrationError};
+use std::time::Duration;
+
+use crate::database::migrations::{
+    apply_migration, dump_project_schema, ensure_schema_migrations_table, get_migrations,
+    plan_migrations, preview_migrations, select_migrations, verify_migrations,
+    CustomMigrationError, Migration, MigrationDirection, MigrationDrift, MigrationLock,
+    MigrationMode, MIGRATIONS_DIR,
+};
+use crate::database::{Database, DatabaseConnection};
 
 /// # Name: run_all_migrations
 /// ## Description: Runs all migrations in the migrations directory in order of creation
+/// Migrating up only applies the migrations that aren't recorded in `schema_migrations` yet,
+/// oldest first. Migrating down rolls back every applied migration, newest first.
+/// Afterwards the schema is dumped to `config/database/schema.sql` and `schema.json`.
+/// Nothing runs when an applied migration was edited or deleted, or a pending one is older
+/// than the latest applied migration, unless `force` is set.
+/// The migration lock is held for the whole run, so concurrent deploys migrate one at a time.
 /// ### Arguments:
 /// * `direction` - The direction to run the migrations (up or down)
+/// * `force` - Whether to run the migrations even though they drifted from `schema_migrations`
+/// * `mode` - Whether to run the migrations or only print (or write) their SQL
+/// * `lock_timeout` - How long to wait for another process that holds the migration lock
 /// ### Returns:
 /// * `Result<(), CustomMigrationError>` - Returns Ok(()) if successful, or a CustomMigrationError if there was an error
 /// ### Example:
 /// ```rust
-/// use rustyroad::database::migrations::run_all_migrations;
+/// use rustyroad::database::migrations::{
+///     run_all_migrations, MigrationMode, DEFAULT_MIGRATION_LOCK_TIMEOUT,
+/// };
 /// use rustyroad::database::MigrationDirection;
-/// 
-/// let result = run_all_migrations(MigrationDirection::Up).await;
+///
+/// let result = run_all_migrations(
+///     MigrationDirection::Up,
+///     false,
+///     MigrationMode::Execute,
+///     DEFAULT_MIGRATION_LOCK_TIMEOUT,
+/// )
+/// .await;
 /// assert!(result.is_ok());
 /// ```
-pub async fn run_all_migrations(direction: MigrationDirection) -> Result<(), CustomMigrationError> {
-    let migrations_dir_path = "./config/database/migrations";
-    
-    // Get all migration directories
-    let mut migration_dirs: Vec<_> = fs::read_dir(migrations_dir_path)
-        .map_err(|e| CustomMigrationError::IoError(e))?
-        .filter_map(Result::ok)
-        .filter(|entry| entry.path().is_dir())
-        .collect();
-    
-    // Sort directories by name (which includes timestamp)
-    migration_dirs.sort_by_key(|dir| dir.file_name());
-    
-    // If we're migrating down, reverse the order
-    if direction == MigrationDirection::Down {
-        migration_dirs.reverse();
+pub async fn run_all_migrations(
+    direction: MigrationDirection,
+    force: bool,
+    mode: MigrationMode,
+    lock_timeout: Duration,
+) -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+
+    if let MigrationMode::DryRun { output } = mode {
+        check_drift(verify_migrations(&connection, MIGRATIONS_DIR).await?, force)?;
+        let migrations = plan_migrations(&connection, MIGRATIONS_DIR, direction).await?;
+        return preview_migrations(&connection, &migrations, direction, output.as_deref());
+    }
+
+    let lock = MigrationLock::acquire(&connection, lock_timeout).await?;
+    let result = run_locked(&connection, direction, force).await;
+    if let Err(why) = lock.release().await {
+        eprintln!("Couldn't release the migration lock: {}", why);
+    }
+    result
+}
+
+/// Checks for drift and runs the migrations; the caller holds the migration lock.
+async fn run_locked(
+    connection: &DatabaseConnection,
+    direction: MigrationDirection,
+    force: bool,
+) -> Result<(), CustomMigrationError> {
+    // Checked under the lock, another process may have applied migrations while we waited
+    check_drift(verify_migrations(connection, MIGRATIONS_DIR).await?, force)?;
+    run_all_migrations_with_connection(connection, MIGRATIONS_DIR, direction).await?;
+    dump_project_schema(connection).await;
+    Ok(())
+}
+
+/// Fails when the migrations drifted from `schema_migrations`, or only warns when `force` is set.
+pub(crate) fn check_drift(
+    drift: Vec<MigrationDrift>,
+    force: bool,
+) -> Result<(), CustomMigrationError> {
+    if !drift.is_empty() {
+        if !force {
+            return Err(CustomMigrationError::Drift(drift));
+        }
+        for change in &drift {
+            println!("Warning: {}", change);
+        }
     }
-    
+    Ok(())
+}
+
+/// # Name: connect_to_project_database
+/// ## Description: Opens a connection pool to the database configured in the rustyroad.toml of the current directory
+/// ### Returns:
+/// * `Result<DatabaseConnection, CustomMigrationError>` - The connection pool, or a CustomMigrationError if the config can't be read or the database can't be reached
+pub async fn connect_to_project_database() -> Result<DatabaseConnection, CustomMigrationError> {
+    let database = Database::get_database_from_rustyroad_toml()?;
+
+    Ok(database.create_database_connection().await?)
+}
+
+/// # Name: run_all_migrations_with_connection
+/// ## Description: Runs the pending (or, going down, the applied) migrations of a directory against an existing connection
+/// ### Arguments:
+/// * `connection` - The connection pool of the project database
+/// * `migrations_dir_path` - The path to the migrations directory
+/// * `direction` - The direction to run the migrations (up or down)
+/// ### Returns:
+/// * `Result<(), CustomMigrationError>` - Returns Ok(()) if successful, or a CustomMigrationError if there was an error
+pub async fn run_all_migrations_with_connection(
+    connection: &DatabaseConnection,
+    migrations_dir_path: &str,
+    direction: MigrationDirection,
+) -> Result<(), CustomMigrationError> {
+    let migrations = get_migrations(migrations_dir_path).map_err(CustomMigrationError::IoError)?;
+    apply_migrations(connection, migrations, direction).await
+}
+
+/// # Name: apply_migrations
+/// ## Description: Runs the pending (or, going down, the applied) migrations of a list against an existing connection
+/// ### Arguments:
+/// * `connection` - The connection pool of the project database
+/// * `migrations` - Every migration, oldest first, e.g. the ones embedded by a [`Migrator`](super::Migrator)
+/// * `direction` - The direction to run the migrations (up or down)
+/// ### Returns:
+/// * `Result<(), CustomMigrationError>` - Returns Ok(()) if successful, or a CustomMigrationError if there was an error
+pub async fn apply_migrations(
+    connection: &DatabaseConnection,
+    migrations: Vec<Migration>,
+    direction: MigrationDirection,
+) -> Result<(), CustomMigrationError> {
+    ensure_schema_migrations_table(connection).await?;
+    let to_run = select_migrations(connection, migrations, direction).await?;
+
+    if to_run.is_empty() {
+        match direction {
+            MigrationDirection::Up => println!("No pending migrations"),
+            MigrationDirection::Down => println!("No applied migrations to roll back"),
+        }
+        return Ok(());
+    }
+
     // Run each migration in order
-    for dir in migration_dirs {
-        let dir_name = dir.file_name();
-        let migration_name = dir_name
-            .to_str()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid directory name"))
-            .map_err(|e| CustomMigrationError::IoError(e))?
-            .split('-')
-            .nth(1)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid migration name format"))
-            .map_err(|e| CustomMigrationError::IoError(e))?
-            .to_string();
-            
-        println!("Running migration: {}", migration_name);
-        run_migration(migration_name, direction).await?;
+    for migration in to_run {
+        println!("Running migration: {}", migration.directory_name());
+        apply_migration(connection, &migration, direction).await?;
     }
-    
+
     Ok(())
 }
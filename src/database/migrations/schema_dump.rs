@@ -0,0 +1,703 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sqlx::Executor;
+
+use crate::database::{DatabaseConnection, DatabaseType, SCHEMA_SEEDS_TABLE};
+
+use super::{
+    connect_to_project_database, get_applied_migrations, load_sqlite_table_schema,
+    CustomMigrationError, MigrationError, MIGRATION_LOCK_TABLE, ROLLED_BACK_MIGRATIONS_TABLE,
+    SCHEMA_MIGRATIONS_TABLE,
+};
+
+/// Where the schema of the project database is written after migrations run.
+pub const SCHEMA_SQL_PATH: &str = "./config/database/schema.sql";
+/// The machine-readable version of [`SCHEMA_SQL_PATH`].
+pub const SCHEMA_JSON_PATH: &str = "./config/database/schema.json";
+
+/// ## Name: DatabaseSchema
+/// ### Description: Every table of a database, plus the migrations that produced it
+/// This is what `schema.json` contains, and `schema.sql` is rendered from it with [`DatabaseSchema::to_sql`].
+/// #### Fields:
+/// - database_type: [`String`] - `postgres`, `mysql` or `sqlite`
+/// - tables: [`Vec<TableSchema>`] - the tables, sorted by name
+/// - migrations: [`Vec<SchemaMigration>`] - the applied migrations, oldest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseSchema {
+    pub database_type: String,
+    pub tables: Vec<TableSchema>,
+    pub migrations: Vec<SchemaMigration>,
+}
+
+/// A row of `schema_migrations`, without the time it was applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaMigration {
+    pub version: String,
+    pub name: String,
+    pub checksum: String,
+}
+
+/// ## Name: TableSchema
+/// ### Description: The columns, keys and indexes of a table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSchema {
+    pub name: String,
+    pub columns: Vec<ColumnSchema>,
+    pub primary_key: Vec<String>,
+    pub foreign_keys: Vec<ForeignKeySchema>,
+    pub indexes: Vec<IndexSchema>,
+}
+
+/// ## Name: ColumnSchema
+/// ### Description: A column as the database reports it
+/// #### Fields:
+/// - name: [`String`] - the name of the column
+/// - data_type: [`String`] - the SQL type, e.g. `character varying(255)` or `varchar(255)`
+/// - nullable: [`bool`] - whether the column accepts NULL
+/// - default: [`Option<String>`] - the default expression
+/// - auto_increment: [`bool`] - whether the column is a serial/auto-increment key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub auto_increment: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeySchema {
+    pub columns: Vec<String>,
+    pub references_table: String,
+    pub references_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSchema {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct ColumnRow {
+    table_name: String,
+    column_name: String,
+    data_type: String,
+    nullable: String,
+    column_default: Option<String>,
+    extra: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct KeyColumnRow {
+    table_name: String,
+    constraint_name: String,
+    column_name: String,
+    references_table: Option<String>,
+    references_column: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct IndexColumnRow {
+    table_name: String,
+    index_name: String,
+    non_unique: i64,
+    column_name: String,
+}
+
+/// ## Name: dump_schema
+/// ### Description: Introspects the live database
+/// Postgres and MySQL are read from `information_schema` (and `pg_index` for Postgres indexes),
+/// SQLite from `sqlite_master` and its `pragma_*` functions.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<DatabaseSchema, MigrationError>`]
+pub async fn dump_schema(connection: &DatabaseConnection) -> Result<DatabaseSchema, MigrationError> {
+    let (database_type, mut tables) = match connection {
+        DatabaseConnection::Pg(pool) => {
+            let columns = sqlx::query_as::<_, ColumnRow>(
+                "SELECT c.table_name::text AS table_name, c.column_name::text AS column_name,
+    CASE
+        WHEN c.character_maximum_length IS NOT NULL
+            THEN c.data_type::text || '(' || c.character_maximum_length::text || ')'
+        WHEN c.data_type = 'numeric' AND c.numeric_precision IS NOT NULL
+            THEN 'numeric(' || c.numeric_precision::text || ', ' || c.numeric_scale::text || ')'
+        WHEN c.data_type = 'ARRAY' THEN c.udt_name::regtype::text
+        WHEN c.data_type = 'USER-DEFINED' THEN c.udt_name::text
+        ELSE c.data_type::text
+    END AS data_type,
+    c.is_nullable::text AS nullable, c.column_default::text AS column_default,
+    c.is_identity::text AS extra
+FROM information_schema.columns c
+JOIN information_schema.tables t
+    ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+WHERE c.table_schema = current_schema() AND t.table_type = 'BASE TABLE'
+ORDER BY c.table_name, c.ordinal_position",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let keys = sqlx::query_as::<_, KeyColumnRow>(
+                "SELECT tc.table_name::text AS table_name, tc.constraint_name::text AS constraint_name,
+    kcu.column_name::text AS column_name,
+    ccu.table_name::text AS references_table, ccu.column_name::text AS references_column
+FROM information_schema.table_constraints tc
+JOIN information_schema.key_column_usage kcu
+    ON kcu.constraint_name = tc.constraint_name AND kcu.table_schema = tc.table_schema
+LEFT JOIN information_schema.constraint_column_usage ccu
+    ON tc.constraint_type = 'FOREIGN KEY'
+    AND ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+WHERE tc.table_schema = current_schema() AND tc.constraint_type IN ('PRIMARY KEY', 'FOREIGN KEY')
+ORDER BY tc.table_name, tc.constraint_name, kcu.ordinal_position",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let indexes = sqlx::query_as::<_, IndexColumnRow>(
+                "SELECT t.relname::text AS table_name, i.relname::text AS index_name,
+    CASE WHEN ix.indisunique THEN 0 ELSE 1 END::int8 AS non_unique, a.attname::text AS column_name
+FROM pg_index ix
+JOIN pg_class t ON t.oid = ix.indrelid
+JOIN pg_class i ON i.oid = ix.indexrelid
+JOIN pg_namespace n ON n.oid = t.relnamespace
+JOIN LATERAL unnest(ix.indkey) WITH ORDINALITY AS k(attnum, ord) ON true
+JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = k.attnum
+WHERE n.nspname = current_schema() AND NOT ix.indisprimary
+ORDER BY t.relname, i.relname, k.ord",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            (
+                DatabaseType::Postgres,
+                build_tables(columns, keys, indexes, &DatabaseType::Postgres),
+            )
+        }
+        DatabaseConnection::MySql(pool) => {
+            let columns = sqlx::query_as::<_, ColumnRow>(
+                "SELECT CAST(TABLE_NAME AS CHAR) AS table_name, CAST(COLUMN_NAME AS CHAR) AS column_name,
+    CAST(COLUMN_TYPE AS CHAR) AS data_type, CAST(IS_NULLABLE AS CHAR) AS nullable,
+    CAST(COLUMN_DEFAULT AS CHAR) AS column_default, CAST(EXTRA AS CHAR) AS extra
+FROM information_schema.COLUMNS
+WHERE TABLE_SCHEMA = DATABASE()
+ORDER BY TABLE_NAME, ORDINAL_POSITION",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let keys = sqlx::query_as::<_, KeyColumnRow>(
+                "SELECT CAST(TABLE_NAME AS CHAR) AS table_name, CAST(CONSTRAINT_NAME AS CHAR) AS constraint_name,
+    CAST(COLUMN_NAME AS CHAR) AS column_name,
+    CAST(REFERENCED_TABLE_NAME AS CHAR) AS references_table,
+    CAST(REFERENCED_COLUMN_NAME AS CHAR) AS references_column
+FROM information_schema.KEY_COLUMN_USAGE
+WHERE TABLE_SCHEMA = DATABASE()
+    AND (CONSTRAINT_NAME = 'PRIMARY' OR REFERENCED_TABLE_NAME IS NOT NULL)
+ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let indexes = sqlx::query_as::<_, IndexColumnRow>(
+                "SELECT CAST(TABLE_NAME AS CHAR) AS table_name, CAST(INDEX_NAME AS CHAR) AS index_name,
+    CAST(NON_UNIQUE AS SIGNED) AS non_unique, CAST(COLUMN_NAME AS CHAR) AS column_name
+FROM information_schema.STATISTICS
+WHERE TABLE_SCHEMA = DATABASE() AND INDEX_NAME <> 'PRIMARY' AND COLUMN_NAME IS NOT NULL
+ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            (
+                DatabaseType::Mysql,
+                build_tables(columns, keys, indexes, &DatabaseType::Mysql),
+            )
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            let table_names: Vec<String> = sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            )
+            .fetch_all(pool.as_ref())
+            .await?;
+
+            let mut tables = Vec::new();
+            for table_name in table_names {
+                let schema = load_sqlite_table_schema(pool, &table_name).await?;
+                let single_primary_key = schema.primary_key.len() == 1;
+
+                let columns = schema
+                    .columns
+                    .iter()
+                    .map(|column| ColumnSchema {
+                        name: column.name.clone(),
+                        data_type: column.column_type.clone(),
+                        // An INTEGER PRIMARY KEY is never NULL even without NOT NULL
+                        nullable: !column.not_null && !schema.primary_key.contains(&column.name),
+                        default: column.default_value.clone(),
+                        auto_increment: schema.autoincrement
+                            && single_primary_key
+                            && schema.primary_key[0] == column.name,
+                    })
+                    .collect();
+
+                let mut indexes: Vec<IndexSchema> = schema
+                    .unique_constraints
+                    .iter()
+                    .map(|columns| IndexSchema {
+                        // SQLite reserves the sqlite_ prefix of its automatic index names
+                        name: format!("index_{}_on_{}", table_name, columns.join("_and_")),
+                        columns: columns.clone(),
+                        unique: true,
+                    })
+                    .collect();
+                indexes.extend(schema.indexes.iter().map(|index| IndexSchema {
+                    name: index.name.clone(),
+                    columns: index.columns.clone(),
+                    unique: index.sql.to_uppercase().starts_with("CREATE UNIQUE"),
+                }));
+                indexes.sort_by(|a, b| a.name.cmp(&b.name));
+
+                tables.push(TableSchema {
+                    name: table_name,
+                    columns,
+                    primary_key: schema.primary_key.clone(),
+                    foreign_keys: schema
+                        .foreign_keys
+                        .iter()
+                        .map(|foreign_key| ForeignKeySchema {
+                            columns: foreign_key.columns.clone(),
+                            references_table: foreign_key.references_table.clone(),
+                            references_columns: foreign_key.references_columns.clone(),
+                        })
+                        .collect(),
+                    indexes,
+                });
+            }
+            (DatabaseType::Sqlite, tables)
+        }
+        DatabaseConnection::Mongo(_) => return Err(no_mongo_schema()),
+    };
+
+    // The migration lock only exists while migrations run, and which seeds ran or migrations
+    // were rolled back is data
+    let bookkeeping = [MIGRATION_LOCK_TABLE, SCHEMA_SEEDS_TABLE, ROLLED_BACK_MIGRATIONS_TABLE];
+    tables.retain(|table| !bookkeeping.contains(&table.name.as_str()));
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let migrations = if tables.iter().any(|table| table.name == SCHEMA_MIGRATIONS_TABLE) {
+        get_applied_migrations(connection)
+            .await?
+            .into_iter()
+            .map(|applied| SchemaMigration {
+                version: applied.version,
+                name: applied.name,
+                checksum: applied.checksum,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(DatabaseSchema {
+        database_type: database_type.to_string(),
+        tables,
+        migrations,
+    })
+}
+
+/// Groups the rows read from `information_schema` into tables.
+fn build_tables(
+    columns: Vec<ColumnRow>,
+    keys: Vec<KeyColumnRow>,
+    indexes: Vec<IndexColumnRow>,
+    database_type: &DatabaseType,
+) -> Vec<TableSchema> {
+    let mut tables: Vec<TableSchema> = Vec::new();
+
+    for row in columns {
+        let (default, auto_increment) = match database_type {
+            DatabaseType::Postgres => {
+                let is_serial = row
+                    .column_default
+                    .as_deref()
+                    .map(|default| default.starts_with("nextval("))
+                    .unwrap_or(false);
+                let is_identity = row.extra.as_deref() == Some("YES");
+                if is_serial || is_identity {
+                    (None, true)
+                } else {
+                    (row.column_default, false)
+                }
+            }
+            _ => {
+                let extra = row.extra.unwrap_or_default().to_lowercase();
+                let default = row.column_default.map(|default| {
+                    mysql_default_expression(&default, &row.data_type, &extra)
+                });
+                (default, extra.contains("auto_increment"))
+            }
+        };
+
+        let column = ColumnSchema {
+            name: row.column_name,
+            data_type: row.data_type,
+            nullable: row.nullable == "YES",
+            default,
+            auto_increment,
+        };
+
+        match tables.last_mut() {
+            Some(table) if table.name == row.table_name => table.columns.push(column),
+            _ => tables.push(TableSchema {
+                name: row.table_name,
+                columns: vec![column],
+                primary_key: Vec::new(),
+                foreign_keys: Vec::new(),
+                indexes: Vec::new(),
+            }),
+        }
+    }
+
+    let mut last_foreign_key: Option<(String, String)> = None;
+    for row in keys {
+        let Some(table) = tables.iter_mut().find(|table| table.name == row.table_name) else {
+            continue;
+        };
+        match (row.references_table, row.references_column) {
+            (Some(references_table), Some(references_column)) => {
+                let key = (row.table_name.clone(), row.constraint_name.clone());
+                let same_constraint = last_foreign_key.as_ref() == Some(&key);
+                match table.foreign_keys.last_mut() {
+                    Some(foreign_key) if same_constraint => {
+                        if !foreign_key.columns.contains(&row.column_name) {
+                            foreign_key.columns.push(row.column_name);
+                        }
+                        if !foreign_key.references_columns.contains(&references_column) {
+                            foreign_key.references_columns.push(references_column);
+                        }
+                    }
+                    _ => table.foreign_keys.push(ForeignKeySchema {
+                        columns: vec![row.column_name],
+                        references_table,
+                        references_columns: vec![references_column],
+                    }),
+                }
+                last_foreign_key = Some(key);
+            }
+            _ => {
+                if !table.primary_key.contains(&row.column_name) {
+                    table.primary_key.push(row.column_name);
+                }
+            }
+        }
+    }
+
+    for row in indexes {
+        let Some(table) = tables.iter_mut().find(|table| table.name == row.table_name) else {
+            continue;
+        };
+        match table.indexes.last_mut() {
+            Some(index) if index.name == row.index_name => index.columns.push(row.column_name),
+            _ => table.indexes.push(IndexSchema {
+                name: row.index_name,
+                columns: vec![row.column_name],
+                unique: row.non_unique == 0,
+            }),
+        }
+    }
+
+    tables
+}
+
+/// MySQL reports string defaults without quotes, so they are quoted again unless they are expressions.
+fn mysql_default_expression(default: &str, data_type: &str, extra: &str) -> String {
+    let is_text = ["char", "text", "enum", "set"]
+        .iter()
+        .any(|text_type| data_type.to_lowercase().contains(text_type));
+    if extra.contains("default_generated") || !is_text {
+        default.to_string()
+    } else {
+        format!("'{}'", default.replace('\'', "''"))
+    }
+}
+
+impl DatabaseSchema {
+    /// ## Name: to_sql
+    /// ### Description: Renders the schema as SQL that recreates it on an empty database
+    /// Tables are created after the tables they reference, and the applied migrations are
+    /// inserted into `schema_migrations` so the history isn't replayed afterwards.
+    pub fn to_sql(&self, database_type: &DatabaseType) -> String {
+        let mut sql = format!(
+            "-- This file is generated by rustyroad from the {} database after migrations run.\n-- Load it into an empty database with `rustyroad db schema:load`.\n",
+            self.database_type
+        );
+
+        for table in self.tables_in_dependency_order() {
+            sql.push('\n');
+            sql.push_str(&table_sql(table, database_type));
+        }
+
+        if !self.migrations.is_empty() {
+            sql.push('\n');
+            for migration in &self.migrations {
+                sql.push_str(&format!(
+                    "INSERT INTO {} (version, name, checksum) VALUES ('{}', '{}', '{}');\n",
+                    SCHEMA_MIGRATIONS_TABLE,
+                    migration.version.replace('\'', "''"),
+                    migration.name.replace('\'', "''"),
+                    migration.checksum.replace('\'', "''")
+                ));
+            }
+        }
+
+        sql
+    }
+
+    /// Orders the tables so that referenced tables come first; cycles fall back to name order.
+    fn tables_in_dependency_order(&self) -> Vec<&TableSchema> {
+        let mut ordered: Vec<&TableSchema> = Vec::new();
+        let mut created: HashSet<&str> = HashSet::new();
+        let mut remaining: Vec<&TableSchema> = self.tables.iter().collect();
+
+        while !remaining.is_empty() {
+            let ready = remaining.iter().position(|table| {
+                table.foreign_keys.iter().all(|foreign_key| {
+                    foreign_key.references_table == table.name
+                        || created.contains(foreign_key.references_table.as_str())
+                        || !self
+                            .tables
+                            .iter()
+                            .any(|other| other.name == foreign_key.references_table)
+                })
+            });
+            let table = remaining.remove(ready.unwrap_or(0));
+            created.insert(table.name.as_str());
+            ordered.push(table);
+        }
+
+        ordered
+    }
+}
+
+fn table_sql(table: &TableSchema, database_type: &DatabaseType) -> String {
+    let inline_primary_key = *database_type == DatabaseType::Sqlite
+        && table.primary_key.len() == 1
+        && table
+            .columns
+            .iter()
+            .any(|column| column.auto_increment && column.name == table.primary_key[0]);
+
+    let mut definitions: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| {
+            let mut definition = match (database_type, column.auto_increment) {
+                (DatabaseType::Postgres, true) if column.data_type.starts_with("bigint") => {
+                    format!("{} BIGSERIAL", column.name)
+                }
+                (DatabaseType::Postgres, true) if column.data_type.starts_with("smallint") => {
+                    format!("{} SMALLSERIAL", column.name)
+                }
+                (DatabaseType::Postgres, true) => format!("{} SERIAL", column.name),
+                (DatabaseType::Mysql, true) => {
+                    format!("{} {} AUTO_INCREMENT", column.name, column.data_type)
+                }
+                (DatabaseType::Sqlite, true) if inline_primary_key => {
+                    format!("{} {} PRIMARY KEY AUTOINCREMENT", column.name, column.data_type)
+                }
+                _ => format!("{} {}", column.name, column.data_type),
+            };
+            if !column.nullable {
+                definition.push_str(" NOT NULL");
+            }
+            if let Some(default) = &column.default {
+                definition.push_str(&format!(" DEFAULT {}", default));
+            }
+            definition
+        })
+        .collect();
+
+    if !table.primary_key.is_empty() && !inline_primary_key {
+        definitions.push(format!("PRIMARY KEY ({})", table.primary_key.join(", ")));
+    }
+
+    // MySQL needs the indexes of foreign keys to exist before the foreign keys, so they're inline
+    if *database_type == DatabaseType::Mysql {
+        for index in &table.indexes {
+            definitions.push(format!(
+                "{}KEY {} ({})",
+                if index.unique { "UNIQUE " } else { "" },
+                index.name,
+                index.columns.join(", ")
+            ));
+        }
+    }
+
+    for foreign_key in &table.foreign_keys {
+        definitions.push(format!(
+            "FOREIGN KEY ({}) REFERENCES {} ({})",
+            foreign_key.columns.join(", "),
+            foreign_key.references_table,
+            foreign_key.references_columns.join(", ")
+        ));
+    }
+
+    let mut sql = format!(
+        "CREATE TABLE {} (\n    {}\n);\n",
+        table.name,
+        definitions.join(",\n    ")
+    );
+
+    if *database_type != DatabaseType::Mysql {
+        for index in &table.indexes {
+            sql.push_str(&format!(
+                "CREATE {}INDEX {} ON {} ({});\n",
+                if index.unique { "UNIQUE " } else { "" },
+                index.name,
+                table.name,
+                index.columns.join(", ")
+            ));
+        }
+    }
+
+    sql
+}
+
+/// ## Name: write_schema_files
+/// ### Description: Dumps the schema of the database to a `schema.sql` and a `schema.json` file
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - sql_path: [`&str`] - where to write the SQL, usually [`SCHEMA_SQL_PATH`]
+/// - json_path: [`&str`] - where to write the JSON, usually [`SCHEMA_JSON_PATH`]
+/// - Returns: [`Result<DatabaseSchema, MigrationError>`] - the schema that was written
+pub async fn write_schema_files(
+    connection: &DatabaseConnection,
+    sql_path: &str,
+    json_path: &str,
+) -> Result<DatabaseSchema, MigrationError> {
+    let schema = dump_schema(connection).await?;
+    let database_type = database_type_of(connection);
+
+    for path in [sql_path, json_path] {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    fs::write(sql_path, schema.to_sql(&database_type))?;
+    let json = serde_json::to_string_pretty(&schema)
+        .map_err(|why| io::Error::new(ErrorKind::InvalidData, why))?;
+    fs::write(json_path, json + "\n")?;
+
+    Ok(schema)
+}
+
+/// ## Name: dump_project_schema
+/// ### Description: Writes `config/database/schema.sql` and `schema.json` after migrations ran
+/// The migrations already succeeded at this point, so a failing dump is reported without failing the command.
+pub async fn dump_project_schema(connection: &DatabaseConnection) {
+    // The migrations of a MongoDB project are its schema
+    if let DatabaseConnection::Mongo(_) = connection {
+        return;
+    }
+    match write_schema_files(connection, SCHEMA_SQL_PATH, SCHEMA_JSON_PATH).await {
+        Ok(_) => println!("Schema written to {}", SCHEMA_SQL_PATH),
+        Err(why) => eprintln!("Couldn't write the schema files: {}", why),
+    }
+}
+
+/// ## Name: load_schema_with_connection
+/// ### Description: Creates every table of a `schema.sql` file on an empty database
+/// On Postgres and SQLite the file is loaded in a single transaction.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the database to load into
+/// - sql_path: [`&str`] - the path to the `schema.sql` file
+/// - Returns: [`Result<(), CustomMigrationError>`] - an error if the database already has tables
+pub async fn load_schema_with_connection(
+    connection: &DatabaseConnection,
+    sql_path: &str,
+) -> Result<(), CustomMigrationError> {
+    let sql = fs::read_to_string(sql_path).map_err(|why| {
+        CustomMigrationError::IoError(io::Error::new(
+            why.kind(),
+            format!("Couldn't read {}: {}", sql_path, why),
+        ))
+    })?;
+
+    let existing = dump_schema(connection).await?;
+    if !existing.tables.is_empty() {
+        return Err(CustomMigrationError::IoError(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "The database already has {} table(s), schema:load only runs on an empty database",
+                existing.tables.len()
+            ),
+        )));
+    }
+
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            let mut transaction = pool.begin().await.map_err(MigrationError::from)?;
+            (&mut *transaction)
+                .execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+            transaction.commit().await.map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            pool.execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            let mut transaction = pool.begin().await.map_err(MigrationError::from)?;
+            (&mut *transaction)
+                .execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+            transaction.commit().await.map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Mongo(_) => return Err(no_mongo_schema().into()),
+    }
+
+    Ok(())
+}
+
+/// ## Name: load_schema
+/// ### Description: Bootstraps the project database from `config/database/schema.sql`
+pub async fn load_schema() -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    load_schema_with_connection(&connection, SCHEMA_SQL_PATH).await?;
+    println!("Schema loaded from {}", SCHEMA_SQL_PATH);
+    Ok(())
+}
+
+/// ## Name: dump_schema_command
+/// ### Description: Writes the schema files of the project database without running migrations
+pub async fn dump_schema_command() -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    write_schema_files(&connection, SCHEMA_SQL_PATH, SCHEMA_JSON_PATH).await?;
+    println!("Schema written to {}", SCHEMA_SQL_PATH);
+    Ok(())
+}
+
+fn database_type_of(connection: &DatabaseConnection) -> DatabaseType {
+    match connection {
+        DatabaseConnection::Pg(_) => DatabaseType::Postgres,
+        DatabaseConnection::MySql(_) => DatabaseType::Mysql,
+        DatabaseConnection::Sqlite(_) => DatabaseType::Sqlite,
+        DatabaseConnection::Mongo(_) => DatabaseType::Mongo,
+    }
+}
+
+fn no_mongo_schema() -> MigrationError {
+    MigrationError::Io(io::Error::new(
+        ErrorKind::Unsupported,
+        "MongoDB collections have no schema.sql, run the migrations instead",
+    ))
+}
@@ -0,0 +1,403 @@
+use chrono::Local;
+use futures_util::TryStreamExt;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use sqlx::Executor;
+
+use crate::database::DatabaseConnection;
+
+use super::{Migration, MigrationError};
+
+/// The table that records which migrations have been applied to a database.
+/// On MongoDB it's a collection with one document per migration.
+pub const SCHEMA_MIGRATIONS_TABLE: &str = "schema_migrations";
+
+/// The table that records which migrations were rolled back, so applying one of them again
+/// isn't reported as out of order. On MongoDB it's a collection with one document per migration.
+pub const ROLLED_BACK_MIGRATIONS_TABLE: &str = "schema_migrations_rolled_back";
+
+/// ## Name: AppliedMigration
+/// ### Description: A row of the `schema_migrations` table
+/// #### Fields:
+/// - version: [`String`] - the timestamp of the migration directory
+/// - name: [`String`] - the name of the migration
+/// - checksum: [`String`] - the SHA-256 of `up.sql` when the migration was applied
+/// - applied_at: [`String`] - when the migration was applied
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, Deserialize)]
+pub struct AppliedMigration {
+    pub version: String,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: String,
+}
+
+/// ## Name: ensure_schema_migrations_table
+/// ### Description: Creates the `schema_migrations` table, and the one of rolled back migrations, if they don't exist yet
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<(), MigrationError>`]
+pub async fn ensure_schema_migrations_table(
+    connection: &DatabaseConnection,
+) -> Result<(), MigrationError> {
+    let sql = create_schema_migrations_table_sql(connection);
+    let rolled_back_sql = create_rolled_back_migrations_table_sql(connection);
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            pool.execute(sql).await?;
+            pool.execute(rolled_back_sql).await?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            pool.execute(sql).await?;
+            pool.execute(rolled_back_sql).await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            pool.execute(sql).await?;
+            pool.execute(rolled_back_sql).await?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            if !mongo_collection_exists(database, SCHEMA_MIGRATIONS_TABLE).await? {
+                database.create_collection(SCHEMA_MIGRATIONS_TABLE).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether a MongoDB collection exists, since creating one twice is an error.
+pub(crate) async fn mongo_collection_exists(
+    database: &mongodb::Database,
+    name: &str,
+) -> Result<bool, MigrationError> {
+    let names = database
+        .list_collection_names()
+        .filter(doc! { "name": name })
+        .await?;
+    Ok(!names.is_empty())
+}
+
+/// Returns the statement that creates `schema_migrations`, in the dialect of the connection.
+pub(crate) fn create_schema_migrations_table_sql(connection: &DatabaseConnection) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) | DatabaseConnection::MySql(_) => {
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version VARCHAR(255) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    checksum VARCHAR(64) NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);"
+        }
+        DatabaseConnection::Sqlite(_) => {
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);"
+        }
+        // A collection needs no statement, see ensure_schema_migrations_table
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// Returns the statement that creates the table of rolled back migrations, in the dialect of the connection.
+pub(crate) fn create_rolled_back_migrations_table_sql(
+    connection: &DatabaseConnection,
+) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) | DatabaseConnection::MySql(_) => {
+            "CREATE TABLE IF NOT EXISTS schema_migrations_rolled_back (
+    version VARCHAR(255) PRIMARY KEY,
+    rolled_back_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);"
+        }
+        DatabaseConnection::Sqlite(_) => {
+            "CREATE TABLE IF NOT EXISTS schema_migrations_rolled_back (
+    version TEXT PRIMARY KEY,
+    rolled_back_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);"
+        }
+        // The collection is created by its first document, see remove_migration_record
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// ## Name: schema_migrations_table_exists
+/// ### Description: Checks whether `schema_migrations` was created, without creating it
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<bool, MigrationError>`]
+pub async fn schema_migrations_table_exists(
+    connection: &DatabaseConnection,
+) -> Result<bool, MigrationError> {
+    table_exists(connection, SCHEMA_MIGRATIONS_TABLE).await
+}
+
+/// Checks whether a table (or MongoDB collection) exists, without creating it.
+async fn table_exists(connection: &DatabaseConnection, name: &str) -> Result<bool, MigrationError> {
+    let count: i64 = match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM information_schema.tables
+WHERE table_schema = current_schema() AND table_name = $1",
+            )
+            .bind(name)
+            .fetch_one(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM information_schema.TABLES
+WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?",
+            )
+            .bind(name)
+            .fetch_one(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(name)
+                .fetch_one(pool.as_ref())
+                .await?
+        }
+        DatabaseConnection::Mongo(database) => {
+            return mongo_collection_exists(database, name).await;
+        }
+    };
+    Ok(count > 0)
+}
+
+/// ## Name: get_rolled_back_versions
+/// ### Description: Returns the versions of the migrations that were rolled back, applied again or not
+/// Nothing is written to the database, a database that never rolled back has none.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<Vec<String>, MigrationError>`]
+pub async fn get_rolled_back_versions(
+    connection: &DatabaseConnection,
+) -> Result<Vec<String>, MigrationError> {
+    if !table_exists(connection, ROLLED_BACK_MIGRATIONS_TABLE).await? {
+        return Ok(Vec::new());
+    }
+    let sql = "SELECT version FROM schema_migrations_rolled_back ORDER BY version";
+    let versions = match connection {
+        DatabaseConnection::Pg(pool) => sqlx::query_scalar(sql).fetch_all(pool.as_ref()).await?,
+        DatabaseConnection::MySql(pool) => sqlx::query_scalar(sql).fetch_all(pool.as_ref()).await?,
+        DatabaseConnection::Sqlite(pool) => sqlx::query_scalar(sql).fetch_all(pool.as_ref()).await?,
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection::<mongodb::bson::Document>(ROLLED_BACK_MIGRATIONS_TABLE)
+                .find(doc! {})
+                .sort(doc! { "_id": 1 })
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+                .into_iter()
+                .filter_map(|document| document.get_str("_id").ok().map(str::to_string))
+                .collect()
+        }
+    };
+    Ok(versions)
+}
+
+/// ## Name: get_applied_migrations_if_tracked
+/// ### Description: Like [`get_applied_migrations`], but a database without `schema_migrations` has no applied migrations
+/// Nothing is written to the database, which is what `--dry-run` and `migration verify` need.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<Vec<AppliedMigration>, MigrationError>`]
+pub async fn get_applied_migrations_if_tracked(
+    connection: &DatabaseConnection,
+) -> Result<Vec<AppliedMigration>, MigrationError> {
+    if schema_migrations_table_exists(connection).await? {
+        get_applied_migrations(connection).await
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// ## Name: get_applied_migrations
+/// ### Description: Returns every applied migration, oldest first
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<Vec<AppliedMigration>, MigrationError>`]
+pub async fn get_applied_migrations(
+    connection: &DatabaseConnection,
+) -> Result<Vec<AppliedMigration>, MigrationError> {
+    let applied = match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query_as::<_, AppliedMigration>(
+                "SELECT version, name, checksum, CAST(applied_at AS TEXT) AS applied_at
+FROM schema_migrations ORDER BY version",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query_as::<_, AppliedMigration>(
+                "SELECT version, name, checksum, CAST(applied_at AS CHAR) AS applied_at
+FROM schema_migrations ORDER BY version",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query_as::<_, AppliedMigration>(
+                "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection::<AppliedMigration>(SCHEMA_MIGRATIONS_TABLE)
+                .find(doc! {})
+                .sort(doc! { "version": 1 })
+                .await?
+                .try_collect()
+                .await?
+        }
+    };
+    Ok(applied)
+}
+
+/// Returns the statement that records an applied migration, in the dialect of the connection.
+pub(crate) fn record_migration_sql(connection: &DatabaseConnection) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            "INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)"
+        }
+        DatabaseConnection::MySql(_) | DatabaseConnection::Sqlite(_) => {
+            "INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)"
+        }
+        // Recorded with insert_one, see record_migration
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// Returns the statement that removes a rolled back migration, in the dialect of the connection.
+pub(crate) fn remove_migration_record_sql(connection: &DatabaseConnection) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) => "DELETE FROM schema_migrations WHERE version = $1",
+        DatabaseConnection::MySql(_) | DatabaseConnection::Sqlite(_) => {
+            "DELETE FROM schema_migrations WHERE version = ?"
+        }
+        // Removed with delete_one, see remove_migration_record
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// Returns the statement that records a rolled back migration, in the dialect of the connection.
+/// A migration rolled back before is already recorded.
+pub(crate) fn record_rollback_sql(connection: &DatabaseConnection) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            "INSERT INTO schema_migrations_rolled_back (version) VALUES ($1) ON CONFLICT DO NOTHING"
+        }
+        DatabaseConnection::MySql(_) => {
+            "INSERT IGNORE INTO schema_migrations_rolled_back (version) VALUES (?)"
+        }
+        DatabaseConnection::Sqlite(_) => {
+            "INSERT OR IGNORE INTO schema_migrations_rolled_back (version) VALUES (?)"
+        }
+        // Recorded with update_one, see remove_migration_record
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// ## Name: record_migration
+/// ### Description: Marks a migration as applied in `schema_migrations`
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - migration: [`&Migration`] - the migration that was applied
+/// - Returns: [`Result<(), MigrationError>`]
+pub async fn record_migration(
+    connection: &DatabaseConnection,
+    migration: &Migration,
+) -> Result<(), MigrationError> {
+    let checksum = migration.checksum()?;
+    let sql = record_migration_sql(connection);
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query(sql)
+                .bind(&migration.version)
+                .bind(&migration.name)
+                .bind(&checksum)
+                .execute(pool.as_ref())
+                .await?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query(sql)
+                .bind(&migration.version)
+                .bind(&migration.name)
+                .bind(&checksum)
+                .execute(pool.as_ref())
+                .await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query(sql)
+                .bind(&migration.version)
+                .bind(&migration.name)
+                .bind(&checksum)
+                .execute(pool.as_ref())
+                .await?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection(SCHEMA_MIGRATIONS_TABLE)
+                .insert_one(doc! {
+                    "version": &migration.version,
+                    "name": &migration.name,
+                    "checksum": &checksum,
+                    "applied_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// ## Name: remove_migration_record
+/// ### Description: Removes a migration from `schema_migrations` after it was rolled back
+/// The migration is recorded in `schema_migrations_rolled_back`, see [`ROLLED_BACK_MIGRATIONS_TABLE`].
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - version: [`&str`] - the version of the migration that was rolled back
+/// - Returns: [`Result<(), MigrationError>`]
+pub async fn remove_migration_record(
+    connection: &DatabaseConnection,
+    version: &str,
+) -> Result<(), MigrationError> {
+    let sql = remove_migration_record_sql(connection);
+    let rollback_sql = record_rollback_sql(connection);
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query(sql).bind(version).execute(pool.as_ref()).await?;
+            sqlx::query(rollback_sql).bind(version).execute(pool.as_ref()).await?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query(sql).bind(version).execute(pool.as_ref()).await?;
+            sqlx::query(rollback_sql).bind(version).execute(pool.as_ref()).await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query(sql).bind(version).execute(pool.as_ref()).await?;
+            sqlx::query(rollback_sql).bind(version).execute(pool.as_ref()).await?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection::<mongodb::bson::Document>(SCHEMA_MIGRATIONS_TABLE)
+                .delete_one(doc! { "version": version })
+                .await?;
+            database
+                .collection::<mongodb::bson::Document>(ROLLED_BACK_MIGRATIONS_TABLE)
+                .update_one(
+                    doc! { "_id": version },
+                    doc! { "$setOnInsert": {
+                        "rolled_back_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    } },
+                )
+                .upsert(true)
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use sqlx::SqlitePool;
+
+use super::MigrationError;
+
+/// ## Name: SqliteColumn
+/// ### Description: A column of an existing SQLite table, as reported by `pragma_table_info`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteColumn {
+    pub name: String,
+    pub column_type: String,
+    pub not_null: bool,
+    pub default_value: Option<String>,
+}
+
+/// ## Name: SqliteForeignKey
+/// ### Description: A foreign key of an existing SQLite table, as reported by `pragma_foreign_key_list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteForeignKey {
+    pub columns: Vec<String>,
+    pub references_table: String,
+    pub references_columns: Vec<String>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+/// ## Name: SqliteIndex
+/// ### Description: An index created with `CREATE INDEX`, recreated after the table is rebuilt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteIndex {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub sql: String,
+}
+
+/// ## Name: SqliteTableSchema
+/// ### Description: The definition of an existing SQLite table
+/// SQLite can't change a column type, drop a constrained column or add a foreign key with
+/// `ALTER TABLE`, so those migrations create a new table from this schema, copy the rows
+/// over and swap the tables, as described in <https://www.sqlite.org/lang_altertable.html#otheralter>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteTableSchema {
+    pub name: String,
+    pub columns: Vec<SqliteColumn>,
+    pub primary_key: Vec<String>,
+    pub autoincrement: bool,
+    pub unique_constraints: Vec<Vec<String>>,
+    pub foreign_keys: Vec<SqliteForeignKey>,
+    pub indexes: Vec<SqliteIndex>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TableInfoRow {
+    name: String,
+    #[sqlx(rename = "type")]
+    column_type: String,
+    notnull: i64,
+    dflt_value: Option<String>,
+    pk: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ForeignKeyRow {
+    id: i64,
+    table: String,
+    from: String,
+    to: Option<String>,
+    on_update: String,
+    on_delete: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct IndexListRow {
+    name: String,
+    origin: String,
+}
+
+/// ## Name: load_sqlite_table_schema
+/// ### Description: Reads the columns, keys and indexes of a SQLite table
+/// #### Parameters:
+/// - pool: [`&SqlitePool`] - the connection pool of the project database
+/// - table_name: [`&str`] - the table to read
+/// - Returns: [`Result<SqliteTableSchema, MigrationError>`] - a `NotFound` error when the table doesn't exist
+pub async fn load_sqlite_table_schema(
+    pool: &SqlitePool,
+    table_name: &str,
+) -> Result<SqliteTableSchema, MigrationError> {
+    let table_sql: Option<String> =
+        sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table_name)
+            .fetch_optional(pool)
+            .await?;
+    let table_sql = table_sql.ok_or_else(|| {
+        MigrationError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "Table '{}' doesn't exist, run the pending migrations first",
+                table_name
+            ),
+        ))
+    })?;
+
+    let mut table_info = sqlx::query_as::<_, TableInfoRow>(
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await?;
+
+    let columns = table_info
+        .iter()
+        .map(|row| SqliteColumn {
+            name: row.name.clone(),
+            column_type: row.column_type.clone(),
+            not_null: row.notnull != 0,
+            default_value: row.dflt_value.clone(),
+        })
+        .collect();
+
+    table_info.retain(|row| row.pk > 0);
+    table_info.sort_by_key(|row| row.pk);
+    let primary_key: Vec<String> = table_info.into_iter().map(|row| row.name).collect();
+
+    let foreign_key_rows = sqlx::query_as::<_, ForeignKeyRow>(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete
+FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await?;
+
+    let mut foreign_keys: Vec<(i64, SqliteForeignKey)> = Vec::new();
+    for row in foreign_key_rows {
+        // Without a target column the foreign key points at the primary key, which is `id` for generated tables
+        let to = row.to.unwrap_or_else(|| "id".to_string());
+        match foreign_keys.last_mut() {
+            Some((id, foreign_key)) if *id == row.id => {
+                foreign_key.columns.push(row.from);
+                foreign_key.references_columns.push(to);
+            }
+            _ => foreign_keys.push((
+                row.id,
+                SqliteForeignKey {
+                    columns: vec![row.from],
+                    references_table: row.table,
+                    references_columns: vec![to],
+                    on_update: row.on_update,
+                    on_delete: row.on_delete,
+                },
+            )),
+        }
+    }
+
+    let index_rows = sqlx::query_as::<_, IndexListRow>(
+        "SELECT name, origin FROM pragma_index_list(?) ORDER BY seq DESC",
+    )
+    .bind(table_name)
+    .fetch_all(pool)
+    .await?;
+
+    let mut unique_constraints = Vec::new();
+    let mut indexes = Vec::new();
+    for row in index_rows {
+        let index_columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_index_info(?) ORDER BY seqno")
+                .bind(&row.name)
+                .fetch_all(pool)
+                .await?;
+
+        match row.origin.as_str() {
+            "u" => unique_constraints.push(index_columns),
+            "c" => {
+                let sql: Option<String> = sqlx::query_scalar(
+                    "SELECT sql FROM sqlite_master WHERE type = 'index' AND name = ?",
+                )
+                .bind(&row.name)
+                .fetch_one(pool)
+                .await?;
+                if let Some(sql) = sql {
+                    indexes.push(SqliteIndex {
+                        name: row.name,
+                        columns: index_columns,
+                        sql,
+                    });
+                }
+            }
+            // The primary key index is recreated with the table
+            _ => {}
+        }
+    }
+
+    Ok(SqliteTableSchema {
+        name: table_name.to_string(),
+        columns,
+        primary_key,
+        autoincrement: table_sql.to_uppercase().contains("AUTOINCREMENT"),
+        unique_constraints,
+        foreign_keys: foreign_keys.into_iter().map(|(_, foreign_key)| foreign_key).collect(),
+        indexes,
+    })
+}
+
+impl SqliteTableSchema {
+    /// Returns whether the table has a column with the given name.
+    pub fn has_column(&self, column_name: &str) -> bool {
+        self.columns.iter().any(|column| column.name == column_name)
+    }
+
+    /// Returns a copy of the schema with a column appended.
+    pub fn with_column(&self, column: SqliteColumn) -> SqliteTableSchema {
+        let mut schema = self.clone();
+        schema.columns.push(column);
+        schema
+    }
+
+    /// Returns a copy of the schema without the column and the constraints and indexes that use it.
+    pub fn without_column(&self, column_name: &str) -> SqliteTableSchema {
+        let mut schema = self.clone();
+        schema.columns.retain(|column| column.name != column_name);
+        schema
+            .unique_constraints
+            .retain(|columns| !columns.iter().any(|column| column == column_name));
+        schema
+            .foreign_keys
+            .retain(|foreign_key| !foreign_key.columns.iter().any(|column| column == column_name));
+        schema
+            .indexes
+            .retain(|index| !index.columns.iter().any(|column| column == column_name));
+        schema
+    }
+
+    /// Returns the `CREATE TABLE` statement for this schema under another table name.
+    pub fn create_table_sql(&self, table_name: &str) -> String {
+        let single_primary_key = self.primary_key.len() == 1;
+        let mut definitions: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut definition = format!("{} {}", column.name, column.column_type);
+                if single_primary_key && self.primary_key[0] == column.name {
+                    definition.push_str(" PRIMARY KEY");
+                    if self.autoincrement {
+                        definition.push_str(" AUTOINCREMENT");
+                    }
+                }
+                if column.not_null {
+                    definition.push_str(" NOT NULL");
+                }
+                if let Some(default_value) = &column.default_value {
+                    definition.push_str(&format!(" DEFAULT {}", default_value));
+                }
+                definition
+            })
+            .collect();
+
+        if self.primary_key.len() > 1 {
+            definitions.push(format!("PRIMARY KEY ({})", self.primary_key.join(", ")));
+        }
+        for columns in &self.unique_constraints {
+            definitions.push(format!("UNIQUE ({})", columns.join(", ")));
+        }
+        for foreign_key in &self.foreign_keys {
+            let mut definition = format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({})",
+                foreign_key.columns.join(", "),
+                foreign_key.references_table,
+                foreign_key.references_columns.join(", ")
+            );
+            if foreign_key.on_update != "NO ACTION" {
+                definition.push_str(&format!(" ON UPDATE {}", foreign_key.on_update));
+            }
+            if foreign_key.on_delete != "NO ACTION" {
+                definition.push_str(&format!(" ON DELETE {}", foreign_key.on_delete));
+            }
+            definitions.push(definition);
+        }
+
+        format!(
+            "CREATE TABLE {} (\n    {}\n);",
+            table_name,
+            definitions.join(",\n    ")
+        )
+    }
+}
+
+/// ## Name: sqlite_rebuild_sql
+/// ### Description: Returns the SQL that turns a table with the `source` schema into the `target` schema
+/// A new table is created from `target`, every column that exists in both schemas is copied over,
+/// and the new table replaces the old one before the indexes are recreated.
+/// Foreign keys are switched off while the tables are swapped, which SQLite only allows outside
+/// of a transaction, so the SQL opts out of the migration transaction and runs its own.
+pub fn sqlite_rebuild_sql(source: &SqliteTableSchema, target: &SqliteTableSchema) -> String {
+    let new_table = format!("{}_rebuild", target.name);
+    let copied_columns: Vec<&str> = target
+        .columns
+        .iter()
+        .filter(|column| source.has_column(&column.name))
+        .map(|column| column.name.as_str())
+        .collect();
+    let copied_columns = copied_columns.join(", ");
+
+    let mut statements = vec![
+        super::NO_TRANSACTION_MARKER.to_string(),
+        "PRAGMA foreign_keys = OFF;".to_string(),
+        "BEGIN;".to_string(),
+        target.create_table_sql(&new_table),
+        format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {};",
+            new_table, copied_columns, copied_columns, source.name
+        ),
+        format!("DROP TABLE {};", source.name),
+        format!("ALTER TABLE {} RENAME TO {};", new_table, target.name),
+    ];
+    for index in &target.indexes {
+        statements.push(format!("{};", index.sql.trim_end_matches(';')));
+    }
+    statements.push("COMMIT;".to_string());
+    statements.push("PRAGMA foreign_keys = ON;".to_string());
+
+    statements.join("\n") + "\n"
+}
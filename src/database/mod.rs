@@ -1,11 +1,33 @@
+pub mod associations;
 pub mod connect;
 pub mod database;
+pub mod database_error;
+pub mod database_url;
 pub mod databasetype;
 pub mod datatype;
+pub mod environment;
+pub mod lifecycle;
 pub mod migrations;
+pub mod model;
+pub mod mongo;
+pub mod pool;
+pub mod query_builder;
+pub mod seeds;
+pub mod validations;
 
+pub use associations::*;
 pub use connect::*;
 pub use database::*;
+pub use database_error::*;
+pub use database_url::*;
 pub use databasetype::*;
 pub use datatype::*;
+pub use environment::*;
+pub use lifecycle::*;
 pub use migrations::*;
+pub use model::*;
+pub use mongo::*;
+pub use pool::*;
+pub use query_builder::*;
+pub use seeds::*;
+pub use validations::*;
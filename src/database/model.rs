@@ -0,0 +1,254 @@
+use std::future::Future;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::database_error::DatabaseError;
+use super::databasetype::DatabaseType;
+use super::query_builder::{MySqlRow, Order, PgRow, Query, QueryPool, RowReader, SqlValue, SqliteRow};
+
+/// ## Name: Model
+/// ### Description: The ActiveRecord-style functions of a model, stored as one row of a table
+/// Implement it with `#[derive(Model)]`, which reads the table from `#[table_name = "..."]` and
+/// the primary key from the field marked `#[primary_key]`, or `id`. The struct also needs
+/// `sqlx::FromRow` to be read back from the database. A field only some of the databases decode,
+/// e.g. a Postgres array, makes reading the rows fail on the others instead of the build.
+///
+/// Every function takes the shared pool of the application, a `DatabaseConnection` or a
+/// `PoolConnection`, and runs on PostgreSQL, MySQL and SQLite.
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::{PoolConnection, SqlValue};
+/// use rustyroad::Model;
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model)]
+/// #[table_name = "posts"]
+/// pub struct Post {
+///     #[primary_key]
+///     pub id: Option<i64>,
+///     pub title: String,
+///     pub views: i64,
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// sqlx::query("CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, views INTEGER NOT NULL)")
+///     .execute(&pool)
+///     .await
+///     .unwrap();
+/// let pool = PoolConnection::Sqlite(pool);
+///
+/// let mut post = Post { id: None, title: "Hello".to_string(), views: 0 }
+///     .create(&pool)
+///     .await
+///     .unwrap();
+/// assert_eq!(post.id, Some(1));
+///
+/// post.views += 1;
+/// assert!(post.update(&pool).await.unwrap());
+/// assert_eq!(Post::find(&pool, 1).await.unwrap().unwrap().views, 1);
+/// assert_eq!(Post::where_eq(&pool, "title", "Hello").await.unwrap().len(), 1);
+///
+/// assert!(post.destroy(&pool).await.unwrap());
+/// assert_eq!(Post::count(&pool).await.unwrap(), 0);
+/// # });
+/// ```
+pub trait Model: Send + Sync + Unpin + Sized {
+    /// The table the rows are stored in.
+    const TABLE_NAME: &'static str;
+    /// The column of the primary key.
+    const PRIMARY_KEY: &'static str;
+    /// Every column, in the order of the fields.
+    const COLUMNS: &'static [&'static str];
+
+    /// The value of the primary key, `SqlValue::Null` when the database hasn't assigned it yet.
+    fn primary_key_value(&self) -> SqlValue;
+
+    /// The value of every column except the primary key.
+    fn column_values(&self) -> Vec<(&'static str, SqlValue)>;
+
+    #[doc(hidden)]
+    fn from_pg_row(row: &PgRow) -> Result<Self, sqlx::Error>;
+
+    #[doc(hidden)]
+    fn from_mysql_row(row: &MySqlRow) -> Result<Self, sqlx::Error>;
+
+    #[doc(hidden)]
+    fn from_sqlite_row(row: &SqliteRow) -> Result<Self, sqlx::Error>;
+
+    /// Reads the rows of the model, for [`Query::read_all`] and [`Query::read_optional`].
+    fn row_reader() -> RowReader<Self> {
+        RowReader::new(Self::from_pg_row, Self::from_mysql_row, Self::from_sqlite_row)
+    }
+
+    /// A `SELECT` of the columns of the model, to add conditions, joins or an order to.
+    fn query() -> Query {
+        Query::select(Self::TABLE_NAME).columns(
+            Self::COLUMNS
+                .iter()
+                .map(|column| format!("{}.{}", Self::TABLE_NAME, column)),
+        )
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    fn find<P, V>(pool: &P, id: V) -> impl Future<Output = Result<Option<Self>, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+        V: Into<SqlValue>,
+    {
+        let query = Self::query().where_eq(&format!("{}.{}", Self::TABLE_NAME, Self::PRIMARY_KEY), id);
+        async move { query.read_optional(pool, Self::row_reader()).await }
+    }
+
+    /// Returns every row, ordered by primary key.
+    fn all<P>(pool: &P) -> impl Future<Output = Result<Vec<Self>, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let query = Self::query().order_by(Self::PRIMARY_KEY, Order::Asc);
+        async move { query.read_all(pool, Self::row_reader()).await }
+    }
+
+    /// Returns the rows where the column equals the value, ordered by primary key.
+    /// Other conditions are built from [`Model::query`].
+    fn where_eq<P, V>(pool: &P, column: &str, value: V) -> impl Future<Output = Result<Vec<Self>, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+        V: Into<SqlValue>,
+    {
+        let query = Self::query()
+            .where_eq(column, value)
+            .order_by(Self::PRIMARY_KEY, Order::Asc);
+        async move { query.read_all(pool, Self::row_reader()).await }
+    }
+
+    /// Counts the rows of the table.
+    fn count<P>(pool: &P) -> impl Future<Output = Result<i64, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        async move { Query::select(Self::TABLE_NAME).count(pool).await }
+    }
+
+    /// Returns a page of rows ordered by primary key; pages start at 1.
+    fn paginate<P>(pool: &P, page: u64, per_page: u64) -> impl Future<Output = Result<Paginated<Self>, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let query = Self::query()
+            .order_by(Self::PRIMARY_KEY, Order::Asc)
+            .limit(per_page)
+            .offset((page - 1) * per_page);
+        async move {
+            let total = Self::count(pool).await?;
+            let items = query.read_all(pool, Self::row_reader()).await?;
+            Ok(Paginated {
+                items,
+                page,
+                per_page,
+                total,
+                total_pages: (total.max(0) as u64).div_ceil(per_page),
+            })
+        }
+    }
+
+    /// Inserts the row and returns it as stored, with the primary key and defaults the database assigned.
+    /// A `None` primary key is left to the database.
+    fn create<P>(&self, pool: &P) -> impl Future<Output = Result<Self, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let id = self.primary_key_value();
+        let mut insert = Query::insert(Self::TABLE_NAME);
+        if id != SqlValue::Null {
+            insert = insert.value(Self::PRIMARY_KEY, id.clone());
+        }
+        for (column, value) in self.column_values() {
+            insert = insert.value(column, value);
+        }
+        async move {
+            if pool.sql_pool()?.database_type() != DatabaseType::Mysql {
+                return insert
+                    .returning(Self::COLUMNS.iter().copied())
+                    .read_optional(pool, Self::row_reader())
+                    .await?
+                    .ok_or(DatabaseError::Sql(sqlx::Error::RowNotFound));
+            }
+            // MySQL has no RETURNING, read the row back by its id
+            let result = insert.execute(pool).await?;
+            let id = match (id, result.last_insert_id) {
+                (SqlValue::Null, Some(last_insert_id)) => SqlValue::Int(last_insert_id),
+                (id, _) => id,
+            };
+            Self::find(pool, id)
+                .await?
+                .ok_or(DatabaseError::Sql(sqlx::Error::RowNotFound))
+        }
+    }
+
+    /// Saves every column of the row with this primary key, returns whether it exists.
+    fn update<P>(&self, pool: &P) -> impl Future<Output = Result<bool, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let mut update = Query::update(Self::TABLE_NAME);
+        for (column, value) in self.column_values() {
+            update = update.set(column, value);
+        }
+        let update = update.where_eq(Self::PRIMARY_KEY, self.primary_key_value());
+        async move { Ok(update.execute(pool).await?.rows_affected > 0) }
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    fn destroy<P>(&self, pool: &P) -> impl Future<Output = Result<bool, DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let delete = Query::delete(Self::TABLE_NAME).where_eq(Self::PRIMARY_KEY, self.primary_key_value());
+        async move { Ok(delete.execute(pool).await?.rows_affected > 0) }
+    }
+}
+
+/// ## Name: deserialize_some
+/// ### Description: Deserializes a field that is present into `Some`, even when it's `null`
+/// With `#[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]` on an
+/// `Option<Option<T>>`, a missing field is `None` and `null` is `Some(None)`, so an update can
+/// tell a column left alone from a column set to NULL.
+///
+/// ### Example:
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct UpdatePost {
+///     #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+///     summary: Option<Option<String>>,
+/// }
+///
+/// let missing: UpdatePost = serde_json::from_str("{}").unwrap();
+/// assert_eq!(missing.summary, None);
+/// let null: UpdatePost = serde_json::from_str(r#"{"summary": null}"#).unwrap();
+/// assert_eq!(null.summary, Some(None));
+/// ```
+pub fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// ## Name: Paginated
+/// ### Description: A page of rows returned by [`Model::paginate`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// The page, starting at 1.
+    pub page: u64,
+    pub per_page: u64,
+    /// The number of rows of the table.
+    pub total: i64,
+    pub total_pages: u64,
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use sqlx::pool::PoolOptions;
+use sqlx::{MySqlPool, PgPool, SqlitePool};
+use tokio::sync::Mutex;
+
+use super::database::{Database, DatabaseConnection};
+use super::database_error::DatabaseError;
+
+lazy_static! {
+    // One pool per database for the whole process, see Database::shared_pool
+    static ref SHARED_POOLS: Mutex<HashMap<String, DatabaseConnection>> = Mutex::new(HashMap::new());
+}
+
+/// ## Name: PoolSettings
+/// ### Description: The size and timeouts of a connection pool
+/// Read from the `pool_max_connections`, `pool_min_connections`, `pool_acquire_timeout` and
+/// `pool_idle_timeout` settings of rustyroad.toml; the timeouts are in seconds, and an idle
+/// timeout of `0` keeps idle connections open. On MongoDB they size the pool of the driver, and
+/// the acquire timeout is how long it waits to select a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        PoolSettings {
+            max_connections: 10,
+            min_connections: 0,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
+impl PoolSettings {
+    /// ## Name: from_settings
+    /// ### Description: Reads the `pool_*` settings, using the defaults for the ones that aren't set
+    /// #### Parameters:
+    /// - setting: [`Fn(&str) -> Result<Option<String>, DatabaseError>`] - looks up a setting of rustyroad.toml
+    /// - Returns: [`Result<PoolSettings, DatabaseError>`]
+    pub fn from_settings<F>(setting: F) -> Result<PoolSettings, DatabaseError>
+    where
+        F: Fn(&str) -> Result<Option<String>, DatabaseError>,
+    {
+        let number = |key: &str| -> Result<Option<u64>, DatabaseError> {
+            setting(key)?
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|why| DatabaseError::Parse(format!("invalid {}: {}", key, why)))
+                })
+                .transpose()
+        };
+        let count = |key: &str| -> Result<Option<u32>, DatabaseError> {
+            number(key)?
+                .map(|value| {
+                    u32::try_from(value)
+                        .map_err(|why| DatabaseError::Parse(format!("invalid {}: {}", key, why)))
+                })
+                .transpose()
+        };
+
+        let defaults = PoolSettings::default();
+        let settings = PoolSettings {
+            max_connections: count("pool_max_connections")?.unwrap_or(defaults.max_connections),
+            min_connections: count("pool_min_connections")?.unwrap_or(defaults.min_connections),
+            acquire_timeout: number("pool_acquire_timeout")?
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.acquire_timeout),
+            idle_timeout: match number("pool_idle_timeout")? {
+                Some(0) => None,
+                Some(seconds) => Some(Duration::from_secs(seconds)),
+                None => defaults.idle_timeout,
+            },
+        };
+
+        if settings.max_connections == 0 {
+            return Err(DatabaseError::Parse(
+                "pool_max_connections must be at least 1".to_string(),
+            ));
+        }
+        if settings.min_connections > settings.max_connections {
+            return Err(DatabaseError::Parse(format!(
+                "pool_min_connections ({}) is larger than pool_max_connections ({})",
+                settings.min_connections, settings.max_connections
+            )));
+        }
+        Ok(settings)
+    }
+
+    /// ## Name: pool_options
+    /// ### Description: Returns the sqlx pool options with these sizes and timeouts
+    pub fn pool_options<DB: sqlx::Database>(&self) -> PoolOptions<DB> {
+        PoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+    }
+}
+
+impl Database {
+    /// ## Name: shared_pool
+    /// ### Description: Returns the connection pool of the database, built on first use and shared by the whole process
+    /// Every later call for the same database returns a clone of the same pool, so request handlers
+    /// don't open new connections. The pool belongs to the Tokio runtime that built it.
+    /// #### Returns:
+    /// - [`Result<DatabaseConnection, DatabaseError>`]
+    /// ### Example:
+    /// ```rust,no_run
+    /// use rustyroad::database::Database;
+    ///
+    /// # async fn example() -> Result<(), rustyroad::database::DatabaseError> {
+    /// let database = Database::get_database_from_rustyroad_toml()?;
+    /// let pool = database.shared_pool().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shared_pool(&self) -> Result<DatabaseConnection, DatabaseError> {
+        let key = format!(
+            "{}://{}:{}@{}:{}/{}",
+            self.database_type, self.username, self.password, self.host, self.port, self.name
+        );
+
+        // Holding the lock while connecting makes concurrent first calls share one pool
+        let mut pools = SHARED_POOLS.lock().await;
+        if let Some(connection) = pools.get(&key) {
+            return Ok(connection.clone());
+        }
+        let connection = self.create_database_connection().await?;
+        pools.insert(key, connection.clone());
+        Ok(connection)
+    }
+}
+
+impl DatabaseConnection {
+    /// Returns the pool if the database is Postgres.
+    pub fn as_pg(&self) -> Option<&PgPool> {
+        match self {
+            DatabaseConnection::Pg(pool) => Some(pool),
+            _ => None,
+        }
+    }
+
+    /// Returns the pool if the database is MySQL.
+    pub fn as_mysql(&self) -> Option<&MySqlPool> {
+        match self {
+            DatabaseConnection::MySql(pool) => Some(pool),
+            _ => None,
+        }
+    }
+
+    /// Returns the pool if the database is SQLite.
+    pub fn as_sqlite(&self) -> Option<&SqlitePool> {
+        match self {
+            DatabaseConnection::Sqlite(pool) => Some(pool),
+            _ => None,
+        }
+    }
+
+    /// Returns the database if it's MongoDB; the driver pools its connections itself.
+    pub fn as_mongo(&self) -> Option<&mongodb::Database> {
+        match self {
+            DatabaseConnection::Mongo(database) => Some(database),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,1105 @@
+use std::marker::PhantomData;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use sqlx::mysql::MySqlArguments;
+use sqlx::postgres::PgArguments;
+use sqlx::query::{Query as SqlxQuery, QueryAs, QueryScalar};
+use sqlx::sqlite::SqliteArguments;
+#[cfg(feature = "decimal")]
+use sqlx::types::Decimal;
+use sqlx::types::{Json, Uuid};
+use sqlx::{FromRow, MySql, MySqlPool, PgPool, Postgres, Sqlite, SqlitePool};
+// The rows a [`RowReader`] reads, for the code `#[derive(Model)]` generates
+pub use sqlx::mysql::MySqlRow;
+pub use sqlx::postgres::PgRow;
+pub use sqlx::sqlite::SqliteRow;
+
+use super::database::{DatabaseConnection, PoolConnection};
+use super::database_error::DatabaseError;
+use super::databasetype::DatabaseType;
+
+/// ## Name: SqlValue
+/// ### Description: A value bound to a [`Query`], converted from the Rust types generated models use
+/// `NULL` is written into the SQL instead of being bound, so it works for columns of any type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    Timestamp(DateTime<Utc>),
+    Uuid(Uuid),
+    Json(serde_json::Value),
+    /// An unsigned integer too large for `Int`, e.g. of a MySQL `BIGINT UNSIGNED`
+    UInt(u64),
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal),
+    Array(SqlArray),
+}
+
+/// ## Name: SqlArray
+/// ### Description: The elements of a Postgres array bound to a [`Query`]
+/// Postgres only compares arrays of the same element type, so each keeps its Rust type.
+/// MySQL and SQLite have no arrays, there the elements are bound as a JSON array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlArray {
+    Bool(Vec<bool>),
+    SmallInt(Vec<i16>),
+    Int(Vec<i32>),
+    BigInt(Vec<i64>),
+    Real(Vec<f32>),
+    Double(Vec<f64>),
+    Text(Vec<String>),
+    Date(Vec<NaiveDate>),
+    Time(Vec<NaiveTime>),
+    DateTime(Vec<NaiveDateTime>),
+    Timestamp(Vec<DateTime<Utc>>),
+    Uuid(Vec<Uuid>),
+    #[cfg(feature = "decimal")]
+    Decimal(Vec<Decimal>),
+}
+
+impl SqlArray {
+    fn to_json(&self) -> serde_json::Value {
+        let json = match self {
+            SqlArray::Bool(values) => serde_json::to_value(values),
+            SqlArray::SmallInt(values) => serde_json::to_value(values),
+            SqlArray::Int(values) => serde_json::to_value(values),
+            SqlArray::BigInt(values) => serde_json::to_value(values),
+            SqlArray::Real(values) => serde_json::to_value(values),
+            SqlArray::Double(values) => serde_json::to_value(values),
+            SqlArray::Text(values) => serde_json::to_value(values),
+            SqlArray::Date(values) => serde_json::to_value(values),
+            SqlArray::Time(values) => serde_json::to_value(values),
+            SqlArray::DateTime(values) => serde_json::to_value(values),
+            SqlArray::Timestamp(values) => serde_json::to_value(values),
+            SqlArray::Uuid(values) => serde_json::to_value(values),
+            #[cfg(feature = "decimal")]
+            SqlArray::Decimal(values) => serde_json::to_value(values),
+        };
+        // Serializing these types can't fail
+        json.unwrap_or_default()
+    }
+}
+
+macro_rules! sql_value_from {
+    ($($rust_type:ty => $variant:ident $(as $cast:ty)?),* $(,)?) => {
+        $(
+            impl From<$rust_type> for SqlValue {
+                fn from(value: $rust_type) -> Self {
+                    SqlValue::$variant(value $(as $cast)?)
+                }
+            }
+        )*
+    };
+}
+
+sql_value_from! {
+    bool => Bool,
+    i8 => Int as i64,
+    i16 => Int as i64,
+    i32 => Int as i64,
+    i64 => Int,
+    u8 => Int as i64,
+    u16 => Int as i64,
+    u32 => Int as i64,
+    f32 => Float as f64,
+    f64 => Float,
+    String => Text,
+    Vec<u8> => Bytes,
+    NaiveDate => Date,
+    NaiveTime => Time,
+    NaiveDateTime => DateTime,
+    DateTime<Utc> => Timestamp,
+    Uuid => Uuid,
+    serde_json::Value => Json,
+}
+
+#[cfg(feature = "decimal")]
+sql_value_from! {
+    Decimal => Decimal,
+}
+
+impl From<u64> for SqlValue {
+    fn from(value: u64) -> Self {
+        i64::try_from(value).map_or(SqlValue::UInt(value), SqlValue::Int)
+    }
+}
+
+macro_rules! sql_array_from {
+    ($($rust_type:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<Vec<$rust_type>> for SqlValue {
+                fn from(values: Vec<$rust_type>) -> Self {
+                    SqlValue::Array(SqlArray::$variant(values))
+                }
+            }
+        )*
+    };
+}
+
+sql_array_from! {
+    bool => Bool,
+    i16 => SmallInt,
+    i32 => Int,
+    i64 => BigInt,
+    f32 => Real,
+    f64 => Double,
+    String => Text,
+    NaiveDate => Date,
+    NaiveTime => Time,
+    NaiveDateTime => DateTime,
+    DateTime<Utc> => Timestamp,
+    Uuid => Uuid,
+}
+
+#[cfg(feature = "decimal")]
+sql_array_from! {
+    Decimal => Decimal,
+}
+
+impl From<&str> for SqlValue {
+    fn from(value: &str) -> Self {
+        SqlValue::Text(value.to_string())
+    }
+}
+
+impl From<&String> for SqlValue {
+    fn from(value: &String) -> Self {
+        SqlValue::Text(value.clone())
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+/// ## Name: Operator
+/// ### Description: How a `WHERE` condition compares a column with a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+}
+
+impl Operator {
+    fn sql(&self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "<>",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Like => "LIKE",
+        }
+    }
+}
+
+/// ## Name: Order
+/// ### Description: The direction of an `ORDER BY` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinKind {
+    Inner,
+    Left,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(String, Operator, SqlValue),
+    In(String, Vec<SqlValue>),
+    Null(String),
+    NotNull(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Join {
+    kind: JoinKind,
+    table: String,
+    left: String,
+    right: String,
+}
+
+/// ## Name: Query
+/// ### Description: A SQL query that renders the placeholders and quoting of each database
+/// Generated models build their queries with it instead of writing `$1` for Postgres and `?`
+/// for MySQL and SQLite, then run them on the shared pool. Table and column names are quoted
+/// as given, `"posts"."title"` on Postgres and SQLite and `` `posts`.`title` `` on MySQL.
+/// Conditions are combined with `AND`.
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::{DatabaseType, Order, Query, SqlValue};
+///
+/// let query = Query::select("posts")
+///     .columns(["posts.id", "posts.title", "users.name"])
+///     .join("users", "users.id", "posts.author_id")
+///     .where_eq("posts.published", true)
+///     .order_by("posts.created_at", Order::Desc)
+///     .limit(10);
+///
+/// let (sql, values) = query.to_sql(&DatabaseType::Postgres).unwrap();
+/// assert_eq!(
+///     sql,
+///     r#"SELECT "posts"."id", "posts"."title", "users"."name" FROM "posts" INNER JOIN "users" ON "users"."id" = "posts"."author_id" WHERE "posts"."published" = $1 ORDER BY "posts"."created_at" DESC LIMIT 10"#
+/// );
+/// assert_eq!(values, vec![SqlValue::Bool(true)]);
+///
+/// let (sql, _) = Query::update("posts")
+///     .set("title", "Hello")
+///     .where_eq("id", 1)
+///     .to_sql(&DatabaseType::Mysql)
+///     .unwrap();
+/// assert_eq!(sql, "UPDATE `posts` SET `title` = ? WHERE `id` = ?");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    kind: QueryKind,
+    table: String,
+    columns: Vec<String>,
+    values: Vec<(String, SqlValue)>,
+    joins: Vec<Join>,
+    conditions: Vec<Condition>,
+    order: Vec<(String, Order)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    returning: Vec<String>,
+}
+
+/// ## Name: QueryResult
+/// ### Description: What an `INSERT`, `UPDATE` or `DELETE` did
+/// `last_insert_id` is the `AUTO_INCREMENT` id on MySQL and the rowid on SQLite; Postgres
+/// doesn't report it, use [`Query::returning`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryResult {
+    pub rows_affected: u64,
+    pub last_insert_id: Option<i64>,
+}
+
+/// A struct that can be read from a row of every SQL database, e.g. one with `#[derive(sqlx::FromRow)]`.
+pub trait FromAnyRow:
+    for<'r> FromRow<'r, PgRow> + for<'r> FromRow<'r, MySqlRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin
+{
+}
+
+impl<T> FromAnyRow for T where
+    T: for<'r> FromRow<'r, PgRow> + for<'r> FromRow<'r, MySqlRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin
+{
+}
+
+/// ## Name: RowReader
+/// ### Description: How to read a `T` from a row of each SQL database
+/// [`Query::fetch_all`] reads a [`FromAnyRow`] struct with its `FromRow` impls; models read
+/// theirs with [`Model::row_reader`](super::Model::row_reader), since a column type may only
+/// decode on some of the databases.
+pub struct RowReader<T> {
+    pg: fn(&PgRow) -> Result<T, sqlx::Error>,
+    mysql: fn(&MySqlRow) -> Result<T, sqlx::Error>,
+    sqlite: fn(&SqliteRow) -> Result<T, sqlx::Error>,
+}
+
+impl<T> RowReader<T> {
+    pub fn new(
+        pg: fn(&PgRow) -> Result<T, sqlx::Error>,
+        mysql: fn(&MySqlRow) -> Result<T, sqlx::Error>,
+        sqlite: fn(&SqliteRow) -> Result<T, sqlx::Error>,
+    ) -> Self {
+        RowReader { pg, mysql, sqlite }
+    }
+}
+
+impl<T: FromAnyRow> RowReader<T> {
+    /// Reads the rows with the `FromRow` impls of `T`.
+    pub fn from_row() -> Self {
+        RowReader::new(|row| T::from_row(row), |row| T::from_row(row), |row| T::from_row(row))
+    }
+}
+
+impl<T> Clone for RowReader<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RowReader<T> {}
+
+/// Reads a model with its `FromRow` impl for a database, see [`DecodeUnsupported`].
+#[doc(hidden)]
+pub struct RowDecoder<T, R>(PhantomData<fn() -> (T, R)>);
+
+impl<T, R> RowDecoder<T, R> {
+    pub fn new() -> Self {
+        RowDecoder(PhantomData)
+    }
+}
+
+impl<T, R> Default for RowDecoder<T, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Picked over [`DecodeUnsupported`] by `(&&RowDecoder::<T, R>::new()).decode(row)` when `T`
+/// implements `FromRow` for the row.
+#[doc(hidden)]
+pub trait DecodeWithFromRow<T, R> {
+    fn decode(&self, row: &R) -> Result<T, sqlx::Error>;
+}
+
+impl<T, R> DecodeWithFromRow<T, R> for &RowDecoder<T, R>
+where
+    T: for<'r> FromRow<'r, R>,
+    R: sqlx::Row,
+{
+    fn decode(&self, row: &R) -> Result<T, sqlx::Error> {
+        T::from_row(row)
+    }
+}
+
+/// What `#[derive(Model)]` reads on a database that can't decode one of the fields, e.g. a
+/// `Vec<String>` on SQLite: an error when a row is read, so the model still compiles for the
+/// database it's made for.
+#[doc(hidden)]
+pub trait DecodeUnsupported<T, R> {
+    fn decode(&self, row: &R) -> Result<T, sqlx::Error>;
+}
+
+impl<T, R> DecodeUnsupported<T, R> for RowDecoder<T, R> {
+    fn decode(&self, _row: &R) -> Result<T, sqlx::Error> {
+        Err(sqlx::Error::Decode(
+            format!(
+                "{} has a field the database of {} can't decode",
+                std::any::type_name::<T>(),
+                std::any::type_name::<R>()
+            )
+            .into(),
+        ))
+    }
+}
+
+/// ## Name: SqlPool
+/// ### Description: The pool a [`Query`] runs on
+pub enum SqlPool<'a> {
+    Pg(&'a PgPool),
+    MySql(&'a MySqlPool),
+    Sqlite(&'a SqlitePool),
+}
+
+impl SqlPool<'_> {
+    /// The database the pool connects to.
+    pub fn database_type(&self) -> DatabaseType {
+        match self {
+            SqlPool::Pg(_) => DatabaseType::Postgres,
+            SqlPool::MySql(_) => DatabaseType::Mysql,
+            SqlPool::Sqlite(_) => DatabaseType::Sqlite,
+        }
+    }
+}
+
+/// A connection a [`Query`] can run on: a [`PoolConnection`] or the shared [`DatabaseConnection`].
+pub trait QueryPool {
+    /// Returns the SQL pool, or an error for MongoDB.
+    fn sql_pool(&self) -> Result<SqlPool<'_>, DatabaseError>;
+}
+
+impl QueryPool for PoolConnection {
+    fn sql_pool(&self) -> Result<SqlPool<'_>, DatabaseError> {
+        match self {
+            PoolConnection::Pg(pool) => Ok(SqlPool::Pg(pool)),
+            PoolConnection::MySql(pool) => Ok(SqlPool::MySql(pool)),
+            PoolConnection::Sqlite(pool) => Ok(SqlPool::Sqlite(pool)),
+            PoolConnection::Mongo(_) => Err(no_sql_on_mongo()),
+        }
+    }
+}
+
+impl QueryPool for DatabaseConnection {
+    fn sql_pool(&self) -> Result<SqlPool<'_>, DatabaseError> {
+        match self {
+            DatabaseConnection::Pg(pool) => Ok(SqlPool::Pg(pool)),
+            DatabaseConnection::MySql(pool) => Ok(SqlPool::MySql(pool)),
+            DatabaseConnection::Sqlite(pool) => Ok(SqlPool::Sqlite(pool)),
+            DatabaseConnection::Mongo(_) => Err(no_sql_on_mongo()),
+        }
+    }
+}
+
+impl Query {
+    fn new(kind: QueryKind, table: &str) -> Query {
+        Query {
+            kind,
+            table: table.to_string(),
+            columns: Vec::new(),
+            values: Vec::new(),
+            joins: Vec::new(),
+            conditions: Vec::new(),
+            order: Vec::new(),
+            limit: None,
+            offset: None,
+            returning: Vec::new(),
+        }
+    }
+
+    /// Starts a `SELECT` of every column of the table, see [`Query::columns`].
+    pub fn select(table: &str) -> Query {
+        Query::new(QueryKind::Select, table)
+    }
+
+    /// Starts an `INSERT`, the values are added with [`Query::value`].
+    pub fn insert(table: &str) -> Query {
+        Query::new(QueryKind::Insert, table)
+    }
+
+    /// Starts an `UPDATE`, the new values are added with [`Query::set`].
+    pub fn update(table: &str) -> Query {
+        Query::new(QueryKind::Update, table)
+    }
+
+    /// Starts a `DELETE`. Without a condition it deletes every row.
+    pub fn delete(table: &str) -> Query {
+        Query::new(QueryKind::Delete, table)
+    }
+
+    /// Selects these columns instead of `*`; `*` and `table.*` are kept as they are.
+    pub fn columns<I, S>(mut self, columns: I) -> Query
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds a column and its value to an `INSERT`.
+    pub fn value<V: Into<SqlValue>>(mut self, column: &str, value: V) -> Query {
+        self.values.push((column.to_string(), value.into()));
+        self
+    }
+
+    /// Sets a column to a value in an `UPDATE`.
+    pub fn set<V: Into<SqlValue>>(self, column: &str, value: V) -> Query {
+        self.value(column, value)
+    }
+
+    /// Adds `INNER JOIN table ON left = right`.
+    pub fn join(self, table: &str, left: &str, right: &str) -> Query {
+        self.add_join(JoinKind::Inner, table, left, right)
+    }
+
+    /// Adds `LEFT JOIN table ON left = right`.
+    pub fn left_join(self, table: &str, left: &str, right: &str) -> Query {
+        self.add_join(JoinKind::Left, table, left, right)
+    }
+
+    fn add_join(mut self, kind: JoinKind, table: &str, left: &str, right: &str) -> Query {
+        self.joins.push(Join {
+            kind,
+            table: table.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        });
+        self
+    }
+
+    /// Keeps the rows where the column equals the value; a `None` value keeps the rows where it's `NULL`.
+    pub fn where_eq<V: Into<SqlValue>>(self, column: &str, value: V) -> Query {
+        self.where_op(column, Operator::Eq, value)
+    }
+
+    /// Keeps the rows where the column compares to the value with the operator.
+    pub fn where_op<V: Into<SqlValue>>(mut self, column: &str, operator: Operator, value: V) -> Query {
+        let condition = match (value.into(), operator) {
+            (SqlValue::Null, Operator::Eq) => Condition::Null(column.to_string()),
+            (SqlValue::Null, Operator::NotEq) => Condition::NotNull(column.to_string()),
+            (value, operator) => Condition::Compare(column.to_string(), operator, value),
+        };
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Keeps the rows where the column is one of the values; no values keeps no rows.
+    pub fn where_in<I, V>(mut self, column: &str, values: I) -> Query
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<SqlValue>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        self.conditions.push(Condition::In(column.to_string(), values));
+        self
+    }
+
+    /// Keeps the rows where the column is `NULL`.
+    pub fn where_null(mut self, column: &str) -> Query {
+        self.conditions.push(Condition::Null(column.to_string()));
+        self
+    }
+
+    /// Keeps the rows where the column isn't `NULL`.
+    pub fn where_not_null(mut self, column: &str) -> Query {
+        self.conditions.push(Condition::NotNull(column.to_string()));
+        self
+    }
+
+    /// Sorts by the column, after the columns given before.
+    pub fn order_by(mut self, column: &str, order: Order) -> Query {
+        self.order.push((column.to_string(), order));
+        self
+    }
+
+    /// Returns at most this many rows.
+    pub fn limit(mut self, limit: u64) -> Query {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skips this many rows.
+    pub fn offset(mut self, offset: u64) -> Query {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Returns these columns of the inserted, updated or deleted rows. MySQL has no `RETURNING`.
+    pub fn returning<I, S>(mut self, columns: I) -> Query
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.returning.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// ## Name: to_sql
+    /// ### Description: Renders the query for a database, with the values to bind in order
+    /// #### Parameters:
+    /// - database_type: [`&DatabaseType`] - the database the query runs on
+    /// - Returns: [`Result<(String, Vec<SqlValue>), DatabaseError>`] - a [`DatabaseError::Query`] when
+    ///   the query can't run there, e.g. an `INSERT` without values or `RETURNING` on MySQL
+    pub fn to_sql(&self, database_type: &DatabaseType) -> Result<(String, Vec<SqlValue>), DatabaseError> {
+        let mut sql = Sql::new(database_type)?;
+
+        match self.kind {
+            QueryKind::Select => {
+                sql.push("SELECT ");
+                if self.columns.is_empty() {
+                    sql.push("*");
+                } else {
+                    let columns: Vec<String> = self.columns.iter().map(|column| sql.quote(column)).collect();
+                    sql.push(&columns.join(", "));
+                }
+                sql.push(" FROM ");
+                sql.push_identifier(&self.table);
+                for join in &self.joins {
+                    sql.push(match join.kind {
+                        JoinKind::Inner => " INNER JOIN ",
+                        JoinKind::Left => " LEFT JOIN ",
+                    });
+                    sql.push_identifier(&join.table);
+                    sql.push(" ON ");
+                    sql.push_identifier(&join.left);
+                    sql.push(" = ");
+                    sql.push_identifier(&join.right);
+                }
+            }
+            QueryKind::Insert => {
+                if self.values.is_empty() {
+                    return Err(DatabaseError::Query(format!(
+                        "the INSERT into {} has no values",
+                        self.table
+                    )));
+                }
+                sql.push("INSERT INTO ");
+                sql.push_identifier(&self.table);
+                let columns: Vec<String> = self.values.iter().map(|(column, _)| sql.quote(column)).collect();
+                sql.push(&format!(" ({}) VALUES (", columns.join(", ")));
+                for (index, (_, value)) in self.values.iter().enumerate() {
+                    if index > 0 {
+                        sql.push(", ");
+                    }
+                    sql.push_value(value);
+                }
+                sql.push(")");
+            }
+            QueryKind::Update => {
+                if self.values.is_empty() {
+                    return Err(DatabaseError::Query(format!(
+                        "the UPDATE of {} sets no columns",
+                        self.table
+                    )));
+                }
+                sql.push("UPDATE ");
+                sql.push_identifier(&self.table);
+                sql.push(" SET ");
+                for (index, (column, value)) in self.values.iter().enumerate() {
+                    if index > 0 {
+                        sql.push(", ");
+                    }
+                    sql.push_identifier(column);
+                    sql.push(" = ");
+                    sql.push_value(value);
+                }
+            }
+            QueryKind::Delete => {
+                sql.push("DELETE FROM ");
+                sql.push_identifier(&self.table);
+            }
+        }
+
+        self.push_conditions(&mut sql);
+
+        if self.kind == QueryKind::Select {
+            if !self.order.is_empty() {
+                let order: Vec<String> = self
+                    .order
+                    .iter()
+                    .map(|(column, order)| {
+                        let direction = match order {
+                            Order::Asc => "ASC",
+                            Order::Desc => "DESC",
+                        };
+                        format!("{} {}", sql.quote(column), direction)
+                    })
+                    .collect();
+                sql.push(&format!(" ORDER BY {}", order.join(", ")));
+            }
+            match (self.limit, self.offset) {
+                (Some(limit), _) => sql.push(&format!(" LIMIT {}", limit)),
+                // MySQL and SQLite only accept OFFSET after a LIMIT
+                (None, Some(_)) => match database_type {
+                    DatabaseType::Mysql => sql.push(" LIMIT 18446744073709551615"),
+                    DatabaseType::Sqlite => sql.push(" LIMIT -1"),
+                    _ => {}
+                },
+                (None, None) => {}
+            }
+            if let Some(offset) = self.offset {
+                sql.push(&format!(" OFFSET {}", offset));
+            }
+        }
+
+        if !self.returning.is_empty() {
+            if self.kind == QueryKind::Select {
+                return Err(DatabaseError::Query("a SELECT can't have RETURNING".to_string()));
+            }
+            if *database_type == DatabaseType::Mysql {
+                return Err(DatabaseError::Query(
+                    "MySQL has no RETURNING, read QueryResult::last_insert_id instead".to_string(),
+                ));
+            }
+            let columns: Vec<String> = self.returning.iter().map(|column| sql.quote(column)).collect();
+            sql.push(&format!(" RETURNING {}", columns.join(", ")));
+        }
+
+        Ok((sql.sql, sql.values))
+    }
+
+    /// Renders a `SELECT COUNT(*)` of the rows this query selects, without its order, limit and offset.
+    pub fn count_sql(&self, database_type: &DatabaseType) -> Result<(String, Vec<SqlValue>), DatabaseError> {
+        let mut sql = Sql::new(database_type)?;
+        sql.push("SELECT COUNT(*) FROM ");
+        sql.push_identifier(&self.table);
+        for join in &self.joins {
+            sql.push(match join.kind {
+                JoinKind::Inner => " INNER JOIN ",
+                JoinKind::Left => " LEFT JOIN ",
+            });
+            sql.push_identifier(&join.table);
+            sql.push(" ON ");
+            sql.push_identifier(&join.left);
+            sql.push(" = ");
+            sql.push_identifier(&join.right);
+        }
+        self.push_conditions(&mut sql);
+        Ok((sql.sql, sql.values))
+    }
+
+    fn push_conditions(&self, sql: &mut Sql) {
+        for (index, condition) in self.conditions.iter().enumerate() {
+            sql.push(if index == 0 { " WHERE " } else { " AND " });
+            match condition {
+                Condition::Compare(column, operator, value) => {
+                    sql.push_identifier(column);
+                    sql.push(&format!(" {} ", operator.sql()));
+                    sql.push_value(value);
+                }
+                // `IN ()` is a syntax error, and no value matches anyway
+                Condition::In(_, values) if values.is_empty() => sql.push("1 = 0"),
+                Condition::In(column, values) => {
+                    sql.push_identifier(column);
+                    sql.push(" IN (");
+                    for (index, value) in values.iter().enumerate() {
+                        if index > 0 {
+                            sql.push(", ");
+                        }
+                        sql.push_value(value);
+                    }
+                    sql.push(")");
+                }
+                Condition::Null(column) => {
+                    sql.push_identifier(column);
+                    sql.push(" IS NULL");
+                }
+                Condition::NotNull(column) => {
+                    sql.push_identifier(column);
+                    sql.push(" IS NOT NULL");
+                }
+            }
+        }
+    }
+
+    /// ## Name: execute
+    /// ### Description: Runs an `INSERT`, `UPDATE` or `DELETE`
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - Returns: [`Result<QueryResult, DatabaseError>`]
+    pub async fn execute<P: QueryPool>(&self, pool: &P) -> Result<QueryResult, DatabaseError> {
+        let result = match pool.sql_pool()? {
+            SqlPool::Pg(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Postgres)?;
+                let result = bind_pg(sqlx::query(&sql), &values).execute(pool).await?;
+                QueryResult {
+                    rows_affected: result.rows_affected(),
+                    last_insert_id: None,
+                }
+            }
+            SqlPool::MySql(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Mysql)?;
+                let result = bind_mysql(sqlx::query(&sql), &values).execute(pool).await?;
+                QueryResult {
+                    rows_affected: result.rows_affected(),
+                    last_insert_id: i64::try_from(result.last_insert_id()).ok().filter(|id| *id > 0),
+                }
+            }
+            SqlPool::Sqlite(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Sqlite)?;
+                let result = bind_sqlite(sqlx::query(&sql), &values).execute(pool).await?;
+                QueryResult {
+                    rows_affected: result.rows_affected(),
+                    last_insert_id: Some(result.last_insert_rowid()).filter(|id| *id > 0),
+                }
+            }
+        };
+        Ok(result)
+    }
+
+    /// ## Name: fetch_all
+    /// ### Description: Runs the query and reads every row it returns
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - Returns: [`Result<Vec<T>, DatabaseError>`]
+    pub async fn fetch_all<T: FromAnyRow, P: QueryPool>(&self, pool: &P) -> Result<Vec<T>, DatabaseError> {
+        self.read_all(pool, RowReader::from_row()).await
+    }
+
+    /// ## Name: read_all
+    /// ### Description: Runs the query and reads every row it returns with the reader
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - reader: [`RowReader<T>`] - how to read a row of each database
+    /// - Returns: [`Result<Vec<T>, DatabaseError>`]
+    pub async fn read_all<T: Send + Unpin, P: QueryPool>(
+        &self,
+        pool: &P,
+        reader: RowReader<T>,
+    ) -> Result<Vec<T>, DatabaseError> {
+        let rows = match pool.sql_pool()? {
+            SqlPool::Pg(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Postgres)?;
+                bind_pg(sqlx::query(&sql), &values)
+                    .try_map(|row: PgRow| (reader.pg)(&row))
+                    .fetch_all(pool)
+                    .await?
+            }
+            SqlPool::MySql(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Mysql)?;
+                bind_mysql(sqlx::query(&sql), &values)
+                    .try_map(|row: MySqlRow| (reader.mysql)(&row))
+                    .fetch_all(pool)
+                    .await?
+            }
+            SqlPool::Sqlite(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Sqlite)?;
+                bind_sqlite(sqlx::query(&sql), &values)
+                    .try_map(|row: SqliteRow| (reader.sqlite)(&row))
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
+        Ok(rows)
+    }
+
+    /// ## Name: fetch_optional
+    /// ### Description: Runs the query and reads the first row it returns, if any
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - Returns: [`Result<Option<T>, DatabaseError>`]
+    pub async fn fetch_optional<T: FromAnyRow, P: QueryPool>(&self, pool: &P) -> Result<Option<T>, DatabaseError> {
+        self.read_optional(pool, RowReader::from_row()).await
+    }
+
+    /// ## Name: read_optional
+    /// ### Description: Runs the query and reads the first row it returns with the reader, if any
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - reader: [`RowReader<T>`] - how to read a row of each database
+    /// - Returns: [`Result<Option<T>, DatabaseError>`]
+    pub async fn read_optional<T: Send + Unpin, P: QueryPool>(
+        &self,
+        pool: &P,
+        reader: RowReader<T>,
+    ) -> Result<Option<T>, DatabaseError> {
+        let row = match pool.sql_pool()? {
+            SqlPool::Pg(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Postgres)?;
+                bind_pg(sqlx::query(&sql), &values)
+                    .try_map(|row: PgRow| (reader.pg)(&row))
+                    .fetch_optional(pool)
+                    .await?
+            }
+            SqlPool::MySql(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Mysql)?;
+                bind_mysql(sqlx::query(&sql), &values)
+                    .try_map(|row: MySqlRow| (reader.mysql)(&row))
+                    .fetch_optional(pool)
+                    .await?
+            }
+            SqlPool::Sqlite(pool) => {
+                let (sql, values) = self.to_sql(&DatabaseType::Sqlite)?;
+                bind_sqlite(sqlx::query(&sql), &values)
+                    .try_map(|row: SqliteRow| (reader.sqlite)(&row))
+                    .fetch_optional(pool)
+                    .await?
+            }
+        };
+        Ok(row)
+    }
+
+    /// ## Name: fetch_one
+    /// ### Description: Runs the query and reads the first row it returns
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - Returns: [`Result<T, DatabaseError>`] - a [`DatabaseError::Sql`] with `RowNotFound` when there is no row
+    pub async fn fetch_one<T: FromAnyRow, P: QueryPool>(&self, pool: &P) -> Result<T, DatabaseError> {
+        self.fetch_optional(pool)
+            .await?
+            .ok_or(DatabaseError::Sql(sqlx::Error::RowNotFound))
+    }
+
+    /// ## Name: count
+    /// ### Description: Counts the rows the query selects, see [`Query::count_sql`]
+    /// #### Parameters:
+    /// - pool: [`&impl QueryPool`] - a [`PoolConnection`] or [`DatabaseConnection`]
+    /// - Returns: [`Result<i64, DatabaseError>`]
+    pub async fn count<P: QueryPool>(&self, pool: &P) -> Result<i64, DatabaseError> {
+        let count = match pool.sql_pool()? {
+            SqlPool::Pg(pool) => {
+                let (sql, values) = self.count_sql(&DatabaseType::Postgres)?;
+                bind_pg(sqlx::query_scalar(&sql), &values).fetch_one(pool).await?
+            }
+            SqlPool::MySql(pool) => {
+                let (sql, values) = self.count_sql(&DatabaseType::Mysql)?;
+                bind_mysql(sqlx::query_scalar(&sql), &values).fetch_one(pool).await?
+            }
+            SqlPool::Sqlite(pool) => {
+                let (sql, values) = self.count_sql(&DatabaseType::Sqlite)?;
+                bind_sqlite(sqlx::query_scalar(&sql), &values).fetch_one(pool).await?
+            }
+        };
+        Ok(count)
+    }
+}
+
+/// The SQL of a query being rendered, and the values of its placeholders.
+struct Sql {
+    database_type: DatabaseType,
+    sql: String,
+    values: Vec<SqlValue>,
+}
+
+impl Sql {
+    fn new(database_type: &DatabaseType) -> Result<Sql, DatabaseError> {
+        if *database_type == DatabaseType::Mongo {
+            return Err(no_sql_on_mongo());
+        }
+        Ok(Sql {
+            database_type: database_type.clone(),
+            sql: String::new(),
+            values: Vec::new(),
+        })
+    }
+
+    fn push(&mut self, sql: &str) {
+        self.sql.push_str(sql);
+    }
+
+    fn push_identifier(&mut self, identifier: &str) {
+        let quoted = self.quote(identifier);
+        self.sql.push_str(&quoted);
+    }
+
+    /// Quotes each part of `table.column`, `*` stays as it is.
+    fn quote(&self, identifier: &str) -> String {
+        let quote = match self.database_type {
+            DatabaseType::Mysql => '`',
+            _ => '"',
+        };
+        identifier
+            .split('.')
+            .map(|part| {
+                if part == "*" {
+                    part.to_string()
+                } else {
+                    let escaped = part.replace(quote, &format!("{}{}", quote, quote));
+                    format!("{}{}{}", quote, escaped, quote)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn push_value(&mut self, value: &SqlValue) {
+        if *value == SqlValue::Null {
+            self.sql.push_str("NULL");
+            return;
+        }
+        self.values.push(value.clone());
+        match self.database_type {
+            DatabaseType::Postgres => self.sql.push_str(&format!("${}", self.values.len())),
+            _ => self.sql.push('?'),
+        }
+    }
+}
+
+fn no_sql_on_mongo() -> DatabaseError {
+    DatabaseError::Query("MongoDB has no SQL, use the collection of the model instead".to_string())
+}
+
+/// Binds the values of a query, one function per database since each has its own argument type.
+/// The last arguments bind the values each database stores its own way.
+macro_rules! bind_values {
+    ($name:ident, $database:ty, $arguments:ty, $($(#[$meta:meta])* $value:pat => $bind:expr),* $(,)?) => {
+        fn $name<'q, Q: BindValue<'q, $database>>(mut query: Q, values: &'q [SqlValue]) -> Q {
+            for value in values {
+                query = match value {
+                    // NULL is written into the SQL, see Sql::push_value
+                    SqlValue::Null => query,
+                    SqlValue::Bool(value) => query.bind_value(*value),
+                    SqlValue::Int(value) => query.bind_value(*value),
+                    SqlValue::Float(value) => query.bind_value(*value),
+                    SqlValue::Text(value) => query.bind_value(value.as_str()),
+                    SqlValue::Bytes(value) => query.bind_value(value.as_slice()),
+                    SqlValue::Date(value) => query.bind_value(*value),
+                    SqlValue::Time(value) => query.bind_value(*value),
+                    SqlValue::DateTime(value) => query.bind_value(*value),
+                    SqlValue::Timestamp(value) => query.bind_value(*value),
+                    SqlValue::Uuid(value) => query.bind_value(*value),
+                    SqlValue::Json(value) => query.bind_value(Json(value)),
+                    $($(#[$meta])* $value => $bind(query),)*
+                };
+            }
+            query
+        }
+
+        impl<'q> BindValue<'q, $database> for SqlxQuery<'q, $database, $arguments> {
+            fn bind_value<T>(self, value: T) -> Self
+            where
+                T: 'q + sqlx::Encode<'q, $database> + sqlx::Type<$database>,
+            {
+                self.bind(value)
+            }
+        }
+
+        impl<'q, O> BindValue<'q, $database> for QueryAs<'q, $database, O, $arguments> {
+            fn bind_value<T>(self, value: T) -> Self
+            where
+                T: 'q + sqlx::Encode<'q, $database> + sqlx::Type<$database>,
+            {
+                self.bind(value)
+            }
+        }
+
+        impl<'q, O> BindValue<'q, $database> for QueryScalar<'q, $database, O, $arguments> {
+            fn bind_value<T>(self, value: T) -> Self
+            where
+                T: 'q + sqlx::Encode<'q, $database> + sqlx::Type<$database>,
+            {
+                self.bind(value)
+            }
+        }
+    };
+}
+
+/// The `bind` shared by sqlx's `query`, `query_as` and `query_scalar`.
+trait BindValue<'q, DB: sqlx::Database>: Sized {
+    fn bind_value<T>(self, value: T) -> Self
+    where
+        T: 'q + sqlx::Encode<'q, DB> + sqlx::Type<DB>;
+}
+
+bind_values!(
+    bind_pg, Postgres, PgArguments,
+    // Postgres has no unsigned integers, only MySQL models have a u64
+    SqlValue::UInt(value) => |query: Q| query.bind_value(value.to_string()),
+    #[cfg(feature = "decimal")]
+    SqlValue::Decimal(value) => |query: Q| query.bind_value(*value),
+    SqlValue::Array(array) => |query: Q| bind_pg_array(query, array),
+);
+bind_values!(
+    bind_mysql, MySql, MySqlArguments,
+    SqlValue::UInt(value) => |query: Q| query.bind_value(*value),
+    #[cfg(feature = "decimal")]
+    SqlValue::Decimal(value) => |query: Q| query.bind_value(*value),
+    SqlValue::Array(array) => |query: Q| query.bind_value(Json(array.to_json())),
+);
+bind_values!(
+    bind_sqlite, Sqlite, SqliteArguments<'q>,
+    // SQLite converts the text by the affinity of the column
+    SqlValue::UInt(value) => |query: Q| query.bind_value(value.to_string()),
+    #[cfg(feature = "decimal")]
+    SqlValue::Decimal(value) => |query: Q| query.bind_value(value.to_string()),
+    SqlValue::Array(array) => |query: Q| query.bind_value(Json(array.to_json())),
+);
+
+fn bind_pg_array<'q, Q: BindValue<'q, Postgres>>(query: Q, array: &'q SqlArray) -> Q {
+    match array {
+        SqlArray::Bool(values) => query.bind_value(values.as_slice()),
+        SqlArray::SmallInt(values) => query.bind_value(values.as_slice()),
+        SqlArray::Int(values) => query.bind_value(values.as_slice()),
+        SqlArray::BigInt(values) => query.bind_value(values.as_slice()),
+        SqlArray::Real(values) => query.bind_value(values.as_slice()),
+        SqlArray::Double(values) => query.bind_value(values.as_slice()),
+        SqlArray::Text(values) => query.bind_value(values.as_slice()),
+        SqlArray::Date(values) => query.bind_value(values.as_slice()),
+        SqlArray::Time(values) => query.bind_value(values.as_slice()),
+        SqlArray::DateTime(values) => query.bind_value(values.as_slice()),
+        SqlArray::Timestamp(values) => query.bind_value(values.as_slice()),
+        SqlArray::Uuid(values) => query.bind_value(values.as_slice()),
+        #[cfg(feature = "decimal")]
+        SqlArray::Decimal(values) => query.bind_value(values.as_slice()),
+    }
+}
@@ -0,0 +1,731 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::Local;
+use futures_util::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+use sqlx::Executor;
+
+use crate::database::migrations::schema_migrations::mongo_collection_exists;
+use crate::database::migrations::{
+    checksum_sql, connect_to_project_database, CustomMigrationError, MigrationError,
+};
+use crate::database::{DatabaseConnection, Environment, ENVIRONMENT_VARIABLE};
+
+/// The directory, relative to the project root, that holds every seed file.
+/// Files directly in it run in every environment, files in `seeders/dev`, `seeders/test`
+/// and `seeders/prod` only in that environment.
+pub const SEEDERS_DIR: &str = "./seeders";
+
+/// The table that records which seeds have been run against a database.
+pub const SCHEMA_SEEDS_TABLE: &str = "schema_seeds";
+
+/// ## Name: SeedKind
+/// ### Description: Whether a seed is a SQL file or a Rust program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedKind {
+    /// A `.sql` file, run in a transaction together with its `schema_seeds` record.
+    /// MongoDB projects use Rust seeds only.
+    Sql,
+    /// A `.rs` file, registered as a `[[bin]]` of the project and run with `cargo run`.
+    Rust,
+}
+
+impl SeedKind {
+    /// Returns the file extension of the seed kind.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SeedKind::Sql => "sql",
+            SeedKind::Rust => "rs",
+        }
+    }
+}
+
+/// ## Name: Seed
+/// ### Description: A seed file on disk, split into its version and name
+/// Seed files are named `{timestamp}-{name}.sql` or `{timestamp}-{name}.rs`, and run in
+/// the order of their timestamps, whichever directory they are in.
+/// #### Fields:
+/// - version: [`String`] - the timestamp prefix of the file name
+/// - name: [`String`] - the rest of the file name, without the extension
+/// - kind: [`SeedKind`] - SQL or Rust
+/// - path: [`PathBuf`] - the path to the seed file
+/// - environment: [`Option<Environment>`] - the only environment the seed runs in, `None` for every environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seed {
+    pub version: String,
+    pub name: String,
+    pub kind: SeedKind,
+    pub path: PathBuf,
+    pub environment: Option<Environment>,
+}
+
+impl Seed {
+    /// ## Name: from_file
+    /// ### Description: Builds a seed from the path of a seed file
+    /// Returns `None` when the file isn't a `.sql` or `.rs` file or its name doesn't start with a timestamp.
+    /// ### Example:
+    /// ```rust
+    /// use rustyroad::database::{Environment, Seed, SeedKind};
+    ///
+    /// let seed = Seed::from_file("seeders/dev/20240101120000-demo_posts.sql", Some(Environment::Development)).unwrap();
+    /// assert_eq!(seed.version, "20240101120000");
+    /// assert_eq!(seed.name, "demo_posts");
+    /// assert_eq!(seed.kind, SeedKind::Sql);
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P, environment: Option<Environment>) -> Option<Seed> {
+        let path = path.as_ref();
+        let kind = match path.extension()?.to_str()? {
+            "sql" => SeedKind::Sql,
+            "rs" => SeedKind::Rust,
+            _ => return None,
+        };
+        let file_stem = path.file_stem()?.to_str()?;
+        let split_at = file_stem.find(|c: char| !c.is_ascii_digit())?;
+        let (version, rest) = file_stem.split_at(split_at);
+        let name = rest.strip_prefix(['-', '_'])?;
+
+        if version.is_empty() || name.is_empty() {
+            return None;
+        }
+
+        Some(Seed {
+            version: version.to_string(),
+            name: name.to_string(),
+            kind,
+            path: path.to_path_buf(),
+            environment,
+        })
+    }
+
+    /// Returns the file name, e.g. `20240101120000-demo_posts.sql`.
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{}-{}.{}", self.version, self.name, self.kind.extension()))
+    }
+
+    /// Returns the name of the cargo binary a Rust seed is built as, e.g. `seed-20240101120000-demo_posts`.
+    pub fn bin_name(&self) -> String {
+        format!("seed-{}-{}", self.version, self.name)
+    }
+
+    /// Returns the SHA-256 of the seed file, stored in `schema_seeds` when the seed is run.
+    pub fn checksum(&self) -> Result<String, io::Error> {
+        Ok(checksum_sql(&fs::read_to_string(&self.path)?))
+    }
+}
+
+/// ## Name: get_seeds
+/// ### Description: Lists the seeds that run in an environment, oldest first
+/// These are the files directly in the seeders directory plus the files of the environment's
+/// own subdirectory. A missing seeders directory has no seeds.
+/// #### Parameters:
+/// - seeders_dir_path: [`&str`] - the path to the seeders directory
+/// - environment: [`Environment`] - the environment being seeded
+/// - Returns: [`Result<Vec<Seed>, std::io::Error>`]
+pub fn get_seeds(seeders_dir_path: &str, environment: Environment) -> Result<Vec<Seed>, io::Error> {
+    let seeders_dir = Path::new(seeders_dir_path);
+    let mut seeds = Vec::new();
+    read_seed_dir(seeders_dir, None, &mut seeds)?;
+    read_seed_dir(
+        &seeders_dir.join(environment.short_name()),
+        Some(environment),
+        &mut seeds,
+    )?;
+
+    seeds.sort_by(|a, b| a.version.cmp(&b.version).then(a.name.cmp(&b.name)));
+
+    Ok(seeds)
+}
+
+fn read_seed_dir(
+    dir: &Path,
+    environment: Option<Environment>,
+    seeds: &mut Vec<Seed>,
+) -> Result<(), io::Error> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_seed_file = path.is_file()
+            && matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("sql") | Some("rs")
+            );
+        if !is_seed_file {
+            continue;
+        }
+        match Seed::from_file(&path, environment) {
+            Some(seed) => seeds.push(seed),
+            None => println!(
+                "Skipping {:?}: seed files must be named <timestamp>-<name>.sql or <timestamp>-<name>.rs",
+                path
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// ## Name: AppliedSeed
+/// ### Description: A row of the `schema_seeds` table
+/// #### Fields:
+/// - version: [`String`] - the timestamp of the seed file
+/// - name: [`String`] - the name of the seed
+/// - environment: [`String`] - the environment the seed was run in
+/// - checksum: [`String`] - the SHA-256 of the seed file when it was run
+/// - applied_at: [`String`] - when the seed was run
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, Deserialize)]
+pub struct AppliedSeed {
+    pub version: String,
+    pub name: String,
+    pub environment: String,
+    pub checksum: String,
+    pub applied_at: String,
+}
+
+/// ## Name: ensure_schema_seeds_table
+/// ### Description: Creates the `schema_seeds` table if it doesn't exist yet
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<(), MigrationError>`]
+pub async fn ensure_schema_seeds_table(connection: &DatabaseConnection) -> Result<(), MigrationError> {
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            pool.execute(
+                "CREATE TABLE IF NOT EXISTS schema_seeds (
+    version VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    environment VARCHAR(32) NOT NULL,
+    checksum VARCHAR(64) NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (version, name)
+);",
+            )
+            .await?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            pool.execute(
+                "CREATE TABLE IF NOT EXISTS schema_seeds (
+    version VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    environment VARCHAR(32) NOT NULL,
+    checksum VARCHAR(64) NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (version, name)
+);",
+            )
+            .await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            pool.execute(
+                "CREATE TABLE IF NOT EXISTS schema_seeds (
+    version TEXT NOT NULL,
+    name TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (version, name)
+);",
+            )
+            .await?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            if !mongo_collection_exists(database, SCHEMA_SEEDS_TABLE).await? {
+                database.create_collection(SCHEMA_SEEDS_TABLE).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// ## Name: get_applied_seeds
+/// ### Description: Returns every seed that was run against the database, oldest first
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<Vec<AppliedSeed>, MigrationError>`]
+pub async fn get_applied_seeds(
+    connection: &DatabaseConnection,
+) -> Result<Vec<AppliedSeed>, MigrationError> {
+    let applied = match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query_as::<_, AppliedSeed>(
+                "SELECT version, name, environment, checksum, CAST(applied_at AS TEXT) AS applied_at
+FROM schema_seeds ORDER BY version, name",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query_as::<_, AppliedSeed>(
+                "SELECT version, name, environment, checksum, CAST(applied_at AS CHAR) AS applied_at
+FROM schema_seeds ORDER BY version, name",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query_as::<_, AppliedSeed>(
+                "SELECT version, name, environment, checksum, applied_at FROM schema_seeds ORDER BY version, name",
+            )
+            .fetch_all(pool.as_ref())
+            .await?
+        }
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection::<AppliedSeed>(SCHEMA_SEEDS_TABLE)
+                .find(doc! {})
+                .sort(doc! { "version": 1, "name": 1 })
+                .await?
+                .try_collect()
+                .await?
+        }
+    };
+    Ok(applied)
+}
+
+/// ## Name: clear_seed_records
+/// ### Description: Forgets every seed that was run, so `rustyroad db seed` runs them all again
+/// Called once every migration was rolled back, which also removed the seeded rows.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - Returns: [`Result<(), MigrationError>`]
+pub async fn clear_seed_records(connection: &DatabaseConnection) -> Result<(), MigrationError> {
+    ensure_schema_seeds_table(connection).await?;
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            pool.execute("DELETE FROM schema_seeds").await?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            pool.execute("DELETE FROM schema_seeds").await?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            pool.execute("DELETE FROM schema_seeds").await?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            database
+                .collection::<Document>(SCHEMA_SEEDS_TABLE)
+                .delete_many(doc! {})
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// ## Name: run_seeds
+/// ### Description: Runs the seeds of the current project that haven't been run yet
+/// #### Parameters:
+/// - environment: [`Environment`] - the environment being seeded
+/// - Returns: [`Result<(), CustomMigrationError>`]
+/// ### Example:
+/// ```rust,no_run
+/// use rustyroad::database::{run_seeds, Environment};
+///
+/// # async fn example() {
+/// run_seeds(Environment::Development).await.expect("Error seeding the database");
+/// # }
+/// ```
+pub async fn run_seeds(environment: Environment) -> Result<(), CustomMigrationError> {
+    let connection = connect_to_project_database().await?;
+    let seeded = run_seeds_with_connection(&connection, SEEDERS_DIR, environment).await?;
+
+    if seeded.is_empty() {
+        println!("Nothing to seed for the {} environment", environment);
+    } else {
+        println!(
+            "{} seed(s) run for the {} environment",
+            seeded.len(),
+            environment
+        );
+    }
+    Ok(())
+}
+
+/// ## Name: run_seeds_with_connection
+/// ### Description: Runs the seeds of a seeders directory that haven't been run against a database yet
+/// Every seed runs once per database: it is recorded in `schema_seeds` by its version and name
+/// and skipped from then on, so seeds created in the same second don't hide each other.
+/// A SQL seed runs in a transaction together with its record, so a failing seed leaves no rows behind.
+/// #### Parameters:
+/// - connection: [`&DatabaseConnection`] - the connection pool of the project database
+/// - seeders_dir_path: [`&str`] - the path to the seeders directory
+/// - environment: [`Environment`] - the environment being seeded
+/// - Returns: [`Result<Vec<Seed>, CustomMigrationError>`] - the seeds that were run
+pub async fn run_seeds_with_connection(
+    connection: &DatabaseConnection,
+    seeders_dir_path: &str,
+    environment: Environment,
+) -> Result<Vec<Seed>, CustomMigrationError> {
+    let seeds = get_seeds(seeders_dir_path, environment).map_err(CustomMigrationError::IoError)?;
+
+    ensure_schema_seeds_table(connection).await?;
+    let applied = get_applied_seeds(connection).await?;
+
+    let mut seeded = Vec::new();
+    for seed in seeds {
+        let checksum = seed.checksum().map_err(CustomMigrationError::IoError)?;
+        if let Some(applied) = applied
+            .iter()
+            .find(|applied| applied.version == seed.version && applied.name == seed.name) {
+            if applied.checksum != checksum {
+                println!(
+                    "Seed '{}' changed since it was run on {}, it won't run again until the seeds are reset",
+                    seed.file_name(),
+                    applied.applied_at
+                );
+            }
+            continue;
+        }
+
+        println!("Running seed: {}", seed.file_name());
+        let result = match seed.kind {
+            SeedKind::Sql => run_sql_seed(connection, &seed, &checksum, environment).await,
+            SeedKind::Rust => run_rust_seed(connection, &seed, &checksum, environment).await,
+        };
+        if let Err(why) = result {
+            eprintln!("Seed '{}' failed: {}", seed.file_name(), why);
+            return Err(why);
+        }
+        seeded.push(seed);
+    }
+
+    Ok(seeded)
+}
+
+/// Returns the statement that records a seed, in the dialect of the connection.
+fn record_seed_sql(connection: &DatabaseConnection) -> &'static str {
+    match connection {
+        DatabaseConnection::Pg(_) => {
+            "INSERT INTO schema_seeds (version, name, environment, checksum) VALUES ($1, $2, $3, $4)"
+        }
+        DatabaseConnection::MySql(_) | DatabaseConnection::Sqlite(_) => {
+            "INSERT INTO schema_seeds (version, name, environment, checksum) VALUES (?, ?, ?, ?)"
+        }
+        // Recorded with insert_one, see record_mongo_seed
+        DatabaseConnection::Mongo(_) => "",
+    }
+}
+
+/// Records a seed that was run against a MongoDB database.
+async fn record_mongo_seed(
+    database: &mongodb::Database,
+    seed: &Seed,
+    checksum: &str,
+    environment: &str,
+) -> Result<(), MigrationError> {
+    database
+        .collection::<Document>(SCHEMA_SEEDS_TABLE)
+        .insert_one(doc! {
+            "version": &seed.version,
+            "name": &seed.name,
+            "environment": environment,
+            "checksum": checksum,
+            "applied_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+        .await?;
+    Ok(())
+}
+
+async fn run_sql_seed(
+    connection: &DatabaseConnection,
+    seed: &Seed,
+    checksum: &str,
+    environment: Environment,
+) -> Result<(), CustomMigrationError> {
+    let sql = fs::read_to_string(&seed.path).map_err(CustomMigrationError::IoError)?;
+    let record_sql = record_seed_sql(connection);
+    let environment = environment.to_string();
+
+    // Seeds only insert data, which MySQL can roll back too
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            let mut transaction = pool.begin().await.map_err(MigrationError::from)?;
+            (&mut *transaction)
+                .execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(&mut *transaction)
+                .await
+                .map_err(MigrationError::from)?;
+            transaction.commit().await.map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            let mut transaction = pool.begin().await.map_err(MigrationError::from)?;
+            (&mut *transaction)
+                .execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(&mut *transaction)
+                .await
+                .map_err(MigrationError::from)?;
+            transaction.commit().await.map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            let mut transaction = pool.begin().await.map_err(MigrationError::from)?;
+            (&mut *transaction)
+                .execute(sql.as_str())
+                .await
+                .map_err(MigrationError::from)?;
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(&mut *transaction)
+                .await
+                .map_err(MigrationError::from)?;
+            transaction.commit().await.map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Mongo(_) => {
+            return Err(CustomMigrationError::IoError(io::Error::new(
+                ErrorKind::Unsupported,
+                format!(
+                    "'{}' is a SQL seed, seed a MongoDB database with a Rust seed: rustyroad generate seed {} --rust",
+                    seed.file_name(),
+                    seed.name
+                ),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Runs a Rust seed with `cargo run`, then records it.
+/// The seed connects to the database itself, so it can't share a transaction with its record.
+async fn run_rust_seed(
+    connection: &DatabaseConnection,
+    seed: &Seed,
+    checksum: &str,
+    environment: Environment,
+) -> Result<(), CustomMigrationError> {
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", &seed.bin_name()])
+        .env(ENVIRONMENT_VARIABLE, environment.short_name())
+        .status()
+        .map_err(CustomMigrationError::IoError)?;
+    if !status.success() {
+        return Err(CustomMigrationError::RunError(
+            format!(
+                "`cargo run --bin {}` exited with {}. Rust seeds must be registered as a [[bin]] in Cargo.toml, see `rustyroad generate seed --rust`",
+                seed.bin_name(),
+                status
+            )
+            .into(),
+        ));
+    }
+
+    let record_sql = record_seed_sql(connection);
+    let environment = environment.to_string();
+    match connection {
+        DatabaseConnection::Pg(pool) => {
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(pool.as_ref())
+                .await
+                .map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::MySql(pool) => {
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(pool.as_ref())
+                .await
+                .map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Sqlite(pool) => {
+            sqlx::query(record_sql)
+                .bind(&seed.version)
+                .bind(&seed.name)
+                .bind(&environment)
+                .bind(checksum)
+                .execute(pool.as_ref())
+                .await
+                .map_err(MigrationError::from)?;
+        }
+        DatabaseConnection::Mongo(database) => {
+            record_mongo_seed(database, seed, checksum, &environment).await?;
+        }
+    }
+    Ok(())
+}
+
+/// ## Name: create_seed
+/// ### Description: Scaffolds a seed file in the seeders directory of the current project
+/// A Rust seed is also registered as a `[[bin]]` in the project's Cargo.toml, which is how
+/// `rustyroad db seed` runs it.
+/// #### Parameters:
+/// - name: [`&str`] - the name of the seed, e.g. `demo_posts`
+/// - environment: [`Option<Environment>`] - the only environment the seed runs in, `None` for every environment
+/// - kind: [`SeedKind`] - SQL or Rust
+/// - Returns: [`Result<Seed, std::io::Error>`] - the new seed
+pub fn create_seed(
+    name: &str,
+    environment: Option<Environment>,
+    kind: SeedKind,
+) -> Result<Seed, io::Error> {
+    if fs::read_to_string("rustyroad.toml").is_err() {
+        return Err(io::Error::other(
+            "Error reading the rustyroad.toml, please see the documentation for more information.",
+        ));
+    }
+
+    let seed = create_seed_file(SEEDERS_DIR, name, environment, kind)?;
+    if kind == SeedKind::Rust {
+        register_rust_seed("Cargo.toml", &seed)?;
+    }
+    println!("Created seed: {}", seed.path.display());
+    Ok(seed)
+}
+
+/// ## Name: create_seed_file
+/// ### Description: Writes a new seed file, named after the current time, to a seeders directory
+/// #### Parameters:
+/// - seeders_dir_path: [`&str`] - the path to the seeders directory
+/// - name: [`&str`] - the name of the seed, letters, digits and underscores only
+/// - environment: [`Option<Environment>`] - the only environment the seed runs in, `None` for every environment
+/// - kind: [`SeedKind`] - SQL or Rust
+/// - Returns: [`Result<Seed, std::io::Error>`] - the new seed
+pub fn create_seed_file(
+    seeders_dir_path: &str,
+    name: &str,
+    environment: Option<Environment>,
+    kind: SeedKind,
+) -> Result<Seed, io::Error> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Invalid seed name '{}', use letters, digits and underscores only",
+                name
+            ),
+        ));
+    }
+
+    let mut dir = PathBuf::from(seeders_dir_path);
+    if let Some(environment) = environment {
+        dir = dir.join(environment.short_name());
+    }
+    fs::create_dir_all(&dir)?;
+
+    let file_name = format!(
+        "{}-{}.{}",
+        Local::now().format("%Y%m%d%H%M%S"),
+        name,
+        kind.extension()
+    );
+    let seed = Seed::from_file(dir.join(file_name), environment).ok_or_else(|| {
+        io::Error::new(ErrorKind::InvalidInput, format!("Invalid seed name '{}'", name))
+    })?;
+    if seed.path.exists() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", seed.path.display()),
+        ));
+    }
+
+    let runs_in = match environment {
+        Some(environment) => format!("the {} environment", environment),
+        None => "every environment".to_string(),
+    };
+    let contents = match kind {
+        SeedKind::Sql => format!(
+            "-- Seed: {name}
+-- Runs once per database in {runs_in}, with `rustyroad db seed`.
+-- Write the INSERT statements of the seed data here, e.g.
+-- INSERT INTO posts (title, body) VALUES ('Hello', 'The first post');
+"
+        ),
+        SeedKind::Rust => format!(
+            "//! Seed: {name}
+//! Runs once per database in {runs_in}, with `rustyroad db seed`.
+//! `RUSTYROAD_ENV` holds the environment being seeded.
+use rustyroad::database::{{connect_to_project_database, DatabaseConnection}};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {{
+    let connection = connect_to_project_database().await?;
+
+    // Insert the seed data here, e.g.
+    if let DatabaseConnection::Pg(_pool) = &connection {{
+        // sqlx::query(\"INSERT INTO posts (title, body) VALUES ($1, $2)\")
+        //     .bind(\"Hello\")
+        //     .bind(\"The first post\")
+        //     .execute(_pool.as_ref())
+        //     .await?;
+    }}
+
+    Ok(())
+}}
+"
+        ),
+    };
+    fs::write(&seed.path, contents)?;
+
+    Ok(seed)
+}
+
+/// ## Name: register_rust_seed
+/// ### Description: Adds a Rust seed to a Cargo.toml as a `[[bin]]` target named [`Seed::bin_name`]
+/// The package gets a `default-run`, so `cargo run` still starts the application. A seed that
+/// is already registered is left alone.
+/// #### Parameters:
+/// - cargo_toml_path: [`&str`] - the path to the Cargo.toml of the project
+/// - seed: [`&Seed`] - the Rust seed, with a path relative to the project root
+/// - Returns: [`Result<(), std::io::Error>`]
+pub fn register_rust_seed(cargo_toml_path: &str, seed: &Seed) -> Result<(), io::Error> {
+    let mut cargo_toml = fs::read_to_string(cargo_toml_path)?;
+    if cargo_toml.contains(&format!("name = \"{}\"", seed.bin_name())) {
+        return Ok(());
+    }
+
+    if !cargo_toml.lines().any(|line| line.trim_start().starts_with("default-run")) {
+        let package_name = cargo_toml
+            .lines()
+            .skip_while(|line| line.trim() != "[package]")
+            .find_map(|line| {
+                let value = line.trim().strip_prefix("name")?.trim_start().strip_prefix('=')?;
+                Some(value.trim().to_string())
+            });
+        if let Some(package_name) = package_name {
+            cargo_toml = cargo_toml.replacen(
+                "[package]",
+                &format!("[package]\ndefault-run = {}", package_name),
+                1,
+            );
+        }
+    }
+
+    let path = seed.path.strip_prefix(".").unwrap_or(&seed.path);
+    let path = path.to_string_lossy().replace('\\', "/");
+    if !cargo_toml.ends_with('\n') {
+        cargo_toml.push('\n');
+    }
+    cargo_toml.push_str(&format!(
+        "\n[[bin]]\nname = \"{}\"\npath = \"{}\"\n",
+        seed.bin_name(),
+        path
+    ));
+    fs::write(cargo_toml_path, cargo_toml)
+}
@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+use super::database_error::DatabaseError;
+use super::model::Model;
+use super::query_builder::{Operator, Query, QueryPool, SqlValue};
+
+lazy_static! {
+    /// The patterns of `format` rules, compiled once.
+    static ref FORMATS: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
+}
+
+/// ## Name: Validate
+/// ### Description: The rules a model checks before it's saved
+/// Implement it with `#[derive(Validate)]`, which reads the rules from the `#[validate(...)]`
+/// attributes of the fields:
+/// - `presence` - not empty, nor only whitespace, nor `None`
+/// - `length(min = 1, max = 120)` - the number of characters, or of items of a `Vec`
+/// - `format = "^[a-z0-9-]+$"` - matches the regular expression
+/// - `range(min = 0, max = 5)` - the number is within the bounds
+/// - `unique` - no other row has the value, checked against the database
+/// - `custom = "path::to::function"` - a `fn(&T) -> Result<(), String>` of the field, the error is the message
+///
+/// A `#[validate(custom = "function")]` on the struct calls a `fn(&Self, &mut ValidationErrors)`,
+/// for rules about several fields. Every rule but `presence` lets `None` pass.
+///
+/// The generated `create_<model>` and `update_<model>` functions validate the row before saving
+/// it and return [`DatabaseError::Validation`] with the message of every failed rule.
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::{DatabaseError, PoolConnection};
+/// use rustyroad::{Model, Validate};
+///
+/// #[derive(Debug, Clone, sqlx::FromRow, Model, Validate)]
+/// #[table_name = "users"]
+/// pub struct User {
+///     pub id: Option<i64>,
+///     #[validate(presence, length(max = 20), format = "^[a-z]+$", unique)]
+///     pub name: String,
+///     #[validate(range(min = 13))]
+///     pub age: Option<i64>,
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER)")
+///     .execute(&pool)
+///     .await
+///     .unwrap();
+/// let pool = PoolConnection::Sqlite(pool);
+///
+/// let ada = User { id: None, name: "ada".to_string(), age: None };
+/// ada.validate(&pool).await.unwrap();
+/// ada.create(&pool).await.unwrap();
+///
+/// let invalid = User { id: None, name: "ada".to_string(), age: Some(7) };
+/// let Err(DatabaseError::Validation(errors)) = invalid.validate(&pool).await else { panic!() };
+/// assert_eq!(errors.get("name"), ["has already been taken"]);
+/// assert_eq!(errors.get("age"), ["must be greater than or equal to 13"]);
+/// # });
+/// ```
+pub trait Validate: Model {
+    /// The columns no two rows may have the same value in.
+    const UNIQUE_COLUMNS: &'static [&'static str] = &[];
+
+    /// Adds the errors of the rules that don't need the database.
+    fn validate_fields(&self, errors: &mut ValidationErrors);
+
+    /// Checks every rule, returns [`DatabaseError::Validation`] when one fails.
+    fn validate<P>(&self, pool: &P) -> impl Future<Output = Result<(), DatabaseError>> + Send
+    where
+        P: QueryPool + Sync,
+    {
+        let mut errors = ValidationErrors::new();
+        self.validate_fields(&mut errors);
+        let id = self.primary_key_value();
+        // A NULL is never equal to another one
+        let unique: Vec<(&'static str, SqlValue)> = self
+            .column_values()
+            .into_iter()
+            .filter(|(column, value)| Self::UNIQUE_COLUMNS.contains(column) && *value != SqlValue::Null)
+            .collect();
+        async move {
+            for (column, value) in unique {
+                let mut query = Query::select(Self::TABLE_NAME).where_eq(column, value);
+                if id != SqlValue::Null {
+                    query = query.where_op(Self::PRIMARY_KEY, Operator::NotEq, id.clone());
+                }
+                if query.count(pool).await? > 0 {
+                    errors.add(column, "has already been taken");
+                }
+            }
+            errors.into_result()
+        }
+    }
+}
+
+/// ## Name: ValidationErrors
+/// ### Description: The messages of the failed rules, by field
+/// Serializes to `{"errors": {"<field>": ["<message>", ...]}}`, the body of the 422 responses of
+/// the generated controllers. The rule functions add a message when the value breaks the rule,
+/// for hand-written [`Validate::validate_fields`].
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::ValidationErrors;
+///
+/// let mut errors = ValidationErrors::new();
+/// errors.presence("title", "  ");
+/// errors.length("summary", "Hi", Some(3), None);
+/// errors.range("views", &-1, Some(0.0), None);
+/// errors.format("slug", &Some("hello-world".to_string()), "^[a-z-]+$");
+/// assert_eq!(errors.get("summary"), ["is too short (minimum is 3 characters)"]);
+/// assert!(errors.get("slug").is_empty());
+/// assert_eq!(
+///     serde_json::to_string(&errors).unwrap(),
+///     r#"{"errors":{"summary":["is too short (minimum is 3 characters)"],"title":["can't be blank"],"views":["must be greater than or equal to 0"]}}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationErrors {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message to the field.
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.entry(field.to_string()).or_default().push(message.into());
+    }
+
+    /// Whether every rule passed.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The messages of the field, empty when its rules passed.
+    pub fn get(&self, field: &str) -> &[String] {
+        self.errors.get(field).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The messages of every field with one, e.g. for the context of a template.
+    pub fn by_field(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.errors
+    }
+
+    /// Every message prefixed with its field, e.g. `title can't be blank`.
+    pub fn full_messages(&self) -> Vec<String> {
+        self.errors
+            .iter()
+            .flat_map(|(field, messages)| messages.iter().map(move |message| format!("{} {}", field, message)))
+            .collect()
+    }
+
+    /// `Ok` when every rule passed, [`DatabaseError::Validation`] otherwise.
+    pub fn into_result(self) -> Result<(), DatabaseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(DatabaseError::Validation(self))
+        }
+    }
+
+    /// The value isn't empty, only whitespace or `None`.
+    pub fn presence<V: Validatable + ?Sized>(&mut self, field: &str, value: &V) {
+        if value.is_blank() {
+            self.add(field, "can't be blank");
+        }
+    }
+
+    /// The number of characters of a text, or items of a list, is within the bounds.
+    pub fn length<V: Validatable + ?Sized>(&mut self, field: &str, value: &V, min: Option<usize>, max: Option<usize>) {
+        let Some(length) = value.length() else { return };
+        if let Some(min) = min.filter(|min| length < *min) {
+            self.add(field, format!("is too short (minimum is {} characters)", min));
+        }
+        if let Some(max) = max.filter(|max| length > *max) {
+            self.add(field, format!("is too long (maximum is {} characters)", max));
+        }
+    }
+
+    /// The text matches the regular expression.
+    /// #### Panics:
+    /// When the pattern isn't a regular expression; `#[derive(Validate)]` checks it when compiling.
+    pub fn format<V: Validatable + ?Sized>(&mut self, field: &str, value: &V, pattern: &str) {
+        let Some(text) = value.text() else { return };
+        let mut formats = FORMATS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let regex = formats.entry(pattern.to_string()).or_insert_with(|| {
+            Regex::new(pattern).unwrap_or_else(|why| panic!("Invalid format of {}: {}", field, why))
+        });
+        if !regex.is_match(text) {
+            self.add(field, "is invalid");
+        }
+    }
+
+    /// The number is within the bounds.
+    pub fn range<V: Validatable + ?Sized>(&mut self, field: &str, value: &V, min: Option<f64>, max: Option<f64>) {
+        let Some(number) = value.number() else { return };
+        if let Some(min) = min.filter(|min| number < *min) {
+            self.add(field, format!("must be greater than or equal to {}", min));
+        }
+        if let Some(max) = max.filter(|max| number > *max) {
+            self.add(field, format!("must be less than or equal to {}", max));
+        }
+    }
+
+    /// Adds the message of a custom rule that failed.
+    pub fn custom(&mut self, field: &str, result: Result<(), String>) {
+        if let Err(message) = result {
+            self.add(field, message);
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.full_messages().join(", "))
+    }
+}
+
+/// ## Name: Validatable
+/// ### Description: A value the rules of [`ValidationErrors`] can check
+/// Text has a length and a format, lists have a length and numbers a range. `None` is blank and
+/// passes the other rules, `Some` is checked like the value inside.
+pub trait Validatable {
+    /// Whether `presence` fails.
+    fn is_blank(&self) -> bool {
+        false
+    }
+
+    /// What `length` checks, `None` to skip it.
+    fn length(&self) -> Option<usize> {
+        None
+    }
+
+    /// What `format` checks, `None` to skip it.
+    fn text(&self) -> Option<&str> {
+        None
+    }
+
+    /// What `range` checks, `None` to skip it.
+    fn number(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl Validatable for str {
+    fn is_blank(&self) -> bool {
+        self.trim().is_empty()
+    }
+
+    fn length(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl Validatable for String {
+    fn is_blank(&self) -> bool {
+        self.as_str().is_blank()
+    }
+
+    fn length(&self) -> Option<usize> {
+        self.as_str().length()
+    }
+
+    fn text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T: Validatable> Validatable for Option<T> {
+    fn is_blank(&self) -> bool {
+        self.as_ref().is_none_or(Validatable::is_blank)
+    }
+
+    fn length(&self) -> Option<usize> {
+        self.as_ref().and_then(Validatable::length)
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.as_ref().and_then(Validatable::text)
+    }
+
+    fn number(&self) -> Option<f64> {
+        self.as_ref().and_then(Validatable::number)
+    }
+}
+
+impl<T> Validatable for Vec<T> {
+    fn is_blank(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+macro_rules! validatable_number {
+    ($($number:ty),*) => {
+        $(impl Validatable for $number {
+            fn number(&self) -> Option<f64> {
+                Some(*self as f64)
+            }
+        })*
+    };
+}
+
+validatable_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+macro_rules! validatable {
+    ($($value:ty),*) => {
+        $(impl Validatable for $value {})*
+    };
+}
+
+validatable!(
+    bool,
+    chrono::NaiveDate,
+    chrono::NaiveTime,
+    chrono::NaiveDateTime,
+    chrono::DateTime<chrono::Utc>,
+    sqlx::types::Uuid
+);
+
+impl Validatable for serde_json::Value {
+    fn is_blank(&self) -> bool {
+        self.is_null()
+    }
+}
@@ -14,7 +14,10 @@ use std::fs::{create_dir, OpenOptions};
 use std::io::Write;
 use std::path::Path;
 
-use crate::database::{run_migration, Database, DatabaseType, MigrationDirection};
+use crate::database::{
+    run_migration, Database, DatabaseType, MigrationDirection, MigrationMode,
+    DEFAULT_MIGRATION_LOCK_TIMEOUT,
+};
 use crate::features::{update_cargo_toml_for_grapesjs, update_index_controller};
 use crate::generators::create_file;
 use color_eyre::eyre::Result;
@@ -74,8 +77,13 @@ impl GrapesJs {
             println!("Writing to create page html");
             write_to_create_page_html().expect("Couldn't write to create page html");
             // run the migrations
-            run_migration("page".to_string(), MigrationDirection::Up)
-                .await
+            run_migration(
+                "page".to_string(),
+                MigrationDirection::Up,
+                MigrationMode::Execute,
+                DEFAULT_MIGRATION_LOCK_TIMEOUT,
+            )
+            .await
                 .expect("Couldn't run page migration");
 
             write_to_all_page_controllers().expect("Couldn't write to all page controllers");
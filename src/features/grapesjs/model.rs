@@ -21,6 +21,7 @@ use sqlx::{FromRow, query_as};
 /// * create_new_database_page(new_html: Page) -> Result<serde_json::Value, sqlx::Error>
 /// * get_page_by_id(id: i32) -> Result<Page, sqlx::Error>
 /// * get_db_pool() -> Result<sqlx::PgPool, sqlx::Error>
+/// * the functions of [`rustyroad::Model`](crate::Model) on the `page` table
 /// ### Example:
 /// ```
 /// use rustyroad::features::Page;
@@ -43,7 +44,8 @@ use sqlx::{FromRow, query_as};
 ///
 /// let result = Page::get_db_pool();
 /// ```
-#[derive(Debug, Clone, serde_derive::Serialize, serde_derive::Deserialize, FromRow)]
+#[derive(Debug, Clone, serde_derive::Serialize, serde_derive::Deserialize, FromRow, crate::Model)]
+#[table_name = "page"]
 pub struct Page {
     pub id: Option<i32>,
     pub title: String,
@@ -142,7 +142,7 @@ sanitize-filename = "0.5"
 
 [dependencies.sqlx]
 features = ["postgres", "macros", "chrono", "json", "uuid", "runtime-tokio", "time"]
-version = "0.7.2"
+version = "0.8"
     "##;
 
     println!("Updating the Cargo.toml file...");
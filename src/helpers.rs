@@ -21,6 +21,65 @@ pub mod helpers {
         }
     }
 
+    /// Turns `blog_post` into `BlogPost`.
+    pub fn pascal_case(s: &str) -> String {
+        s.split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+            .map(capitalize_first)
+            .collect()
+    }
+
+    /// Words whose plural doesn't follow the suffix rules, as (singular, plural).
+    const IRREGULAR_PLURALS: [(&str, &str); 6] = [
+        ("person", "people"),
+        ("man", "men"),
+        ("woman", "women"),
+        ("child", "children"),
+        ("mouse", "mice"),
+        ("goose", "geese"),
+    ];
+
+    /// Replaces the last word of a snake case name, e.g. the `person` of `sales_person`, when it
+    /// is irregular.
+    fn replace_irregular(s: &str, to_plural: bool) -> Option<String> {
+        let (prefix, word) = s.split_at(s.rfind('_').map_or(0, |index| index + 1));
+        IRREGULAR_PLURALS.iter().find_map(|(singular, plural)| {
+            let (from, to) = if to_plural { (singular, plural) } else { (plural, singular) };
+            (word == *from).then(|| format!("{}{}", prefix, to))
+        })
+    }
+
+    /// Turns a table name like `posts`, `categories` or `addresses` into a model name.
+    pub fn singularize(s: &str) -> String {
+        if let Some(singular) = replace_irregular(s, false) {
+            singular
+        } else if let Some(stem) = s.strip_suffix("ies") {
+            format!("{}y", stem)
+        } else if let Some(stem) = ["sses", "shes", "ches", "xes"]
+            .iter()
+            .find_map(|suffix| s.strip_suffix(suffix).map(|stem| (stem, suffix)))
+        {
+            format!("{}{}", stem.0, &stem.1[..stem.1.len() - 2])
+        } else if s.ends_with('s') && !s.ends_with("ss") && !s.ends_with("us") {
+            s[..s.len() - 1].to_string()
+        } else {
+            s.to_string()
+        }
+    }
+
+    /// Turns a model name like `post`, `category` or `address` into a table name.
+    pub fn pluralize(s: &str) -> String {
+        let consonant_y = s.strip_suffix('y').filter(|stem| !stem.ends_with(['a', 'e', 'i', 'o', 'u']));
+        if let Some(plural) = replace_irregular(s, true) {
+            plural
+        } else if let Some(stem) = consonant_y {
+            format!("{}ies", stem)
+        } else if ["s", "sh", "ch", "x"].iter().any(|suffix| s.ends_with(suffix)) {
+            format!("{}es", s)
+        } else {
+            format!("{}s", s)
+        }
+    }
+
 
 
     use regex::Regex;
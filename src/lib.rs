@@ -24,6 +24,9 @@
 #![deny(warnings)]
 #![allow(dead_code)]
 
+// Lets `#[derive(Model)]` refer to `::rustyroad` inside this crate too
+extern crate self as rustyroad;
+
 use clap::{arg, Arg, Command, Parser};
 use color_eyre::eyre::Result;
 use dialoguer::Confirm;
@@ -31,9 +34,8 @@ use eyre::Error;
 use serde::Deserialize;
 use sqlx::postgres::PgConnectOptions;
 use sqlx::ConnectOptions;
-use std::{env, fs};
+use std::env;
 use std::{fs::OpenOptions, io::Write};
-use std::path::Path;
 use sqlx::mysql::MySqlConnectOptions;
 use sqlx::sqlite::SqliteConnectOptions;
 use tokio::io;
@@ -47,6 +49,15 @@ use database::*;
 
 pub mod helpers;
 pub mod writers;
+
+pub use database::{Model, Validate};
+pub use rustyroad_derive::{Model, Validate};
+
+// Used by `embed_migrations!`, so generated applications don't need their own dependency
+#[doc(hidden)]
+pub use include_dir;
+// The sqlx of the pools in `DatabaseConnection`, so generated code names the same types
+pub use sqlx;
 use crate::generators::create_directories_for_new_project;
 use crate::helpers::helpers::get_project_name_from_rustyroad_toml;
 use crate::writers::*;
@@ -210,23 +221,39 @@ impl Project {
             .truncate(true)
             .open(&self.rustyroad_toml)?;
 
+        // The shared settings apply to every environment, RUSTYROAD_ENV selects the table that overrides them
         let config = format!(
             "[rustyroad_project]
 name = \"{}\"
+
 [database]
-database_name = \"{}\"
 database_user = \"{}\"
 database_password = \"{}\"
 database_host = \"{}\"
 database_port = \"{}\"
-database_type = \"{}\"",
+database_type = \"{}\"
+pool_max_connections = 10
+pool_min_connections = 0
+pool_acquire_timeout = 30
+pool_idle_timeout = 600
+
+[database.development]
+database_name = \"{name}\"
+
+[database.test]
+database_name = \"{name}_test\"
+
+[database.production]
+database_name = \"{name}\"
+database_password = \"${{DATABASE_PASSWORD}}\"
+",
             self.name,
-            database_data.clone().name,
             database_data.username,
             database_data.password,
             database_data.host,
             database_data.port,
-            database_data.database_type.to_string().to_ascii_lowercase()
+            database_data.database_type.to_string().to_ascii_lowercase(),
+            name = database_data.name
         );
         file.write_all(config.as_bytes())?;
         Ok(())
@@ -606,17 +633,8 @@ static/styles.css
             }
 
             DatabaseType::Postgres => {
-                // Replace this line with the correct URL for the default "postgres" database
-                let admin_database_url = format!(
-                    "postgres://{}:{}@{}:{}/postgres",
-                    database_data.username,
-                    database_data.password,
-                    database_data.host,
-                    database_data.port,
-                );
-
-                // Call the function with the admin_database_url
-                create_database_if_not_exists(&admin_database_url, database_data.clone())
+                // Create the database through the server's default "postgres" database
+                create_database(&database_data)
                     .await
                     .unwrap_or_else(|why| {
                         panic!("Failed to create database: {why}");
@@ -681,17 +699,8 @@ static/styles.css
             }
 
             DatabaseType::Mysql => {
-                // Create the database URL for the default "mysql" database
-                let admin_database_url = format!(
-                    "mysql://{}:{}@{}:{}/mysql",
-                    database_data.username,
-                    database_data.password,
-                    database_data.host,
-                    database_data.port,
-                );
-
-                // Call the function with the admin_database_url
-                create_database_if_not_exists(&admin_database_url, database_data.clone())
+                // Create the database through the MySQL server
+                create_database(&database_data)
                     .await
                     .unwrap_or_else(|why| {
                         panic!("Failed to create database: {:?}", why);
@@ -762,18 +771,30 @@ static/styles.css
             }
 
             DatabaseType::Mongo => {
-                // Create the database
-                let database_url = format!(
-                    "DATABASE_URL=mongodb://localhost:27017/{}",
-                    &database_data.clone().name
-                );
-                println!("database_url: {database_url}");
-                let output = std::process::Command::new("diesel")
-                    .arg("setup")
-                    .env("DATABASE_URL", database_url)
-                    .output()
-                    .expect("Failed to execute process");
-                println!("output: {:?}", output);
+                // MongoDB creates the database with its first collection
+                project.config_dev_db = database_data.mongo_url();
+
+                println!("database_url: {}", project.config_dev_db);
+
+                // Write the up.json and down.json of the initial migration
+                load_sql_for_new_project(&project, database_data.clone()).await?;
+
+                // Connect to the database
+                let connection = database_data
+                    .create_database_connection()
+                    .await
+                    .unwrap_or_else(|why| panic!("Failed to establish connection: {why}"));
+
+                // Apply the initial migration, so `migration all` doesn't run it again
+                let migration = Migration::from_dir(&project.initial_migration_directory)
+                    .expect("The initial migration folder isn't named <version>_<name>");
+                apply_migration(&connection, &migration, MigrationDirection::Up)
+                    .await
+                    .unwrap_or_else(|why| panic!("Failed to run the initial migration: {why}"));
+
+                write_to_mongo_user_models(&project).unwrap_or_else(|why| {
+                    println!("Failed to write to user models: {:?}", why.to_string());
+                });
             }
         }
 
@@ -799,6 +820,10 @@ static/styles.css
             .subcommand_required(true)
             .arg_required_else_help(true)
             .allow_external_subcommands(true)
+            .arg(
+                arg!(--env <ENV> "The environment to run in: dev, test or prod, defaults to RUSTYROAD_ENV or dev")
+                    .global(true),
+            )
             .subcommand(
                 Command::new("new")
                     .about("Creates a new project")
@@ -818,17 +843,30 @@ static/styles.css
                     .subcommand(
                         Command::new("model")
                             .about("Generates a new model")
-                            .arg(arg!(<name> "The name of the model"))
+                            .arg(arg!([name] "The name of the model").required_unless_present_any(["from-db", "all"]))
+                            .arg(arg!([columns] ... "The columns of a new migration as name:type, e.g. title:text author_id:references:users").conflicts_with_all(["from-db", "all"]))
+                            .arg(arg!(--"has-many" <table> ... "Rows of another table point to this model, as table[:foreign_key]").conflicts_with_all(["from-db", "all"]))
+                            .arg(arg!(--"has-one" <table> ... "A row of another table points to this model, as table[:foreign_key]").conflicts_with_all(["from-db", "all"]))
+                            .arg(arg!(--"many-to-many" <table> ... "Links this model to another table through a join table, as table[:join_table]").conflicts_with_all(["from-db", "all"]))
+                            .arg(arg!(--"from-db" <table> "Reads the model from a table of the live database instead of a migration"))
+                            .arg(arg!(--all "Generates a model for every table of the live database").conflicts_with_all(["from-db", "name"]))
                             .subcommand_required(false)
                             .arg_required_else_help(true)
                             .allow_external_subcommands(false),
                     )
-                    .subcommand(Command::new("controller").about("Generates a new controller"))
                     .subcommand(
                         Command::new("migration")
                             .about("Generates a new migration")
                             .arg(arg!(<name> "The name of the migration"
                             ))
+                            .arg(arg!([columns] ... "The columns as name:type, e.g. title:text author_id:references:users"))
+                            .arg_required_else_help(true),
+                    )
+                    .subcommand(
+                        Command::new("seed")
+                            .about("Generates a new seed file in the seeders directory, only run in the --env environment when it's given")
+                            .arg(arg!(<name> "The name of the seed"))
+                            .arg(arg!(--rust "Generates a Rust seed instead of a SQL one"))
                             .arg_required_else_help(true),
                     )
                     .after_help(
@@ -837,10 +875,20 @@ static/styles.css
                     rustyroad generate controller <name>
                 To generate a new model:
                     rustyroad generate model <name>
+                To generate a model with its migration and associations:
+                    rustyroad generate model post title:string author_id:references:users --many-to-many tags
+                    rustyroad generate model user name:string --has-many posts:author_id --has-one profiles
+                To generate the models of existing tables from the database:
+                    rustyroad generate model --from-db <table> [name]
+                    rustyroad generate model --all
                 To generate a new controller:
                     rustyroad generate controller <name>
                 To generate a new migration:
-                    rustyroad generate migration <name>",
+                    rustyroad generate migration <name>
+                To generate a migration without prompting for the columns:
+                    rustyroad generate migration create_posts title:text published:boolean author_id:references:users
+                To generate a seed that only runs in development:
+                    rustyroad generate seed demo_posts --env dev",
                     )
                     .subcommand_required(true),
             )
@@ -850,36 +898,111 @@ static/styles.css
                     .subcommand(
                         Command::new("generate")
                             .about("Generates a migration")
-                            .arg(arg!(<name> "The name of the migration")),
+                            .arg(arg!(<name> "The name of the migration"))
+                            .arg(arg!([columns] ... "The columns as name:type, e.g. title:text author_id:references:users")),
                     )
                     .subcommand(
                         Command::new("all")
-                            .about("Runs all the migrations in the migration directory"),
+                            .about("Runs all the migrations in the migration directory")
+                            .arg(arg!(--force "Runs the migrations even if applied ones were edited or deleted"))
+                            .arg(arg!(--"dry-run" "Prints the SQL of the pending migrations without running it"))
+                            .arg(arg!(--output <FILE> "Writes the SQL of the pending migrations to a file without running it"))
+                            .arg(
+                                arg!(--"lock-timeout" <SECONDS> "How long to wait for another process that is running migrations")
+                                    .value_parser(clap::value_parser!(u64))
+                                    .default_value("60"),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("verify")
+                            .about("Reports applied migrations that were edited or deleted, and pending ones that are out of order"),
                     )
                     .subcommand(
                         Command::new("run")
                             .about("Run a specific migration by name")
-                            .arg(arg!(<name> "The name of the migration to run.")),
+                            .arg(arg!(<name> "The name of the migration to run."))
+                            .arg(arg!(--"dry-run" "Prints the SQL of the migration without running it"))
+                            .arg(arg!(--output <FILE> "Writes the SQL of the migration to a file without running it"))
+                            .arg(
+                                arg!(--"lock-timeout" <SECONDS> "How long to wait for another process that is running migrations")
+                                    .value_parser(clap::value_parser!(u64))
+                                    .default_value("60"),
+                            ),
                     )
                     .subcommand(
                         Command::new("rollback")
                             .about("Rolls back the last migration")
-                            .arg(arg!(<name> "The name of the migration")),
+                            .arg(arg!(<name> "The name of the migration"))
+                            .arg(arg!(--"dry-run" "Prints the SQL of the rollback without running it"))
+                            .arg(arg!(--output <FILE> "Writes the SQL of the rollback to a file without running it"))
+                            .arg(
+                                arg!(--"lock-timeout" <SECONDS> "How long to wait for another process that is running migrations")
+                                    .value_parser(clap::value_parser!(u64))
+                                    .default_value("60"),
+                            ),
                     )
                     .subcommand(
                         Command::new("redo")
                             .about("Rolls back the last migration and runs it again")
-                            .arg(arg!(<name> "The name of the migration")),
+                            .arg(arg!([name] "The name of the migration, defaults to the latest applied one"))
+                            .arg(
+                                arg!(--"lock-timeout" <SECONDS> "How long to wait for another process that is running migrations")
+                                    .value_parser(clap::value_parser!(u64))
+                                    .default_value("60"),
+                            ),
                     )
                     .subcommand(
                         Command::new("reset")
-                            .about("Rolls back all migrations")
-                            .arg(arg!(<name> "The name of the migration")),
+                            .about("Rolls back all migrations and runs them again")
+                            .arg(
+                                arg!(--"lock-timeout" <SECONDS> "How long to wait for another process that is running migrations")
+                                    .value_parser(clap::value_parser!(u64))
+                                    .default_value("60"),
+                            ),
                     )
                     .subcommand(
                         Command::new("status")
-                            .about("Prints the status of all migrations")
-                            .arg(arg!(<name> "The name of the migration")),
+                            .about("Prints the status of all migrations"),
+                    )
+                    .subcommand(
+                        Command::new("add_column")
+                            .about("Generates a migration that adds a column to a table")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<column> "The column as name:type, e.g. subtitle:string:null")),
+                    )
+                    .subcommand(
+                        Command::new("remove_column")
+                            .about("Generates a migration that removes a column from a table")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<column> "The column as name:type, used to add it back on rollback")),
+                    )
+                    .subcommand(
+                        Command::new("rename_column")
+                            .about("Generates a migration that renames a column")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<from> "The current name of the column"))
+                            .arg(arg!(<to> "The new name of the column")),
+                    )
+                    .subcommand(
+                        Command::new("change_column_type")
+                            .about("Generates a migration that changes the type of a column")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<column> "The column with its new type, e.g. views:bigint"))
+                            .arg(arg!(<from> "The current type of the column, used on rollback, e.g. integer")),
+                    )
+                    .subcommand(
+                        Command::new("add_index")
+                            .about("Generates a migration that adds an index to a table")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<columns> ... "The indexed columns"))
+                            .arg(arg!(--unique "Creates a unique index")),
+                    )
+                    .subcommand(
+                        Command::new("add_foreign_key")
+                            .about("Generates a migration that adds a foreign key to a column")
+                            .arg(arg!(<table> "The name of the table"))
+                            .arg(arg!(<column> "The name of the column"))
+                            .arg(arg!(<references> "The referenced table")),
                     )
                     .subcommand_help_heading("SUBCOMMANDS:")
                     // if no subcommand is provided, print help
@@ -887,6 +1010,42 @@ static/styles.css
                     .arg_required_else_help(true)
                     .allow_external_subcommands(true),
             )
+            .subcommand(
+                Command::new("db")
+                    .about("Manages the database of the project")
+                    .subcommand(
+                        Command::new("create")
+                            .about("Creates the database configured in rustyroad.toml"),
+                    )
+                    .subcommand(
+                        Command::new("drop")
+                            .about("Drops the database configured in rustyroad.toml")
+                            .arg(arg!(--yes "Drops the database without asking for confirmation")),
+                    )
+                    .subcommand(
+                        Command::new("setup")
+                            .about("Creates the database if needed, then runs the pending migrations and seeds"),
+                    )
+                    .subcommand(
+                        Command::new("reset")
+                            .about("Drops and creates the database, then runs every migration and seed")
+                            .arg(arg!(--yes "Resets the database without asking for confirmation")),
+                    )
+                    .subcommand(
+                        Command::new("schema:load")
+                            .about("Creates the tables of config/database/schema.sql on an empty database"),
+                    )
+                    .subcommand(
+                        Command::new("schema:dump")
+                            .about("Writes the current schema to config/database/schema.sql and schema.json"),
+                    )
+                    .subcommand(
+                        Command::new("seed")
+                            .about("Runs the seeds of the seeders directory that haven't run yet"),
+                    )
+                    .subcommand_required(true)
+                    .arg_required_else_help(true),
+            )
             .subcommand(
                 Command::new("feature")
                     .about("Adds a feature to the project")
@@ -963,8 +1122,40 @@ static/styles.css
         vec![arg!(-m --message <MESSAGE>)]
     }
 
+    /// Returns the environment of the `--env` flag, wherever it was given on the command line.
+    fn environment_flag(matches: &clap::ArgMatches) -> Option<Environment> {
+        let mut matches = matches;
+        while let Some((_, subcommand_matches)) = matches.subcommand() {
+            matches = subcommand_matches;
+        }
+        matches.get_one::<String>("env").map(|environment| {
+            environment.parse::<Environment>().unwrap_or_else(|why| {
+                eprintln!("Error: {}", why);
+                std::process::exit(1);
+            })
+        })
+    }
+
+    /// Returns the environment the command runs in, exiting when `RUSTYROAD_ENV` is unknown.
+    fn current_environment() -> Environment {
+        Environment::current().unwrap_or_else(|why| {
+            eprintln!("Error: {}", why);
+            std::process::exit(1);
+        })
+    }
+
+    /// Reports database settings that can't be used, e.g. an unsupported database type, and exits.
+    fn exit_on_database_error(why: DatabaseError) -> Database {
+        eprintln!("Error: {}", why);
+        std::process::exit(1);
+    }
+
     pub async fn run() {
         let matches = Self::cli().get_matches();
+        // Every command, and the rust seeds it starts, reads the environment from RUSTYROAD_ENV
+        if let Some(environment) = Self::environment_flag(&matches) {
+            env::set_var(ENVIRONMENT_VARIABLE, environment.short_name());
+        }
         match matches.subcommand() {
             // New Project Case
             Some(("new", matches)) => {
@@ -1032,7 +1223,8 @@ static/styles.css
                             database_host,
                             database_port,
                             database_choice.as_str(),
-                        );
+                        )
+                        .unwrap_or_else(Self::exit_on_database_error);
                         Self::create_new_project(name, database).await.err();
                     }
                     2 => {
@@ -1076,7 +1268,8 @@ static/styles.css
                             database_host,
                             database_port,
                             database_choice.as_str(),
-                        );
+                        )
+                        .unwrap_or_else(Self::exit_on_database_error);
                         Self::create_new_project(name, database).await.err();
                     }
                     3 => {
@@ -1090,7 +1283,8 @@ static/styles.css
                             "localhost".to_string(),
                             0,
                             "sqlite".trim_end(),
-                        );
+                        )
+                        .unwrap_or_else(Self::exit_on_database_error);
                         Self::create_new_project(name, database).await.err();
                     }
                     4 => {
@@ -1125,29 +1319,21 @@ static/styles.css
                             .read_line(&mut database_host)
                             .expect("Failed to read line");
                         let database_host = database_host.trim().to_string();
-                        database_choice = "mongodb".to_string();
                         // create a new project with the name and database information
                         let database: Database = Database::new(
-                            database_choice.to_string(),
                             database_name,
                             database_username,
                             database_password,
+                            database_host,
                             database_port,
-                            database_host.as_str(),
-                        );
+                            "mongo",
+                        )
+                        .unwrap_or_else(Self::exit_on_database_error);
                         Self::create_new_project(name, database).await.err();
                     }
                     5 => {
-                        // create a new project with the name and database information
-                        let database: Database = Database::new(
-                            database_choice.to_string(),
-                            "".to_string(),
-                            "".to_string(),
-                            "".to_string(),
-                            0,
-                            "".to_string().as_str(),
-                        );
-                        Self::create_new_project(name, database).await.err();
+                        // Projects are generated with a database connection, there's no driver for "none"
+                        Self::exit_on_database_error(DatabaseError::UnsupportedDriver("none".to_string()));
                     }
                     _ => {
                         println!("Invalid database choice");
@@ -1196,17 +1382,70 @@ static/styles.css
                 }
                 Some(("model", matches)) => {
                     // derive the name of the model from the arguments
-                    let model_name = matches.get_one::<String>("name").unwrap().as_str();
+                    let model_name = matches.get_one::<String>("name").map(String::as_str);
+                    let from_db = matches.get_one::<String>("from-db").map(String::as_str);
 
-                    create_base_model(model_name)
-                        .await
-                        .expect("Error creating model");
+                    if from_db.is_some() || matches.get_flag("all") {
+                        if let Err(why) = create_models_from_database(from_db, model_name).await {
+                            eprintln!("Error creating models: {}", why);
+                            std::process::exit(1);
+                        }
+                    } else {
+                        let model_name = model_name.unwrap();
+                        let columns: Vec<String> = matches
+                            .get_many::<String>("columns")
+                            .map(|columns| columns.cloned().collect())
+                            .unwrap_or_default();
+                        let table_name = helpers::helpers::pluralize(model_name);
+                        let associations = ["has-many", "has-one", "many-to-many"]
+                            .iter()
+                            .flat_map(|flag| {
+                                let kind = flag.replace('-', "_");
+                                let table_name = &table_name;
+                                matches
+                                    .get_many::<String>(flag)
+                                    .into_iter()
+                                    .flatten()
+                                    .map(move |spec| Association::parse(&kind, table_name, spec))
+                            })
+                            .collect::<Result<Vec<_>, _>>();
+                        let result = match associations {
+                            Ok(associations) => create_base_model(model_name, &columns, &associations).await,
+                            Err(why) => Err(why),
+                        };
+                        if let Err(why) = result {
+                            eprintln!("Error creating model: {}", why);
+                            std::process::exit(1);
+                        }
+                    }
                 }
                 Some(("migration", matches)) => {
                     let name = matches.get_one::<String>("name").unwrap().to_string();
-                    create_migration(&name)
-                        .await
-                        .expect("Error creating migration");
+                    let columns: Vec<String> = matches
+                        .get_many::<String>("columns")
+                        .map(|columns| columns.cloned().collect())
+                        .unwrap_or_default();
+                    if columns.is_empty() {
+                        create_migration(&name)
+                            .await
+                            .expect("Error creating migration");
+                    } else if let Err(why) = create_migration_from_columns(&name, &columns) {
+                        eprintln!("Error creating migration: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("seed", matches)) => {
+                    let name = matches.get_one::<String>("name").unwrap();
+                    let environment = Self::environment_flag(matches);
+                    let kind = if matches.get_flag("rust") {
+                        SeedKind::Rust
+                    } else {
+                        SeedKind::Sql
+                    };
+                    if let Err(why) = create_seed(name, environment, kind) {
+                        eprintln!("Error creating seed: {}", why);
+                        std::process::exit(1);
+                    }
                 }
                 _ => {
                     println!("Invalid generate choice");
@@ -1216,11 +1455,20 @@ static/styles.css
             Some(("migration", matches)) => match matches.subcommand() {
                 Some(("generate", matches)) => {
                     let name = matches.get_one::<String>("name").unwrap().to_string();
-                    create_migration(&name)
-                        .await
-                        .expect("Error creating migration");
+                    let columns: Vec<String> = matches
+                        .get_many::<String>("columns")
+                        .map(|columns| columns.cloned().collect())
+                        .unwrap_or_default();
+                    if columns.is_empty() {
+                        create_migration(&name)
+                            .await
+                            .expect("Error creating migration");
+                    } else if let Err(why) = create_migration_from_columns(&name, &columns) {
+                        eprintln!("Error creating migration: {}", why);
+                        std::process::exit(1);
+                    }
                 }
-                Some(("all", _)) => {
+                Some(("all", matches)) => {
                     // run all the migrations
                     // get each migration from the migrations directory
                     // then run the command for each
@@ -1230,34 +1478,66 @@ static/styles.css
                         panic!("This is not a Rusty Road project: {why}")
                     });
 
-                    let migrations_path = Path::new("./config/database/migrations");
-                    let mut migrations = Vec::new();
-
-                    for entry in fs::read_dir(migrations_path).unwrap() {
-                        let entry = entry.unwrap();
-                        let path = entry.path();
-                        let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
-                        migrations.push(file_name);
-                    }
-
-                    for migration in migrations {
-                        run_migration(migration, MigrationDirection::Up)
-                            .await
-                            .expect("Error running migration");
+                    let mode = MigrationMode::from_flags(
+                        matches.get_flag("dry-run"),
+                        matches.get_one::<String>("output"),
+                    );
+                    let lock_timeout = std::time::Duration::from_secs(
+                        *matches.get_one::<u64>("lock-timeout").unwrap(),
+                    );
+                    if let Err(why) = run_all_migrations(
+                        MigrationDirection::Up,
+                        matches.get_flag("force"),
+                        mode,
+                        lock_timeout,
+                    )
+                    .await
+                    {
+                        eprintln!("Error running migrations: {}", why);
+                        std::process::exit(1);
                     }
                 }
                 Some(("run", matches)) => {
                     let name = matches.get_one::<String>("name").unwrap().to_string();
+                    let mode = MigrationMode::from_flags(
+                        matches.get_flag("dry-run"),
+                        matches.get_one::<String>("output"),
+                    );
+                    let dry_run = mode != MigrationMode::Execute;
+                    let lock_timeout = std::time::Duration::from_secs(
+                        *matches.get_one::<u64>("lock-timeout").unwrap(),
+                    );
 
-
-                        run_migration(name.clone(), MigrationDirection::Up)
-                            .await
-                            .expect("Error running migration");
+                    if let Err(why) =
+                        run_migration(name.clone(), MigrationDirection::Up, mode, lock_timeout).await
+                    {
+                        eprintln!("Error running migration: {}", why);
+                        std::process::exit(1);
+                    }
+                    if !dry_run {
                         println!("'{}' migration completed successfully!", name.clone());
-
+                    }
                 }
                 Some(("rollback", matches)) => {
                     let name = matches.get_one::<String>("name").unwrap().to_string();
+                    let mode = MigrationMode::from_flags(
+                        matches.get_flag("dry-run"),
+                        matches.get_one::<String>("output"),
+                    );
+                    let lock_timeout = std::time::Duration::from_secs(
+                        *matches.get_one::<u64>("lock-timeout").unwrap(),
+                    );
+                    if mode != MigrationMode::Execute {
+                        // Nothing is rolled back, so there's nothing to confirm
+                        if let Err(why) =
+                            run_migration(name, MigrationDirection::Down, mode, lock_timeout).await
+                        {
+                            eprintln!("Error rolling back migration: {}", why);
+                            std::process::exit(1);
+                        }
+                        return;
+                    }
+
                     // Create a confirmation prompt
                     let confirmation = Confirm::new()
                         .with_prompt(&format!(
@@ -1270,9 +1550,17 @@ static/styles.css
 
                     if confirmation {
                         println!("Rolling back the '{}' migration...", name.clone());
-                        run_migration(name.clone(), MigrationDirection::Down)
-                            .await
-                            .expect("Error rolling back migration");
+                        if let Err(why) = run_migration(
+                            name.clone(),
+                            MigrationDirection::Down,
+                            MigrationMode::Execute,
+                            lock_timeout,
+                        )
+                        .await
+                        {
+                            eprintln!("Error rolling back migration: {}", why);
+                            std::process::exit(1);
+                        }
                         println!(
                             "'{}' migration rollback completed successfully!",
                             name.clone()
@@ -1281,10 +1569,177 @@ static/styles.css
                         println!("'{}' migration rollback canceled by user.", name);
                     }
                 }
+                Some(("verify", _)) => {
+                    if let Err(why) = verify_project_migrations().await {
+                        eprintln!("{}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("status", _)) => {
+                    print_migration_status()
+                        .await
+                        .expect("Error reading migration status");
+                }
+                Some(("redo", matches)) => {
+                    let name = matches.get_one::<String>("name");
+                    let lock_timeout = std::time::Duration::from_secs(
+                        *matches.get_one::<u64>("lock-timeout").unwrap(),
+                    );
+                    if let Err(why) = redo_migration(name.map(|name| name.as_str()), lock_timeout).await {
+                        eprintln!("Error redoing migration: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("reset", matches)) => {
+                    let lock_timeout = std::time::Duration::from_secs(
+                        *matches.get_one::<u64>("lock-timeout").unwrap(),
+                    );
+                    let confirmation = Confirm::new()
+                        .with_prompt("Are you sure you want to roll back and re-apply every migration?")
+                        .interact()
+                        .expect("Error resetting migrations: ");
+
+                    if confirmation {
+                        if let Err(why) = reset_migrations(lock_timeout).await {
+                            eprintln!("Error resetting migrations: {}", why);
+                            std::process::exit(1);
+                        }
+                        println!("Migrations reset successfully!");
+                    } else {
+                        println!("Migration reset canceled by user.");
+                    }
+                }
+                Some((
+                    alter @ ("add_column" | "remove_column" | "rename_column" | "change_column_type"
+                    | "add_index" | "add_foreign_key"),
+                    matches,
+                )) => {
+                    let arg = |name: &str| matches.get_one::<String>(name).unwrap().to_string();
+                    let change = match alter {
+                        "add_column" => ColumnSpec::parse(&arg("column")).map(|column| {
+                            AlterTable::AddColumn {
+                                table: arg("table"),
+                                column,
+                            }
+                        }),
+                        "remove_column" => ColumnSpec::parse(&arg("column")).map(|column| {
+                            AlterTable::RemoveColumn {
+                                table: arg("table"),
+                                column,
+                            }
+                        }),
+                        "rename_column" => Ok(AlterTable::RenameColumn {
+                            table: arg("table"),
+                            from: arg("from"),
+                            to: arg("to"),
+                        }),
+                        "change_column_type" => ColumnSpec::parse(&arg("column")).and_then(|column| {
+                            let from = ColumnSpec::parse(&format!("{}:{}", column.name, arg("from")))?;
+                            Ok(AlterTable::ChangeColumnType {
+                                table: arg("table"),
+                                column,
+                                from,
+                            })
+                        }),
+                        "add_index" => Ok(AlterTable::AddIndex {
+                            table: arg("table"),
+                            columns: matches
+                                .get_many::<String>("columns")
+                                .unwrap()
+                                .cloned()
+                                .collect(),
+                            unique: matches.get_flag("unique"),
+                        }),
+                        _ => Ok(AlterTable::AddForeignKey {
+                            table: arg("table"),
+                            column: arg("column"),
+                            references: arg("references"),
+                        }),
+                    };
+
+                    let result = match change {
+                        Ok(change) => create_alter_table_migration(change).await.map(|_| ()),
+                        Err(why) => Err(CustomMigrationError::IoError(why)),
+                    };
+                    if let Err(why) = result {
+                        eprintln!("Error creating migration: {}", why);
+                        std::process::exit(1);
+                    }
+                }
                 _ => {
                     println!("Invalid migration choice");
                 }
             },
+            Some(("db", matches)) => match matches.subcommand() {
+                Some(("create", _)) => {
+                    if let Err(why) = create_project_database().await {
+                        eprintln!("Error creating the database: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("drop", matches)) => {
+                    let confirmation = matches.get_flag("yes")
+                        || Confirm::new()
+                            .with_prompt("Are you sure you want to drop the database? Every table and row will be deleted")
+                            .interact()
+                            .expect("Error dropping the database: ");
+
+                    if confirmation {
+                        if let Err(why) = drop_project_database().await {
+                            eprintln!("Error dropping the database: {}", why);
+                            std::process::exit(1);
+                        }
+                    } else {
+                        println!("Drop cancelled");
+                    }
+                }
+                Some(("setup", _)) => {
+                    let environment = Self::current_environment();
+                    if let Err(why) = setup_project_database(environment).await {
+                        eprintln!("Error setting up the database: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("reset", matches)) => {
+                    let environment = Self::current_environment();
+                    let confirmation = matches.get_flag("yes")
+                        || Confirm::new()
+                            .with_prompt("Are you sure you want to drop and recreate the database? Every row will be deleted")
+                            .interact()
+                            .expect("Error resetting the database: ");
+
+                    if confirmation {
+                        if let Err(why) = reset_project_database(environment).await {
+                            eprintln!("Error resetting the database: {}", why);
+                            std::process::exit(1);
+                        }
+                    } else {
+                        println!("Reset cancelled");
+                    }
+                }
+                Some(("schema:load", _)) => {
+                    if let Err(why) = load_schema().await {
+                        eprintln!("Error loading schema: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("schema:dump", _)) => {
+                    if let Err(why) = dump_schema_command().await {
+                        eprintln!("Error dumping schema: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                Some(("seed", _)) => {
+                    let environment = Self::current_environment();
+                    if let Err(why) = run_seeds(environment).await {
+                        eprintln!("Error seeding the database: {}", why);
+                        std::process::exit(1);
+                    }
+                }
+                _ => {
+                    println!("Invalid db choice");
+                }
+            },
             // Add Feature Case
             Some(("feature", matches)) => match matches.subcommand() {
                 Some(("add", matches)) => match matches.subcommand() {
@@ -1327,7 +1782,7 @@ static/styles.css
                     "postgres" => DatabaseType::Postgres,
                     "mysql" => DatabaseType::Mysql,
                     "sqlite" => DatabaseType::Sqlite,
-                    "mongodb" => DatabaseType::Mongo,
+                    "mongo" | "mongodb" => DatabaseType::Mongo,
                     _ => DatabaseType::Sqlite,
                 };
                 let database: Database = match database_type {
@@ -1340,6 +1795,7 @@ static/styles.css
                             port.clone().parse::<u16>().unwrap(),
                             database_type.to_string().as_str(),
                         )
+                        .unwrap_or_else(Self::exit_on_database_error)
 
                     }
                     DatabaseType::Mysql => {
@@ -1351,6 +1807,7 @@ static/styles.css
                             port.clone().parse::<u16>().unwrap(),
                             database_type.to_string().as_str(),
                         )
+                        .unwrap_or_else(Self::exit_on_database_error)
                     }
                     DatabaseType::Sqlite => {
                         Database::new(
@@ -1361,9 +1818,18 @@ static/styles.css
                             0,
                             "sqlite".trim_end(),
                         )
+                        .unwrap_or_else(Self::exit_on_database_error)
                     }
                     DatabaseType::Mongo => {
-                        todo!("Implement this");
+                        Database::new(
+                            name.clone(),
+                            username.clone(),
+                            password.clone(),
+                            host.clone(),
+                            port.clone().parse::<u16>().unwrap(),
+                            "mongo",
+                        )
+                        .unwrap_or_else(Self::exit_on_database_error)
                     }
                 };
                 
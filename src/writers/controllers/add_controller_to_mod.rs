@@ -39,10 +39,11 @@ pub fn add_module_declaration(model_name: String, file_path: &Path) -> Result<()
         .last()
         .map_or(0, |m| m.end());
 
+    // Without use declarations, the new one goes right after the new mod declaration
     let last_use_end_pos = re_use
         .find_iter(&contents)
         .last()
-        .map_or(0, |m| m.end());
+        .map_or(last_mod_end_pos, |m| m.end());
 
     // Insert the new controller mod and use declarations
     contents.insert_str(last_mod_end_pos, &new_controller_mod);
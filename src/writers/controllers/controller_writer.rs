@@ -53,6 +53,101 @@ pub fn write_to_controller_name_html(controller_name: &str) -> Result<(), Error>
     Ok(())
 }
 
+/// # Name: model_form_html_contents
+/// This function returns the html of the form the create and update controllers of a model re-render.
+/// It has an input for each of the `values` in the context, the messages of the field in `errors`
+/// under it, and posts to `action`.
+/// # Arguments:
+/// * model_name: &str
+/// # Returns:
+/// * String
+/// # Example:
+/// ```
+/// use rustyroad::writers::model_form_html_contents;
+/// assert!(model_form_html_contents("post").contains("The post couldn't be saved."));
+/// ```
+pub fn model_form_html_contents(model_name: &str) -> String {
+    format!(
+        r#"{{% extends 'base.html.tera' %}}
+{{% block title %}}{{{{ title }}}}{{% endblock title %}}
+{{% block head %}}
+{{{{ super() }}}}
+{{% endblock head %}}
+{{% block content %}}
+<div class='mx-auto max-w-2xl px-6 py-16 lg:px-8'>
+<h1 class='text-3xl font-bold tracking-tight text-gray-900 mb-8'>{{{{ title }}}}</h1>
+{{% if errors %}}
+<div class='rounded-md bg-red-50 p-4 mb-6'>
+<p class='text-sm font-medium text-red-800'>The {model_name} couldn't be saved.</p>
+</div>
+{{% endif %}}
+<form class='space-y-6' action='{{{{ action }}}}' method='POST'>
+{{% for name, value in values %}}{{% if name != 'id' %}}
+<div>
+<label for='{{{{ name }}}}' class='block text-sm font-medium leading-6 text-gray-900'>{{{{ name | replace(from='_', to=' ') | capitalize }}}}</label>
+<input id='{{{{ name }}}}' name='{{{{ name }}}}' value='{{% if value is string %}}{{{{ value | escape }}}}{{% else %}}{{{{ value }}}}{{% endif %}}' class='mt-2 block w-full rounded-md border-0 py-1.5 text-gray-900 shadow-sm ring-1 ring-inset {{% if name in errors %}}ring-red-500{{% else %}}ring-gray-300{{% endif %}} sm:text-sm sm:leading-6'>
+{{% if name in errors %}}{{% for message in errors[name] %}}
+<p class='mt-2 text-sm text-red-600'>{{{{ name | replace(from='_', to=' ') | capitalize }}}} {{{{ message | escape }}}}</p>
+{{% endfor %}}{{% endif %}}
+</div>
+{{% endif %}}{{% endfor %}}
+<button type='submit' class='flex w-full justify-center rounded-md bg-indigo-600 px-3 py-1.5 text-sm font-semibold leading-6 text-white shadow-sm hover:bg-indigo-500'>Save</button>
+</form>
+</div>
+{{% endblock content %}}"#,
+        model_name = model_name
+    )
+}
+
+/// # Name: write_to_model_form_html
+/// This function writes the form of a model to `src/views/pages/<model_name>_form.html.tera`,
+/// unless it exists, see [`model_form_html_contents`].
+/// # Arguments:
+/// * model_name: &str
+/// # Returns:
+/// * Result<(), Error>
+pub fn write_to_model_form_html(model_name: &str) -> Result<(), Error> {
+    let path = format!("src/views/pages/{}_form.html.tera", model_name);
+    if Path::new(&path).exists() {
+        return Ok(());
+    }
+    fs::create_dir_all("src/views/pages")?;
+    fs::write(&path, model_form_html_contents(model_name))?;
+    Ok(())
+}
+
+/// # Name: model_form_renderer_contents
+/// The function the create and update controllers of a model render its form with.
+fn model_form_renderer_contents(model_name: &str) -> String {
+    format!(
+        r#"
+/// Renders pages/{model_name}_form.html.tera with the values of the form and the messages of its fields.
+fn render_{model_name}_form<T: Serialize>(
+    tmpl: &Tera,
+    action: &str,
+    values: &T,
+    errors: Option<&ValidationErrors>,
+    status: StatusCode,
+) -> HttpResponse {{
+    let mut context = Context::new();
+    context.insert("title", "{capitalized_model_name}");
+    context.insert("action", action);
+    context.insert("values", values);
+    context.insert("errors", &errors.map(ValidationErrors::by_field).cloned().unwrap_or_default());
+    match tmpl.render("pages/{model_name}_form.html.tera", &context) {{
+        Ok(rendered) => HttpResponse::build(status).content_type("text/html").body(rendered),
+        Err(e) => {{
+            eprintln!("Error rendering the {model_name} form: {{:?}}", e);
+            HttpResponse::InternalServerError().finish()
+        }}
+    }}
+}}
+"#,
+        model_name = model_name,
+        capitalized_model_name = capitalize_first(model_name)
+    )
+}
+
 /// # Name: write_to_controller_name_html_with_authorized_view
 /// This function generates the html for a controller's view with an authorized view.
 /// It is a standard template that can be used for any controller that requires authorization.
@@ -147,10 +242,10 @@ pub fn write_to_new_get_all_controller(model_name: String) -> Result<(), Error>
 
     let controller_contents = format!(
         r#"#[get("/{}/all")]
-pub async fn get_all_{}s() -> HttpResponse {{
-    let result = {}::get_all_{}s().await;
+pub async fn get_all_{}s(pool: Data<DatabaseConnection>) -> HttpResponse {{
+    let result = {}::get_all_{}s(pool.get_ref()).await;
     match result {{
-        Ok({}) => HttpResponse::Ok().json({}),
+        Ok({}s) => HttpResponse::Ok().json({}s),
         Err(e) => HttpResponse::BadRequest().json(e.to_string()),
     }}
 }}
@@ -160,8 +255,8 @@ pub async fn get_all_{}s() -> HttpResponse {{
         model_name,
         capitalize_model_name,
         model_name,
-        capitalize_model_name,
-        capitalize_model_name
+        model_name,
+        model_name
     );
 
     // Determine the controller file path
@@ -182,6 +277,7 @@ pub async fn get_all_{}s() -> HttpResponse {{
     file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
     file_contents = add_or_update_import(&file_contents, "crate", "models");
     file_contents = add_or_update_import(&file_contents, "models",&capitalize_model_name);
+    file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseConnection");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
 
@@ -438,12 +534,23 @@ pub fn write_to_new_post_controller(model_name: String) -> Result<(), Error> {
 /// The controller is generated by the rustyroad CLI.
 /// It is a best guess at what the controller should look like.
 /// Please review the controller and make any necessary changes.
+/// A JSON body is answered with JSON, a form of pages/{model_name}_form.html.tera with the form.
 #[post("/{}")]
-pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
+pub async fn create_{}({}: Either<Json<New{}>, Form<New{model_capitalized}>>, tmpl: Data<Tera>, pool: Data<DatabaseConnection>, user: Option<Identity>) -> HttpResponse {{
     if let Some(_user) = user {{
-        let result = {}::create_{}({}.into_inner()).await;
+        let (new_{model_name}, from_form) = match {model_name} {{
+            Either::Left(json) => (json.into_inner(), false),
+            Either::Right(form) => (form.into_inner(), true),
+        }};
+        let result = {}::create_{}(pool.get_ref(), new_{}.clone()).await;
         match result {{
-            Ok(page) => HttpResponse::Ok().json(page),
+            Ok(created) if from_form => render_{model_name}_form(&tmpl, "/{model_name}", &created, None, StatusCode::CREATED),
+            Ok(created) => HttpResponse::Ok().json(created),
+            // The messages of the fields that broke the validations of the model
+            Err(DatabaseError::Validation(errors)) if from_form => {{
+                render_{model_name}_form(&tmpl, "/{model_name}", &new_{model_name}, Some(&errors), StatusCode::UNPROCESSABLE_ENTITY)
+            }}
+            Err(DatabaseError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(errors),
             Err(e) => {{
                 eprintln!("Error creating page: {{:?}}", e); // Log the error
                 HttpResponse::BadRequest().json(e.to_string())
@@ -471,7 +578,9 @@ pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
                 &model_name,
                 &model_name,
                 &model_name,
-                &model_name
+                &model_name,
+                model_name = &model_name,
+                model_capitalized = &capitalized_model_name
         );
 
     // Determine the controller file path
@@ -496,10 +605,21 @@ pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
         file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
         file_contents = add_or_update_import(&file_contents, "crate", "models");
         file_contents = add_or_update_import(&file_contents, "models", &capitalized_model_name);
+        file_contents = add_or_update_import(&file_contents, "models", &format!("New{}", capitalized_model_name));
+        file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseConnection");
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
         file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
+        file_contents = add_or_update_import(&file_contents, "actix_web", "web::Form");
+        file_contents = add_or_update_import(&file_contents, "actix_web", "Either");
+        file_contents = add_or_update_import(&file_contents, "actix_web", "http::StatusCode");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseError");
+        file_contents = add_or_update_import(&file_contents, "rustyroad::database", "ValidationErrors");
+        file_contents = add_or_update_import(&file_contents, "serde", "Serialize");
 
         // Add the new controller content to the file
+        if !file_contents.contains(&format!("fn render_{}_form", model_name)) {
+            file_contents.push_str(&model_form_renderer_contents(&model_name));
+        }
         file_contents.push_str("\n\n");
         file_contents.push_str(&controller_contents);
 
@@ -519,6 +639,7 @@ pub async fn create_{}({}: Json<{}>,user: Option<Identity>) -> HttpResponse {{
         file.flush()?;
 
         add_new_controller_to_main_rs(None,Some(&model_name), &format!("create_{}", &model_name))?; // Assuming this function exists
+        write_to_model_form_html(&model_name)?;
 
         println!("Successfully written to {}.rs", model_name);
         Ok(())
@@ -569,11 +690,12 @@ pub fn write_to_new_delete_controller(model_name: String) -> Result<(), Error> {
 
     let contents = format!(
         r#"#[delete("/{}/{{id}}")]
-        pub async fn delete_{}(id: Path<i32>, user: Option<Identity>) -> HttpResponse {{
+        pub async fn delete_{}(id: Path<i32>, pool: Data<DatabaseConnection>, user: Option<Identity>) -> HttpResponse {{
             if let Some(_user) = user {{
-                let result = {}::delete_{}(id.into_inner()).await;
+                let result = {}::delete_{}(pool.get_ref(), id.into_inner()).await;
                 match result {{
-                    Ok(_) => HttpResponse::Ok().json("Successfully deleted."),
+                    Ok(true) => HttpResponse::Ok().json("Successfully deleted."),
+                    Ok(false) => HttpResponse::NotFound().json("Not found."),
                     Err(e) => HttpResponse::BadRequest().json(e.to_string()),
                 }}
             }} else {{
@@ -608,6 +730,9 @@ pub fn write_to_new_delete_controller(model_name: String) -> Result<(), Error> {
     file_contents = add_or_update_import(&file_contents, "crate", "models");
     file_contents = add_or_update_import(&file_contents, "models::user", "UserLogin");
     file_contents = add_or_update_import(&file_contents, format!("models::{}", &model_name).as_str(), &capitalized_model_name);
+    file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseConnection");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
 
     // Add the new controller content to the file
     file_contents.push_str("\n\n");
@@ -659,12 +784,26 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
 
     // Define the contents to be written to the file
     let new_controller_content = format!(
-        r#"#[patch("/{}/{{id}}")]
-        pub async fn update_{}(id: Path<i32>, {}: Json<{}>, user: Option<Identity>) -> HttpResponse {{
+        r#"/// A JSON body is answered with JSON, a form of pages/{model_name}_form.html.tera, which can only POST, with the form.
+        #[route("/{}/{{id}}", method = "PATCH", method = "POST")]
+        pub async fn update_{}(id: Path<i32>, {}: Either<Json<Update{}>, Form<Update{model_capitalized}>>, tmpl: Data<Tera>, pool: Data<DatabaseConnection>, user: Option<Identity>) -> HttpResponse {{
             if let Some(_user) = user {{
-                let result = {}::update_{}(id.into_inner(), {}.into_inner()).await;
+                let id = id.into_inner();
+                let (changes, from_form) = match {model_name} {{
+                    Either::Left(json) => (json.into_inner(), false),
+                    Either::Right(form) => (form.into_inner(), true),
+                }};
+                let action = format!("/{model_name}/{{}}", id);
+                let result = {}::update_{}(pool.get_ref(), id, changes.clone()).await;
                 match result {{
-                    Ok(page) => HttpResponse::Ok().json(page),
+                    Ok(Some(updated)) if from_form => render_{}_form(&tmpl, &action, &updated, None, StatusCode::OK),
+                    Ok(Some(updated)) => HttpResponse::Ok().json(updated),
+                    Ok(None) => HttpResponse::NotFound().json("Not found."),
+                    // The messages of the fields that broke the validations of the model
+                    Err(DatabaseError::Validation(errors)) if from_form => {{
+                        render_{model_name}_form(&tmpl, &action, &changes, Some(&errors), StatusCode::UNPROCESSABLE_ENTITY)
+                    }}
+                    Err(DatabaseError::Validation(errors)) => HttpResponse::UnprocessableEntity().json(errors),
                     Err(e) => HttpResponse::BadRequest().json(e.to_string()),
                 }}
             }} else {{
@@ -677,7 +816,9 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
         &capitalized_model_name,
         &capitalized_model_name,
         &model_name,
-        &model_name
+        &model_name,
+        model_name = &model_name,
+        model_capitalized = &capitalized_model_name
     );
 
     // Determine the controller file path
@@ -693,18 +834,30 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
     let mut file_contents = fs::read_to_string(&path)?;
 
     // Update imports
-    file_contents = add_or_update_import(&file_contents, "actix_web", "patch");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "route");
     file_contents = add_or_update_import(&file_contents, "actix_web", "HttpResponse");
     file_contents = add_or_update_import(&file_contents, "tera", "Context");
     file_contents = add_or_update_import(&file_contents, "tera", "Tera");
     file_contents = add_or_update_import(&file_contents, "actix_identity", "Identity");
     file_contents = add_or_update_import(&file_contents, "crate", "models");
     file_contents = add_or_update_import(&file_contents, "models", &capitalized_model_name);
+    file_contents = add_or_update_import(&file_contents, "models", &format!("Update{}", capitalized_model_name));
+    file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseConnection");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "web::Data");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Json");
     file_contents = add_or_update_import(&file_contents, "actix_web", "web::Path");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "web::Form");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "Either");
+    file_contents = add_or_update_import(&file_contents, "actix_web", "http::StatusCode");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::database", "DatabaseError");
+    file_contents = add_or_update_import(&file_contents, "rustyroad::database", "ValidationErrors");
+    file_contents = add_or_update_import(&file_contents, "serde", "Serialize");
 
 
     // Add the new controller content to the file
+    if !file_contents.contains(&format!("fn render_{}_form", model_name)) {
+        file_contents.push_str(&model_form_renderer_contents(&model_name));
+    }
     file_contents.push_str("\n\n");
     file_contents.push_str(&new_controller_content);
     file_contents.push_str("\n\n");
@@ -720,6 +873,7 @@ pub fn write_to_new_update_controller(model_name: String) -> Result<(), Error> {
     file.flush()?;
 
     add_new_controller_to_main_rs(None,Some(&model_name), &format!("update_{}", &model_name))?; // Assuming this function exists
+    write_to_model_form_html(&model_name)?;
 
     println!("Successfully written to {}.rs", model_name);
 
@@ -1025,7 +1179,7 @@ pub fn write_to_initial_get_controller(controller_name: String) -> Result<(), Er
         r#"use actix_web::{{get, web, HttpResponse, HttpRequest, Error}};
 use tera::{{Context, Tera}};
 use crate::models;
-use rustyroad::database::Database;
+use rustyroad::database::DatabaseConnection;
 use models::user::UserLogin;
 
 #[get("/{}")]
@@ -1077,10 +1231,10 @@ pub fn write_to_initial_post_controller_authentication(controller_name: String)
 async fn login_function(
     form: web::Form<UserLogin>,
     tmpl: web::Data<Tera>, // Updated line
-    db: web::Data<Database>,
+    pool: web::Data<DatabaseConnection>,
     req: HttpRequest
 ) -> Result<HttpResponse, actix_web::Error> {
-     form.user_login(req, tmpl, db.get_ref().clone()).await
+     form.user_login(req, tmpl, pool.get_ref()).await
 }
 
 
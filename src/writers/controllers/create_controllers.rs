@@ -1,7 +1,8 @@
 
 use eyre::Error;
 use crate::{CRUDType};
-use crate::writers::{create_create_controller_in_existing_folder, create_create_controller_in_new_folder, create_read_controller_in_existing_folder, create_read_controller_in_new_folder, create_update_controller_in_existing_folder, create_update_model};
+use crate::database::{Database, DatabaseType};
+use crate::writers::{create_create_controller_in_existing_folder, create_create_controller_in_new_folder, create_read_controller_in_existing_folder, create_read_controller_in_new_folder, create_update_controller_in_existing_folder, create_update_model, write_to_mongo_crud_controller};
 /// # Name: create_new_controller
 /// ### Description:
 /// - Creates a new controller
@@ -26,6 +27,13 @@ pub async fn create_new_controller(model_name: String, controller_type: CRUDType
     // if it is not, it will return an error and ask the user to run the command in a rustyroad project
     // if it is a rustyroad project, it will create a new directory with the controllerName
     // it will create a new file with the controllerName.rs
+    // MongoDB models get one controller with every CRUD handler
+    if let Ok(database) = Database::get_database_from_rustyroad_toml() {
+        if database.database_type == DatabaseType::Mongo {
+            return write_to_mongo_crud_controller(model_name);
+        }
+    }
+
     // ask the user if a controller folder already exists
     // if it does, ask the user if they want to add this controller to the file for that controller
     // if it does not, continue with the rest of the code
@@ -6,6 +6,7 @@ pub mod create_update_controller;
 pub mod create_delete_controller;
 pub mod create_get_all_controller;
 pub mod add_controller_to_mod;
+pub mod mongo_controller;
 
 pub use controller_writer::*;
 pub use create_controllers::*;
@@ -14,4 +15,5 @@ pub use create_create_controller::*;
 pub use create_update_controller::*;
 pub use create_delete_controller::*;
 pub use create_get_all_controller::*;
-pub use add_controller_to_mod::*;
\ No newline at end of file
+pub use add_controller_to_mod::*;
+pub use mongo_controller::*;
\ No newline at end of file
@@ -0,0 +1,161 @@
+use crate::helpers::helpers::capitalize_first;
+use crate::writers::add_module_declaration;
+use crate::writers::write_to_main::add_new_controller_to_main_rs;
+use eyre::{eyre, Error};
+use std::fs;
+use std::path::Path;
+
+/// ## Name: mongo_controller_handlers
+/// ### Description: Returns the names of the handlers written by [`mongo_controller_contents`]
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - Returns: [`Vec<String>`] - the handlers in the order they are registered in main.rs
+pub fn mongo_controller_handlers(model_name: &str) -> Vec<String> {
+    ["list", "show", "create", "update", "delete"]
+        .iter()
+        .map(|action| format!("{}_{}", action, model_name))
+        .collect()
+}
+
+/// ## Name: mongo_controller_contents
+/// ### Description: Returns a JSON CRUD controller for the model of a MongoDB collection
+/// The handlers use the shared pool of main.rs and the functions of the model written by
+/// `create_mongo_model`; ids are the hex strings of the `ObjectId`s.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - Returns: [`String`] - the contents of `src/controllers/<model_name>.rs`
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::writers::mongo_controller_contents;
+///
+/// let contents = mongo_controller_contents("post");
+/// assert!(contents.contains("#[get(\"/post/{id}\")]"));
+/// assert!(contents.contains("pub async fn delete_post("));
+/// ```
+pub fn mongo_controller_contents(model_name: &str) -> String {
+    format!(
+        r#"use actix_web::{{delete, get, post, put, web, HttpResponse}};
+use mongodb::bson::oid::ObjectId;
+use rustyroad::database::DatabaseConnection;
+
+use crate::models::{struct_name};
+
+fn mongo(pool: &DatabaseConnection) -> Result<&mongodb::Database, actix_web::Error> {{
+    pool.as_mongo()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("The database isn't MongoDB"))
+}}
+
+fn object_id(id: &str) -> Result<ObjectId, actix_web::Error> {{
+    ObjectId::parse_str(id).map_err(actix_web::error::ErrorBadRequest)
+}}
+
+#[get("/{model_name}")]
+pub async fn list_{model_name}(
+    pool: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {{
+    let {model_name}s = {struct_name}::all(mongo(&pool)?)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json({model_name}s))
+}}
+
+#[get("/{model_name}/{{id}}")]
+pub async fn show_{model_name}(
+    pool: web::Data<DatabaseConnection>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {{
+    let {model_name} = {struct_name}::find_by_id(mongo(&pool)?, object_id(&id)?)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(match {model_name} {{
+        Some({model_name}) => HttpResponse::Ok().json({model_name}),
+        None => HttpResponse::NotFound().finish(),
+    }})
+}}
+
+#[post("/{model_name}")]
+pub async fn create_{model_name}(
+    pool: web::Data<DatabaseConnection>,
+    {model_name}: web::Json<{struct_name}>,
+) -> Result<HttpResponse, actix_web::Error> {{
+    let {model_name} = {model_name}
+        .into_inner()
+        .create(mongo(&pool)?)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Created().json({model_name}))
+}}
+
+#[put("/{model_name}/{{id}}")]
+pub async fn update_{model_name}(
+    pool: web::Data<DatabaseConnection>,
+    id: web::Path<String>,
+    {model_name}: web::Json<{struct_name}>,
+) -> Result<HttpResponse, actix_web::Error> {{
+    let id = object_id(&id)?;
+    let mut {model_name} = {model_name}.into_inner();
+    let updated = {struct_name}::update(mongo(&pool)?, id, {model_name}.clone())
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if !updated {{
+        return Ok(HttpResponse::NotFound().finish());
+    }}
+    {model_name}.id = Some(id);
+    Ok(HttpResponse::Ok().json({model_name}))
+}}
+
+#[delete("/{model_name}/{{id}}")]
+pub async fn delete_{model_name}(
+    pool: web::Data<DatabaseConnection>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, actix_web::Error> {{
+    let deleted = {struct_name}::delete(mongo(&pool)?, object_id(&id)?)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(if deleted {{
+        HttpResponse::NoContent().finish()
+    }} else {{
+        HttpResponse::NotFound().finish()
+    }})
+}}
+"#,
+        struct_name = capitalize_first(model_name),
+        model_name = model_name,
+    )
+}
+
+/// ## Name: write_to_mongo_crud_controller
+/// ### Description: Writes the CRUD controller of a MongoDB model and registers its handlers
+/// Writes `src/controllers/<model_name>.rs`, declares it in `src/controllers/mod.rs` and adds
+/// the handlers to main.rs. When main.rs can't be updated, the services to add are printed.
+/// #### Parameters:
+/// - model_name: [`String`] - the name of a model written by `create_mongo_model`
+/// - Returns: [`Result<(), Error>`]
+pub fn write_to_mongo_crud_controller(model_name: String) -> Result<(), Error> {
+    if !Path::new(&format!("./src/models/{}.rs", model_name)).exists() {
+        return Err(eyre!(
+            "The model {} does not exist, run `rustyroad generate model {}` first",
+            model_name,
+            model_name
+        ));
+    }
+    let controller_path = format!("./src/controllers/{}.rs", model_name);
+    if Path::new(&controller_path).exists() {
+        return Err(eyre!("{} already exists", controller_path));
+    }
+
+    fs::write(&controller_path, mongo_controller_contents(&model_name))?;
+    add_module_declaration(model_name.clone(), Path::new("./src/controllers/mod.rs"))?;
+
+    for handler in mongo_controller_handlers(&model_name) {
+        if let Err(why) = add_new_controller_to_main_rs(None, Some(&model_name), &handler) {
+            println!(
+                "Couldn't add {} to main.rs ({}), add .service(controllers::{}::{}) to the App",
+                handler, why, model_name, handler
+            );
+        }
+    }
+    println!("Created the {} controller", model_name);
+    Ok(())
+}
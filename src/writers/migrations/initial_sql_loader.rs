@@ -1,6 +1,9 @@
 use crate::{database::Database, writers::write_to_file, Project};
 use bcrypt::{hash as new_hash, DEFAULT_COST};
 use color_eyre::eyre::Result;
+use mongodb::bson::{doc, Document};
+use std::fs;
+use std::path::Path;
 async fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
     new_hash(password, DEFAULT_COST)
 }
@@ -330,7 +333,104 @@ CREATE TABLE Sessions (
                 });
             }
         }
-        crate::database::DatabaseType::Mongo => todo!(),
+        crate::database::DatabaseType::Mongo => {
+            // MongoDB migrations are JSON commands, so the initial migration is up.json and down.json
+            let up = vec![
+                doc! {
+                    "create": "roles",
+                    "validator": { "$jsonSchema": {
+                        "bsonType": "object",
+                        "required": ["name"],
+                        "properties": { "name": { "bsonType": "string" } },
+                    } },
+                },
+                doc! {
+                    "create": "users",
+                    "validator": { "$jsonSchema": {
+                        "bsonType": "object",
+                        "required": ["password", "username"],
+                        "properties": {
+                            "password": { "bsonType": "string" },
+                            "username": { "bsonType": "string" },
+                            "role": { "bsonType": ["string", "null"] },
+                        },
+                    } },
+                },
+                doc! {
+                    "create": "permissions",
+                    "validator": { "$jsonSchema": {
+                        "bsonType": "object",
+                        "required": ["name", "role"],
+                        "properties": {
+                            "name": { "bsonType": "string" },
+                            "role": { "bsonType": "string" },
+                        },
+                    } },
+                },
+                doc! {
+                    "create": "sessions",
+                    "validator": { "$jsonSchema": {
+                        "bsonType": "object",
+                        "required": ["user_id", "session_token"],
+                        "properties": {
+                            "user_id": { "bsonType": "objectId" },
+                            "session_token": { "bsonType": "string" },
+                            "session_data": { "bsonType": ["string", "null"] },
+                            "expiration_date": { "bsonType": ["date", "null"] },
+                        },
+                    } },
+                },
+                doc! { "createIndexes": "roles", "indexes": [
+                    { "key": { "name": 1 }, "name": "roles_name_unique", "unique": true },
+                ] },
+                doc! { "createIndexes": "users", "indexes": [
+                    { "key": { "username": 1 }, "name": "users_username_unique", "unique": true },
+                ] },
+                doc! { "createIndexes": "permissions", "indexes": [
+                    { "key": { "name": 1 }, "name": "permissions_name_unique", "unique": true },
+                ] },
+                doc! { "createIndexes": "sessions", "indexes": [
+                    { "key": { "session_token": 1 }, "name": "sessions_session_token_unique", "unique": true },
+                    { "key": { "user_id": 1 }, "name": "sessions_user_id_index" },
+                ] },
+                // add admin role
+                doc! { "insert": "roles", "documents": [{ "name": "admin" }] },
+                // create default permissions
+                doc! { "insert": "permissions", "documents": [
+                    { "name": "create_user", "role": "admin" },
+                    { "name": "read_user", "role": "admin" },
+                ] },
+                // add admin user
+                doc! { "insert": "users", "documents": [
+                    { "password": &hashed_admin_password, "username": "admin", "role": "admin" },
+                ] },
+            ];
+            let down = vec![
+                doc! { "drop": "sessions" },
+                doc! { "drop": "permissions" },
+                doc! { "drop": "users" },
+                doc! { "drop": "roles" },
+            ];
+
+            let to_json = |commands: &Vec<Document>| {
+                serde_json::to_string_pretty(commands)
+                    .map(|json| json + "\n")
+                    .map_err(|why| std::io::Error::new(std::io::ErrorKind::InvalidData, why))
+            };
+            let up_json = to_json(&up)?;
+            let directory = Path::new(&project.initial_migration_directory);
+            fs::write(directory.join("up.json"), &up_json)?;
+            fs::write(directory.join("down.json"), to_json(&down)?)?;
+
+            // The empty up.sql and down.sql would shadow the JSON files
+            for sql_file in [&project.initial_migration_up, &project.initial_migration_down] {
+                if Path::new(sql_file).exists() {
+                    fs::remove_file(sql_file)?;
+                }
+            }
+
+            statements.push(up_json);
+        }
     }
 
     Ok(statements)
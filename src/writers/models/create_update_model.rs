@@ -1,9 +1,17 @@
-use crate::database::{create_migration, Database, DatabaseType, find_migration_dir, MySqlTypes, PostgresTypes, SqliteTypes};
+use crate::database::migrations::{
+    create_join_table_migration, create_migration_from_columns, find_migration_named, join_table_name,
+    ColumnSchema, ForeignKeySchema, IndexSchema, TableSchema,
+};
+use crate::database::{create_migration, Database, DatabaseType, find_migration_dir};
 use crate::generators::create_file;
-use crate::helpers::helpers::get_project_name_from_rustyroad_toml;
-use color_eyre::{eyre::Error, Result};
+use crate::writers::add_module_declaration;
+use crate::writers::models::{associated_model_contents, create_mongo_model, Association};
+use crate::helpers::helpers::{capitalize_first, get_project_name_from_rustyroad_toml, pluralize, singularize};
+use color_eyre::{eyre::{eyre, Error}, Result};
+use sqlparser::ast::{ColumnOption, Expr, ObjectName, Statement, TableConstraint};
+use sqlparser::dialect::{Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
 use std::fs;
-use std::path::Path;
 
 pub async fn create_update_model(model_name: &str) -> Result<(), Error> {
     // check if the current directory is a rustyroad project
@@ -52,72 +60,13 @@ pub async fn create_update_model(model_name: &str) -> Result<(), Error> {
 
     let fields = fields.split(",").collect::<Vec<&str>>();
 
-    let mut contents = String::new();
-
-    contents.push_str("use serde::{Serialize, Deserialize};\n");
-
-    contents.push_str("#[derive(Serialize, Deserialize)]\n");
-
-    contents.push_str("#[derive(Debug)]\n");
-
-    contents.push_str("#[derive(Clone)]\n");
-
-    contents.push_str("#[derive(Queryable)]\n");
-
-    contents.push_str("#[derive(Insertable)]\n");
-
-    contents.push_str("#[table_name = \"");
-
-    contents.push_str(&model_name);
-
-    contents.push_str("\"]\n");
-
-    contents.push_str("pub struct ");
-
-    contents.push_str(&model_name);
-
-    contents.push_str(" {\n");
+    let mut struct_fields = String::new();
 
     for (i, field) in fields.iter().enumerate() {
-        contents.push_str("    pub ");
-        contents.push_str(field);
-        contents.push_str(": ");
-        contents.push_str(types[i]);
-        contents.push_str(",\n");
+        struct_fields.push_str(&format!("    pub {}: {},\n", field.trim(), types[i].trim()));
     }
 
-    contents.push_str("}\n");
-
-    contents.push_str("impl ");
-
-    contents.push_str(&model_name);
-
-    contents.push_str(" {\n");
-
-    contents.push_str("    pub fn new(");
-
-    for (i, field) in fields.iter().enumerate() {
-        contents.push_str(field);
-        contents.push_str(": ");
-        contents.push_str(types[i]);
-        contents.push_str(", ");
-    }
-
-    contents.push_str(") -> Self {\n");
-
-    contents.push_str("        Self {\n");
-
-    for (_i, field) in fields.iter().enumerate() {
-        contents.push_str("            ");
-        contents.push_str(field);
-        contents.push_str(",\n");
-    }
-
-    contents.push_str("        }\n");
-
-    contents.push_str("    }\n");
-
-    contents.push_str("}\n");
+    let contents = model_contents(model_name, model_name, &struct_fields);
 
     // create the migration
 
@@ -135,15 +84,20 @@ pub async fn create_update_model(model_name: &str) -> Result<(), Error> {
 /// # Arguments
 ///
 /// * `model_name` - The name of the model to create
+/// * `columns` - The columns of a new `create_<table>` migration as `name:type`, e.g. `author_id:references:users`;
+///   when empty, the model is read from the existing migration of the table
+/// * `associations` - The `has_many`, `has_one` and `many_to_many` associations of the model,
+///   the foreign keys of the table are `belongs_to` associations
 ///
 /// # Description
 ///
 /// This function will create a base model when a user runs the model command in the CLI.
+/// A `many_to_many` association also creates the migration of its join table.
 ///
 /// # Example
 ///
 /// ```
-/// rustyroad::writers::models::create_base_model("User");
+/// rustyroad::writers::models::create_base_model("user", &[], &[]);
 ///
 /// ```
 ///
@@ -154,7 +108,7 @@ pub async fn create_update_model(model_name: &str) -> Result<(), Error> {
 /// # Errors
 ///
 /// This function will return an error if the current directory is not a rustyroad project.
-pub async fn create_base_model(model_name: &str) -> Result<(), Error> {
+pub async fn create_base_model(model_name: &str, columns: &[String], associations: &[Association]) -> Result<(), Error> {
     println!("Creating base model: {}", model_name);
 
     // check to see if this is a rustyroad project
@@ -162,13 +116,21 @@ pub async fn create_base_model(model_name: &str) -> Result<(), Error> {
 
     println!("This is a rustyroad project.");
 
+    let table_name = pluralize(model_name);
+
     // based on the newly created migration, create the model
     // search the migrations folder for the migration that was just created
     let mut migration_dir = "./config/database/migrations".to_string();
 
-    println!("Searching for migration: {}", model_name);
-
-    let migration_dir_result = find_migration_dir(migration_dir.clone(), model_name.to_string());
+    let migration_dir_result = if columns.is_empty() {
+        println!("Searching for migration: {}", model_name);
+        find_migration_dir(migration_dir.clone(), model_name.to_string())
+    } else {
+        // the columns are given, write the migration of the table without prompting
+        let name = format!("create_{}", table_name);
+        create_migration_from_columns(&name, columns)?;
+        find_migration_named(&name).ok_or_else(|| eyre!("Couldn't find the {} migration", name).into())
+    };
 
     println!("Migration dir result: {:?}", migration_dir_result);
 
@@ -189,274 +151,275 @@ pub async fn create_base_model(model_name: &str) -> Result<(), Error> {
         println!("The migration does not exist.");
     }
 
-    // read the file path and get the contents of the migration
-    let file_path_string = migration_dir + "/up.sql";
-    let file_path = std::path::Path::new(&file_path_string);
-    // validate the file path
-    let file_path_bool = std::path::Path::is_file(&file_path);
-
-    println!("File path: {:?}", file_path_bool);
-
     // get database type
-    let database_type = Database::get_database_from_rustyroad_toml().unwrap().database_type;
-
-
-    let struct_from_sql = match database_type {
-        DatabaseType::Postgres => generate_struct_from_postgres_sql(file_path, &PostgresTypes::BigInt),
-        DatabaseType::Mysql => generate_struct_from_mysql_sql(file_path, &MySqlTypes::TinyInt),
-        DatabaseType::Sqlite => generate_struct_from_sqlite_sql(file_path, &SqliteTypes::Integer),
-        DatabaseType::Mongo => todo!(),
-    };
-
-    //write the struct to the file
-    let struct_from_sql = struct_from_sql.unwrap();
-
-    let mut contents = String::new();
-
-    contents.push_str("use serde::{Serialize, Deserialize};\n");
-
-    contents.push_str("#[derive(Serialize, Deserialize)]\n");
-
-    contents.push_str("#[derive(Debug)]\n");
-
-    contents.push_str("#[derive(Clone)]\n");
-
-    contents.push_str("#[derive(Queryable)]\n");
-
-    contents.push_str("#[derive(Insertable)]\n");
-
-    contents.push_str("#[table_name = \"");
-
-    contents.push_str(&model_name);
-
-    contents.push_str("\"]\n");
-
-    contents.push_str("pub struct ");
+    let database_type = Database::get_database_from_rustyroad_toml()?.database_type;
 
-    contents.push_str(&model_name);
-
-    contents.push_str(" {\n");
-
-    contents.push_str(&struct_from_sql);
-
-    contents.push_str("}\n");
-
-    contents.push_str("impl ");
-
-    contents.push_str(&model_name);
-
-    contents.push_str(" {\n");
+    // MongoDB migrations create collections with up.json instead of tables with up.sql
+    if database_type == DatabaseType::Mongo {
+        if !associations.is_empty() {
+            return Err(eyre!("Associations need a SQL database, MongoDB collections have no foreign keys"));
+        }
+        return create_mongo_model(model_name, &migration_dir);
+    }
 
-    contents.push_str("    pub fn new(");
+    // read the file path and get the contents of the migration
+    let file_path_string = migration_dir + "/up.sql";
+    let sql = fs::read_to_string(&file_path_string)
+        .map_err(|why| eyre!("Couldn't read {}: {}", file_path_string, why))?;
+
+    // a migration can create several tables, prefer the one named after the model
+    let tables = parse_create_tables(&sql, &database_type)?;
+    let table = tables
+        .iter()
+        .find(|table| table.name == model_name || singularize(&table.name) == model_name)
+        .or_else(|| tables.first())
+        .ok_or_else(|| eyre!("{} doesn't create a table", file_path_string))?;
+
+    // the join table of a many-to-many association gets a migration unless it's named differently
+    for association in associations {
+        if let Association::ManyToMany { table: other_table, join_table, .. } = association {
+            if *join_table == join_table_name(&table.name, other_table) {
+                create_join_table_migration(&table.name, other_table)?;
+            }
+        }
+    }
 
-    // parse the sql and categorize it
-    parse_and_categorize_sql(file_path).unwrap();
+    let mut model_associations = Association::belongs_to(table);
+    model_associations.extend(associations.iter().cloned());
+    let contents = associated_model_contents(model_name, table, &model_associations, &database_type);
 
-    // create the controller
+    // create the model
     create_file(&format!("./src/models/{}.rs", model_name)).unwrap();
 
     // write the contents to the file
-    fs::write(&format!("./src/models/{}.rs", model_name), &contents)?;
+    fs::write(format!("./src/models/{}.rs", model_name), &contents)?;
+
+    // associations refer to each other through the models module
+    let models_mod = std::path::Path::new("./src/models/mod.rs");
+    if models_mod.exists() {
+        add_module_declaration(model_name.to_string(), models_mod)?;
+    }
 
     println!("Contents: {}", contents);
 
     Ok(())
 }
 
-
-
-fn generate_struct_from_postgres_sql(file_path: &Path, db_type: &PostgresTypes) -> Result<String, Error> {
-    // Read the SQL file
-    let sql = fs::read_to_string(file_path)?;
-
-    // Split the SQL into lines
-    let lines: Vec<&str> = sql.lines().collect();
-
-    // Initialize an empty string to hold the struct definition
-    let mut struct_def = String::new();
-
-    // Iterate over the lines of the SQL
-    for line in lines {
-        // If the line starts with "CREATE TABLE", extract the table name and start a new struct
-        if line.to_uppercase().starts_with("CREATE TABLE") {
-            let table_name = line.split_whitespace().nth(2).unwrap();
-            struct_def.push_str(&format!("\n#[derive(Debug, sqlx::FromRow)]\nstruct {} {{\n", table_name));
-        }
-        // If the line starts with a word followed by a space and a word, treat it as a column definition
-        else if line.chars().next().unwrap().is_alphanumeric() && line.contains(" ") {
-            let parts: Vec<&str> = line.split_whitespace().take(2).collect();
-            let column_name = parts[0];
-            let column_type = match db_type {
-                PostgresTypes::SmallInt | PostgresTypes::Integer => "i32",
-                PostgresTypes::BigInt => "i64",
-                PostgresTypes::Decimal | PostgresTypes::Numeric => "BigDecimal",
-                PostgresTypes::Real => "f32",
-                PostgresTypes::DoublePrecision => "f64",
-                PostgresTypes::Serial | PostgresTypes::BigSerial => "u32",
-                PostgresTypes::Money => "Money",
-                PostgresTypes::Char | PostgresTypes::VarChar | PostgresTypes::Text => "String",
-                PostgresTypes::ByteA => "Vec<u8>",
-                PostgresTypes::Timestamp | PostgresTypes::TimestampWithTimeZone => "NaiveDateTime",
-                PostgresTypes::Date => "NaiveDate",
-                PostgresTypes::Time |  PostgresTypes::TimeWithoutTimeZone | PostgresTypes::TimestampWithoutTimeZone  => "NaiveTime",
-                PostgresTypes::Interval => "Interval",
-                PostgresTypes::Boolean => "bool",
-                PostgresTypes::Json | PostgresTypes::JsonB => "serde_json::Value",
-                PostgresTypes::Uuid => "Uuid",
-                _ => "String", // Default to String for unknown types
-            };
-            struct_def.push_str(&format!("    {}: {},\n", column_name, column_type));
-        }
-        // If the line is ");", close the struct
-        else if line.trim() == ");" {
-            struct_def.push_str("}\n");
-        }
+/// ## Name: model_contents
+/// ### Description: Returns the model of a table, a struct that derives `rustyroad::Model`
+/// The derive gives the model `find`, `all`, `where_eq`, `create`, `update`, `destroy`,
+/// `count` and `paginate`, so the file only declares the columns.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - table_name: [`&str`] - the table the rows are stored in
+/// - fields: [`&str`] - the fields of the struct, one `    pub name: Type,` per line
+/// - Returns: [`String`] - the contents of `src/models/<model_name>.rs`
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::writers::models::model_contents;
+///
+/// let contents = model_contents("post", "posts", "    pub id: Option<i64>,\n    pub title: String,\n");
+/// assert!(contents.contains("#[table_name = \"posts\"]\npub struct Post {"));
+/// assert!(contents.contains("    pub title: String,\n}"));
+/// ```
+pub fn model_contents(model_name: &str, table_name: &str, fields: &str) -> String {
+    let mut imports = String::new();
+    if fields.contains("Naive") {
+        imports.push_str("use chrono::{NaiveDate, NaiveDateTime, NaiveTime};\n");
     }
-
-    Ok(struct_def)
+    imports.push_str("use rustyroad::Model;\n");
+    imports.push_str("use serde::{Deserialize, Serialize};\n");
+
+    format!(
+        "{imports}\n#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model)]\n#[table_name = \"{table_name}\"]\npub struct {struct_name} {{\n{fields}}}\n",
+        imports = imports,
+        table_name = table_name,
+        struct_name = capitalize_first(model_name),
+        fields = fields,
+    )
 }
 
-fn generate_struct_from_mysql_sql(file_path: &Path, db_type: &MySqlTypes) -> Result<String, Error> {
-    // Read the SQL file
-    let sql = fs::read_to_string(file_path)?;
-
-    // Split the SQL into lines
-    let lines: Vec<&str> = sql.lines().collect();
-
-    // Initialize an empty string to hold the struct definition
-    let mut struct_def = String::new();
+/// ## Name: parse_create_tables
+/// ### Description: Reads the tables created by the `CREATE TABLE` statements of a migration
+/// The SQL is parsed with the dialect of the database, so definitions spread over several
+/// lines, quoted identifiers, table constraints and composite keys are read like the
+/// database reads them. Columns are nullable unless they're `NOT NULL` or in the primary key,
+/// and `UNIQUE` constraints and unique indexes are the unique indexes of their table.
+/// #### Parameters:
+/// - sql: [`&str`] - the contents of an `up.sql`
+/// - database_type: [`&DatabaseType`] - the dialect of the SQL
+/// - Returns: [`Result<Vec<TableSchema>, Error>`] - the tables in the order they're created, an error when the SQL doesn't parse
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::DatabaseType;
+/// use rustyroad::writers::models::parse_create_tables;
+///
+/// let sql = r#"CREATE TABLE "posts" (
+///     id SERIAL PRIMARY KEY,
+///     title VARCHAR(255) NOT NULL,
+///     body TEXT
+/// );"#;
+/// let tables = parse_create_tables(sql, &DatabaseType::Postgres).unwrap();
+/// assert_eq!(tables[0].name, "posts");
+/// assert_eq!(tables[0].primary_key, vec!["id"]);
+/// assert!(tables[0].columns[0].auto_increment);
+/// assert!(!tables[0].columns[1].nullable);
+/// assert!(tables[0].columns[2].nullable);
+/// ```
+pub fn parse_create_tables(sql: &str, database_type: &DatabaseType) -> Result<Vec<TableSchema>, Error> {
+    let dialect: Box<dyn Dialect> = match database_type {
+        DatabaseType::Postgres => Box::new(PostgreSqlDialect {}),
+        DatabaseType::Mysql => Box::new(MySqlDialect {}),
+        DatabaseType::Sqlite => Box::new(SQLiteDialect {}),
+        DatabaseType::Mongo => return Err(eyre!("MongoDB migrations don't create tables")),
+    };
+    let statements = Parser::parse_sql(dialect.as_ref(), sql)
+        .map_err(|why| eyre!("Couldn't parse the migration: {}", why))?;
 
-    // Iterate over the lines of the SQL
-    for line in lines {
-        // If the line starts with "CREATE TABLE", extract the table name and start a new struct
-        if line.to_uppercase().starts_with("CREATE TABLE") {
-            let table_name = line.split_whitespace().nth(2).unwrap();
-            struct_def.push_str(&format!("\n#[derive(Debug, sqlx::FromRow)]\nstruct {} {{\n", table_name));
-        }
-        // If the line starts with a word followed by a space and a word, treat it as a column definition
-        else if line.chars().next().unwrap().is_alphanumeric() && line.contains(" ") {
-            let parts: Vec<&str> = line.split_whitespace().take(2).collect();
-            let column_name = parts[0];
-            let column_type = match db_type {
-                MySqlTypes::TinyInt | MySqlTypes::SmallInt | MySqlTypes::MediumInt | MySqlTypes::Int | MySqlTypes::BigInt => "i32",
-                MySqlTypes::Float | MySqlTypes::Double | MySqlTypes::Decimal => "BigDecimal",
-                MySqlTypes::Bit => "bool",
-                MySqlTypes::Char | MySqlTypes::VarChar | MySqlTypes::Binary | MySqlTypes::VarBinary | MySqlTypes::TinyBlob | MySqlTypes::Blob | MySqlTypes::MediumBlob | MySqlTypes::LongBlob | MySqlTypes::TinyText | MySqlTypes::Text | MySqlTypes::MediumText | MySqlTypes::LongText | MySqlTypes::Enum | MySqlTypes::Set => "String",
-                MySqlTypes::Date | MySqlTypes::DateTime | MySqlTypes::Time | MySqlTypes::Timestamp | MySqlTypes::Year => "NaiveDateTime",
-                MySqlTypes::Geometry | MySqlTypes::Point | MySqlTypes::LineString | MySqlTypes::Polygon | MySqlTypes::MultiPoint | MySqlTypes::MultiLineString | MySqlTypes::MultiPolygon | MySqlTypes::GeometryCollection => "String",
-                MySqlTypes::Json | MySqlTypes::BinaryJson => "serde_json::Value",
-                MySqlTypes::BitField => "String",
-                MySqlTypes::NewDecimal => "BigDecimal",
-                MySqlTypes::Boolean => "bool",
-                MySqlTypes::NotFound => "String",
-                MySqlTypes::EnumInner => "String",
-                MySqlTypes::SetInner => "String",
-                MySqlTypes::GeometryInner => "String"
-            };
-            struct_def.push_str(&format!("    {}: {},\n", column_name, column_type));
+    let mut tables: Vec<TableSchema> = Vec::new();
+    for statement in statements {
+        if let Statement::CreateIndex {
+            name,
+            table_name,
+            columns,
+            unique: true,
+            ..
+        } = &statement
+        {
+            let table_name = object_name(table_name);
+            let columns: Vec<String> = columns
+                .iter()
+                .map(|column| match &column.expr {
+                    Expr::Identifier(ident) => ident.value.clone(),
+                    expr => expr.to_string(),
+                })
+                .collect();
+            if let Some(table) = tables.iter_mut().find(|table| table.name == table_name) {
+                table.indexes.push(IndexSchema {
+                    name: name.as_ref().map(object_name).unwrap_or_else(|| unique_index_name(&table_name, &columns)),
+                    columns,
+                    unique: true,
+                });
+            }
+            continue;
         }
-        // If the line is ");", close the struct
-        else if line.trim() == ");" {
-            struct_def.push_str("}\n");
+        let Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            ..
+        } = statement
+        else {
+            continue;
+        };
+
+        let table_name = object_name(&name);
+        let mut primary_key = Vec::new();
+        let mut foreign_keys = Vec::new();
+        let mut indexes = Vec::new();
+        for constraint in &constraints {
+            match constraint {
+                TableConstraint::Unique {
+                    columns,
+                    is_primary: true,
+                    ..
+                } => primary_key = columns.iter().map(|column| column.value.clone()).collect(),
+                TableConstraint::Unique { name, columns, .. } => {
+                    let columns: Vec<String> = columns.iter().map(|column| column.value.clone()).collect();
+                    indexes.push(IndexSchema {
+                        name: name.as_ref().map(|name| name.value.clone()).unwrap_or_else(|| unique_index_name(&table_name, &columns)),
+                        columns,
+                        unique: true,
+                    });
+                }
+                TableConstraint::ForeignKey {
+                    columns,
+                    foreign_table,
+                    referred_columns,
+                    ..
+                } => foreign_keys.push(ForeignKeySchema {
+                    columns: columns.iter().map(|column| column.value.clone()).collect(),
+                    references_table: object_name(foreign_table),
+                    references_columns: referred_columns.iter().map(|column| column.value.clone()).collect(),
+                }),
+                _ => {}
+            }
         }
-    }
-
-    Ok(struct_def)
-}
 
-fn generate_struct_from_sqlite_sql(file_path: &Path, db_type: &SqliteTypes) -> Result<String, Error> {
-    // Read the SQL file
-    let sql = fs::read_to_string(file_path)?;
-
-    // Split the SQL into lines
-    let lines: Vec<&str> = sql.lines().collect();
-
-    // Initialize an empty string to hold the struct definition
-    let mut struct_def = String::new();
-
-    // Iterate over the lines of the SQL
-    for line in lines {
-        // If the line starts with "CREATE TABLE", extract the table name and start a new struct
-        if line.to_uppercase().starts_with("CREATE TABLE") {
-            let table_name = line.split_whitespace().nth(2).unwrap();
-            struct_def.push_str(&format!("\n#[derive(Debug, sqlx::FromRow)]\nstruct {} {{\n", table_name));
+        let mut column_schemas = Vec::new();
+        for column in &columns {
+            let mut not_null = false;
+            let mut default = None;
+            let data_type = column.data_type.to_string();
+            // SERIAL isn't a type but a sequence default
+            let mut auto_increment = data_type.to_lowercase().ends_with("serial");
+            for option in &column.options {
+                match &option.option {
+                    ColumnOption::NotNull => not_null = true,
+                    ColumnOption::Default(expression) => default = Some(expression.to_string()),
+                    ColumnOption::Unique { is_primary: true, .. } => {
+                        primary_key = vec![column.name.value.clone()];
+                    }
+                    ColumnOption::Unique { .. } => {
+                        let columns = vec![column.name.value.clone()];
+                        indexes.push(IndexSchema {
+                            name: unique_index_name(&table_name, &columns),
+                            columns,
+                            unique: true,
+                        });
+                    }
+                    ColumnOption::ForeignKey {
+                        foreign_table,
+                        referred_columns,
+                        ..
+                    } => foreign_keys.push(ForeignKeySchema {
+                        columns: vec![column.name.value.clone()],
+                        references_table: object_name(foreign_table),
+                        references_columns: referred_columns.iter().map(|column| column.value.clone()).collect(),
+                    }),
+                    // MySQL's AUTO_INCREMENT and SQLite's AUTOINCREMENT
+                    ColumnOption::DialectSpecific(tokens) => {
+                        auto_increment |= tokens
+                            .iter()
+                            .any(|token| token.to_string().to_uppercase().starts_with("AUTO"));
+                    }
+                    ColumnOption::Generated { .. } => auto_increment = true,
+                    _ => {}
+                }
+            }
+            column_schemas.push(ColumnSchema {
+                name: column.name.value.clone(),
+                data_type,
+                nullable: !not_null,
+                default,
+                auto_increment,
+            });
         }
-        // If the line starts with a word followed by a space and a word, treat it as a column definition
-        else if line.chars().next().unwrap().is_alphanumeric() && line.contains(" ") {
-            let parts: Vec<&str> = line.split_whitespace().take(2).collect();
-            let column_name = parts[0];
-            let column_type = match db_type {
-                SqliteTypes::Integer | SqliteTypes::Real | SqliteTypes::Numeric => "BigDecimal",
-                SqliteTypes::Date | SqliteTypes::Time | SqliteTypes::DateTime => "NaiveDateTime",
-                SqliteTypes::Text => "String",
-                SqliteTypes::Blob => "Vec<u8>",
-                SqliteTypes::Boolean => "bool",
-                SqliteTypes::Null => "String",
-            };
-            struct_def.push_str(&format!("    {}: {},\n", column_name, column_type));
+        for column in column_schemas.iter_mut() {
+            if primary_key.contains(&column.name) {
+                column.nullable = false;
+            }
         }
-        // If the line is ");", close the struct
-        else if line.trim() == ");" {
-            struct_def.push_str("}\n");
-        }
-    }
 
-    Ok(struct_def)
+        tables.push(TableSchema {
+            name: table_name,
+            columns: column_schemas,
+            primary_key,
+            foreign_keys,
+            indexes,
+        });
+    }
+    Ok(tables)
 }
 
-use regex::Regex;
-fn parse_and_categorize_sql(file_path: &Path) -> Result<(), Error> {
-    // Read the SQL file
-    let sql = fs::read_to_string(file_path)?;
-
-    // Define regex patterns for SQL keywords and constraints
-    let select_pattern = Regex::new(r"(?i)SELECT").unwrap();
-    let insert_pattern = Regex::new(r"(?i)INSERT").unwrap();
-    let update_pattern = Regex::new(r"(?i)UPDATE").unwrap();
-    let delete_pattern = Regex::new(r"(?i)DELETE").unwrap();
-    let foreign_key_pattern = Regex::new(r"(?i)FOREIGN KEY").unwrap();
-    let null_pattern = Regex::new(r"(?i)NULL").unwrap();
-    let unique_pattern = Regex::new(r"(?i)UNIQUE").unwrap();
-    let create_table_pattern = Regex::new(r"(?i)CREATE TABLE").unwrap();
-    let create_index_pattern = Regex::new(r"(?i)CREATE INDEX").unwrap();
-    let alter_table_pattern = Regex::new(r"(?i)ALTER TABLE").unwrap();
-    let drop_table_pattern = Regex::new(r"(?i)DROP TABLE").unwrap();
-    let drop_index_pattern = Regex::new(r"(?i)DROP INDEX").unwrap();
-
-
-    // Split the SQL into statements
-    let statements: Vec<&str> = sql.split(';').collect();
-
-    // Iterate over the statements
-    for statement in statements {
-        if select_pattern.is_match(statement) {
-            println!("SELECT statement: {}", statement);
-        } else if insert_pattern.is_match(statement) {
-            println!("INSERT statement: {}", statement);
-        } else if update_pattern.is_match(statement) {
-            println!("UPDATE statement: {}", statement);
-        } else if delete_pattern.is_match(statement) {
-            println!("DELETE statement: {}", statement);
-        }  else if foreign_key_pattern.is_match(statement) {
-            println!("FOREIGN KEY constraint found: {}", statement);
-        } else if null_pattern.is_match(statement) {
-            println!("NULL constraint found: {}", statement);
-        } else if unique_pattern.is_match(statement) {
-            println!("UNIQUE constraint found: {}", statement);
-        } else if create_table_pattern.is_match(statement) {
-            println!("CREATE TABLE statement: {}", statement);
-        } else if create_index_pattern.is_match(statement) {
-            println!("CREATE INDEX statement: {}", statement);
-        } else if alter_table_pattern.is_match(statement) {
-            println!("ALTER TABLE statement: {}", statement);
-        } else if drop_table_pattern.is_match(statement) {
-            println!("DROP TABLE statement: {}", statement);
-        } else if drop_index_pattern.is_match(statement) {
-            println!("DROP INDEX statement: {}", statement);
-        } else {
-            println!("Other statement: {}", statement);
-        }
-    }
+/// The name `dump_schema` gives the unnamed unique constraints of SQLite.
+fn unique_index_name(table_name: &str, columns: &[String]) -> String {
+    format!("index_{}_on_{}", table_name, columns.join("_and_"))
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// The unquoted name of a table, without its schema.
+fn object_name(name: &ObjectName) -> String {
+    name.0.last().map(|ident| ident.value.clone()).unwrap_or_default()
+}
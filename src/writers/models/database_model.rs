@@ -0,0 +1,536 @@
+use crate::database::migrations::{connect_to_project_database, dump_schema, ColumnSchema, TableSchema, SCHEMA_MIGRATIONS_TABLE};
+use crate::database::{sqlite_affinity, DatabaseConnection, DatabaseType, SqliteTypes};
+use crate::generators::create_file;
+use crate::helpers::helpers::{pascal_case, singularize};
+use crate::writers::add_module_declaration;
+use crate::writers::models::{associations_contents, Association};
+use color_eyre::eyre::{eyre, Error};
+use std::fs;
+use std::path::Path;
+
+/// Words that can't be field names without `r#`.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// ## Name: sql_rust_type
+/// ### Description: Returns the Rust type sqlx decodes a column type into
+/// #### Parameters:
+/// - data_type: [`&str`] - the type as the database reports it, e.g. `character varying(255)`,
+///   `int(10) unsigned` or `DATETIME`
+/// - database_type: [`&DatabaseType`] - the database the column belongs to
+/// - Returns: [`Option<String>`] - `None` when sqlx has no Rust type for it with the features
+///   of a generated project
+///
+/// ### Example:
+/// ```rust
+/// use rustyroad::database::DatabaseType;
+/// use rustyroad::writers::models::sql_rust_type;
+///
+/// assert_eq!(sql_rust_type("timestamp with time zone", &DatabaseType::Postgres).unwrap(), "chrono::DateTime<chrono::Utc>");
+/// assert_eq!(sql_rust_type("TIMESTAMP(3) WITH TIME ZONE", &DatabaseType::Postgres).unwrap(), "chrono::DateTime<chrono::Utc>");
+/// assert_eq!(sql_rust_type("text[]", &DatabaseType::Postgres).unwrap(), "Vec<String>");
+/// assert_eq!(sql_rust_type("tinyint(1)", &DatabaseType::Mysql).unwrap(), "bool");
+/// assert_eq!(sql_rust_type("int(10) unsigned", &DatabaseType::Mysql).unwrap(), "u32");
+/// assert_eq!(sql_rust_type("VARCHAR(80)", &DatabaseType::Sqlite).unwrap(), "String");
+/// assert_eq!(sql_rust_type("numeric(10, 2)", &DatabaseType::Postgres).unwrap(), "sqlx::types::Decimal");
+/// assert_eq!(sql_rust_type("tsvector", &DatabaseType::Postgres), None);
+/// assert_eq!(sql_rust_type("bytea[]", &DatabaseType::Postgres), None);
+/// ```
+pub fn sql_rust_type(data_type: &str, database_type: &DatabaseType) -> Option<String> {
+    let data_type = data_type.trim().to_lowercase();
+    let rust_type = match database_type {
+        DatabaseType::Postgres => {
+            if let Some(element_type) = data_type.strip_suffix("[]") {
+                // Models bind arrays of scalars, see SqlArray, and sqlx has no nested arrays
+                let element_type = sql_rust_type(element_type, database_type)?;
+                if element_type.starts_with("Vec<") || element_type == "serde_json::Value" {
+                    return None;
+                }
+                return Some(format!("Vec<{}>", element_type));
+            }
+            // `character varying(255)` and `timestamp(3) with time zone` are known by their name
+            let mut name = String::new();
+            let mut depth = 0;
+            for character in data_type.chars() {
+                match character {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ if depth == 0 => name.push(character),
+                    _ => {}
+                }
+            }
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            match name.as_str() {
+                "character varying" | "varchar" | "character" | "char" | "bpchar" | "text" | "name" => "String",
+                "smallint" | "int2" | "smallserial" => "i16",
+                "integer" | "int" | "int4" | "serial" => "i32",
+                "bigint" | "int8" | "bigserial" => "i64",
+                "real" | "float4" => "f32",
+                "double precision" | "float8" => "f64",
+                "numeric" | "decimal" => "sqlx::types::Decimal",
+                "boolean" | "bool" => "bool",
+                "bytea" => "Vec<u8>",
+                "date" => "chrono::NaiveDate",
+                "time" | "time without time zone" => "chrono::NaiveTime",
+                "timestamp" | "timestamp without time zone" => "chrono::NaiveDateTime",
+                "timestamptz" | "timestamp with time zone" => "chrono::DateTime<chrono::Utc>",
+                "json" | "jsonb" => "serde_json::Value",
+                "uuid" => "sqlx::types::Uuid",
+                _ => return None,
+            }
+        }
+        DatabaseType::Mysql => {
+            let unsigned = data_type.contains("unsigned");
+            let name = data_type
+                .split(|c: char| c == '(' || c.is_whitespace())
+                .next()
+                .unwrap_or_default();
+            match name {
+                // MySQL has no boolean, BOOLEAN columns are TINYINT(1)
+                "tinyint" if data_type.starts_with("tinyint(1)") => "bool",
+                "bool" | "boolean" => "bool",
+                "tinyint" if unsigned => "u8",
+                "tinyint" => "i8",
+                "smallint" if unsigned => "u16",
+                "smallint" => "i16",
+                "mediumint" | "int" | "integer" if unsigned => "u32",
+                "mediumint" | "int" | "integer" => "i32",
+                "bigint" if unsigned => "u64",
+                "bigint" => "i64",
+                "float" => "f32",
+                "double" | "real" => "f64",
+                "decimal" | "numeric" => "sqlx::types::Decimal",
+                "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum" | "set" => "String",
+                "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => "Vec<u8>",
+                "date" => "chrono::NaiveDate",
+                "time" => "chrono::NaiveTime",
+                "datetime" => "chrono::NaiveDateTime",
+                "timestamp" => "chrono::DateTime<chrono::Utc>",
+                "year" => "u16",
+                "json" => "serde_json::Value",
+                _ => return None,
+            }
+        }
+        DatabaseType::Sqlite => {
+            // SQLite stores any declared type by its affinity, sqlx reads dates and booleans from their names
+            let name = data_type.split('(').next().unwrap_or_default().trim();
+            match name {
+                "boolean" | "bool" => "bool",
+                "date" => "chrono::NaiveDate",
+                "time" => "chrono::NaiveTime",
+                "datetime" | "timestamp" => "chrono::NaiveDateTime",
+                _ => match sqlite_affinity(&data_type) {
+                    SqliteTypes::Integer => "i64",
+                    SqliteTypes::Text => "String",
+                    SqliteTypes::Blob => "Vec<u8>",
+                    _ => "f64",
+                },
+            }
+        }
+        DatabaseType::Mongo => return None,
+    };
+    Some(rust_type.to_string())
+}
+
+/// Returns the field for a column, and the `#[sqlx(rename)]` it needs when the column isn't a valid field name.
+pub(crate) fn field_name(column_name: &str) -> (String, Option<String>) {
+    let mut name = String::new();
+    let mut previous: Option<char> = None;
+    for character in column_name.chars() {
+        if character.is_uppercase() {
+            if previous.is_some_and(|previous| previous.is_lowercase() || previous.is_ascii_digit()) {
+                name.push('_');
+            }
+            name.extend(character.to_lowercase());
+        } else if character.is_alphanumeric() || character == '_' {
+            name.push(character);
+        } else {
+            name.push('_');
+        }
+        previous = Some(character);
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    // These can't be raw identifiers
+    if ["self", "super", "crate"].contains(&name.as_str()) {
+        name.push('_');
+    }
+    let rename = (name != column_name).then(|| column_name.to_string());
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+    (name, rename)
+}
+
+/// ## Name: table_model_contents
+/// ### Description: Returns the model of a table, read from the live database or a migration
+/// Every column is a field of the type sqlx decodes it into, an `Option` when it's nullable.
+/// A primary key the database fills in, an auto-increment or one with a default, is an `Option`
+/// too so new rows can leave it out. Defaults and foreign keys are documented on their fields.
+/// The model derives `rustyroad::Model` when the table has a single-column primary key, and
+/// then comes with `New<Model>` and `Update<Model>` input structs and the `create_<model>`,
+/// `get_<model>`, `get_all_<model>s`, `update_<model>` and `delete_<model>` functions the
+/// generated controllers call. It derives `rustyroad::Validate` too, with the rules the table
+/// already enforces: `unique` for a column with a unique index and `length(max = n)` for a
+/// `VARCHAR(n)`, so a create or update breaking them is a validation error instead of a SQL one.
+/// Each foreign key is a `belongs_to` association, see [`associated_model_contents`] for the others.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - table: [`&TableSchema`] - the table as read by [`dump_schema`]
+/// - database_type: [`&DatabaseType`] - the database the table belongs to
+/// - Returns: [`String`] - the contents of `src/models/<model_name>.rs`
+pub fn table_model_contents(model_name: &str, table: &TableSchema, database_type: &DatabaseType) -> String {
+    associated_model_contents(model_name, table, &Association::belongs_to(table), database_type)
+}
+
+/// ## Name: associated_model_contents
+/// ### Description: Returns the model of a table like [`table_model_contents`], with these associations
+/// The associations are only written for a model that derives `rustyroad::Model`.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - table: [`&TableSchema`] - the table of the model
+/// - associations: [`&[Association]`] - the associations of the model, see [`Association::from_schema`]
+/// - database_type: [`&DatabaseType`] - the database the table belongs to
+/// - Returns: [`String`] - the contents of `src/models/<model_name>.rs`
+pub fn associated_model_contents(
+    model_name: &str,
+    table: &TableSchema,
+    associations: &[Association],
+    database_type: &DatabaseType,
+) -> String {
+    let primary_key = match table.primary_key.as_slice() {
+        [primary_key] => Some(primary_key.as_str()),
+        _ => None,
+    };
+
+    let struct_name = pascal_case(model_name);
+    let mut fields = String::new();
+    // (field, type) of the columns a new row gives, and of the columns an update can change
+    let mut new_fields: Vec<(String, String)> = Vec::new();
+    let mut update_fields: Vec<(String, String)> = Vec::new();
+    let mut primary_key_field = None;
+    for column in &table.columns {
+        let (name, rename) = field_name(&column.name);
+        let is_primary_key = primary_key == Some(column.name.as_str());
+
+        if let Some(default) = &column.default {
+            fields.push_str(&format!("    /// Defaults to `{}`\n", default));
+        }
+        for foreign_key in &table.foreign_keys {
+            if let Some(index) = foreign_key.columns.iter().position(|name| *name == column.name) {
+                fields.push_str(&format!(
+                    "    /// References `{}`.`{}`\n",
+                    foreign_key.references_table,
+                    foreign_key.references_columns.get(index).map(String::as_str).unwrap_or("id")
+                ));
+            }
+        }
+        let rust_type = sql_rust_type(&column.data_type, database_type).unwrap_or_else(|| {
+            fields.push_str(&format!(
+                "    /// sqlx has no Rust type for `{}`, cast the column to text or change this type\n",
+                column.data_type
+            ));
+            "String".to_string()
+        });
+        if rust_type.contains("Decimal") {
+            fields.push_str("    /// Needs the `rust_decimal` feature of sqlx\n");
+        }
+
+        if is_primary_key && name != "id" {
+            fields.push_str("    #[primary_key]\n");
+        }
+        if let Some(rename) = rename {
+            fields.push_str(&format!("    #[sqlx(rename = \"{}\")]\n", rename));
+        }
+        let rules = column_rules(column, table);
+        if primary_key.is_some() && !is_primary_key && !rules.is_empty() {
+            fields.push_str(&format!("    #[validate({})]\n", rules.join(", ")));
+        }
+
+        // SQLite assigns the rowid to an INTEGER PRIMARY KEY
+        let assigned_by_database = column.auto_increment
+            || column.default.is_some()
+            || (*database_type == DatabaseType::Sqlite && column.data_type.eq_ignore_ascii_case("integer"));
+        let field_type = if column.nullable || (is_primary_key && assigned_by_database) {
+            format!("Option<{}>", rust_type)
+        } else {
+            rust_type
+        };
+        fields.push_str(&format!("    pub {}: {},\n", name, field_type));
+
+        if is_primary_key {
+            primary_key_field = Some((name.clone(), assigned_by_database));
+            if !assigned_by_database {
+                new_fields.push((name, field_type));
+            }
+        } else {
+            new_fields.push((name.clone(), field_type.clone()));
+            update_fields.push((name, field_type));
+        }
+    }
+
+    let associations = match primary_key {
+        Some(_) => associations_contents(&struct_name, associations),
+        None => String::new(),
+    };
+    let (model_import, derives, attributes) = match primary_key {
+        Some(_) if !associations.is_empty() => (
+            "use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};\nuse rustyroad::{Model, Validate};\n",
+            "sqlx::FromRow, Model, Validate",
+            format!("#[table_name = \"{}\"]\n", table.name),
+        ),
+        Some(_) => (
+            "use rustyroad::database::{DatabaseError, QueryPool, SqlValue};\nuse rustyroad::{Model, Validate};\n",
+            "sqlx::FromRow, Model, Validate",
+            format!("#[table_name = \"{}\"]\n", table.name),
+        ),
+        None => (
+            "",
+            "sqlx::FromRow",
+            String::new(),
+        ),
+    };
+    let note = if primary_key.is_none() {
+        "/// The table has no single-column primary key, so the model doesn't derive `rustyroad::Model`.\n"
+    } else {
+        ""
+    };
+
+    format!(
+        "{model_import}use serde::{{Deserialize, Serialize}};\n\n\
+         /// A row of the `{table_name}` table, generated from the database.\n\
+         {note}\
+         #[derive(Debug, Clone, Serialize, Deserialize, {derives})]\n\
+         {attributes}\
+         pub struct {struct_name} {{\n{fields}}}\n{crud}{associations}",
+        model_import = model_import,
+        table_name = table.name,
+        note = note,
+        derives = derives,
+        attributes = attributes,
+        struct_name = struct_name,
+        fields = fields,
+        crud = match primary_key_field {
+            Some((primary_key, assigned_by_database)) => crud_contents(
+                &field_name(model_name).0.replace("r#", ""),
+                &struct_name,
+                &primary_key,
+                assigned_by_database,
+                &new_fields,
+                &update_fields,
+            ),
+            None => String::new(),
+        },
+        associations = associations,
+    )
+}
+
+/// The `#[validate(...)]` rules of the constraints of a column.
+fn column_rules(column: &ColumnSchema, table: &TableSchema) -> Vec<String> {
+    let mut rules = Vec::new();
+    let data_type = column.data_type.to_lowercase();
+    let length = ["varchar(", "character varying(", "nvarchar(", "char(", "character(", "nchar("]
+        .iter()
+        .find_map(|prefix| data_type.strip_prefix(prefix))
+        .and_then(|rest| rest.trim_end_matches(')').trim().parse::<u64>().ok());
+    if let Some(length) = length {
+        rules.push(format!("length(max = {})", length));
+    }
+    if table
+        .indexes
+        .iter()
+        .any(|index| index.unique && index.columns == [column.name.as_str()])
+    {
+        rules.push("unique".to_string());
+    }
+    rules
+}
+
+/// Returns the `New<Model>` and `Update<Model>` input structs of a model and its CRUD functions.
+fn crud_contents(
+    method_name: &str,
+    struct_name: &str,
+    primary_key: &str,
+    assigned_by_database: bool,
+    new_fields: &[(String, String)],
+    update_fields: &[(String, String)],
+) -> String {
+    let mut new_struct = String::new();
+    let mut from_new = String::new();
+    if assigned_by_database {
+        from_new.push_str(&format!("            {}: None,\n", primary_key));
+    }
+    for (name, field_type) in new_fields {
+        new_struct.push_str(&format!("    pub {}: {},\n", name, field_type));
+        from_new.push_str(&format!("            {name}: new_{method_name}.{name},\n", name = name, method_name = method_name));
+    }
+
+    // A nullable column is an `Option<Option<T>>`, so `null` sets it to NULL
+    let mut update_struct = String::new();
+    let mut apply_update = String::new();
+    for (name, field_type) in update_fields {
+        if field_type.starts_with("Option<") {
+            update_struct.push_str("    #[serde(default, deserialize_with = \"rustyroad::database::deserialize_some\")]\n");
+        }
+        update_struct.push_str(&format!("    pub {}: Option<{}>,\n", name, field_type));
+        apply_update.push_str(&format!(
+            "        if let Some(value) = changes.{name} {{\n            {method_name}.{name} = value;\n        }}\n",
+            name = name,
+            method_name = method_name,
+        ));
+    }
+
+    format!(
+        "
+/// The columns of a new `{struct_name}`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct New{struct_name} {{
+{new_struct}}}
+
+/// The columns to change on a `{struct_name}`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Update{struct_name} {{
+{update_struct}}}
+
+impl From<New{struct_name}> for {struct_name} {{
+    fn from(new_{method_name}: New{struct_name}) -> Self {{
+        {struct_name} {{
+{from_new}        }}
+    }}
+}}
+
+impl {struct_name} {{
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_{method_name}<P: QueryPool + Sync>(pool: &P, new_{method_name}: New{struct_name}) -> Result<{struct_name}, DatabaseError> {{
+        let {method_name} = {struct_name}::from(new_{method_name});
+        {method_name}.validate(pool).await?;
+        {method_name}.create(pool).await
+    }}
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_{method_name}<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<{struct_name}>, DatabaseError> {{
+        {struct_name}::find(pool, id).await
+    }}
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_{method_name}s<P: QueryPool + Sync>(pool: &P) -> Result<Vec<{struct_name}>, DatabaseError> {{
+        {struct_name}::all(pool).await
+    }}
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_{method_name}<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: Update{struct_name},
+    ) -> Result<Option<{struct_name}>, DatabaseError> {{
+        let Some(mut {method_name}) = {struct_name}::find(pool, id).await? else {{
+            return Ok(None);
+        }};
+{apply_update}        {method_name}.validate(pool).await?;
+        {method_name}.update(pool).await?;
+        Ok(Some({method_name}))
+    }}
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_{method_name}<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {{
+        match {struct_name}::find(pool, id).await? {{
+            Some({method_name}) => {method_name}.destroy(pool).await,
+            None => Ok(false),
+        }}
+    }}
+}}
+",
+        struct_name = struct_name,
+        method_name = method_name,
+        new_struct = new_struct,
+        update_struct = update_struct,
+        from_new = from_new,
+        apply_update = apply_update,
+    )
+}
+
+/// ## Name: create_models_from_database
+/// ### Description: Writes the models of existing tables, read from the live database
+/// Used by `rustyroad generate model --from-db <table>` and `rustyroad generate model --all`.
+/// Models are named after the singular of their table unless a name is given, and a model
+/// file that already exists is left alone. The associations come from the foreign keys of the
+/// database, see [`Association::from_schema`].
+/// #### Parameters:
+/// - table_name: [`Option<&str>`] - the table to read, `None` for every table
+/// - model_name: [`Option<&str>`] - the name of the model of `table_name`
+/// - Returns: [`Result<(), Error>`] - an error when the table doesn't exist, listing the tables that do
+pub async fn create_models_from_database(table_name: Option<&str>, model_name: Option<&str>) -> Result<(), Error> {
+    let connection = connect_to_project_database()
+        .await
+        .map_err(|why| eyre!("Couldn't connect to the database: {}", why))?;
+    let schema = dump_schema(&connection)
+        .await
+        .map_err(|why| eyre!("Couldn't read the database schema: {}", why))?;
+    let database_type = match connection {
+        DatabaseConnection::Pg(_) => DatabaseType::Postgres,
+        DatabaseConnection::MySql(_) => DatabaseType::Mysql,
+        DatabaseConnection::Sqlite(_) => DatabaseType::Sqlite,
+        DatabaseConnection::Mongo(_) => unreachable!("dump_schema fails on MongoDB"),
+    };
+
+    let tables: Vec<&TableSchema> = match table_name {
+        Some(table_name) => {
+            let table = schema
+                .tables
+                .iter()
+                .find(|table| table.name == table_name)
+                .ok_or_else(|| {
+                    let tables: Vec<&str> = schema
+                        .tables
+                        .iter()
+                        .map(|table| table.name.as_str())
+                        .filter(|name| *name != SCHEMA_MIGRATIONS_TABLE)
+                        .collect();
+                    eyre!(
+                        "The table {} doesn't exist, the database has: {}",
+                        table_name,
+                        tables.join(", ")
+                    )
+                })?;
+            vec![table]
+        }
+        None => schema
+            .tables
+            .iter()
+            .filter(|table| table.name != SCHEMA_MIGRATIONS_TABLE)
+            .collect(),
+    };
+
+    fs::create_dir_all("./src/models")?;
+    let models_mod = Path::new("./src/models/mod.rs");
+    for table in tables {
+        let model_name = match (table_name, model_name) {
+            (Some(_), Some(model_name)) => model_name.to_string(),
+            _ => singularize(field_name(&table.name).0.trim_start_matches("r#")),
+        };
+        let model_path = format!("./src/models/{}.rs", model_name);
+        if Path::new(&model_path).exists() {
+            println!("{} already exists, skipping the {} table", model_path, table.name);
+            continue;
+        }
+
+        create_file(&model_path).map_err(|why| eyre!("Couldn't create {}: {}", model_path, why))?;
+        let associations = Association::from_schema(table, &schema.tables);
+        fs::write(
+            &model_path,
+            associated_model_contents(&model_name, table, &associations, &database_type),
+        )?;
+        if models_mod.exists() {
+            add_module_declaration(model_name.clone(), models_mod)?;
+        }
+        println!("Created the {} model for the {} table", model_name, table.name);
+    }
+    Ok(())
+}
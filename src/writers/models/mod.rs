@@ -1,4 +1,10 @@
 pub mod create_update_model;
+pub mod database_model;
+pub mod model_associations;
+pub mod mongo_model;
 
 pub use create_update_model::*;
+pub use database_model::*;
+pub use model_associations::*;
+pub use mongo_model::*;
 
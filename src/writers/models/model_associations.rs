@@ -0,0 +1,322 @@
+use crate::database::migrations::{join_table_name, TableSchema};
+use crate::helpers::helpers::{pascal_case, singularize};
+use crate::writers::models::database_model::field_name;
+use color_eyre::eyre::{eyre, Error};
+
+/// ## Name: Association
+/// ### Description: An association of a generated model with the model of another table
+/// Every association becomes a function of the model that reads the associated rows, and a
+/// `preload_` function that reads them for many rows with one query, see
+/// [`Associations`](crate::database::Associations).
+/// The model of a table is the singular of its name, e.g. `super::user::User` for `users`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Association {
+    /// The `foreign_key` column of this table points to a row of `table`, e.g. `post.author()`.
+    BelongsTo { table: String, foreign_key: String },
+    /// The `foreign_key` column of `table` points to this table, e.g. `user.posts()`.
+    HasMany { table: String, foreign_key: String },
+    /// Like `HasMany`, for a single row, e.g. `user.profile()`.
+    HasOne { table: String, foreign_key: String },
+    /// The rows of `table` linked through `join_table`, e.g. `post.tags()`.
+    /// `foreign_key` is the column of the join table pointing to this table.
+    ManyToMany {
+        table: String,
+        join_table: String,
+        foreign_key: String,
+        association_foreign_key: String,
+    },
+}
+
+impl Association {
+    /// ## Name: parse
+    /// ### Description: Parses an association declared on the command line
+    /// `has_many` and `has_one` take `<table>[:<foreign key>]`, the foreign key defaulting to
+    /// `<singular table_name>_id`. `many_to_many` takes `<table>[:<join table>]`, the join table
+    /// defaulting to the two tables in alphabetical order, e.g. `posts_tags`.
+    /// #### Parameters:
+    /// - kind: [`&str`] - `has_many`, `has_one` or `many_to_many`
+    /// - table_name: [`&str`] - the table of the model declaring the association
+    /// - spec: [`&str`] - e.g. `posts`, `posts:author_id` or `tags`
+    /// - Returns: [`Result<Association, Error>`]
+    ///
+    /// ### Example:
+    /// ```rust
+    /// use rustyroad::writers::models::Association;
+    ///
+    /// let posts = Association::parse("has_many", "users", "posts:author_id").unwrap();
+    /// assert_eq!(posts, Association::HasMany { table: "posts".to_string(), foreign_key: "author_id".to_string() });
+    ///
+    /// let tags = Association::parse("many_to_many", "posts", "tags").unwrap();
+    /// assert_eq!(
+    ///     tags,
+    ///     Association::ManyToMany {
+    ///         table: "tags".to_string(),
+    ///         join_table: "posts_tags".to_string(),
+    ///         foreign_key: "post_id".to_string(),
+    ///         association_foreign_key: "tag_id".to_string(),
+    ///     }
+    /// );
+    /// ```
+    pub fn parse(kind: &str, table_name: &str, spec: &str) -> Result<Association, Error> {
+        let (table, option) = match spec.split_once(':') {
+            Some((table, option)) => (table, Some(option.to_string())),
+            None => (spec, None),
+        };
+        let is_identifier = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier(table) || !option.as_deref().is_none_or(is_identifier) {
+            return Err(eyre!("Invalid association '{}', expected <table>[:<column or join table>]", spec));
+        }
+
+        let table = table.to_string();
+        let own_key = format!("{}_id", singularize(table_name));
+        match kind {
+            "has_many" => Ok(Association::HasMany {
+                table,
+                foreign_key: option.unwrap_or(own_key),
+            }),
+            "has_one" => Ok(Association::HasOne {
+                table,
+                foreign_key: option.unwrap_or(own_key),
+            }),
+            "many_to_many" => Ok(Association::ManyToMany {
+                join_table: option.unwrap_or_else(|| join_table_name(table_name, &table)),
+                foreign_key: own_key,
+                association_foreign_key: format!("{}_id", singularize(&table)),
+                table,
+            }),
+            other => Err(eyre!("Unknown association '{}'", other)),
+        }
+    }
+
+    /// ## Name: belongs_to
+    /// ### Description: Returns a `BelongsTo` for every single-column foreign key of the table
+    pub fn belongs_to(table: &TableSchema) -> Vec<Association> {
+        table
+            .foreign_keys
+            .iter()
+            .filter(|foreign_key| foreign_key.columns.len() == 1)
+            .map(|foreign_key| Association::BelongsTo {
+                table: foreign_key.references_table.clone(),
+                foreign_key: foreign_key.columns[0].clone(),
+            })
+            .collect()
+    }
+
+    /// ## Name: from_schema
+    /// ### Description: Returns the associations of a table implied by the foreign keys of a schema
+    /// The table belongs to the tables its foreign keys point to, and has many rows of the tables
+    /// with a foreign key pointing to it. A join table, whose columns are two foreign keys, links
+    /// the two tables it points to many-to-many instead.
+    /// #### Parameters:
+    /// - table: [`&TableSchema`] - the table of the model
+    /// - tables: [`&[TableSchema]`] - every table of the database
+    /// - Returns: [`Vec<Association>`]
+    pub fn from_schema(table: &TableSchema, tables: &[TableSchema]) -> Vec<Association> {
+        let mut associations = Association::belongs_to(table);
+        for other in tables {
+            let foreign_keys: Vec<&str> = other
+                .foreign_keys
+                .iter()
+                .filter(|foreign_key| foreign_key.columns.len() == 1 && foreign_key.references_table == table.name)
+                .map(|foreign_key| foreign_key.columns[0].as_str())
+                .collect();
+            let Some(foreign_key) = foreign_keys.first() else {
+                continue;
+            };
+
+            if is_join_table(other) {
+                let association = other
+                    .foreign_keys
+                    .iter()
+                    .find(|association| association.columns[0] != *foreign_key);
+                if let Some(association) = association {
+                    associations.push(Association::ManyToMany {
+                        table: association.references_table.clone(),
+                        join_table: other.name.clone(),
+                        foreign_key: foreign_key.to_string(),
+                        association_foreign_key: association.columns[0].clone(),
+                    });
+                }
+            } else {
+                for foreign_key in foreign_keys {
+                    associations.push(Association::HasMany {
+                        table: other.name.clone(),
+                        foreign_key: foreign_key.to_string(),
+                    });
+                }
+            }
+        }
+        associations
+    }
+
+    /// The name of the function reading the association, e.g. `author` for `author_id`.
+    pub fn method_name(&self) -> String {
+        let name = match self {
+            Association::BelongsTo { table, foreign_key } => match foreign_key.strip_suffix("_id") {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => singularize(table),
+            },
+            Association::HasMany { table, .. } | Association::ManyToMany { table, .. } => table.clone(),
+            Association::HasOne { table, .. } => singularize(table),
+        };
+        field_name(&name).0
+    }
+
+    /// The path of the associated model from a sibling module of `src/models`.
+    fn model_path(&self) -> String {
+        let table = match self {
+            Association::BelongsTo { table, .. }
+            | Association::HasMany { table, .. }
+            | Association::HasOne { table, .. }
+            | Association::ManyToMany { table, .. } => table,
+        };
+        let model_name = singularize(table);
+        format!(
+            "super::{}::{}",
+            field_name(&model_name).0,
+            pascal_case(&model_name)
+        )
+    }
+}
+
+/// A join table has two single-column foreign keys, and no other columns than them and an `id`.
+fn is_join_table(table: &TableSchema) -> bool {
+    table.foreign_keys.len() == 2
+        && table.foreign_keys.iter().all(|foreign_key| foreign_key.columns.len() == 1)
+        && table.columns.iter().all(|column| {
+            column.name == "id"
+                || table
+                    .foreign_keys
+                    .iter()
+                    .any(|foreign_key| foreign_key.columns[0] == column.name)
+        })
+}
+
+/// ## Name: associations_contents
+/// ### Description: Returns the `impl` block with a function per association of a model
+/// Associations whose function would have the name of an earlier one are left out.
+/// #### Parameters:
+/// - struct_name: [`&str`] - the struct of the model, e.g. `Post`
+/// - associations: [`&[Association]`] - the associations of the model
+/// - Returns: [`String`] - an empty string when there are no associations
+pub fn associations_contents(struct_name: &str, associations: &[Association]) -> String {
+    let mut names: Vec<String> = Vec::new();
+    let mut functions: Vec<String> = Vec::new();
+    for association in associations {
+        let name = association.method_name();
+        if names.contains(&name) {
+            continue;
+        }
+        let bare_name = name.trim_start_matches("r#");
+        let model = association.model_path();
+        let function = match association {
+            Association::BelongsTo { table, foreign_key } => format!(
+                "    /// The `{table}` row `{foreign_key}` points to.
+    pub async fn {name}<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<{model}>, DatabaseError> {{
+        self.belongs_to(pool, \"{foreign_key}\").await
+    }}
+
+    /// The `{bare_name}` of every row, read with one query.
+    pub async fn preload_{bare_name}<P: QueryPool + Sync>(pool: &P, rows: &[{struct_name}]) -> Result<Vec<Option<{model}>>, DatabaseError> {{
+        {struct_name}::preload_belongs_to(pool, rows, \"{foreign_key}\").await
+    }}
+",
+                table = table,
+                foreign_key = foreign_key,
+                name = name,
+                bare_name = bare_name,
+                model = model,
+                struct_name = struct_name,
+            ),
+            Association::HasMany { table, foreign_key } => format!(
+                "    /// The `{table}` rows whose `{foreign_key}` points to this row.
+    pub async fn {name}<P: QueryPool + Sync>(&self, pool: &P) -> Result<Vec<{model}>, DatabaseError> {{
+        self.has_many(pool, \"{foreign_key}\").await
+    }}
+
+    /// The `{bare_name}` of every row, read with one query.
+    pub async fn preload_{bare_name}<P: QueryPool + Sync>(pool: &P, rows: &[{struct_name}]) -> Result<Vec<Vec<{model}>>, DatabaseError> {{
+        {struct_name}::preload_has_many(pool, rows, \"{foreign_key}\").await
+    }}
+",
+                table = table,
+                foreign_key = foreign_key,
+                name = name,
+                bare_name = bare_name,
+                model = model,
+                struct_name = struct_name,
+            ),
+            Association::HasOne { table, foreign_key } => format!(
+                "    /// The `{table}` row whose `{foreign_key}` points to this row.
+    pub async fn {name}<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<{model}>, DatabaseError> {{
+        self.has_one(pool, \"{foreign_key}\").await
+    }}
+
+    /// The `{bare_name}` of every row, read with one query.
+    pub async fn preload_{bare_name}<P: QueryPool + Sync>(pool: &P, rows: &[{struct_name}]) -> Result<Vec<Option<{model}>>, DatabaseError> {{
+        {struct_name}::preload_has_one(pool, rows, \"{foreign_key}\").await
+    }}
+",
+                table = table,
+                foreign_key = foreign_key,
+                name = name,
+                bare_name = bare_name,
+                model = model,
+                struct_name = struct_name,
+            ),
+            Association::ManyToMany {
+                table,
+                join_table,
+                foreign_key,
+                association_foreign_key,
+            } => {
+                let singular = field_name(&singularize(bare_name)).0;
+                let bare_singular = singular.trim_start_matches("r#");
+                let keys = format!("\"{}\", \"{}\", \"{}\"", join_table, foreign_key, association_foreign_key);
+                format!(
+                    "    /// The `{table}` rows linked to this row through `{join_table}`.
+    pub async fn {name}<P: QueryPool + Sync>(&self, pool: &P) -> Result<Vec<{model}>, DatabaseError> {{
+        self.many_to_many(pool, {keys}).await
+    }}
+
+    /// Links a `{table}` row to this row.
+    pub async fn attach_{bare_singular}<P: QueryPool + Sync>(&self, pool: &P, {singular}: &{model}) -> Result<(), DatabaseError> {{
+        self.attach(pool, {singular}, {keys}).await
+    }}
+
+    /// Unlinks a `{table}` row from this row, returns whether they were linked.
+    pub async fn detach_{bare_singular}<P: QueryPool + Sync>(&self, pool: &P, {singular}: &{model}) -> Result<bool, DatabaseError> {{
+        self.detach(pool, {singular}, {keys}).await
+    }}
+
+    /// The `{bare_name}` of every row, read with one query.
+    pub async fn preload_{bare_name}<P: QueryPool + Sync>(pool: &P, rows: &[{struct_name}]) -> Result<Vec<Vec<{model}>>, DatabaseError> {{
+        {struct_name}::preload_many_to_many(pool, rows, {keys}).await
+    }}
+",
+                    table = table,
+                    join_table = join_table,
+                    name = name,
+                    bare_name = bare_name,
+                    singular = singular,
+                    bare_singular = bare_singular,
+                    keys = keys,
+                    model = model,
+                    struct_name = struct_name,
+                )
+            }
+        };
+        names.push(name);
+        functions.push(function);
+    }
+
+    if functions.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n/// The associations of `{}`.\nimpl {} {{\n{}}}\n",
+        struct_name,
+        struct_name,
+        functions.join("\n")
+    )
+}
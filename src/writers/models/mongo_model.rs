@@ -0,0 +1,166 @@
+use crate::database::{bson_rust_type, find_collection_schema};
+use crate::generators::create_file;
+use crate::helpers::helpers::capitalize_first;
+use crate::writers::add_module_declaration;
+use color_eyre::eyre::{eyre, Error};
+use mongodb::bson::{Bson, Document};
+use std::fs;
+use std::path::Path;
+
+/// ## Name: mongo_model_contents
+/// ### Description: Returns the model of a MongoDB collection, a BSON-serializable struct with CRUD functions
+/// The fields come from the `$jsonSchema` of the collection; the ones that aren't `required`
+/// are `Option`s, and the `_id` is `id`, left out of inserts until the database assigns it.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model, e.g. `post`
+/// - collection_name: [`&str`] - the collection the documents are stored in
+/// - schema: [`&Document`] - the `$jsonSchema` of the collection
+/// - Returns: [`String`] - the contents of `src/models/<model_name>.rs`
+///
+/// ### Example:
+/// ```rust
+/// use mongodb::bson::doc;
+/// use rustyroad::writers::models::mongo_model_contents;
+///
+/// let schema = doc! {
+///     "bsonType": "object",
+///     "required": ["title"],
+///     "properties": { "title": { "bsonType": "string" }, "views": { "bsonType": ["int", "null"] } },
+/// };
+/// let contents = mongo_model_contents("post", "posts", &schema);
+/// assert!(contents.contains("pub struct Post {"));
+/// assert!(contents.contains("pub title: String,"));
+/// assert!(contents.contains("pub views: Option<i32>,"));
+/// ```
+pub fn mongo_model_contents(model_name: &str, collection_name: &str, schema: &Document) -> String {
+    let struct_name = capitalize_first(model_name);
+    let required: Vec<&str> = schema
+        .get_array("required")
+        .map(|required| required.iter().filter_map(Bson::as_str).collect())
+        .unwrap_or_default();
+
+    let mut fields = String::new();
+    if let Ok(properties) = schema.get_document("properties") {
+        for (name, property) in properties {
+            // `_id` is always the `id` field below
+            if name == "_id" {
+                continue;
+            }
+            let bson_type = match property.as_document().and_then(|property| property.get("bsonType")) {
+                Some(Bson::String(bson_type)) => bson_type.as_str(),
+                // A nullable field is `[type, "null"]`
+                Some(Bson::Array(types)) => types
+                    .iter()
+                    .filter_map(Bson::as_str)
+                    .find(|bson_type| *bson_type != "null")
+                    .unwrap_or("null"),
+                _ => "",
+            };
+            let rust_type = bson_rust_type(bson_type);
+            if required.contains(&name.as_str()) {
+                fields.push_str(&format!("    pub {}: {},\n", name, rust_type));
+            } else {
+                fields.push_str(&format!(
+                    "    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub {}: Option<{}>,\n",
+                    name, rust_type
+                ));
+            }
+        }
+    }
+
+    format!(
+        r#"use futures::TryStreamExt;
+use mongodb::bson::{{doc, oid::ObjectId}};
+use mongodb::{{Collection, Database}};
+use serde::{{Deserialize, Serialize}};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct {struct_name} {{
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+{fields}}}
+
+impl {struct_name} {{
+    pub const COLLECTION: &'static str = "{collection_name}";
+
+    pub fn collection(database: &Database) -> Collection<{struct_name}> {{
+        database.collection(Self::COLLECTION)
+    }}
+
+    pub async fn all(database: &Database) -> Result<Vec<{struct_name}>, mongodb::error::Error> {{
+        Self::collection(database).find(doc! {{}}).await?.try_collect().await
+    }}
+
+    pub async fn find_by_id(
+        database: &Database,
+        id: ObjectId,
+    ) -> Result<Option<{struct_name}>, mongodb::error::Error> {{
+        Self::collection(database).find_one(doc! {{ "_id": id }}).await
+    }}
+
+    pub async fn create(mut self, database: &Database) -> Result<{struct_name}, mongodb::error::Error> {{
+        let result = Self::collection(database).insert_one(&self).await?;
+        self.id = result.inserted_id.as_object_id();
+        Ok(self)
+    }}
+
+    /// Replaces the document with this id, returns whether it existed.
+    pub async fn update(
+        database: &Database,
+        id: ObjectId,
+        mut {model_name}: {struct_name},
+    ) -> Result<bool, mongodb::error::Error> {{
+        {model_name}.id = Some(id);
+        let result = Self::collection(database)
+            .replace_one(doc! {{ "_id": id }}, &{model_name})
+            .await?;
+        Ok(result.matched_count == 1)
+    }}
+
+    /// Deletes the document with this id, returns whether it existed.
+    pub async fn delete(database: &Database, id: ObjectId) -> Result<bool, mongodb::error::Error> {{
+        let result = Self::collection(database).delete_one(doc! {{ "_id": id }}).await?;
+        Ok(result.deleted_count == 1)
+    }}
+}}
+"#,
+        struct_name = struct_name,
+        fields = fields,
+        collection_name = collection_name,
+        model_name = model_name,
+    )
+}
+
+/// ## Name: create_mongo_model
+/// ### Description: Writes the model of the collection created by a MongoDB migration
+/// Reads the `create` command of the `up.json` of the migration, then writes
+/// `src/models/<model_name>.rs` and declares it in `src/models/mod.rs`.
+/// #### Parameters:
+/// - model_name: [`&str`] - the name of the model
+/// - migration_dir: [`&str`] - the folder of the migration that creates the collection
+/// - Returns: [`Result<(), Error>`]
+pub fn create_mongo_model(model_name: &str, migration_dir: &str) -> Result<(), Error> {
+    let up_path = Path::new(migration_dir).join("up.json");
+    let up_json = fs::read_to_string(&up_path)
+        .map_err(|why| eyre!("Couldn't read {}: {}", up_path.display(), why))?;
+    let (collection_name, schema) = find_collection_schema(&up_json)?.ok_or_else(|| {
+        eyre!(
+            "{} doesn't create a collection, add a `create` command to it",
+            up_path.display()
+        )
+    })?;
+
+    let model_path = format!("./src/models/{}.rs", model_name);
+    create_file(&model_path).map_err(|why| eyre!("Couldn't create {}: {}", model_path, why))?;
+    fs::write(
+        &model_path,
+        mongo_model_contents(model_name, &collection_name, &schema),
+    )?;
+
+    let models_mod = Path::new("./src/models/mod.rs");
+    if models_mod.exists() {
+        add_module_declaration(model_name.to_string(), models_mod)?;
+    }
+    println!("Created the {} model for the {} collection", model_name, collection_name);
+    Ok(())
+}
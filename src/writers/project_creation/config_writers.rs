@@ -3,6 +3,20 @@ use crate::Project;
 use std::fs;
 use std::io::Error;
 
+/// The sqlx release rustyroad is built with. Generated apps depend on the same one, so the
+/// pools of [`DatabaseConnection`](crate::database::DatabaseConnection) are their pool types.
+pub const SQLX_VERSION: &str = "0.8";
+
+/// The `[dependencies.sqlx]` table of a generated app, for the sqlx driver of its database.
+fn sqlx_dependency(driver: &str) -> String {
+    format!(
+        r#"[dependencies.sqlx]
+features = ["{}", "macros", "chrono", "json", "uuid", "rust_decimal", "runtime-tokio"]
+version = "{}""#,
+        driver, SQLX_VERSION
+    )
+}
+
 /// This function writes the dependencies for the project to the Cargo.toml file.
 ///
 /// # Arguments
@@ -15,29 +29,12 @@ use std::io::Error;
 /// * `Ok(())` if the content was successfully written to the file, or an Error if something went wrong.
 pub fn write_to_cargo_toml(project: &Project, database_data: &Database) -> Result<(), Error> {
     let dependencies = match database_data.database_type {
-        DatabaseType::Postgres => {
-            r#"[dependencies.sqlx]
-features = ["postgres", "macros", "chrono", "json", "uuid", "runtime-tokio"]
-version = "0.7.2""#
-        }
-        DatabaseType::Mysql => {
-            r#"[dependencies.sqlx]
-features = ["mysql", "macros", "chrono", "json", "uuid", "runtime-tokio"]
-version = "0.7.2""#
-        }
-        DatabaseType::Sqlite => {
-            r#"[dependencies.rusqlite]
-version = "0.28.0"
-features = ["bundled"]
-libsqlite3-sys = { version = "0.25.0", features = ["bundled"] }
-"#
-        }
-        DatabaseType::Mongo => {
-            r#"[dependencies.mongodb]
-version =  "2.4.0"
-default-features = false
-features = ["sync", "bson", "tls"]"#
-        }
+        DatabaseType::Postgres => sqlx_dependency("postgres"),
+        DatabaseType::Mysql => sqlx_dependency("mysql"),
+        DatabaseType::Sqlite => sqlx_dependency("sqlite"),
+        DatabaseType::Mongo => r#"[dependencies.mongodb]
+version = "3.9.1""#
+            .to_string(),
     };
     let config = format!(
         r#"[package]
@@ -61,7 +58,7 @@ local-ip-address = "0.5.0"
 futures = "0.3.23"
 tera = "1.17.1"
 reqwest = "0.11"
-rustyroad = "1.0.8"
+rustyroad = "{}"
 rand = "0.8.5"
 chrono = {{ version = "0.4.24", features = ["serde"] }}
 base64 = "0.21.0"
@@ -71,7 +68,9 @@ color-eyre = "0.6.2"
 serde_derive = "1.0.189"
 {}
 "#,
-        &project.name, dependencies
+        &project.name,
+        env!("CARGO_PKG_VERSION"),
+        dependencies
     );
 
     fs::write(&project.cargo_toml, config.as_bytes())
@@ -18,9 +18,8 @@ use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use bcrypt::verify;
-use rustyroad::database::Database;
+use rustyroad::database::DatabaseConnection;
 use serde::Deserialize;
-use sqlx::MySqlPool;
 
 use tera::Context;
 use tera::Tera;
@@ -43,9 +42,9 @@ pub struct UserLogin {
 impl UserLogin {
     async fn get_hashed_password_from_db(
         username: &str,
-        pool: &sqlx::MySqlPool,
-    ) -> Result<String, sqlx::Error> {
-        let row: (String,) = sqlx::query_as("SELECT password FROM Users WHERE username = ?")
+        pool: &rustyroad::sqlx::MySqlPool,
+    ) -> Result<String, rustyroad::sqlx::Error> {
+        let row: (String,) = rustyroad::sqlx::query_as("SELECT password FROM Users WHERE username = ?")
             .bind(username)
             .fetch_one(pool)
             .await?;
@@ -56,27 +55,17 @@ impl UserLogin {
         &self,
         request: HttpRequest,
         tmpl: web::Data<Tera>,
-        database: Database,
+        pool: &DatabaseConnection,
     ) -> Result<HttpResponse, Error> {
         let mut ctx = Context::new();
 
-        // Create the database URL
-        let database_url = format!(
-            "mysql://{}:{}@{}:{}/{}",
-            database.username,
-            database.password,
-            database.host,
-            database.port,
-            database.name
-        );
-
-        // Create the database connection pool
-        let db_pool = MySqlPool::connect(&database_url)
-            .await
-            .expect("Failed to connect to MySQL.");
+        // The shared pool of main.rs
+        let db_pool = pool
+            .as_mysql()
+            .ok_or_else(|| actix_web::error::ErrorInternalServerError("The database isn't MySQL"))?;
 
          // Retrieve the hashed password from the database
-        match Self::get_hashed_password_from_db(&self.username, &db_pool).await {
+        match Self::get_hashed_password_from_db(&self.username, db_pool).await {
             Ok(hashed_password) => {
                 match verify(&self.password, &hashed_password) {
                     Ok(password_match) => {
@@ -146,9 +135,8 @@ use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use bcrypt::verify;
-use rustyroad::database::Database;
+use rustyroad::database::DatabaseConnection;
 use serde::Deserialize;
-use sqlx::PgPool;
 
 use tera::Context;
 use tera::Tera;
@@ -171,9 +159,9 @@ pub struct UserLogin {
 impl UserLogin {
     async fn get_hashed_password_from_db(
         username: &str,
-        pool: &sqlx::PgPool,
-    ) -> Result<String, sqlx::Error> {
-        let row: (String,) = sqlx::query_as("SELECT password FROM Users WHERE username = $1")
+        pool: &rustyroad::sqlx::PgPool,
+    ) -> Result<String, rustyroad::sqlx::Error> {
+        let row: (String,) = rustyroad::sqlx::query_as("SELECT password FROM Users WHERE username = $1")
             .bind(username)
             .fetch_one(pool)
             .await?;
@@ -184,27 +172,17 @@ impl UserLogin {
         &self,
         request: HttpRequest,
         tmpl: web::Data<Tera>,
-        database: Database
+        pool: &DatabaseConnection
     ) -> Result<HttpResponse, Error> {
         let mut ctx = Context::new();
 
-        // Create the database URL
-        let database_url = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            database.username,
-            database.password,
-            database.host,
-            database.port,
-            database.name
-        );
-
-        // Create the database connection pool
-        let db_pool = PgPool::connect(&database_url)
-            .await
-            .expect("Failed to connect to Postgres.");
+        // The shared pool of main.rs
+        let db_pool = pool
+            .as_pg()
+            .ok_or_else(|| actix_web::error::ErrorInternalServerError("The database isn't Postgres"))?;
 
       // Retrieve the hashed password from the database
-        match Self::get_hashed_password_from_db(&self.username, &db_pool).await {
+        match Self::get_hashed_password_from_db(&self.username, db_pool).await {
             Ok(hashed_password) => {
                 match verify(&self.password, &hashed_password) {
                     Ok(password_match) => {
@@ -275,9 +253,8 @@ use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use bcrypt::verify;
-use rustyroad::database::Database;
+use rustyroad::database::DatabaseConnection;
 use serde::Deserialize;
-use sqlx::SqlitePool;
 
 use tera::Context;
 use tera::Tera;
@@ -300,9 +277,9 @@ password: String,
 impl UserLogin {
 async fn get_hashed_password_from_db(
 username: &str,
-pool: &sqlx::SqlitePool,
-) -> Result<String, sqlx::Error> {
-let row: (String,) = sqlx::query_as("SELECT password FROM Users WHERE username = ?")
+pool: &rustyroad::sqlx::SqlitePool,
+) -> Result<String, rustyroad::sqlx::Error> {
+let row: (String,) = rustyroad::sqlx::query_as("SELECT password FROM Users WHERE username = ?")
 .bind(username)
 .fetch_one(pool)
 .await?;
@@ -312,22 +289,16 @@ pub async fn user_login(
     &self,
     request: HttpRequest,
     tmpl: web::Data<Tera>,
-    database: Database
+    pool: &DatabaseConnection
 ) -> Result<HttpResponse, Error> {
     let mut ctx = Context::new();
 
-    // Create the database URL
-    let database_url = format!(
-        "sqlite://{}",
-        database.name
-    );
-
-    // Create the database connection pool
-    let db_pool = SqlitePool::connect(&database_url)
-        .await
-.expect("Failed to connect to SQLite.");
+    // The shared pool of main.rs
+    let db_pool = pool
+        .as_sqlite()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("The database isn't SQLite"))?;
           // Retrieve the hashed password from the database
-        match Self::get_hashed_password_from_db(&self.username, &db_pool).await {
+        match Self::get_hashed_password_from_db(&self.username, db_pool).await {
             Ok(hashed_password) => {
                                 match verify(&self.password, &hashed_password) {
                     Ok(password_match) => {
@@ -380,3 +351,126 @@ pub async fn user_login(
 
     Ok(())
 }
+
+// Write to models/users.rs
+pub fn write_to_mongo_user_models(project: &Project) -> Result<(), Error> {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(project.clone().user_model)?;
+    file.write_all(
+        r#"use actix_identity::Identity;
+use actix_web::HttpMessage;
+use actix_web::web;
+use actix_web::Error;
+use actix_web::HttpRequest;
+use actix_web::HttpResponse;
+use bcrypt::verify;
+use mongodb::bson::{doc, oid::ObjectId};
+use rustyroad::database::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+use tera::Context;
+use tera::Tera;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct User {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    id: Option<ObjectId>,
+    username: String,
+    password: String,
+    role: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UserLogin {
+    username: String,
+    password: String,
+}
+
+impl UserLogin {
+    async fn get_hashed_password_from_db(
+        username: &str,
+        database: &mongodb::Database,
+    ) -> Result<Option<String>, mongodb::error::Error> {
+        let user = database
+            .collection::<User>("users")
+            .find_one(doc! { "username": username })
+            .await?;
+
+        Ok(user.map(|user| user.password))
+    }
+    pub async fn user_login(
+        &self,
+        request: HttpRequest,
+        tmpl: web::Data<Tera>,
+        pool: &DatabaseConnection
+    ) -> Result<HttpResponse, Error> {
+        let mut ctx = Context::new();
+
+        // The shared pool of main.rs
+        let database = pool
+            .as_mongo()
+            .ok_or_else(|| actix_web::error::ErrorInternalServerError("The database isn't MongoDB"))?;
+
+        // Retrieve the hashed password from the database
+        match Self::get_hashed_password_from_db(&self.username, database).await {
+            Ok(Some(hashed_password)) => {
+                match verify(&self.password, &hashed_password) {
+                    Ok(password_match) => {
+                        if password_match {
+                            // Here you can set the identity directly
+                            Identity::login(&request.extensions(), self.username.clone()).unwrap();
+
+
+                            ctx.insert("username", &self.username.clone());
+                            ctx.insert("route_name", "dashboard");
+                            ctx.insert("title", "Dashboard");
+                            let body = tmpl
+                                .render("pages/dashboard.html.tera", &ctx)
+                                .unwrap();
+                            return Ok(HttpResponse::Ok()
+                                .append_header((actix_web::http::header::LOCATION, "/dashboard"))
+                                .body(body));
+                        } else {
+                            ctx.insert("error", "Invalid username or password");
+                            let rendered = tmpl.render("pages/login.html.tera", &ctx).unwrap();
+                            return Ok(HttpResponse::Ok().body(rendered));
+                        }
+                    }
+                    Err(e) => {
+                        panic!("Failed to verify password: {}", e);
+                    }
+                }
+            },
+            Ok(None) => {
+                ctx.insert("error", "Invalid username or password");
+                let rendered = tmpl.render("pages/login.html.tera", &ctx).unwrap();
+                Ok(HttpResponse::Ok().body(rendered))
+            },
+            Err(e) => {
+                panic!("Failed to retrieve hashed password from database: {}", e);
+            }
+        }
+    }
+
+    pub async fn user_logout(
+       tmpl: web::Data<Tera>,
+       user: Identity,
+    ) -> Result<HttpResponse, Error> {
+       user.logout();
+       let mut context = Context::new();
+       context.insert("route_name", "login");
+       context.insert("message", "You have been logged out.");
+       let rendered = tmpl.render("pages/login.html.tera", &context).unwrap();
+       Ok(HttpResponse::Ok().body(rendered))
+    }
+
+}
+"#
+        .as_ref(),
+    )?;
+
+    write_to_models_mod(&project.models_module, "user".to_string())?;
+
+    Ok(())
+}
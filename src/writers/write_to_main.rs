@@ -4,29 +4,14 @@ use color_eyre::eyre::Result;
 use regex::Regex;
 use std::{env, fs};
 use std::io::Error;
-/// This function writes initial content to the main.rs file of a new RustyRoad project.
-/// The content includes setting up an Actix web server with three controllers: index, dashboard, and login.
-///
-/// # Arguments
-///
-/// * `project` - A reference to the Project struct containing the paths to the project files.
-///
-/// # Returns
-///
-/// * `Ok(())` if the content was successfully written to the main.rs file, or an Error if something went wrong.
-/// # Example
-/// ```rust
-/// use rustyroad::writers::write_to_main::write_to_main_rs;
-/// use rustyroad::Project;
-///
-/// let project = Project::new();
-/// write_to_main_rs(&project).expect("Failed to write to main.rs");
-/// ```
-pub fn write_to_main_rs(project: &Project) -> Result<(), Error> {
-    // Define the contents to be written to the main.rs file
-    // This includes importing necessary Actix Web modules, defining the main function, setting up the HTTP server,
-    // binding it to the localhost on port 8000, and defining three controllers: index, dashboard, and login
-    let contents = r#"use actix_cors::Cors;
+
+/// ## Name: main_rs_contents
+/// ### Description: The main.rs of a new RustyRoad project
+/// It registers the [`Database`](crate::database::Database), its shared
+/// [`DatabaseConnection`](crate::database::DatabaseConnection) and the `Tera` views as `web::Data`,
+/// the types the generated controllers take.
+pub fn main_rs_contents() -> &'static str {
+    r#"use actix_cors::Cors;
 use actix_files::Files;
 use actix_identity::IdentityMiddleware;
 use actix_session::storage::CookieSessionStore;
@@ -60,7 +45,30 @@ async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
 
-    let database = web::Data::new(Database::get_database_from_rustyroad_toml().unwrap());
+    // `--migrate` applies the migrations compiled into this binary and exits,
+    // RUN_MIGRATIONS=true applies them before the server starts
+    let migrate_only = env::args().any(|arg| arg == "--migrate");
+    if migrate_only || env::var("RUN_MIGRATIONS").map(|value| value == "true").unwrap_or(false) {
+        if let Err(why) = rustyroad::embed_migrations!().run_for_project().await {
+            eprintln!("Error running migrations: {}", why);
+            std::process::exit(1);
+        }
+        if migrate_only {
+            return Ok(());
+        }
+    }
+
+    let database = Database::get_database_from_rustyroad_toml().unwrap_or_else(|why| {
+        eprintln!("Error reading the database configuration: {}", why);
+        std::process::exit(1);
+    });
+    // One pool for the whole process, sized by the pool_* settings of rustyroad.toml
+    let pool = database.shared_pool().await.unwrap_or_else(|why| {
+        eprintln!("Error connecting to the database: {}", why);
+        std::process::exit(1);
+    });
+    let database = web::Data::new(database);
+    let pool = web::Data::new(pool);
 
     println!("Starting Actix web server...");
 
@@ -86,6 +94,7 @@ async fn main() -> std::io::Result<()> {
             .wrap(cors)
             .wrap(IdentityMiddleware::default())
             .app_data(database.clone())
+            .app_data(pool.clone())
             .wrap(session_mw)
             .app_data(web::Data::new(tera.clone())) // Updated line
             .service(controllers::index::index)
@@ -101,7 +110,32 @@ async fn main() -> std::io::Result<()> {
     .run()
     .await
 }
-"#;
+"#
+}
+
+/// This function writes initial content to the main.rs file of a new RustyRoad project.
+/// The content includes setting up an Actix web server with three controllers: index, dashboard, and login.
+///
+/// # Arguments
+///
+/// * `project` - A reference to the Project struct containing the paths to the project files.
+///
+/// # Returns
+///
+/// * `Ok(())` if the content was successfully written to the main.rs file, or an Error if something went wrong.
+/// # Example
+/// ```rust
+/// use rustyroad::writers::write_to_main::write_to_main_rs;
+/// use rustyroad::Project;
+///
+/// let project = Project::new();
+/// write_to_main_rs(&project).expect("Failed to write to main.rs");
+/// ```
+pub fn write_to_main_rs(project: &Project) -> Result<(), Error> {
+    // Define the contents to be written to the main.rs file
+    // This includes importing necessary Actix Web modules, defining the main function, setting up the HTTP server,
+    // binding it to the localhost on port 8000, and defining three controllers: index, dashboard, and login
+    let contents = main_rs_contents();
 
     // Write the contents to the main.rs file
     // The write_to_file function is assumed to be a function that takes a path and a byte slice and writes the bytes to the file at the path
@@ -0,0 +1,263 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use rustyroad::database::migrations::{
+        load_sqlite_table_schema, run_all_migrations_with_connection, AlterTable, ColumnSpec,
+    };
+    use rustyroad::database::{DatabaseConnection, DatabaseType, MigrationDirection};
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::Executor;
+
+    use crate::common::{sqlite_pool, write_migration};
+
+    async fn column_types(pool: &SqlitePool, table: &str) -> Vec<(String, String)> {
+        sqlx::query_as("SELECT name, type FROM pragma_table_info(?) ORDER BY cid")
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Writes the change as the second migration, after one that creates users and posts.
+    async fn setup(dir: &Path, pool: &SqlitePool, change: &AlterTable) -> DatabaseConnection {
+        pool.execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL);
+CREATE TABLE posts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL UNIQUE,
+    views INTEGER NOT NULL DEFAULT 0,
+    author_id INTEGER NULL
+);
+CREATE INDEX index_posts_on_views ON posts (views);
+INSERT INTO users (id, name) VALUES (1, 'ada');
+INSERT INTO posts (title, views, author_id) VALUES ('hello', 3, 1);",
+        )
+        .await
+        .unwrap();
+
+        let schema = load_sqlite_table_schema(pool, change.table()).await.unwrap();
+        let (up, down) = change
+            .to_sql(&DatabaseType::Sqlite, Some(&schema))
+            .unwrap();
+        write_migration(
+            &dir.join("migrations"),
+            &format!("20240102000000-{}", change.migration_name()),
+            &up,
+            &down,
+        );
+
+        DatabaseConnection::Sqlite(Arc::new(pool.clone()))
+    }
+
+    #[test]
+    fn test_postgres_and_mysql_alter_sql() {
+        let add_reference = AlterTable::AddColumn {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("editor_id:references:users:null").unwrap(),
+        };
+        let (up, down) = add_reference.to_sql(&DatabaseType::Postgres, None).unwrap();
+        assert_eq!(
+            up,
+            "ALTER TABLE posts ADD COLUMN editor_id INTEGER NULL;
+ALTER TABLE posts ADD CONSTRAINT fk_posts_editor_id FOREIGN KEY (editor_id) REFERENCES users (id);
+"
+        );
+        assert_eq!(
+            down,
+            "ALTER TABLE posts DROP CONSTRAINT fk_posts_editor_id;
+ALTER TABLE posts DROP COLUMN editor_id;
+"
+        );
+
+        let change_type = AlterTable::ChangeColumnType {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("views:bigint").unwrap(),
+            from: ColumnSpec::parse("views:integer").unwrap(),
+        };
+        let (up, down) = change_type.to_sql(&DatabaseType::Mysql, None).unwrap();
+        assert_eq!(up, "ALTER TABLE posts MODIFY COLUMN views BIGINT NOT NULL;\n");
+        assert_eq!(down, "ALTER TABLE posts MODIFY COLUMN views INT NOT NULL;\n");
+
+        let add_index = AlterTable::AddIndex {
+            table: "posts".to_string(),
+            columns: vec!["title".to_string(), "views".to_string()],
+            unique: true,
+        };
+        let (up, down) = add_index.to_sql(&DatabaseType::Mysql, None).unwrap();
+        assert_eq!(
+            up,
+            "CREATE UNIQUE INDEX index_posts_on_title_and_views ON posts (title, views);\n"
+        );
+        assert_eq!(down, "DROP INDEX index_posts_on_title_and_views ON posts;\n");
+
+        let rename = AlterTable::RenameColumn {
+            table: "posts; DROP TABLE users".to_string(),
+            from: "title".to_string(),
+            to: "headline".to_string(),
+        };
+        assert!(rename.to_sql(&DatabaseType::Postgres, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_change_column_type_rebuilds_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = sqlite_pool(&dir.path().join("test.db")).await;
+        let change = AlterTable::ChangeColumnType {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("views:bigint").unwrap(),
+            from: ColumnSpec::parse("views:integer").unwrap(),
+        };
+        let connection = setup(dir.path(), &pool, &change).await;
+        let migrations_dir = dir.path().join("migrations");
+        let migrations_dir = migrations_dir.to_str().unwrap();
+
+        let before = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        run_all_migrations_with_connection(&connection, migrations_dir, MigrationDirection::Up)
+            .await
+            .unwrap();
+
+        let after = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        assert_eq!(after.unique_constraints, before.unique_constraints);
+        assert_eq!(after.indexes, before.indexes);
+        assert!(after.autoincrement);
+        let (title, views): (String, i64) = sqlx::query_as("SELECT title, views FROM posts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((title.as_str(), views), ("hello", 3));
+
+        run_all_migrations_with_connection(&connection, migrations_dir, MigrationDirection::Down)
+            .await
+            .unwrap();
+        assert_eq!(load_sqlite_table_schema(&pool, "posts").await.unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_remove_column_and_add_foreign_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = sqlite_pool(&dir.path().join("test.db")).await;
+        let change = AlterTable::AddForeignKey {
+            table: "posts".to_string(),
+            column: "author_id".to_string(),
+            references: "users".to_string(),
+        };
+        let connection = setup(dir.path(), &pool, &change).await;
+        let migrations_dir = dir.path().join("migrations");
+        run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+
+        // The rebuild is generated from the table as it is after the foreign key was added
+        let remove = AlterTable::RemoveColumn {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("views:integer").unwrap(),
+        };
+        let schema = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        let (up, down) = remove.to_sql(&DatabaseType::Sqlite, Some(&schema)).unwrap();
+        write_migration(
+            &migrations_dir,
+            "20240103000000-remove_views_from_posts",
+            &up,
+            &down,
+        );
+
+        run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+
+        let after = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        assert_eq!(after.foreign_keys.len(), 1);
+        assert_eq!(after.foreign_keys[0].references_table, "users");
+        assert!(after.indexes.is_empty());
+        assert_eq!(
+            column_types(&pool, "posts").await,
+            vec![
+                ("id".to_string(), "INTEGER".to_string()),
+                ("title".to_string(), "TEXT".to_string()),
+                ("author_id".to_string(), "INTEGER".to_string()),
+            ]
+        );
+        let foreign_key_violation = pool
+            .execute("INSERT INTO posts (title, author_id) VALUES ('orphan', 42);")
+            .await;
+        assert!(foreign_key_violation.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_add_column_uses_alter_table_when_possible() {
+        let nullable = AlterTable::AddColumn {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("subtitle:string:null").unwrap(),
+        };
+        assert!(!nullable.requires_sqlite_rebuild());
+        let (up, _) = nullable.to_sql(&DatabaseType::Sqlite, None).unwrap();
+        assert_eq!(up, "ALTER TABLE posts ADD COLUMN subtitle TEXT NULL;\n");
+
+        let unique = AlterTable::AddColumn {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("slug:string:null:unique").unwrap(),
+        };
+        assert!(unique.requires_sqlite_rebuild());
+        assert!(unique.to_sql(&DatabaseType::Sqlite, None).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let pool = sqlite_pool(&dir.path().join("test.db")).await;
+        let connection = setup(dir.path(), &pool, &unique).await;
+        run_all_migrations_with_connection(
+            &connection,
+            dir.path().join("migrations").to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+
+        let after = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        assert!(after.has_column("slug"));
+        assert!(after.unique_constraints.contains(&vec!["slug".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_add_references_column_rolls_back() {
+        let change = AlterTable::AddColumn {
+            table: "posts".to_string(),
+            column: ColumnSpec::parse("editor_id:references:users:null").unwrap(),
+        };
+        assert!(change.requires_sqlite_rebuild());
+
+        let dir = tempfile::tempdir().unwrap();
+        let pool = sqlite_pool(&dir.path().join("test.db")).await;
+        let connection = setup(dir.path(), &pool, &change).await;
+        let migrations_dir = dir.path().join("migrations");
+        let migrations_dir = migrations_dir.to_str().unwrap();
+
+        let before = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        run_all_migrations_with_connection(&connection, migrations_dir, MigrationDirection::Up)
+            .await
+            .unwrap();
+        let after = load_sqlite_table_schema(&pool, "posts").await.unwrap();
+        assert!(after.has_column("editor_id"));
+        assert_eq!(after.foreign_keys[0].references_table, "users");
+
+        run_all_migrations_with_connection(&connection, migrations_dir, MigrationDirection::Down)
+            .await
+            .unwrap();
+        assert_eq!(load_sqlite_table_schema(&pool, "posts").await.unwrap(), before);
+        let (title, views): (String, i64) = sqlx::query_as("SELECT title, views FROM posts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((title.as_str(), views), ("hello", 3));
+    }
+}
@@ -0,0 +1,314 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::migrations::{
+        build_join_table_migration, build_migration_from_columns, create_join_table_migration,
+        create_migration_from_columns, get_applied_migrations, run_all_migrations_with_connection, ColumnSpec,
+        TableSchema, MIGRATIONS_DIR,
+    };
+    use rustyroad::database::{DatabaseConnection, DatabaseType, MigrationDirection, PoolConnection};
+    use rustyroad::writers::models::{associated_model_contents, parse_create_tables, Association};
+    use sqlx::sqlite::SqlitePool;
+    use std::sync::Arc;
+
+    /// The models `rustyroad generate model` writes for the tables of [`schema`], compiled with the tests
+    mod models {
+        pub mod post {
+            include!("fixtures/models/blog/post.rs");
+        }
+        pub mod profile {
+            include!("fixtures/models/blog/profile.rs");
+        }
+        pub mod tag {
+            include!("fixtures/models/blog/tag.rs");
+        }
+        pub mod user {
+            include!("fixtures/models/blog/user.rs");
+        }
+    }
+    use models::post::{NewPost, Post};
+    use models::profile::{NewProfile, Profile};
+    use models::tag::{NewTag, Tag};
+    use models::user::{NewUser, User};
+
+    /// The up.sql of each migration: users, posts, tags, profiles and the join table of posts and tags.
+    fn migrations() -> Vec<String> {
+        let tables: [(&str, &[&str]); 4] = [
+            ("users", &["name:string"]),
+            ("posts", &["title:string", "author_id:references:users"]),
+            ("tags", &["name:string"]),
+            ("profiles", &["bio:text", "user_id:references:users"]),
+        ];
+        let mut migrations: Vec<String> = tables
+            .iter()
+            .map(|(table, specs)| {
+                let columns: Vec<ColumnSpec> = specs.iter().map(|spec| ColumnSpec::parse(spec).unwrap()).collect();
+                build_migration_from_columns(table, &columns, &DatabaseType::Sqlite)
+                    .unwrap()
+                    .up_sql_contents
+            })
+            .collect();
+        migrations.push(build_join_table_migration(["posts", "tags"], &DatabaseType::Sqlite).unwrap());
+        migrations
+    }
+
+    fn schema() -> Vec<TableSchema> {
+        migrations()
+            .iter()
+            .flat_map(|sql| parse_create_tables(sql, &DatabaseType::Sqlite).unwrap())
+            .collect()
+    }
+
+    async fn pool() -> PoolConnection {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        for sql in migrations() {
+            sqlx::raw_sql(&sql).execute(&pool).await.unwrap();
+        }
+        PoolConnection::Sqlite(pool)
+    }
+
+    fn fixture(contents: &str) -> String {
+        contents.replace("\r\n", "\n")
+    }
+
+    #[test]
+    fn test_fixtures_are_the_generated_models() {
+        let schema = schema();
+        let table = |name: &str| schema.iter().find(|table| table.name == name).unwrap();
+        let model = |model_name: &str, table: &TableSchema, declared: Vec<Association>| {
+            let mut associations = Association::belongs_to(table);
+            associations.extend(declared);
+            associated_model_contents(model_name, table, &associations, &DatabaseType::Sqlite)
+        };
+
+        let user = model(
+            "user",
+            table("users"),
+            vec![
+                Association::parse("has_many", "users", "posts:author_id").unwrap(),
+                Association::parse("has_one", "users", "profiles").unwrap(),
+            ],
+        );
+        assert_eq!(user, fixture(include_str!("fixtures/models/blog/user.rs")));
+        let post = model(
+            "post",
+            table("posts"),
+            vec![Association::parse("many_to_many", "posts", "tags").unwrap()],
+        );
+        assert_eq!(post, fixture(include_str!("fixtures/models/blog/post.rs")));
+        let tag = model(
+            "tag",
+            table("tags"),
+            vec![Association::parse("many_to_many", "tags", "posts").unwrap()],
+        );
+        assert_eq!(tag, fixture(include_str!("fixtures/models/blog/tag.rs")));
+        let profile = model("profile", table("profiles"), Vec::new());
+        assert_eq!(profile, fixture(include_str!("fixtures/models/blog/profile.rs")));
+    }
+
+    #[test]
+    fn test_associations_from_the_schema() {
+        let schema = schema();
+        let table = |name: &str| schema.iter().find(|table| table.name == name).unwrap();
+        let has_many = |table: &str, foreign_key: &str| Association::HasMany {
+            table: table.to_string(),
+            foreign_key: foreign_key.to_string(),
+        };
+
+        assert_eq!(
+            Association::from_schema(table("users"), &schema),
+            vec![has_many("posts", "author_id"), has_many("profiles", "user_id")]
+        );
+        // The join table links posts and tags instead of having many of either
+        assert_eq!(
+            Association::from_schema(table("posts"), &schema),
+            vec![
+                Association::BelongsTo {
+                    table: "users".to_string(),
+                    foreign_key: "author_id".to_string(),
+                },
+                Association::ManyToMany {
+                    table: "tags".to_string(),
+                    join_table: "posts_tags".to_string(),
+                    foreign_key: "post_id".to_string(),
+                    association_foreign_key: "tag_id".to_string(),
+                },
+            ]
+        );
+        assert_eq!(Association::belongs_to(table("posts"))[0].method_name(), "author");
+        assert_eq!(Association::from_schema(table("tags"), &schema)[0].method_name(), "posts");
+    }
+
+    #[test]
+    fn test_invalid_associations() {
+        assert!(Association::parse("has_many", "users", "").is_err());
+        assert!(Association::parse("has_many", "users", "posts:author id").is_err());
+        assert!(Association::parse("belongs_to_many", "users", "posts").is_err());
+        assert_eq!(
+            Association::parse("has_one", "categories", "icons").unwrap(),
+            Association::HasOne {
+                table: "icons".to_string(),
+                foreign_key: "category_id".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_join_table_migration() {
+        let up_sql = build_join_table_migration(["tags", "posts"], &DatabaseType::Postgres).unwrap();
+        assert_eq!(
+            up_sql,
+            "CREATE TABLE posts_tags (
+    post_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (post_id, tag_id),
+    FOREIGN KEY (post_id) REFERENCES posts (id),
+    FOREIGN KEY (tag_id) REFERENCES tags (id)
+);
+"
+        );
+        assert!(build_join_table_migration(["tags", "posts"], &DatabaseType::Mongo).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_join_table_migration_runs_with_the_migration_of_the_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        std::fs::write(
+            "rustyroad.toml",
+            "[database]\ndatabase_name = \"blog\"\ndatabase_user = \"\"\ndatabase_password = \"\"\n\
+             database_host = \"localhost\"\ndatabase_port = 0\ndatabase_type = \"sqlite\"\n\n[database.development]\n",
+        )
+        .unwrap();
+
+        // What `generate model post title:string many_to_many:tags` writes, within the same second
+        create_migration_from_columns("create_tags", &["name:string".to_string()]).unwrap();
+        create_migration_from_columns("create_posts", &["title:string".to_string()]).unwrap();
+        create_join_table_migration("posts", "tags").unwrap().unwrap();
+        let mut folders: Vec<String> = std::fs::read_dir(MIGRATIONS_DIR)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        folders.sort();
+        let migrations_dir = dir.path().join(MIGRATIONS_DIR);
+        std::env::set_current_dir(backup_dir).unwrap();
+
+        let names: Vec<&str> = folders.iter().map(|folder| folder.split_once('-').unwrap().1).collect();
+        assert_eq!(names, vec!["create_tags", "create_posts", "create_posts_tags"]);
+
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let connection = DatabaseConnection::Sqlite(Arc::new(pool));
+        run_all_migrations_with_connection(&connection, migrations_dir.to_str().unwrap(), MigrationDirection::Up)
+            .await
+            .unwrap();
+        let applied: Vec<String> = get_applied_migrations(&connection)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|migration| migration.name)
+            .collect();
+        assert_eq!(applied, vec!["create_tags", "create_posts", "create_posts_tags"]);
+        let tables: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE 'posts%' ORDER BY name")
+                .fetch_all(connection.as_sqlite().unwrap())
+                .await
+                .unwrap();
+        assert_eq!(tables, vec!["posts", "posts_tags"]);
+    }
+
+    #[tokio::test]
+    async fn test_generated_association_functions() {
+        let pool = pool().await;
+        let user = |name: &str| NewUser { name: name.to_string() };
+        let ada = User::create_user(&pool, user("Ada")).await.unwrap();
+        let grace = User::create_user(&pool, user("Grace")).await.unwrap();
+        let post = |title: &str, author: &User| NewPost {
+            title: title.to_string(),
+            author_id: author.id.unwrap(),
+        };
+        let hello = Post::create_post(&pool, post("Hello", &ada)).await.unwrap();
+        let world = Post::create_post(&pool, post("World", &ada)).await.unwrap();
+        let rust = Tag::create_tag(&pool, NewTag { name: "rust".to_string() }).await.unwrap();
+        let sql = Tag::create_tag(&pool, NewTag { name: "sql".to_string() }).await.unwrap();
+        Profile::create_profile(
+            &pool,
+            NewProfile {
+                bio: "Countess".to_string(),
+                user_id: ada.id.unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // belongs_to, has_many and has_one
+        assert_eq!(hello.author(&pool).await.unwrap().unwrap().name, "Ada");
+        let titles: Vec<String> = ada.posts(&pool).await.unwrap().into_iter().map(|post| post.title).collect();
+        assert_eq!(titles, vec!["Hello", "World"]);
+        assert!(grace.posts(&pool).await.unwrap().is_empty());
+        assert_eq!(ada.profile(&pool).await.unwrap().unwrap().bio, "Countess");
+        assert!(grace.profile(&pool).await.unwrap().is_none());
+
+        // many_to_many through posts_tags
+        hello.attach_tag(&pool, &rust).await.unwrap();
+        hello.attach_tag(&pool, &sql).await.unwrap();
+        world.attach_tag(&pool, &rust).await.unwrap();
+        let names = |tags: &[Tag]| tags.iter().map(|tag| tag.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&hello.tags(&pool).await.unwrap()), vec!["rust", "sql"]);
+        assert_eq!(rust.posts(&pool).await.unwrap().len(), 2);
+        assert!(hello.detach_tag(&pool, &sql).await.unwrap());
+        assert!(!hello.detach_tag(&pool, &sql).await.unwrap());
+        assert_eq!(names(&hello.tags(&pool).await.unwrap()), vec!["rust"]);
+        assert!(sql.posts(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preloading() {
+        let pool = pool().await;
+        let ada = User::create_user(&pool, NewUser { name: "Ada".to_string() }).await.unwrap();
+        let grace = User::create_user(&pool, NewUser { name: "Grace".to_string() }).await.unwrap();
+        let alan = User::create_user(&pool, NewUser { name: "Alan".to_string() }).await.unwrap();
+        let mut posts = Vec::new();
+        for (title, author) in [("Hello", &ada), ("Compilers", &grace), ("World", &ada)] {
+            let new_post = NewPost {
+                title: title.to_string(),
+                author_id: author.id.unwrap(),
+            };
+            posts.push(Post::create_post(&pool, new_post).await.unwrap());
+        }
+        let rust = Tag::create_tag(&pool, NewTag { name: "rust".to_string() }).await.unwrap();
+        let sql = Tag::create_tag(&pool, NewTag { name: "sql".to_string() }).await.unwrap();
+        posts[0].attach_tag(&pool, &rust).await.unwrap();
+        posts[0].attach_tag(&pool, &sql).await.unwrap();
+        posts[2].attach_tag(&pool, &sql).await.unwrap();
+        Profile::create_profile(
+            &pool,
+            NewProfile {
+                bio: "Admiral".to_string(),
+                user_id: grace.id.unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // One entry per row, in the order of the rows
+        let authors = Post::preload_author(&pool, &posts).await.unwrap();
+        let authors: Vec<&str> = authors.iter().map(|author| author.as_ref().unwrap().name.as_str()).collect();
+        assert_eq!(authors, vec!["Ada", "Grace", "Ada"]);
+
+        let users = vec![ada, grace, alan];
+        let posts_of_users = User::preload_posts(&pool, &users).await.unwrap();
+        let counts: Vec<usize> = posts_of_users.iter().map(Vec::len).collect();
+        assert_eq!(counts, vec![2, 1, 0]);
+        let profiles = User::preload_profile(&pool, &users).await.unwrap();
+        let bios: Vec<Option<&str>> = profiles.iter().map(|profile| profile.as_ref().map(|profile| profile.bio.as_str())).collect();
+        assert_eq!(bios, vec![None, Some("Admiral"), None]);
+
+        let tags = Post::preload_tags(&pool, &posts).await.unwrap();
+        let tags: Vec<Vec<&str>> = tags
+            .iter()
+            .map(|tags| tags.iter().map(|tag| tag.name.as_str()).collect())
+            .collect();
+        assert_eq!(tags, vec![vec!["rust", "sql"], vec![], vec!["sql"]]);
+        assert!(Post::preload_tags(&pool, &[]).await.unwrap().is_empty());
+    }
+}
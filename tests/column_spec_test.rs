@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::migrations::{
+        build_migration_from_columns, parse_column_specs, ColumnKind, ColumnSpec,
+    };
+    use rustyroad::database::DatabaseType;
+    use rustyroad::writers::models::sql_rust_type;
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::Executor;
+
+    fn specs(specs: &[&str]) -> Vec<String> {
+        specs.iter().map(|spec| spec.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_column_spec() {
+        let column = ColumnSpec::parse("title:string:null:unique").unwrap();
+        assert_eq!(column.name, "title");
+        assert_eq!(column.kind, ColumnKind::String);
+        assert!(column.nullable);
+        assert!(column.unique);
+
+        let inferred = ColumnSpec::parse("author_id:references").unwrap();
+        assert_eq!(inferred.kind, ColumnKind::References("authors".to_string()));
+
+        let nullable_reference = ColumnSpec::parse("editor_id:references:null").unwrap();
+        assert_eq!(
+            nullable_reference.kind,
+            ColumnKind::References("editors".to_string())
+        );
+        assert!(nullable_reference.nullable);
+
+        for (spec, table) in [
+            ("category_id:references", "categories"),
+            ("person_id:references", "people"),
+            ("sales_person_id:references", "sales_people"),
+            ("child_id:references", "children"),
+            ("address_id:references", "addresses"),
+        ] {
+            assert_eq!(
+                ColumnSpec::parse(spec).unwrap().kind,
+                ColumnKind::References(table.to_string())
+            );
+        }
+
+        assert!(ColumnSpec::parse("title").is_err());
+        assert!(ColumnSpec::parse("title:sometype").is_err());
+        assert!(ColumnSpec::parse("title:text:indexed").is_err());
+        assert!(parse_column_specs(&specs(&["title:text", "title:string"])).is_err());
+        assert!(parse_column_specs(&specs(&["id:integer"])).is_err());
+    }
+
+    #[test]
+    fn test_build_postgres_migration() {
+        let columns = parse_column_specs(&specs(&[
+            "title:text",
+            "body:text",
+            "published:boolean",
+            "author_id:references:users",
+        ]))
+        .unwrap();
+
+        let migration =
+            build_migration_from_columns("posts", &columns, &DatabaseType::Postgres).unwrap();
+
+        assert_eq!(
+            migration.up_sql_contents,
+            "CREATE TABLE posts (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    published BOOLEAN NOT NULL,
+    author_id INTEGER NOT NULL,
+    FOREIGN KEY (author_id) REFERENCES users (id)
+);
+"
+        );
+        assert_eq!(
+            migration.rust_struct_contents,
+            "pub struct Posts {
+    pub id: i32,
+    pub title: String,
+    pub body: String,
+    pub published: bool,
+    pub author_id: i32,
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_build_mysql_migration() {
+        let columns = parse_column_specs(&specs(&["name:string:unique", "price:decimal:null"])).unwrap();
+
+        let migration = build_migration_from_columns("products", &columns, &DatabaseType::Mysql).unwrap();
+
+        assert!(migration.up_sql_contents.contains("id INT AUTO_INCREMENT PRIMARY KEY"));
+        assert!(migration.up_sql_contents.contains("name VARCHAR(255) NOT NULL UNIQUE"));
+        assert!(migration.up_sql_contents.contains("price DECIMAL(10, 2) NULL"));
+        assert!(migration.rust_struct_contents.contains("pub price: Option<sqlx::types::Decimal>,"));
+        assert!(build_migration_from_columns("products", &columns, &DatabaseType::Mongo).is_err());
+
+        // The same type as a model generated from the column
+        let price = &columns[1];
+        assert_eq!(
+            price.rust_type(&DatabaseType::Postgres),
+            format!("Option<{}>", sql_rust_type("numeric", &DatabaseType::Postgres).unwrap())
+        );
+        assert_eq!(price.rust_type(&DatabaseType::Sqlite), "Option<f64>");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_migration_is_valid_sql() {
+        let columns = parse_column_specs(&specs(&[
+            "title:string",
+            "views:bigint",
+            "rating:float:null",
+            "published_at:datetime:null",
+            "author_id:references:users",
+        ]))
+        .unwrap();
+        let migration = build_migration_from_columns("posts", &columns, &DatabaseType::Sqlite).unwrap();
+
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        pool.execute("CREATE TABLE users (id INTEGER PRIMARY KEY);")
+            .await
+            .unwrap();
+        pool.execute(migration.up_sql_contents.as_str()).await.unwrap();
+        pool.execute("INSERT INTO users (id) VALUES (1); INSERT INTO posts (title, views, author_id) VALUES ('hello', 0, 1);")
+            .await
+            .unwrap();
+    }
+}
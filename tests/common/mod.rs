@@ -0,0 +1,51 @@
+//! Helpers shared by the integration tests, included with `mod common;`.
+// Every test file uses only some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use rustyroad::database::DatabaseConnection;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+/// Writes a `<timestamp>-<name>` migration directory with its `up.sql` and `down.sql`.
+pub fn write_migration(dir: &Path, name: &str, up: &str, down: &str) {
+    let migration_dir = dir.join(name);
+    fs::create_dir_all(&migration_dir).unwrap();
+    fs::write(migration_dir.join("up.sql"), up).unwrap();
+    fs::write(migration_dir.join("down.sql"), down).unwrap();
+}
+
+/// Opens a pool to a SQLite database file, which is created when it doesn't exist.
+pub async fn sqlite_pool(path: &Path) -> SqlitePool {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .foreign_keys(true);
+    SqlitePool::connect_with(options).await.unwrap()
+}
+
+/// Opens a connection to a SQLite database file, see [`sqlite_pool`].
+pub async fn sqlite_connection(path: &Path) -> DatabaseConnection {
+    DatabaseConnection::Sqlite(Arc::new(sqlite_pool(path).await))
+}
+
+/// Opens an in-memory SQLite database, returning the pool too for queries the connection doesn't offer.
+pub async fn memory_connection() -> (SqlitePool, DatabaseConnection) {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    (pool.clone(), DatabaseConnection::Sqlite(Arc::new(pool)))
+}
+
+/// Lists the tables of a SQLite database, sorted by name.
+pub async fn table_names(connection: &DatabaseConnection) -> Vec<String> {
+    match connection {
+        DatabaseConnection::Sqlite(pool) => sqlx::query_scalar(
+            "SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name",
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .unwrap(),
+        _ => unreachable!(),
+    }
+}
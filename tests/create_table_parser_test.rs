@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::migrations::{build_migration_from_columns, ColumnSpec, ForeignKeySchema};
+    use rustyroad::database::DatabaseType;
+    use rustyroad::writers::models::{parse_create_tables, table_model_contents};
+
+    #[test]
+    fn test_postgres_constraints_defaults_and_quoted_identifiers() {
+        let sql = r#"
+CREATE TABLE IF NOT EXISTS "public"."Posts" (
+    id BIGSERIAL,
+    "Title" VARCHAR(255)
+        NOT NULL,
+    body TEXT,
+    published BOOLEAN NOT NULL DEFAULT false,
+    published_at TIMESTAMP(3) WITH TIME ZONE,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    author_id INTEGER NOT NULL,
+    CONSTRAINT posts_pkey PRIMARY KEY (id),
+    CONSTRAINT posts_author_fk FOREIGN KEY (author_id) REFERENCES users (id) ON DELETE CASCADE,
+    CHECK (char_length("Title") > 0)
+);
+CREATE INDEX posts_author_id_index ON "Posts" (author_id);
+"#;
+        let tables = parse_create_tables(sql, &DatabaseType::Postgres).unwrap();
+        assert_eq!(tables.len(), 1);
+        let posts = &tables[0];
+        assert_eq!(posts.name, "Posts");
+        assert_eq!(posts.primary_key, vec!["id"]);
+        assert_eq!(
+            posts.foreign_keys,
+            vec![ForeignKeySchema {
+                columns: vec!["author_id".to_string()],
+                references_table: "users".to_string(),
+                references_columns: vec!["id".to_string()],
+            }]
+        );
+        assert_eq!(posts.columns[1].name, "Title");
+        assert_eq!(posts.columns[3].default.as_deref(), Some("false"));
+
+        let contents = table_model_contents("post", posts, &DatabaseType::Postgres);
+        assert!(contents.contains("#[table_name = \"Posts\"]\npub struct Post {\n"));
+        assert!(contents.contains("    pub id: Option<i64>,\n"));
+        assert!(contents.contains("    #[sqlx(rename = \"Title\")]\n    #[validate(length(max = 255))]\n    pub title: String,\n"));
+        assert!(contents.contains("    pub body: Option<String>,\n"));
+        assert!(contents.contains("    /// Defaults to `false`\n    pub published: bool,\n"));
+        assert!(contents.contains("    pub published_at: Option<chrono::DateTime<chrono::Utc>>,\n"));
+        assert!(contents.contains("    pub tags: Vec<String>,\n"));
+        assert!(contents.contains("    /// References `users`.`id`\n    pub author_id: i32,\n"));
+    }
+
+    #[test]
+    fn test_mysql_auto_increment_and_unsigned_columns() {
+        let sql = "CREATE TABLE `orders` (
+  `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+  `total` DECIMAL(10,2) NOT NULL DEFAULT 0.00,
+  `status` ENUM('pending','paid') NOT NULL,
+  `paid` TINYINT(1),
+  `customer_id` BIGINT NOT NULL REFERENCES customers(id),
+  PRIMARY KEY (`id`),
+  KEY `orders_status_index` (`status`)
+) ENGINE=InnoDB;";
+        let tables = parse_create_tables(sql, &DatabaseType::Mysql).unwrap();
+        let orders = &tables[0];
+        assert_eq!(orders.name, "orders");
+        assert!(orders.columns[0].auto_increment);
+        assert_eq!(orders.foreign_keys[0].references_table, "customers");
+
+        let contents = table_model_contents("order", orders, &DatabaseType::Mysql);
+        assert!(contents.contains("    pub id: Option<u32>,\n"));
+        assert!(contents.contains("    pub total: sqlx::types::Decimal,\n"));
+        assert!(contents.contains("    pub status: String,\n"));
+        assert!(contents.contains("    pub paid: Option<bool>,\n"));
+        assert!(contents.contains("    pub customer_id: i64,\n"));
+    }
+
+    #[test]
+    fn test_sqlite_composite_keys_and_several_tables() {
+        let sql = r#"
+CREATE TABLE tags (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE);
+CREATE TABLE "post tags" (
+    post_id INTEGER,
+    tag_id INTEGER REFERENCES tags (id),
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (post_id, tag_id)
+);
+"#;
+        let tables = parse_create_tables(sql, &DatabaseType::Sqlite).unwrap();
+        assert_eq!(tables.len(), 2);
+        assert!(tables[0].columns[0].auto_increment);
+
+        let post_tags = &tables[1];
+        assert_eq!(post_tags.name, "post tags");
+        assert_eq!(post_tags.primary_key, vec!["post_id", "tag_id"]);
+        // Columns of the primary key are never NULL
+        assert!(!post_tags.columns[0].nullable);
+        assert!(!post_tags.columns[1].nullable);
+        assert!(post_tags.columns[2].nullable);
+
+        let contents = table_model_contents("post_tag", post_tags, &DatabaseType::Sqlite);
+        assert!(contents.contains("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]\npub struct PostTag {\n"));
+        assert!(contents.contains("    pub post_id: i64,\n"));
+        assert!(contents.contains("    pub created_at: Option<chrono::NaiveDateTime>,\n"));
+    }
+
+    #[test]
+    fn test_generated_migrations_round_trip() {
+        let columns: Vec<ColumnSpec> = ["title:string", "views:integer:null", "author_id:references:users"]
+            .iter()
+            .map(|spec| ColumnSpec::parse(spec).unwrap())
+            .collect();
+        for database_type in [DatabaseType::Postgres, DatabaseType::Mysql, DatabaseType::Sqlite] {
+            let migration = build_migration_from_columns("posts", &columns, &database_type).unwrap();
+            let tables = parse_create_tables(&migration.up_sql_contents, &database_type).unwrap();
+            let posts = &tables[0];
+            assert_eq!(posts.name, "posts");
+            assert_eq!(posts.primary_key, vec!["id"]);
+            assert!(posts.columns[0].auto_increment, "{:?}", database_type);
+            let nullable: Vec<bool> = posts.columns.iter().map(|column| column.nullable).collect();
+            assert_eq!(nullable, vec![false, false, true, false], "{:?}", database_type);
+            assert_eq!(posts.foreign_keys[0].references_table, "users");
+        }
+    }
+
+    #[test]
+    fn test_invalid_sql_is_an_error() {
+        assert!(parse_create_tables("CREATE TABLE posts (id INTEGER", &DatabaseType::Postgres).is_err());
+        assert!(parse_create_tables("DROP TABLE posts;", &DatabaseType::Postgres)
+            .unwrap()
+            .is_empty());
+        assert!(parse_create_tables("", &DatabaseType::Mongo).is_err());
+    }
+}
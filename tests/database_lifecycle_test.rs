@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rustyroad::database::{
+        create_database, database_exists, drop_database, sqlite_database_path, Database,
+        DatabaseError,
+    };
+
+    fn sqlite_database(name: &str) -> Database {
+        Database::new(
+            name.to_string(),
+            String::new(),
+            String::new(),
+            "localhost".to_string(),
+            0,
+            "sqlite",
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_create_and_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = sqlite_database(dir.path().join("blog").to_str().unwrap());
+        let path = sqlite_database_path(&database);
+        assert_eq!(path, dir.path().join("blog.db"));
+
+        assert!(!database_exists(&database).await.unwrap());
+        assert!(create_database(&database).await.unwrap());
+        assert!(path.is_file());
+        // Creating it again keeps the existing file
+        assert!(!create_database(&database).await.unwrap());
+
+        fs::write(dir.path().join("blog.db-wal"), "").unwrap();
+        assert!(drop_database(&database).await.unwrap());
+        assert!(!path.exists());
+        assert!(!dir.path().join("blog.db-wal").exists());
+        assert!(!drop_database(&database).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mongo_server_down_is_transient() {
+        // Nothing listens on port 1, so selecting a server times out
+        let mut database = Database::new(
+            "blog".to_string(),
+            String::new(),
+            String::new(),
+            "127.0.0.1".to_string(),
+            1,
+            "mongo",
+        )
+        .unwrap();
+        database.pool.acquire_timeout = std::time::Duration::from_millis(200);
+        let error = create_database(&database).await.unwrap_err();
+        assert!(matches!(error, DatabaseError::ConnectionRefused(_)));
+        assert!(error.is_transient());
+    }
+}
@@ -0,0 +1,117 @@
+use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `posts` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "posts"]
+pub struct Post {
+    pub id: Option<i64>,
+    pub title: String,
+    /// References `users`.`id`
+    pub author_id: i64,
+}
+
+/// The columns of a new `Post`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPost {
+    pub title: String,
+    pub author_id: i64,
+}
+
+/// The columns to change on a `Post`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePost {
+    pub title: Option<String>,
+    pub author_id: Option<i64>,
+}
+
+impl From<NewPost> for Post {
+    fn from(new_post: NewPost) -> Self {
+        Post {
+            id: None,
+            title: new_post.title,
+            author_id: new_post.author_id,
+        }
+    }
+}
+
+impl Post {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_post<P: QueryPool + Sync>(pool: &P, new_post: NewPost) -> Result<Post, DatabaseError> {
+        let post = Post::from(new_post);
+        post.validate(pool).await?;
+        post.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_post<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Post>, DatabaseError> {
+        Post::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_posts<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Post>, DatabaseError> {
+        Post::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_post<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdatePost,
+    ) -> Result<Option<Post>, DatabaseError> {
+        let Some(mut post) = Post::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.title {
+            post.title = value;
+        }
+        if let Some(value) = changes.author_id {
+            post.author_id = value;
+        }
+        post.validate(pool).await?;
+        post.update(pool).await?;
+        Ok(Some(post))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_post<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Post::find(pool, id).await? {
+            Some(post) => post.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
+
+/// The associations of `Post`.
+impl Post {
+    /// The `users` row `author_id` points to.
+    pub async fn author<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<super::user::User>, DatabaseError> {
+        self.belongs_to(pool, "author_id").await
+    }
+
+    /// The `author` of every row, read with one query.
+    pub async fn preload_author<P: QueryPool + Sync>(pool: &P, rows: &[Post]) -> Result<Vec<Option<super::user::User>>, DatabaseError> {
+        Post::preload_belongs_to(pool, rows, "author_id").await
+    }
+
+    /// The `tags` rows linked to this row through `posts_tags`.
+    pub async fn tags<P: QueryPool + Sync>(&self, pool: &P) -> Result<Vec<super::tag::Tag>, DatabaseError> {
+        self.many_to_many(pool, "posts_tags", "post_id", "tag_id").await
+    }
+
+    /// Links a `tags` row to this row.
+    pub async fn attach_tag<P: QueryPool + Sync>(&self, pool: &P, tag: &super::tag::Tag) -> Result<(), DatabaseError> {
+        self.attach(pool, tag, "posts_tags", "post_id", "tag_id").await
+    }
+
+    /// Unlinks a `tags` row from this row, returns whether they were linked.
+    pub async fn detach_tag<P: QueryPool + Sync>(&self, pool: &P, tag: &super::tag::Tag) -> Result<bool, DatabaseError> {
+        self.detach(pool, tag, "posts_tags", "post_id", "tag_id").await
+    }
+
+    /// The `tags` of every row, read with one query.
+    pub async fn preload_tags<P: QueryPool + Sync>(pool: &P, rows: &[Post]) -> Result<Vec<Vec<super::tag::Tag>>, DatabaseError> {
+        Post::preload_many_to_many(pool, rows, "posts_tags", "post_id", "tag_id").await
+    }
+}
@@ -0,0 +1,97 @@
+use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `profiles` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "profiles"]
+pub struct Profile {
+    pub id: Option<i64>,
+    pub bio: String,
+    /// References `users`.`id`
+    pub user_id: i64,
+}
+
+/// The columns of a new `Profile`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewProfile {
+    pub bio: String,
+    pub user_id: i64,
+}
+
+/// The columns to change on a `Profile`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProfile {
+    pub bio: Option<String>,
+    pub user_id: Option<i64>,
+}
+
+impl From<NewProfile> for Profile {
+    fn from(new_profile: NewProfile) -> Self {
+        Profile {
+            id: None,
+            bio: new_profile.bio,
+            user_id: new_profile.user_id,
+        }
+    }
+}
+
+impl Profile {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_profile<P: QueryPool + Sync>(pool: &P, new_profile: NewProfile) -> Result<Profile, DatabaseError> {
+        let profile = Profile::from(new_profile);
+        profile.validate(pool).await?;
+        profile.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_profile<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Profile>, DatabaseError> {
+        Profile::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_profiles<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Profile>, DatabaseError> {
+        Profile::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_profile<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdateProfile,
+    ) -> Result<Option<Profile>, DatabaseError> {
+        let Some(mut profile) = Profile::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.bio {
+            profile.bio = value;
+        }
+        if let Some(value) = changes.user_id {
+            profile.user_id = value;
+        }
+        profile.validate(pool).await?;
+        profile.update(pool).await?;
+        Ok(Some(profile))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_profile<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Profile::find(pool, id).await? {
+            Some(profile) => profile.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
+
+/// The associations of `Profile`.
+impl Profile {
+    /// The `users` row `user_id` points to.
+    pub async fn user<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<super::user::User>, DatabaseError> {
+        self.belongs_to(pool, "user_id").await
+    }
+
+    /// The `user` of every row, read with one query.
+    pub async fn preload_user<P: QueryPool + Sync>(pool: &P, rows: &[Profile]) -> Result<Vec<Option<super::user::User>>, DatabaseError> {
+        Profile::preload_belongs_to(pool, rows, "user_id").await
+    }
+}
@@ -0,0 +1,99 @@
+use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `tags` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "tags"]
+pub struct Tag {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+/// The columns of a new `Tag`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTag {
+    pub name: String,
+}
+
+/// The columns to change on a `Tag`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateTag {
+    pub name: Option<String>,
+}
+
+impl From<NewTag> for Tag {
+    fn from(new_tag: NewTag) -> Self {
+        Tag {
+            id: None,
+            name: new_tag.name,
+        }
+    }
+}
+
+impl Tag {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_tag<P: QueryPool + Sync>(pool: &P, new_tag: NewTag) -> Result<Tag, DatabaseError> {
+        let tag = Tag::from(new_tag);
+        tag.validate(pool).await?;
+        tag.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_tag<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Tag>, DatabaseError> {
+        Tag::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_tags<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Tag>, DatabaseError> {
+        Tag::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_tag<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdateTag,
+    ) -> Result<Option<Tag>, DatabaseError> {
+        let Some(mut tag) = Tag::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.name {
+            tag.name = value;
+        }
+        tag.validate(pool).await?;
+        tag.update(pool).await?;
+        Ok(Some(tag))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_tag<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Tag::find(pool, id).await? {
+            Some(tag) => tag.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
+
+/// The associations of `Tag`.
+impl Tag {
+    /// The `posts` rows linked to this row through `posts_tags`.
+    pub async fn posts<P: QueryPool + Sync>(&self, pool: &P) -> Result<Vec<super::post::Post>, DatabaseError> {
+        self.many_to_many(pool, "posts_tags", "tag_id", "post_id").await
+    }
+
+    /// Links a `posts` row to this row.
+    pub async fn attach_post<P: QueryPool + Sync>(&self, pool: &P, post: &super::post::Post) -> Result<(), DatabaseError> {
+        self.attach(pool, post, "posts_tags", "tag_id", "post_id").await
+    }
+
+    /// Unlinks a `posts` row from this row, returns whether they were linked.
+    pub async fn detach_post<P: QueryPool + Sync>(&self, pool: &P, post: &super::post::Post) -> Result<bool, DatabaseError> {
+        self.detach(pool, post, "posts_tags", "tag_id", "post_id").await
+    }
+
+    /// The `posts` of every row, read with one query.
+    pub async fn preload_posts<P: QueryPool + Sync>(pool: &P, rows: &[Tag]) -> Result<Vec<Vec<super::post::Post>>, DatabaseError> {
+        Tag::preload_many_to_many(pool, rows, "posts_tags", "tag_id", "post_id").await
+    }
+}
@@ -0,0 +1,99 @@
+use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `users` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "users"]
+pub struct User {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+/// The columns of a new `User`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewUser {
+    pub name: String,
+}
+
+/// The columns to change on a `User`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateUser {
+    pub name: Option<String>,
+}
+
+impl From<NewUser> for User {
+    fn from(new_user: NewUser) -> Self {
+        User {
+            id: None,
+            name: new_user.name,
+        }
+    }
+}
+
+impl User {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_user<P: QueryPool + Sync>(pool: &P, new_user: NewUser) -> Result<User, DatabaseError> {
+        let user = User::from(new_user);
+        user.validate(pool).await?;
+        user.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_user<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<User>, DatabaseError> {
+        User::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_users<P: QueryPool + Sync>(pool: &P) -> Result<Vec<User>, DatabaseError> {
+        User::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_user<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdateUser,
+    ) -> Result<Option<User>, DatabaseError> {
+        let Some(mut user) = User::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.name {
+            user.name = value;
+        }
+        user.validate(pool).await?;
+        user.update(pool).await?;
+        Ok(Some(user))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_user<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match User::find(pool, id).await? {
+            Some(user) => user.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
+
+/// The associations of `User`.
+impl User {
+    /// The `posts` rows whose `author_id` points to this row.
+    pub async fn posts<P: QueryPool + Sync>(&self, pool: &P) -> Result<Vec<super::post::Post>, DatabaseError> {
+        self.has_many(pool, "author_id").await
+    }
+
+    /// The `posts` of every row, read with one query.
+    pub async fn preload_posts<P: QueryPool + Sync>(pool: &P, rows: &[User]) -> Result<Vec<Vec<super::post::Post>>, DatabaseError> {
+        User::preload_has_many(pool, rows, "author_id").await
+    }
+
+    /// The `profiles` row whose `user_id` points to this row.
+    pub async fn profile<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<super::profile::Profile>, DatabaseError> {
+        self.has_one(pool, "user_id").await
+    }
+
+    /// The `profile` of every row, read with one query.
+    pub async fn preload_profile<P: QueryPool + Sync>(pool: &P, rows: &[User]) -> Result<Vec<Option<super::profile::Profile>>, DatabaseError> {
+        User::preload_has_one(pool, rows, "user_id").await
+    }
+}
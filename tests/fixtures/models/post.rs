@@ -0,0 +1,117 @@
+use rustyroad::database::{DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `posts` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "posts"]
+pub struct Post {
+    pub id: Option<i64>,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Defaults to `0`
+    pub views: i64,
+    pub r#type: Option<String>,
+    pub published_at: Option<chrono::NaiveDateTime>,
+    #[validate(length(max = 20), unique)]
+    pub slug: Option<String>,
+}
+
+/// The columns of a new `Post`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPost {
+    pub title: String,
+    pub summary: Option<String>,
+    pub views: i64,
+    pub r#type: Option<String>,
+    pub published_at: Option<chrono::NaiveDateTime>,
+    pub slug: Option<String>,
+}
+
+/// The columns to change on a `Post`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePost {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub summary: Option<Option<String>>,
+    pub views: Option<i64>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub r#type: Option<Option<String>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub published_at: Option<Option<chrono::NaiveDateTime>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub slug: Option<Option<String>>,
+}
+
+impl From<NewPost> for Post {
+    fn from(new_post: NewPost) -> Self {
+        Post {
+            id: None,
+            title: new_post.title,
+            summary: new_post.summary,
+            views: new_post.views,
+            r#type: new_post.r#type,
+            published_at: new_post.published_at,
+            slug: new_post.slug,
+        }
+    }
+}
+
+impl Post {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_post<P: QueryPool + Sync>(pool: &P, new_post: NewPost) -> Result<Post, DatabaseError> {
+        let post = Post::from(new_post);
+        post.validate(pool).await?;
+        post.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_post<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Post>, DatabaseError> {
+        Post::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_posts<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Post>, DatabaseError> {
+        Post::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_post<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdatePost,
+    ) -> Result<Option<Post>, DatabaseError> {
+        let Some(mut post) = Post::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.title {
+            post.title = value;
+        }
+        if let Some(value) = changes.summary {
+            post.summary = value;
+        }
+        if let Some(value) = changes.views {
+            post.views = value;
+        }
+        if let Some(value) = changes.r#type {
+            post.r#type = value;
+        }
+        if let Some(value) = changes.published_at {
+            post.published_at = value;
+        }
+        if let Some(value) = changes.slug {
+            post.slug = value;
+        }
+        post.validate(pool).await?;
+        post.update(pool).await?;
+        Ok(Some(post))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_post<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Post::find(pool, id).await? {
+            Some(post) => post.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use rustyroad::database::{DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `readings` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "readings"]
+pub struct Reading {
+    pub id: Option<u32>,
+    pub views: u64,
+    /// Needs the `rust_decimal` feature of sqlx
+    pub price: Option<sqlx::types::Decimal>,
+    #[validate(length(max = 80))]
+    pub name: String,
+    pub published: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// The columns of a new `Reading`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewReading {
+    pub views: u64,
+    pub price: Option<sqlx::types::Decimal>,
+    pub name: String,
+    pub published: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// The columns to change on a `Reading`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateReading {
+    pub views: Option<u64>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub price: Option<Option<sqlx::types::Decimal>>,
+    pub name: Option<String>,
+    pub published: Option<bool>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub created_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+}
+
+impl From<NewReading> for Reading {
+    fn from(new_reading: NewReading) -> Self {
+        Reading {
+            id: None,
+            views: new_reading.views,
+            price: new_reading.price,
+            name: new_reading.name,
+            published: new_reading.published,
+            created_at: new_reading.created_at,
+        }
+    }
+}
+
+impl Reading {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_reading<P: QueryPool + Sync>(pool: &P, new_reading: NewReading) -> Result<Reading, DatabaseError> {
+        let reading = Reading::from(new_reading);
+        reading.validate(pool).await?;
+        reading.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_reading<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Reading>, DatabaseError> {
+        Reading::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_readings<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Reading>, DatabaseError> {
+        Reading::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_reading<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdateReading,
+    ) -> Result<Option<Reading>, DatabaseError> {
+        let Some(mut reading) = Reading::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.views {
+            reading.views = value;
+        }
+        if let Some(value) = changes.price {
+            reading.price = value;
+        }
+        if let Some(value) = changes.name {
+            reading.name = value;
+        }
+        if let Some(value) = changes.published {
+            reading.published = value;
+        }
+        if let Some(value) = changes.created_at {
+            reading.created_at = value;
+        }
+        reading.validate(pool).await?;
+        reading.update(pool).await?;
+        Ok(Some(reading))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_reading<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Reading::find(pool, id).await? {
+            Some(reading) => reading.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use rustyroad::database::{DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `readings` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = "readings"]
+pub struct Reading {
+    pub id: Option<i32>,
+    #[validate(length(max = 80))]
+    pub name: String,
+    /// Needs the `rust_decimal` feature of sqlx
+    pub price: Option<sqlx::types::Decimal>,
+    pub score: f64,
+    pub tags: Vec<String>,
+    pub counts: Option<Vec<i32>>,
+    pub taken_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub token: Option<sqlx::types::Uuid>,
+    pub data: Option<serde_json::Value>,
+    /// sqlx has no Rust type for `bytea[]`, cast the column to text or change this type
+    pub blobs: Option<String>,
+}
+
+/// The columns of a new `Reading`, e.g. the body of a create request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewReading {
+    pub name: String,
+    pub price: Option<sqlx::types::Decimal>,
+    pub score: f64,
+    pub tags: Vec<String>,
+    pub counts: Option<Vec<i32>>,
+    pub taken_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub token: Option<sqlx::types::Uuid>,
+    pub data: Option<serde_json::Value>,
+    pub blobs: Option<String>,
+}
+
+/// The columns to change on a `Reading`, the ones left out keep their value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateReading {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub price: Option<Option<sqlx::types::Decimal>>,
+    pub score: Option<f64>,
+    pub tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub counts: Option<Option<Vec<i32>>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub taken_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub token: Option<Option<sqlx::types::Uuid>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub data: Option<Option<serde_json::Value>>,
+    #[serde(default, deserialize_with = "rustyroad::database::deserialize_some")]
+    pub blobs: Option<Option<String>>,
+}
+
+impl From<NewReading> for Reading {
+    fn from(new_reading: NewReading) -> Self {
+        Reading {
+            id: None,
+            name: new_reading.name,
+            price: new_reading.price,
+            score: new_reading.score,
+            tags: new_reading.tags,
+            counts: new_reading.counts,
+            taken_at: new_reading.taken_at,
+            token: new_reading.token,
+            data: new_reading.data,
+            blobs: new_reading.blobs,
+        }
+    }
+}
+
+impl Reading {
+    /// Inserts a new row once it's valid and returns it as stored.
+    pub async fn create_reading<P: QueryPool + Sync>(pool: &P, new_reading: NewReading) -> Result<Reading, DatabaseError> {
+        let reading = Reading::from(new_reading);
+        reading.validate(pool).await?;
+        reading.create(pool).await
+    }
+
+    /// Returns the row with this primary key, if it exists.
+    pub async fn get_reading<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<Option<Reading>, DatabaseError> {
+        Reading::find(pool, id).await
+    }
+
+    /// Returns every row, ordered by primary key.
+    pub async fn get_all_readings<P: QueryPool + Sync>(pool: &P) -> Result<Vec<Reading>, DatabaseError> {
+        Reading::all(pool).await
+    }
+
+    /// Saves the changes to the row with this primary key once they're valid, returns the row or `None` when it doesn't exist.
+    pub async fn update_reading<P: QueryPool + Sync>(
+        pool: &P,
+        id: impl Into<SqlValue>,
+        changes: UpdateReading,
+    ) -> Result<Option<Reading>, DatabaseError> {
+        let Some(mut reading) = Reading::find(pool, id).await? else {
+            return Ok(None);
+        };
+        if let Some(value) = changes.name {
+            reading.name = value;
+        }
+        if let Some(value) = changes.price {
+            reading.price = value;
+        }
+        if let Some(value) = changes.score {
+            reading.score = value;
+        }
+        if let Some(value) = changes.tags {
+            reading.tags = value;
+        }
+        if let Some(value) = changes.counts {
+            reading.counts = value;
+        }
+        if let Some(value) = changes.taken_at {
+            reading.taken_at = value;
+        }
+        if let Some(value) = changes.token {
+            reading.token = value;
+        }
+        if let Some(value) = changes.data {
+            reading.data = value;
+        }
+        if let Some(value) = changes.blobs {
+            reading.blobs = value;
+        }
+        reading.validate(pool).await?;
+        reading.update(pool).await?;
+        Ok(Some(reading))
+    }
+
+    /// Deletes the row with this primary key, returns whether it existed.
+    pub async fn delete_reading<P: QueryPool + Sync>(pool: &P, id: impl Into<SqlValue>) -> Result<bool, DatabaseError> {
+        match Reading::find(pool, id).await? {
+            Some(reading) => reading.destroy(pool).await,
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use rustyroad::database::{create_database, Database, DatabaseConnection};
+    use rustyroad::writers::{
+        main_rs_contents, write_to_new_delete_controller, write_to_new_get_all_controller,
+        write_to_new_post_controller, write_to_new_update_controller,
+    };
+    use std::collections::BTreeSet;
+    use std::fs;
+
+    /// The model the controllers call, compiled with the tests
+    mod post {
+        include!("fixtures/models/post.rs");
+    }
+    use post::{NewPost, Post, UpdatePost};
+
+    /// The `Data<...>` extractors of the handlers
+    fn data_types(contents: &str) -> BTreeSet<String> {
+        Regex::new(r"(?:pool|tmpl): Data<(\w+)>")
+            .unwrap()
+            .captures_iter(contents)
+            .map(|captures| captures[1].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_controllers_take_the_data_main_registers() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        fs::create_dir_all("src/models").unwrap();
+        fs::create_dir_all("src/controllers").unwrap();
+        fs::write("src/main.rs", main_rs_contents()).unwrap();
+        fs::write("src/models/post.rs", include_str!("fixtures/models/post.rs")).unwrap();
+        fs::write("src/controllers/post.rs", "").unwrap();
+
+        write_to_new_get_all_controller("post".to_string()).unwrap();
+        write_to_new_post_controller("post".to_string()).unwrap();
+        write_to_new_update_controller("post".to_string()).unwrap();
+        write_to_new_delete_controller("post".to_string()).unwrap();
+        let main = fs::read_to_string("src/main.rs").unwrap();
+        let controller = fs::read_to_string("src/controllers/post.rs").unwrap();
+        std::env::set_current_dir(backup_dir).unwrap();
+
+        // main.rs registers the pool of `Database::shared_pool` and the Tera views
+        assert!(main.contains("let pool = database.shared_pool().await"));
+        assert!(main.contains(".app_data(pool.clone())"));
+        assert!(main.contains(".app_data(web::Data::new(tera.clone()))"));
+        for handler in ["get_all_posts", "create_post", "update_post", "delete_post"] {
+            assert!(main.contains(&format!(".service(controllers::post::{})", handler)));
+        }
+
+        assert_eq!(
+            data_types(&controller),
+            BTreeSet::from(["DatabaseConnection".to_string(), "Tera".to_string()])
+        );
+        assert!(!controller.contains("PoolConnection"));
+        assert!(Regex::new(r"use rustyroad::database::\{[^}]*DatabaseConnection")
+            .unwrap()
+            .is_match(&controller));
+    }
+
+    #[tokio::test]
+    async fn test_model_functions_take_the_registered_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = Database::new(
+            dir.path().join("blog").to_str().unwrap().to_string(),
+            String::new(),
+            String::new(),
+            "localhost".to_string(),
+            0,
+            "sqlite",
+        )
+        .unwrap();
+        create_database(&database).await.unwrap();
+
+        // What main.rs registers, and the handlers get with `pool.get_ref()`
+        let pool: DatabaseConnection = database.shared_pool().await.unwrap();
+        sqlx::query(
+            "CREATE TABLE posts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                summary TEXT,
+                views INTEGER NOT NULL DEFAULT 0,
+                type TEXT,
+                published_at DATETIME,
+                slug VARCHAR(20) UNIQUE
+            )",
+        )
+        .execute(pool.as_sqlite().unwrap())
+        .await
+        .unwrap();
+
+        let new_post: NewPost = serde_json::from_str(r#"{"title": "Hello", "views": 0}"#).unwrap();
+        let post = Post::create_post(&pool, new_post).await.unwrap();
+        assert_eq!(Post::get_all_posts(&pool).await.unwrap().len(), 1);
+        assert!(Post::get_post(&pool, post.id).await.unwrap().is_some());
+        let changes: UpdatePost = serde_json::from_str(r#"{"views": 3}"#).unwrap();
+        let post = Post::update_post(&pool, post.id.unwrap() as i32, changes).await.unwrap().unwrap();
+        assert_eq!(post.views, 3);
+        assert!(Post::delete_post(&pool, post.id.unwrap() as i32).await.unwrap());
+    }
+}
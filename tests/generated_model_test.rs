@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::{DatabaseError, DatabaseType, PoolConnection};
+    use rustyroad::writers::models::{parse_create_tables, table_model_contents};
+    use sqlx::sqlite::SqlitePool;
+
+    /// The model `rustyroad generate model post` writes for [`MIGRATION`], compiled with the tests
+    mod post {
+        include!("fixtures/models/post.rs");
+    }
+    use post::{NewPost, Post, UpdatePost};
+
+    const MIGRATION: &str = "CREATE TABLE posts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    summary TEXT,
+    views INTEGER NOT NULL DEFAULT 0,
+    type TEXT,
+    published_at DATETIME,
+    slug VARCHAR(20) UNIQUE
+);";
+
+    async fn pool() -> PoolConnection {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(MIGRATION).execute(&pool).await.unwrap();
+        PoolConnection::Sqlite(pool)
+    }
+
+    #[test]
+    fn test_fixture_is_the_generated_model() {
+        let tables = parse_create_tables(MIGRATION, &DatabaseType::Sqlite).unwrap();
+        let contents = table_model_contents("post", &tables[0], &DatabaseType::Sqlite);
+        let fixture = include_str!("fixtures/models/post.rs").replace("\r\n", "\n");
+        assert_eq!(contents, fixture);
+    }
+
+    #[tokio::test]
+    async fn test_generated_crud_functions() {
+        let pool = pool().await;
+
+        let new_post: NewPost = serde_json::from_str(r#"{"title": "Hello", "summary": "First", "views": 1}"#).unwrap();
+        let post = Post::create_post(&pool, new_post).await.unwrap();
+        assert_eq!(post.id, Some(1));
+        assert_eq!(post.summary.as_deref(), Some("First"));
+        assert_eq!(post.r#type, None);
+
+        assert_eq!(Post::get_post(&pool, 1).await.unwrap().unwrap().title, "Hello");
+        assert!(Post::get_post(&pool, 2).await.unwrap().is_none());
+        assert_eq!(Post::get_all_posts(&pool).await.unwrap().len(), 1);
+
+        // Fields left out keep their value, null clears a nullable column
+        let changes: UpdatePost = serde_json::from_str(r#"{"views": 2, "summary": null}"#).unwrap();
+        let post = Post::update_post(&pool, 1, changes).await.unwrap().unwrap();
+        assert_eq!(post.title, "Hello");
+        assert_eq!(post.views, 2);
+        assert_eq!(post.summary, None);
+        assert_eq!(Post::get_post(&pool, 1).await.unwrap().unwrap().views, 2);
+        assert!(Post::update_post(&pool, 2, UpdatePost::default()).await.unwrap().is_none());
+
+        assert!(Post::delete_post(&pool, 1).await.unwrap());
+        assert!(!Post::delete_post(&pool, 1).await.unwrap());
+        assert!(Post::get_all_posts(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generated_validations() {
+        let pool = pool().await;
+        let new_post = |slug: &str| -> NewPost {
+            serde_json::from_str(&format!(r#"{{"title": "Hello", "views": 0, "slug": "{}"}}"#, slug)).unwrap()
+        };
+        let hello = Post::create_post(&pool, new_post("hello")).await.unwrap();
+
+        // VARCHAR(20) UNIQUE is `length(max = 20)` and `unique`, checked before the INSERT
+        let Err(DatabaseError::Validation(errors)) = Post::create_post(&pool, new_post("hello")).await else {
+            panic!("a second post with the slug hello was created");
+        };
+        assert_eq!(errors.get("slug"), ["has already been taken"]);
+        let Err(DatabaseError::Validation(errors)) = Post::create_post(&pool, new_post(&"a".repeat(21))).await else {
+            panic!("a slug longer than VARCHAR(20) was accepted");
+        };
+        assert_eq!(errors.get("slug"), ["is too long (maximum is 20 characters)"]);
+        assert_eq!(Post::get_all_posts(&pool).await.unwrap().len(), 1);
+
+        // A row keeps its own value, and NULL is never taken
+        let changes: UpdatePost = serde_json::from_str(r#"{"slug": "hello", "views": 1}"#).unwrap();
+        assert!(Post::update_post(&pool, hello.id.unwrap(), changes).await.unwrap().is_some());
+        let world: NewPost = serde_json::from_str(r#"{"title": "World", "views": 0, "slug": null}"#).unwrap();
+        let world = Post::create_post(&pool, world).await.unwrap();
+        let changes: UpdatePost = serde_json::from_str(r#"{"slug": "hello"}"#).unwrap();
+        assert!(matches!(
+            Post::update_post(&pool, world.id.unwrap(), changes).await,
+            Err(DatabaseError::Validation(_))
+        ));
+        assert_eq!(Post::get_post(&pool, world.id.unwrap()).await.unwrap().unwrap().slug, None);
+    }
+}
@@ -16,7 +16,8 @@ async fn test_integration_load_sql_for_new_project() {
         "localhost".to_owned(),
         3306,
         "sqlite".to_string().as_str(),
-    );
+    )
+    .unwrap();
 
     // Create a sample project
     let project = Project::create_new_project("example".to_string(), database_data).await;
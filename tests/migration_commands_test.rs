@@ -0,0 +1,172 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use rustyroad::database::migrations::{
+        get_applied_migrations, get_migration_status, redo_migration_with_connection,
+        reset_migrations_with_connection, run_all_migrations_with_connection, run_migration,
+        MigrationMode, MigrationState, DEFAULT_MIGRATION_LOCK_TIMEOUT, MIGRATIONS_DIR,
+    };
+    use rustyroad::database::{DatabaseConnection, MigrationDirection};
+
+    use crate::common::{sqlite_connection, write_migration};
+
+    async fn count_rows(connection: &DatabaseConnection, table: &str) -> i64 {
+        match connection {
+            DatabaseConnection::Sqlite(pool) => {
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+                    .fetch_one(pool.as_ref())
+                    .await
+                    .unwrap()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Two migrations where the second seeds a row, so re-running it is observable.
+    fn write_fixture(migrations_dir: &Path) {
+        write_migration(
+            migrations_dir,
+            "20240101000000-create_posts",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT);",
+            "DROP TABLE posts;",
+        );
+        write_migration(
+            migrations_dir,
+            "20240102000000-seed_posts",
+            "INSERT INTO posts (title) VALUES ('hello');",
+            "DELETE FROM posts WHERE title = 'hello';",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_status_reports_applied_pending_and_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_posts",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY);",
+            "DROP TABLE posts;",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240102000000-drop_me",
+            "SELECT 1;",
+            "SELECT 1;",
+        );
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+        fs::remove_dir_all(migrations_dir.join("20240102000000-drop_me")).unwrap();
+        write_migration(
+            &migrations_dir,
+            "20240103000000-create_comments",
+            "CREATE TABLE comments (id INTEGER PRIMARY KEY);",
+            "DROP TABLE comments;",
+        );
+
+        let statuses = get_migration_status(&connection, migrations_dir_path)
+            .await
+            .unwrap();
+        let states: Vec<_> = statuses
+            .iter()
+            .map(|status| (status.name.as_str(), status.state.clone()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("create_posts", MigrationState::Applied),
+                ("drop_me", MigrationState::Missing),
+                ("create_comments", MigrationState::Pending),
+            ]
+        );
+        assert!(statuses[0].applied_at.is_some());
+        assert!(statuses[2].applied_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_redo_latest_migration() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_fixture(&migrations_dir);
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+        redo_migration_with_connection(&connection, migrations_dir_path, None)
+            .await
+            .unwrap();
+
+        // seed_posts was rolled back and applied again, leaving a single row
+        assert_eq!(count_rows(&connection, "posts").await, 1);
+        assert_eq!(get_applied_migrations(&connection).await.unwrap().len(), 2);
+
+        let missing = redo_migration_with_connection(&connection, migrations_dir_path, Some("nope")).await;
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reset_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_fixture(&migrations_dir);
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+        reset_migrations_with_connection(&connection, migrations_dir_path)
+            .await
+            .unwrap();
+
+        assert_eq!(count_rows(&connection, "posts").await, 1);
+        assert_eq!(get_applied_migrations(&connection).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_migration_returns_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+
+        // No rustyroad.toml
+        let without_config = run_migration(
+            "create_posts".to_string(),
+            MigrationDirection::Up,
+            MigrationMode::Execute,
+            DEFAULT_MIGRATION_LOCK_TIMEOUT,
+        )
+        .await;
+
+        fs::write(
+            "rustyroad.toml",
+            "[database]\ndatabase_name = \"blog\"\ndatabase_user = \"\"\ndatabase_password = \"\"\n\
+             database_host = \"localhost\"\ndatabase_port = 0\ndatabase_type = \"sqlite\"\n\n[database.development]\n",
+        )
+        .unwrap();
+        fs::create_dir_all(MIGRATIONS_DIR).unwrap();
+        // No migration with that name
+        let without_migration = run_migration(
+            "create_posts".to_string(),
+            MigrationDirection::Up,
+            MigrationMode::Execute,
+            DEFAULT_MIGRATION_LOCK_TIMEOUT,
+        )
+        .await;
+        std::env::set_current_dir(backup_dir).unwrap();
+
+        assert!(without_config.is_err());
+        let err = without_migration.unwrap_err().to_string();
+        assert!(err.contains("Couldn't find migration directory"), "{}", err);
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rustyroad::database::migrations::{
+        migration_script, plan_migrations, run_all_migrations_with_connection,
+        schema_migrations_table_exists, MigrationMode,
+    };
+    use rustyroad::database::{DatabaseConnection, MigrationDirection};
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::Executor;
+
+    use crate::common::write_migration;
+
+    #[tokio::test]
+    async fn test_dry_run_plans_without_touching_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_users",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY);\n",
+            "DROP TABLE users;\n",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240102000000-add_index_to_users",
+            "-- rustyroad:no-transaction\nCREATE INDEX index_users_on_id ON users (id);\n",
+            "DROP INDEX index_users_on_id;\n",
+        );
+
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let connection = DatabaseConnection::Sqlite(Arc::new(pool.clone()));
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+
+        let plan = plan_migrations(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+        assert_eq!(plan.len(), 2);
+        assert!(!schema_migrations_table_exists(&connection).await.unwrap());
+
+        let script = migration_script(
+            &connection,
+            "sqlite database 'test'",
+            &plan,
+            MigrationDirection::Up,
+        )
+        .unwrap();
+        assert!(script.starts_with(
+            "-- Generated by rustyroad for the sqlite database 'test'\n-- 2 migration(s), direction: up\n"
+        ));
+        assert!(script.contains(&format!(
+            "-- 20240101000000-create_users (up)
+BEGIN;
+CREATE TABLE users (id INTEGER PRIMARY KEY);
+INSERT INTO schema_migrations (version, name, checksum) VALUES ('20240101000000', 'create_users', '{}');
+COMMIT;
+",
+            plan[0].checksum().unwrap()
+        )));
+        assert!(script.contains(
+            "-- 20240102000000-add_index_to_users (up)
+-- rustyroad:no-transaction
+CREATE INDEX index_users_on_id ON users (id);
+INSERT INTO schema_migrations"
+        ));
+
+        // Applying the script by hand leaves the database as running the migrations would
+        pool.execute(script.as_str()).await.unwrap();
+        assert!(plan_migrations(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap()
+            .is_empty());
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+
+        let rollback = plan_migrations(&connection, migrations_dir_path, MigrationDirection::Down)
+            .await
+            .unwrap();
+        let versions: Vec<&str> = rollback.iter().map(|migration| migration.version.as_str()).collect();
+        assert_eq!(versions, vec!["20240102000000", "20240101000000"]);
+        let script = migration_script(&connection, "test", &rollback, MigrationDirection::Down).unwrap();
+        assert!(script.contains("DROP INDEX index_users_on_id;\nDELETE FROM schema_migrations WHERE version = '20240102000000';\n"));
+    }
+
+    #[test]
+    fn test_output_implies_dry_run() {
+        let output = "migrations.sql".to_string();
+        assert_eq!(MigrationMode::from_flags(false, None), MigrationMode::Execute);
+        assert_eq!(
+            MigrationMode::from_flags(true, None),
+            MigrationMode::DryRun { output: None }
+        );
+        assert_eq!(
+            MigrationMode::from_flags(false, Some(&output)),
+            MigrationMode::DryRun {
+                output: Some(output.into())
+            }
+        );
+    }
+}
@@ -0,0 +1,215 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rustyroad::database::migrations::{
+        get_applied_migrations, run_all_migrations_with_connection, runs_in_transaction,
+        split_sql_statements,
+    };
+    use rustyroad::database::{DatabaseConnection, MigrationDirection};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    use crate::common::{sqlite_connection, table_names, write_migration};
+
+    #[test]
+    fn test_runs_in_transaction_marker() {
+        assert!(runs_in_transaction("CREATE TABLE posts (id INTEGER);"));
+        assert!(!runs_in_transaction(
+            "-- rustyroad:no-transaction\nCREATE INDEX CONCURRENTLY posts_id ON posts (id);"
+        ));
+        assert!(!runs_in_transaction("  -- rustyroad:no-transaction  \r\nSELECT 1;"));
+    }
+
+    #[test]
+    fn test_split_sql_statements() {
+        let sql = "-- rustyroad:no-transaction
+CREATE INDEX CONCURRENTLY index_posts_on_title ON posts (title);
+/* leading comments are left out; */ INSERT INTO posts /* ; */ (title, body) VALUES ('it''s; fine', \"a;b\");
+CREATE FUNCTION touch() RETURNS trigger AS $body$
+BEGIN
+    NEW.updated_at = now();
+    RETURN NEW;
+END;
+$body$ LANGUAGE plpgsql;
+CREATE TRIGGER count_posts AFTER INSERT ON posts
+BEGIN
+    UPDATE stats SET posts = posts + CASE WHEN NEW.published THEN 1 ELSE 0 END;
+    UPDATE stats SET updated_at = CURRENT_TIMESTAMP;
+END;
+-- the last statement has no ;
+SELECT $1 FROM posts";
+        assert_eq!(
+            split_sql_statements(sql),
+            [
+                "CREATE INDEX CONCURRENTLY index_posts_on_title ON posts (title);",
+                "INSERT INTO posts /* ; */ (title, body) VALUES ('it''s; fine', \"a;b\");",
+                "CREATE FUNCTION touch() RETURNS trigger AS $body$
+BEGIN
+    NEW.updated_at = now();
+    RETURN NEW;
+END;
+$body$ LANGUAGE plpgsql;",
+                "CREATE TRIGGER count_posts AFTER INSERT ON posts
+BEGIN
+    UPDATE stats SET posts = posts + CASE WHEN NEW.published THEN 1 ELSE 0 END;
+    UPDATE stats SET updated_at = CURRENT_TIMESTAMP;
+END;",
+                "SELECT $1 FROM posts",
+            ]
+        );
+        assert!(split_sql_statements("-- rustyroad:no-transaction\n;\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_transaction_migration_runs_statement_by_statement() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_posts",
+            "-- rustyroad:no-transaction
+CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL);
+CREATE TABLE stats (posts INTEGER NOT NULL);
+INSERT INTO stats (posts) VALUES (0);
+CREATE TRIGGER count_posts AFTER INSERT ON posts
+BEGIN
+    UPDATE stats SET posts = posts + 1;
+END;
+INSERT INTO posts (title) VALUES ('a; b');",
+            "DROP TABLE stats;
+DROP TABLE posts;",
+        );
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+
+        run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(get_applied_migrations(&connection).await.unwrap().len(), 1);
+        let posts: i64 = sqlx::query_scalar("SELECT posts FROM stats")
+            .fetch_one(connection.as_sqlite().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(posts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_rolled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_posts",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY);
+CREATE TABLE comments (id INTEGER PRIMARY KEY);
+INSERT INTO missing_table (id) VALUES (1);",
+            "DROP TABLE comments;
+DROP TABLE posts;",
+        );
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+
+        let result = run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await;
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("missing_table"), "{}", error);
+        assert!(get_applied_migrations(&connection).await.unwrap().is_empty());
+        assert_eq!(table_names(&connection).await, vec!["schema_migrations", "schema_migrations_rolled_back"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_transaction_marker_runs_outside_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_posts",
+            "-- rustyroad:no-transaction
+CREATE TABLE posts (id INTEGER PRIMARY KEY);
+INSERT INTO missing_table (id) VALUES (1);",
+            "DROP TABLE posts;",
+        );
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+
+        let result = run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await;
+
+        // The statements before the failure stay applied, but the migration isn't recorded
+        assert!(result.is_err());
+        assert!(get_applied_migrations(&connection).await.unwrap().is_empty());
+        assert_eq!(
+            table_names(&connection).await,
+            vec!["posts", "schema_migrations", "schema_migrations_rolled_back"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_sqlite_rebuild_restores_the_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        // A rebuild making a column NOT NULL, which a row breaks
+        write_migration(
+            &migrations_dir,
+            "20240101000000-change_title_type_in_posts",
+            "-- rustyroad:no-transaction
+PRAGMA foreign_keys = OFF;
+BEGIN;
+CREATE TABLE posts_rebuild (id INTEGER PRIMARY KEY, title TEXT NOT NULL);
+INSERT INTO posts_rebuild (id, title) SELECT id, title FROM posts;
+DROP TABLE posts;
+ALTER TABLE posts_rebuild RENAME TO posts;
+COMMIT;
+PRAGMA foreign_keys = ON;",
+            "",
+        );
+        // One connection, so the next queries get the one the migration ran on
+        let options = SqliteConnectOptions::new()
+            .filename(dir.path().join("test.db"))
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT);
+INSERT INTO posts (title) VALUES (NULL);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let connection = DatabaseConnection::Sqlite(Arc::new(pool.clone()));
+
+        let result = run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await;
+
+        assert!(result.unwrap_err().to_string().contains("NOT NULL"));
+        assert!(get_applied_migrations(&connection).await.unwrap().is_empty());
+        let foreign_keys: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(foreign_keys);
+        // The transaction of the rebuild was rolled back, so a new one can begin
+        assert_eq!(table_names(&connection).await, vec!["posts", "schema_migrations", "schema_migrations_rolled_back"]);
+        sqlx::raw_sql("BEGIN; COMMIT;").execute(&pool).await.unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rustyroad::database::migrations::{
+        apply_migration, checksum_sql, run_all_migrations_with_connection, verify_migrations,
+        Migration, MigrationDrift,
+    };
+    use rustyroad::database::MigrationDirection;
+
+    use crate::common::{memory_connection, write_migration};
+
+    #[tokio::test]
+    async fn test_verify_reports_edited_missing_and_out_of_order_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_users",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY);",
+            "DROP TABLE users;",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240103000000-create_posts",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY);",
+            "DROP TABLE posts;",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240104000000-create_tags",
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY);",
+            "DROP TABLE tags;",
+        );
+
+        let (_, connection) = memory_connection().await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+        assert!(verify_migrations(&connection, migrations_dir_path)
+            .await
+            .unwrap()
+            .is_empty());
+
+        fs::write(
+            migrations_dir.join("20240103000000-create_posts/up.sql"),
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT);",
+        )
+        .unwrap();
+        fs::remove_dir_all(migrations_dir.join("20240104000000-create_tags")).unwrap();
+        write_migration(
+            &migrations_dir,
+            "20240102000000-create_comments",
+            "CREATE TABLE comments (id INTEGER PRIMARY KEY);",
+            "DROP TABLE comments;",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240105000000-create_likes",
+            "CREATE TABLE likes (id INTEGER PRIMARY KEY);",
+            "DROP TABLE likes;",
+        );
+
+        let report = verify_migrations(&connection, migrations_dir_path)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            vec![
+                MigrationDrift::OutOfOrder {
+                    version: "20240102000000".to_string(),
+                    name: "create_comments".to_string(),
+                },
+                MigrationDrift::Edited {
+                    version: "20240103000000".to_string(),
+                    name: "create_posts".to_string(),
+                },
+                MigrationDrift::Missing {
+                    version: "20240104000000".to_string(),
+                    name: "create_tags".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rolled_back_migrations_are_not_out_of_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        for (version, table) in [("20240101000000", "users"), ("20240103000000", "posts")] {
+            write_migration(
+                &migrations_dir,
+                &format!("{}-create_{}", version, table),
+                &format!("CREATE TABLE {} (id INTEGER PRIMARY KEY);", table),
+                &format!("DROP TABLE {};", table),
+            );
+        }
+
+        let (_, connection) = memory_connection().await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+
+        // Rolling back a migration that isn't the latest leaves it pending, and applying it again is fine
+        let users = Migration::from_dir(&migrations_dir.join("20240101000000-create_users")).unwrap();
+        assert!(apply_migration(&connection, &users, MigrationDirection::Down)
+            .await
+            .unwrap());
+        assert!(verify_migrations(&connection, migrations_dir_path)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // A migration that was never applied still is
+        write_migration(
+            &migrations_dir,
+            "20240102000000-create_comments",
+            "CREATE TABLE comments (id INTEGER PRIMARY KEY);",
+            "DROP TABLE comments;",
+        );
+        assert_eq!(
+            verify_migrations(&connection, migrations_dir_path)
+                .await
+                .unwrap(),
+            vec![MigrationDrift::OutOfOrder {
+                version: "20240102000000".to_string(),
+                name: "create_comments".to_string(),
+            }]
+        );
+
+        // Applied again and rolled back again, it's still only recorded once
+        assert!(apply_migration(&connection, &users, MigrationDirection::Up)
+            .await
+            .unwrap());
+        assert!(apply_migration(&connection, &users, MigrationDirection::Down)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn test_checksum_ignores_line_endings() {
+        assert_eq!(
+            checksum_sql("CREATE TABLE users (\r\n    id INTEGER PRIMARY KEY\r\n);\r\n"),
+            checksum_sql("CREATE TABLE users (\n    id INTEGER PRIMARY KEY\n);\n")
+        );
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::migrations::{
+        get_applied_migrations, CustomMigrationError, MigrationDirection,
+    };
+    use sqlx::Executor;
+
+    use crate::common::memory_connection;
+
+    #[tokio::test]
+    async fn test_embedded_migrations_run_once() {
+        let migrator = rustyroad::embed_migrations!("$CARGO_MANIFEST_DIR/tests/fixtures/migrations");
+        let names: Vec<&str> = migrator
+            .migrations()
+            .iter()
+            .map(|migration| migration.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["create_users", "create_posts"]);
+        assert!(migrator.migrations()[0]
+            .sql(MigrationDirection::Down)
+            .unwrap()
+            .contains("DROP TABLE users;"));
+
+        let (pool, connection) = memory_connection().await;
+        migrator.run(&connection).await.unwrap();
+        pool.execute("INSERT INTO users (email) VALUES ('ada@example.com'); INSERT INTO posts (title, author_id) VALUES ('hello', 1);")
+            .await
+            .unwrap();
+
+        // Running again applies nothing and keeps the data
+        migrator.run(&connection).await.unwrap();
+        let applied = get_applied_migrations(&connection).await.unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[1].checksum, migrator.migrations()[1].checksum().unwrap());
+        let posts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM posts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(posts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_embedded_migrations_refuse_drift_unless_forced() {
+        let migrator = rustyroad::embed_migrations!("$CARGO_MANIFEST_DIR/tests/fixtures/migrations");
+        let (pool, connection) = memory_connection().await;
+        migrator.run(&connection).await.unwrap();
+        pool.execute("UPDATE schema_migrations SET checksum = 'edited' WHERE version = '20240101000000';")
+            .await
+            .unwrap();
+
+        match migrator.run(&connection).await {
+            Err(CustomMigrationError::Drift(drift)) => assert_eq!(drift.len(), 1),
+            _ => panic!("expected the edited migration to be reported"),
+        }
+        migrator.force(true).run(&connection).await.unwrap();
+    }
+}
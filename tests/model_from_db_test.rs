@@ -0,0 +1,320 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rustyroad::database::migrations::{dump_schema, ColumnSchema, TableSchema};
+    use rustyroad::database::{DatabaseConnection, DatabaseError, DatabaseType, Model, PoolConnection, SqlArray, SqlValue};
+    use rustyroad::writers::models::{sql_rust_type, table_model_contents};
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::types::Decimal;
+
+    /// The models of `readings`, with the types of each database, compiled with the tests
+    #[allow(dead_code)]
+    mod postgres {
+        include!("fixtures/models/readings/postgres.rs");
+    }
+    #[allow(dead_code)]
+    mod mysql {
+        include!("fixtures/models/readings/mysql.rs");
+    }
+    #[allow(dead_code)]
+    mod sqlite {
+        include!("fixtures/models/readings/sqlite.rs");
+    }
+
+    async fn sqlite_schema(sql: &str) -> Vec<TableSchema> {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(sql).execute(&pool).await.unwrap();
+        dump_schema(&DatabaseConnection::Sqlite(Arc::new(pool)))
+            .await
+            .unwrap()
+            .tables
+    }
+
+    fn column(name: &str, data_type: &str, nullable: bool) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default: None,
+            auto_increment: false,
+        }
+    }
+
+    fn readings(columns: Vec<ColumnSchema>) -> TableSchema {
+        TableSchema {
+            name: "readings".to_string(),
+            columns,
+            primary_key: vec!["id".to_string()],
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+        }
+    }
+
+    fn id(data_type: &str) -> ColumnSchema {
+        ColumnSchema {
+            auto_increment: true,
+            ..column("id", data_type, false)
+        }
+    }
+
+    fn fixture(contents: &str) -> String {
+        contents.replace("\r\n", "\n")
+    }
+
+    #[test]
+    fn test_column_types_map_to_rust_types() {
+        let postgres = [
+            ("character varying(255)", "String"),
+            ("integer", "i32"),
+            ("bigint", "i64"),
+            ("numeric(10, 2)", "sqlx::types::Decimal"),
+            ("timestamp without time zone", "chrono::NaiveDateTime"),
+            ("jsonb", "serde_json::Value"),
+            ("uuid", "sqlx::types::Uuid"),
+            ("integer[]", "Vec<i32>"),
+        ];
+        for (data_type, rust_type) in postgres {
+            assert_eq!(sql_rust_type(data_type, &DatabaseType::Postgres).unwrap(), rust_type);
+        }
+
+        let mysql = [
+            ("tinyint(1)", "bool"),
+            ("tinyint(4)", "i8"),
+            ("bigint(20) unsigned", "u64"),
+            ("varchar(255)", "String"),
+            ("enum('draft','published')", "String"),
+            ("datetime(6)", "chrono::NaiveDateTime"),
+            ("timestamp", "chrono::DateTime<chrono::Utc>"),
+            ("longblob", "Vec<u8>"),
+        ];
+        for (data_type, rust_type) in mysql {
+            assert_eq!(sql_rust_type(data_type, &DatabaseType::Mysql).unwrap(), rust_type);
+        }
+
+        let sqlite = [
+            ("INTEGER", "i64"),
+            ("BOOLEAN", "bool"),
+            ("DATE", "chrono::NaiveDate"),
+            ("NUMERIC(10,2)", "f64"),
+            ("", "Vec<u8>"),
+        ];
+        for (data_type, rust_type) in sqlite {
+            assert_eq!(sql_rust_type(data_type, &DatabaseType::Sqlite).unwrap(), rust_type);
+        }
+
+        assert_eq!(sql_rust_type("inet", &DatabaseType::Postgres), None);
+        assert_eq!(sql_rust_type("geometry", &DatabaseType::Mysql), None);
+    }
+
+    #[tokio::test]
+    async fn test_model_from_an_altered_sqlite_table() {
+        // A table migrations can't describe on their own: built by hand, then altered
+        let tables = sqlite_schema(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE posts (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 \"Title\" VARCHAR(200) NOT NULL,
+                 user_id INTEGER NOT NULL REFERENCES users (id)
+             );
+             ALTER TABLE posts ADD COLUMN published BOOLEAN NOT NULL DEFAULT 0;
+             ALTER TABLE posts ADD COLUMN type TEXT;
+             ALTER TABLE posts ADD COLUMN published_at DATETIME;",
+        )
+        .await;
+        let posts = tables.iter().find(|table| table.name == "posts").unwrap();
+
+        let contents = table_model_contents("post", posts, &DatabaseType::Sqlite);
+        // The input structs and functions that follow are covered by generated_model_test,
+        // and the association of the foreign key by associations_test
+        assert!(contents.starts_with(
+            "use rustyroad::database::{Associations, DatabaseError, QueryPool, SqlValue};
+use rustyroad::{Model, Validate};
+use serde::{Deserialize, Serialize};
+
+/// A row of the `posts` table, generated from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model, Validate)]
+#[table_name = \"posts\"]
+pub struct Post {
+    pub id: Option<i64>,
+    #[sqlx(rename = \"Title\")]
+    #[validate(length(max = 200))]
+    pub title: String,
+    /// References `users`.`id`
+    pub user_id: i64,
+    /// Defaults to `0`
+    pub published: bool,
+    pub r#type: Option<String>,
+    pub published_at: Option<chrono::NaiveDateTime>,
+}
+"
+        ));
+        assert!(contents.contains("pub struct NewPost {\n    pub title: String,\n"));
+        assert!(contents.contains(
+            "    pub async fn user<P: QueryPool + Sync>(&self, pool: &P) -> Result<Option<super::user::User>, DatabaseError> {"
+        ));
+        assert!(contents.contains(
+            "    pub published: Option<bool>,\n    #[serde(default, deserialize_with = \"rustyroad::database::deserialize_some\")]\n    pub r#type: Option<Option<String>>,\n"
+        ));
+    }
+
+    #[test]
+    fn test_primary_keys() {
+        let table = TableSchema {
+            name: "api_keys".to_string(),
+            columns: vec![
+                ColumnSchema {
+                    default: Some("gen_random_uuid()".to_string()),
+                    ..column("token", "uuid", false)
+                },
+                column("label", "text", true),
+            ],
+            primary_key: vec!["token".to_string()],
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+        };
+        let contents = table_model_contents("api_key", &table, &DatabaseType::Postgres);
+        assert!(contents.contains("pub struct ApiKey {"));
+        assert!(contents.contains(
+            "    /// Defaults to `gen_random_uuid()`\n    #[primary_key]\n    pub token: Option<sqlx::types::Uuid>,\n"
+        ));
+
+        let join_table = TableSchema {
+            name: "categories_posts".to_string(),
+            columns: vec![
+                column("category_id", "bigint", false),
+                column("post_id", "bigint", false),
+                column("rank", "tsvector", true),
+            ],
+            primary_key: vec!["category_id".to_string(), "post_id".to_string()],
+            ..table
+        };
+        let contents = table_model_contents("categories_post", &join_table, &DatabaseType::Postgres);
+        assert!(contents.contains("#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]\npub struct CategoriesPost {"));
+        assert!(!contents.contains("table_name"));
+        assert!(contents.contains("sqlx has no Rust type for `tsvector`"));
+        assert!(contents.contains("    pub rank: Option<String>,\n"));
+    }
+
+    #[test]
+    fn test_readings_fixtures_are_the_generated_models() {
+        let postgres = readings(vec![
+            id("integer"),
+            column("name", "character varying(80)", false),
+            column("price", "numeric(10,2)", true),
+            column("score", "double precision", false),
+            column("tags", "text[]", false),
+            column("counts", "integer[]", true),
+            column("taken_at", "timestamp with time zone", true),
+            column("token", "uuid", true),
+            column("data", "jsonb", true),
+            column("blobs", "bytea[]", true),
+        ]);
+        assert_eq!(
+            table_model_contents("reading", &postgres, &DatabaseType::Postgres),
+            fixture(include_str!("fixtures/models/readings/postgres.rs"))
+        );
+
+        let mysql = readings(vec![
+            id("int unsigned"),
+            column("views", "bigint unsigned", false),
+            column("price", "decimal(10,2)", true),
+            column("name", "varchar(80)", false),
+            column("published", "tinyint(1)", false),
+            column("created_at", "timestamp", true),
+        ]);
+        assert_eq!(
+            table_model_contents("reading", &mysql, &DatabaseType::Mysql),
+            fixture(include_str!("fixtures/models/readings/mysql.rs"))
+        );
+
+        let sqlite = readings(vec![
+            id("INTEGER"),
+            column("price", "NUMERIC(10,2)", true),
+            column("name", "TEXT", false),
+            column("published", "BOOLEAN", false),
+            column("data", "BLOB", true),
+            column("created_at", "DATETIME", true),
+        ]);
+        assert_eq!(
+            table_model_contents("reading", &sqlite, &DatabaseType::Sqlite),
+            fixture(include_str!("fixtures/models/readings/sqlite.rs"))
+        );
+    }
+
+    #[test]
+    fn test_readings_bind_the_types_of_their_database() {
+        let reading = postgres::Reading {
+            id: None,
+            name: "Oven".to_string(),
+            price: Some(Decimal::new(1999, 2)),
+            score: 0.5,
+            tags: vec!["kitchen".to_string()],
+            counts: Some(vec![1, 2]),
+            taken_at: None,
+            token: None,
+            data: None,
+            blobs: None,
+        };
+        let values = reading.column_values();
+        assert_eq!(values[1], ("price", SqlValue::Decimal(Decimal::new(1999, 2))));
+        assert_eq!(values[3], ("tags", SqlValue::Array(SqlArray::Text(vec!["kitchen".to_string()]))));
+        assert_eq!(values[4], ("counts", SqlValue::Array(SqlArray::Int(vec![1, 2]))));
+
+        let reading = mysql::Reading {
+            id: Some(7),
+            views: u64::MAX,
+            price: None,
+            name: "Oven".to_string(),
+            published: true,
+            created_at: None,
+        };
+        assert_eq!(reading.primary_key_value(), SqlValue::Int(7));
+        assert_eq!(reading.column_values()[0], ("views", SqlValue::UInt(u64::MAX)));
+    }
+
+    #[tokio::test]
+    async fn test_readings_on_sqlite() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE readings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                price NUMERIC(10,2),
+                name TEXT NOT NULL,
+                published BOOLEAN NOT NULL,
+                data BLOB,
+                created_at DATETIME
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let pool = PoolConnection::Sqlite(pool);
+
+        let new_reading: sqlite::NewReading =
+            serde_json::from_str(r#"{"price": 19.99, "name": "Oven", "published": true, "data": [1, 2]}"#).unwrap();
+        let reading = sqlite::Reading::create_reading(&pool, new_reading).await.unwrap();
+        assert_eq!(reading.id, Some(1));
+        assert_eq!(reading.data, Some(vec![1, 2]));
+        assert_eq!(sqlite::Reading::get_all_readings(&pool).await.unwrap().len(), 1);
+
+        // SQLite has no arrays: the model of Postgres compiles, and reading it fails
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE readings (
+                id INTEGER PRIMARY KEY,
+                name TEXT, price TEXT, score REAL, tags TEXT, counts TEXT,
+                taken_at TEXT, token TEXT, data TEXT, blobs TEXT
+            );
+            INSERT INTO readings (name, score, tags) VALUES ('Oven', 0.5, 'kitchen');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let pool = PoolConnection::Sqlite(pool);
+        match postgres::Reading::get_all_readings(&pool).await {
+            Err(DatabaseError::Sql(sqlx::Error::Decode(_))) => {}
+            result => panic!("expected a decode error, got {:?}", result),
+        }
+    }
+}
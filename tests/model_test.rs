@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use rustyroad::database::{Operator, Order, Paginated, PoolConnection};
+    use rustyroad::writers::models::model_contents;
+    use rustyroad::Model;
+    use sqlx::sqlite::SqlitePool;
+
+    #[derive(Debug, Clone, PartialEq, sqlx::FromRow, Model)]
+    #[table_name = "posts"]
+    struct Post {
+        id: Option<i64>,
+        title: String,
+        views: i64,
+        published_at: Option<DateTime<Utc>>,
+    }
+
+    /// A primary key that isn't `id`, on the default table `api_key`.
+    #[derive(Debug, Clone, PartialEq, sqlx::FromRow, Model)]
+    struct ApiKey {
+        #[primary_key]
+        token: String,
+        r#type: String,
+        #[sqlx(rename = "createdBy", default)]
+        created_by: Option<String>,
+    }
+
+    async fn pool() -> PoolConnection {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, views INTEGER NOT NULL DEFAULT 0, published_at TIMESTAMP);
+             CREATE TABLE api_key (token TEXT PRIMARY KEY, type TEXT NOT NULL, \"createdBy\" TEXT);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        PoolConnection::Sqlite(pool)
+    }
+
+    fn post(title: &str, views: i64) -> Post {
+        Post {
+            id: None,
+            title: title.to_string(),
+            views,
+            published_at: None,
+        }
+    }
+
+    #[test]
+    fn test_derive_reads_the_attributes() {
+        assert_eq!(Post::TABLE_NAME, "posts");
+        assert_eq!(Post::PRIMARY_KEY, "id");
+        assert_eq!(Post::COLUMNS, &["id", "title", "views", "published_at"]);
+        assert_eq!(ApiKey::TABLE_NAME, "api_key");
+        assert_eq!(ApiKey::PRIMARY_KEY, "token");
+        assert_eq!(ApiKey::COLUMNS, &["token", "type", "createdBy"]);
+
+        let columns: Vec<&str> = post("Hello", 1)
+            .column_values()
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        assert_eq!(columns, vec!["title", "views", "published_at"]);
+    }
+
+    #[tokio::test]
+    async fn test_crud_on_sqlite() {
+        let pool = pool().await;
+
+        let published_at = "2024-01-31T13:45:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut hello = Post {
+            published_at: Some(published_at),
+            ..post("Hello", 3)
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+        assert_eq!(hello.id, Some(1));
+        assert_eq!(hello.published_at, Some(published_at));
+        let world = post("World", 7).create(&pool).await.unwrap();
+        assert_eq!(world.id, Some(2));
+
+        assert_eq!(Post::find(&pool, 1).await.unwrap(), Some(hello.clone()));
+        assert_eq!(Post::find(&pool, 42).await.unwrap(), None);
+        assert_eq!(Post::all(&pool).await.unwrap(), vec![hello.clone(), world.clone()]);
+        assert_eq!(Post::where_eq(&pool, "title", "World").await.unwrap(), vec![world.clone()]);
+
+        let popular: Vec<Post> = Post::query()
+            .where_op("views", Operator::Gt, 5)
+            .order_by("views", Order::Desc)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(popular, vec![world.clone()]);
+
+        hello.title = "Hello again".to_string();
+        hello.published_at = None;
+        assert!(hello.update(&pool).await.unwrap());
+        assert_eq!(Post::find(&pool, 1).await.unwrap(), Some(hello.clone()));
+
+        assert!(world.destroy(&pool).await.unwrap());
+        assert!(!world.destroy(&pool).await.unwrap());
+        assert!(!world.update(&pool).await.unwrap());
+        assert_eq!(Post::count(&pool).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let pool = pool().await;
+        for views in 0..5 {
+            post(&format!("Post {}", views), views).create(&pool).await.unwrap();
+        }
+
+        let page: Paginated<Post> = Post::paginate(&pool, 2, 2).await.unwrap();
+        assert_eq!(page.page, 2);
+        assert_eq!(page.total, 5);
+        assert_eq!(page.total_pages, 3);
+        let ids: Vec<Option<i64>> = page.items.iter().map(|post| post.id).collect();
+        assert_eq!(ids, vec![Some(3), Some(4)]);
+
+        // Pages start at 1
+        let first = Post::paginate(&pool, 0, 2).await.unwrap();
+        assert_eq!(first.page, 1);
+        assert_eq!(first.items[0].id, Some(1));
+        assert!(Post::paginate(&pool, 4, 2).await.unwrap().items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_primary_key_set_by_the_application() {
+        let pool = pool().await;
+        let key = ApiKey {
+            token: "abc".to_string(),
+            r#type: "read".to_string(),
+            created_by: Some("ada".to_string()),
+        }
+        .create(&pool)
+        .await
+        .unwrap();
+        assert_eq!(ApiKey::find(&pool, "abc").await.unwrap(), Some(key));
+    }
+
+    #[test]
+    fn test_generated_model_is_a_short_struct() {
+        let contents = model_contents(
+            "post",
+            "posts",
+            "    pub id: Option<i64>,\n    pub created_at: NaiveDateTime,\n",
+        );
+        assert_eq!(
+            contents,
+            "use chrono::{NaiveDate, NaiveDateTime, NaiveTime};\n\
+             use rustyroad::Model;\n\
+             use serde::{Deserialize, Serialize};\n\
+             \n\
+             #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, Model)]\n\
+             #[table_name = \"posts\"]\n\
+             pub struct Post {\n    pub id: Option<i64>,\n    pub created_at: NaiveDateTime,\n}\n"
+        );
+    }
+}
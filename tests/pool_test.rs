@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use rustyroad::database::{
+        Database, DatabaseConnection, DatabaseError, Environment, PoolSettings,
+    };
+
+    const TOML: &str = r#"
+[database]
+database_name = "blog"
+database_user = "postgres"
+database_password = "postgres"
+database_host = "localhost"
+database_port = 5432
+database_type = "postgres"
+pool_max_connections = 20
+pool_acquire_timeout = "5"
+
+[database.development]
+
+[database.production]
+pool_min_connections = 2
+pool_idle_timeout = 0
+"#;
+
+    #[test]
+    fn test_pool_settings_from_rustyroad_toml() {
+        let development = Database::from_rustyroad_toml(TOML, Environment::Development).unwrap();
+        assert_eq!(development.pool.max_connections, 20);
+        assert_eq!(development.pool.min_connections, 0);
+        assert_eq!(development.pool.acquire_timeout, Duration::from_secs(5));
+        assert_eq!(development.pool.idle_timeout, PoolSettings::default().idle_timeout);
+
+        let production = Database::from_rustyroad_toml(TOML, Environment::Production).unwrap();
+        assert_eq!(production.pool.min_connections, 2);
+        assert_eq!(production.pool.idle_timeout, None);
+
+        let invalid = TOML.replace("pool_min_connections = 2", "pool_min_connections = 50");
+        let error = Database::from_rustyroad_toml(&invalid, Environment::Production).unwrap_err();
+        assert!(matches!(error, DatabaseError::Parse(_)));
+
+        let invalid = TOML.replace("\"5\"", "\"soon\"");
+        let error = Database::from_rustyroad_toml(&invalid, Environment::Development).unwrap_err();
+        assert!(error.to_string().contains("pool_acquire_timeout"));
+    }
+
+    #[tokio::test]
+    async fn test_shared_pool_is_built_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut database = Database::new(
+            dir.path().join("blog").to_str().unwrap().to_string(),
+            String::new(),
+            String::new(),
+            "localhost".to_string(),
+            0,
+            "sqlite",
+        )
+        .unwrap();
+        database.pool.max_connections = 3;
+        rustyroad::database::create_database(&database).await.unwrap();
+
+        let first = database.shared_pool().await.unwrap();
+        let second = database.shared_pool().await.unwrap();
+        match (&first, &second) {
+            (DatabaseConnection::Sqlite(first), DatabaseConnection::Sqlite(second)) => {
+                assert!(Arc::ptr_eq(first, second));
+                assert_eq!(first.options().get_max_connections(), 3);
+            }
+            _ => panic!("expected a SQLite pool"),
+        }
+        assert!(first.as_sqlite().is_some());
+        assert!(first.as_pg().is_none());
+    }
+}
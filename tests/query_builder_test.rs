@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::{
+        DatabaseError, DatabaseType, Operator, Order, PoolConnection, Query, SqlValue,
+    };
+    use sqlx::sqlite::SqlitePool;
+
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    struct Post {
+        id: i64,
+        title: String,
+        views: Option<i64>,
+    }
+
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    struct PostWithAuthor {
+        title: String,
+        name: String,
+    }
+
+    #[test]
+    fn test_placeholders_and_quoting_follow_the_database() {
+        let query = Query::select("posts")
+            .where_eq("title", "Hello")
+            .where_op("views", Operator::Gte, 10)
+            .where_in("id", [1, 2]);
+
+        let (sql, values) = query.to_sql(&DatabaseType::Postgres).unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "posts" WHERE "title" = $1 AND "views" >= $2 AND "id" IN ($3, $4)"#
+        );
+        assert_eq!(
+            values,
+            vec![
+                SqlValue::Text("Hello".to_string()),
+                SqlValue::Int(10),
+                SqlValue::Int(1),
+                SqlValue::Int(2)
+            ]
+        );
+
+        let (sql, _) = query.to_sql(&DatabaseType::Mysql).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM `posts` WHERE `title` = ? AND `views` >= ? AND `id` IN (?, ?)"
+        );
+        let (sql, _) = query.to_sql(&DatabaseType::Sqlite).unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "posts" WHERE "title" = ? AND "views" >= ? AND "id" IN (?, ?)"#
+        );
+
+        let error = query.to_sql(&DatabaseType::Mongo).unwrap_err();
+        assert!(matches!(error, DatabaseError::Query(_)));
+    }
+
+    #[test]
+    fn test_insert_update_and_delete() {
+        let (sql, values) = Query::insert("posts")
+            .value("title", "Hello")
+            .value("views", None::<i64>)
+            .returning(["id"])
+            .to_sql(&DatabaseType::Postgres)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "posts" ("title", "views") VALUES ($1, NULL) RETURNING "id""#
+        );
+        assert_eq!(values, vec![SqlValue::Text("Hello".to_string())]);
+
+        let (sql, _) = Query::update("posts")
+            .set("views", 1)
+            .where_eq("deleted_at", None::<String>)
+            .to_sql(&DatabaseType::Sqlite)
+            .unwrap();
+        assert_eq!(sql, r#"UPDATE "posts" SET "views" = ? WHERE "deleted_at" IS NULL"#);
+
+        let (sql, _) = Query::delete("posts")
+            .where_not_null("deleted_at")
+            .to_sql(&DatabaseType::Mysql)
+            .unwrap();
+        assert_eq!(sql, "DELETE FROM `posts` WHERE `deleted_at` IS NOT NULL");
+
+        assert!(Query::insert("posts").to_sql(&DatabaseType::Sqlite).is_err());
+        assert!(Query::update("posts").to_sql(&DatabaseType::Sqlite).is_err());
+        let error = Query::insert("posts")
+            .value("title", "Hello")
+            .returning(["id"])
+            .to_sql(&DatabaseType::Mysql)
+            .unwrap_err();
+        assert!(matches!(error, DatabaseError::Query(_)));
+    }
+
+    #[test]
+    fn test_order_limit_and_offset() {
+        let query = Query::select("posts")
+            .order_by("views", Order::Desc)
+            .order_by("id", Order::Asc)
+            .offset(20);
+        let (sql, _) = query.to_sql(&DatabaseType::Postgres).unwrap();
+        assert_eq!(sql, r#"SELECT * FROM "posts" ORDER BY "views" DESC, "id" ASC OFFSET 20"#);
+        // MySQL and SQLite need a LIMIT before the OFFSET
+        let (sql, _) = query.to_sql(&DatabaseType::Sqlite).unwrap();
+        assert!(sql.ends_with(" LIMIT -1 OFFSET 20"));
+        let (sql, _) = query.clone().limit(10).to_sql(&DatabaseType::Mysql).unwrap();
+        assert!(sql.ends_with(" LIMIT 10 OFFSET 20"));
+
+        let (sql, _) = query.where_eq("id", 1).count_sql(&DatabaseType::Postgres).unwrap();
+        assert_eq!(sql, r#"SELECT COUNT(*) FROM "posts" WHERE "id" = $1"#);
+    }
+
+    #[tokio::test]
+    async fn test_queries_run_on_sqlite() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, views INTEGER, user_id INTEGER);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let connection = PoolConnection::Sqlite(pool);
+
+        let result = Query::insert("users").value("name", "Ada").execute(&connection).await.unwrap();
+        assert_eq!(result.rows_affected, 1);
+        let user_id = result.last_insert_id.unwrap();
+        for (title, views) in [("First", Some(3)), ("Second", None), ("Third", Some(7))] {
+            Query::insert("posts")
+                .value("title", title)
+                .value("views", views)
+                .value("user_id", user_id)
+                .execute(&connection)
+                .await
+                .unwrap();
+        }
+
+        let posts: Vec<Post> = Query::select("posts")
+            .columns(["id", "title", "views"])
+            .where_not_null("views")
+            .order_by("views", Order::Desc)
+            .fetch_all(&connection)
+            .await
+            .unwrap();
+        assert_eq!(
+            posts.iter().map(|post| post.title.as_str()).collect::<Vec<_>>(),
+            vec!["Third", "First"]
+        );
+
+        let second: Post = Query::select("posts")
+            .columns(["id", "title", "views"])
+            .order_by("id", Order::Asc)
+            .offset(1)
+            .fetch_one(&connection)
+            .await
+            .unwrap();
+        assert_eq!(second.title, "Second");
+        assert_eq!(second.views, None);
+
+        let joined: Vec<PostWithAuthor> = Query::select("posts")
+            .columns(["posts.title", "users.name"])
+            .join("users", "users.id", "posts.user_id")
+            .where_op("posts.title", Operator::Like, "F%")
+            .fetch_all(&connection)
+            .await
+            .unwrap();
+        assert_eq!(
+            joined,
+            vec![PostWithAuthor {
+                title: "First".to_string(),
+                name: "Ada".to_string()
+            }]
+        );
+
+        let updated = Query::update("posts")
+            .set("views", None::<i64>)
+            .where_eq("title", "Third")
+            .execute(&connection)
+            .await
+            .unwrap();
+        assert_eq!(updated.rows_affected, 1);
+        let without_views = Query::select("posts").where_null("views").count(&connection).await.unwrap();
+        assert_eq!(without_views, 2);
+
+        Query::delete("posts").where_in("id", Vec::<i64>::new()).execute(&connection).await.unwrap();
+        assert_eq!(Query::select("posts").count(&connection).await.unwrap(), 3);
+
+        let missing = Query::select("posts")
+            .columns(["id", "title", "views"])
+            .where_eq("id", 42)
+            .fetch_optional::<Post, _>(&connection)
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+    }
+}
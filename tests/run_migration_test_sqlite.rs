@@ -22,7 +22,8 @@ mod tests {
             "".to_owned(),
             0,
             "sqlite".to_string().as_str(),
-        );
+        )
+        .unwrap();
 
         // Create a new project with the desired name
         let project_name = String::from("example");
@@ -0,0 +1,169 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rustyroad::database::migrations::{
+        dump_schema, load_schema_with_connection, run_all_migrations_with_connection,
+        write_schema_files, ColumnSchema, DatabaseSchema, ForeignKeySchema, IndexSchema,
+        TableSchema,
+    };
+    use rustyroad::database::{DatabaseType, MigrationDirection};
+
+    use crate::common::{sqlite_connection, write_migration};
+
+    fn column(name: &str, data_type: &str, auto_increment: bool) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: false,
+            default: None,
+            auto_increment,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_dump_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_users",
+            "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL UNIQUE);",
+            "DROP TABLE users;",
+        );
+        write_migration(
+            &migrations_dir,
+            "20240102000000-create_posts",
+            "CREATE TABLE posts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    views INTEGER NOT NULL DEFAULT 0,
+    author_id INTEGER NULL,
+    FOREIGN KEY (author_id) REFERENCES users (id)
+);
+CREATE INDEX index_posts_on_views ON posts (views);",
+            "DROP TABLE posts;",
+        );
+
+        let connection = sqlite_connection(&dir.path().join("source.db")).await;
+        run_all_migrations_with_connection(
+            &connection,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+
+        let sql_path = dir.path().join("schema.sql");
+        let json_path = dir.path().join("schema.json");
+        let schema = write_schema_files(
+            &connection,
+            sql_path.to_str().unwrap(),
+            json_path.to_str().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let table_names: Vec<&str> = schema.tables.iter().map(|table| table.name.as_str()).collect();
+        assert_eq!(table_names, vec!["posts", "schema_migrations", "users"]);
+        let versions: Vec<&str> = schema
+            .migrations
+            .iter()
+            .map(|migration| migration.version.as_str())
+            .collect();
+        assert_eq!(versions, vec!["20240101000000", "20240102000000"]);
+
+        let posts = &schema.tables[0];
+        assert!(posts.columns[0].auto_increment);
+        assert_eq!(posts.columns[2].default.as_deref(), Some("0"));
+        assert_eq!(posts.foreign_keys[0].references_table, "users");
+        assert_eq!(posts.indexes[0].name, "index_posts_on_views");
+
+        let json: DatabaseSchema =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json, schema);
+
+        let fresh = sqlite_connection(&dir.path().join("fresh.db")).await;
+        load_schema_with_connection(&fresh, sql_path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(dump_schema(&fresh).await.unwrap(), schema);
+
+        // Every migration is recorded, so there's nothing left to run on the loaded database
+        run_all_migrations_with_connection(
+            &fresh,
+            migrations_dir.to_str().unwrap(),
+            MigrationDirection::Up,
+        )
+        .await
+        .unwrap();
+        assert_eq!(dump_schema(&fresh).await.unwrap().migrations, schema.migrations);
+    }
+
+    #[tokio::test]
+    async fn test_load_refuses_a_database_with_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let sql_path = dir.path().join("schema.sql");
+        fs::write(&sql_path, "CREATE TABLE users (id INTEGER PRIMARY KEY);\n").unwrap();
+
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+        load_schema_with_connection(&connection, sql_path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert!(load_schema_with_connection(&connection, sql_path.to_str().unwrap())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_postgres_schema_sql_creates_referenced_tables_first() {
+        let schema = DatabaseSchema {
+            database_type: "postgres".to_string(),
+            tables: vec![
+                TableSchema {
+                    name: "posts".to_string(),
+                    columns: vec![
+                        column("id", "bigint", true),
+                        column("author_id", "integer", false),
+                    ],
+                    primary_key: vec!["id".to_string()],
+                    foreign_keys: vec![ForeignKeySchema {
+                        columns: vec!["author_id".to_string()],
+                        references_table: "users".to_string(),
+                        references_columns: vec!["id".to_string()],
+                    }],
+                    indexes: vec![IndexSchema {
+                        name: "index_posts_on_author_id".to_string(),
+                        columns: vec!["author_id".to_string()],
+                        unique: false,
+                    }],
+                },
+                TableSchema {
+                    name: "users".to_string(),
+                    columns: vec![column("id", "integer", true)],
+                    primary_key: vec!["id".to_string()],
+                    foreign_keys: Vec::new(),
+                    indexes: Vec::new(),
+                },
+            ],
+            migrations: Vec::new(),
+        };
+
+        let sql = schema.to_sql(&DatabaseType::Postgres);
+        let users = sql.find("CREATE TABLE users").unwrap();
+        let posts = sql.find("CREATE TABLE posts").unwrap();
+        assert!(users < posts);
+        assert!(sql.contains(
+            "CREATE TABLE posts (
+    id BIGSERIAL NOT NULL,
+    author_id integer NOT NULL,
+    PRIMARY KEY (id),
+    FOREIGN KEY (author_id) REFERENCES users (id)
+);
+CREATE INDEX index_posts_on_author_id ON posts (author_id);
+"
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use rustyroad::database::migrations::{
+        get_applied_migrations, get_migrations, run_all_migrations_with_connection, Migration,
+    };
+    use rustyroad::database::MigrationDirection;
+
+    use crate::common::{sqlite_connection, table_names, write_migration};
+
+    #[test]
+    fn test_migration_from_dir() {
+        let migration = Migration::from_dir("migrations/20240101120000-create_posts").unwrap();
+        assert_eq!(migration.version, "20240101120000");
+        assert_eq!(migration.name, "create_posts");
+
+        let initial = Migration::from_dir("migrations/20240101120000_user").unwrap();
+        assert_eq!(initial.name, "user");
+
+        assert!(Migration::from_dir("migrations/create_posts").is_none());
+    }
+
+    #[test]
+    fn test_get_migrations_sorted_by_version() {
+        let dir = tempfile::tempdir().unwrap();
+        write_migration(dir.path(), "20240102000000-b", "", "");
+        write_migration(dir.path(), "20240101000000-a", "", "");
+        fs::write(dir.path().join("README.md"), "not a migration").unwrap();
+
+        let migrations = get_migrations(dir.path().to_str().unwrap()).unwrap();
+        let names: Vec<_> = migrations.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_all_migrations_only_applies_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let migrations_dir = dir.path().join("migrations");
+        write_migration(
+            &migrations_dir,
+            "20240101000000-create_posts",
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY);",
+            "DROP TABLE posts;",
+        );
+        let connection = sqlite_connection(&dir.path().join("test.db")).await;
+        let migrations_dir_path = migrations_dir.to_str().unwrap();
+
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+
+        // A second migration added later is the only one applied on the next run;
+        // re-running create_posts would fail because the table already exists.
+        write_migration(
+            &migrations_dir,
+            "20240102000000-create_comments",
+            "CREATE TABLE comments (id INTEGER PRIMARY KEY);",
+            "DROP TABLE comments;",
+        );
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Up)
+            .await
+            .unwrap();
+
+        let applied = get_applied_migrations(&connection).await.unwrap();
+        let versions: Vec<_> = applied.iter().map(|a| a.version.as_str()).collect();
+        assert_eq!(versions, vec!["20240101000000", "20240102000000"]);
+        assert_eq!(applied[0].name, "create_posts");
+        assert_eq!(applied[0].checksum.len(), 64);
+        assert_eq!(
+            table_names(&connection).await,
+            vec!["comments", "posts", "schema_migrations", "schema_migrations_rolled_back"]
+        );
+
+        run_all_migrations_with_connection(&connection, migrations_dir_path, MigrationDirection::Down)
+            .await
+            .unwrap();
+
+        assert!(get_applied_migrations(&connection).await.unwrap().is_empty());
+        assert_eq!(table_names(&connection).await, vec!["schema_migrations", "schema_migrations_rolled_back"]);
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use rustyroad::database::{
+        clear_seed_records, create_seed_file, get_applied_seeds, register_rust_seed,
+        run_seeds_with_connection, DatabaseConnection, Environment, Seed, SeedKind,
+    };
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::Executor;
+
+    use crate::common::memory_connection;
+
+    fn write_seed(dir: &Path, file_name: &str, sql: &str) {
+        let path = dir.join(file_name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, sql).unwrap();
+    }
+
+    async fn sqlite_connection() -> (SqlitePool, DatabaseConnection) {
+        let (pool, connection) = memory_connection().await;
+        pool.execute("CREATE TABLE roles (name TEXT NOT NULL); CREATE TABLE users (username TEXT NOT NULL);")
+            .await
+            .unwrap();
+        (pool, connection)
+    }
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_seeds_run_once_per_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        write_seed(dir.path(), "20240101000000-roles.sql", "INSERT INTO roles (name) VALUES ('admin');\n");
+        write_seed(dir.path(), "dev/20240102000000-demo_users.sql", "INSERT INTO users (username) VALUES ('demo');\n");
+        write_seed(dir.path(), "prod/20240103000000-operators.sql", "INSERT INTO users (username) VALUES ('ops');\n");
+        write_seed(dir.path(), "README.md", "Not a seed\n");
+        let seeders_dir = dir.path().to_str().unwrap();
+
+        let (pool, connection) = sqlite_connection().await;
+        let seeded = run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .unwrap();
+        let names: Vec<&str> = seeded.iter().map(|seed| seed.name.as_str()).collect();
+        assert_eq!(names, vec!["roles", "demo_users"]);
+        assert_eq!(seeded[1].environment, Some(Environment::Development));
+
+        // Seeding again inserts nothing
+        assert!(run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(count(&pool, "roles").await, 1);
+        assert_eq!(count(&pool, "users").await, 1);
+
+        let seeded = run_seeds_with_connection(&connection, seeders_dir, Environment::Production)
+            .await
+            .unwrap();
+        assert_eq!(seeded.len(), 1);
+        assert_eq!(seeded[0].name, "operators");
+        let applied = get_applied_seeds(&connection).await.unwrap();
+        assert_eq!(applied.len(), 3);
+        assert_eq!(applied[2].environment, "production");
+
+        // Once the seeded rows are gone the seeds run again
+        pool.execute("DELETE FROM roles; DELETE FROM users;").await.unwrap();
+        clear_seed_records(&connection).await.unwrap();
+        run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .unwrap();
+        assert_eq!(count(&pool, "roles").await, 1);
+        assert_eq!(count(&pool, "users").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_seeds_with_the_same_version_all_run() {
+        let dir = tempfile::tempdir().unwrap();
+        // Generated within the same second
+        write_seed(dir.path(), "20240101000000-roles.sql", "INSERT INTO roles (name) VALUES ('admin');\n");
+        write_seed(dir.path(), "20240101000000-users.sql", "INSERT INTO users (username) VALUES ('admin');\n");
+        let seeders_dir = dir.path().to_str().unwrap();
+
+        let (pool, connection) = sqlite_connection().await;
+        let seeded = run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .unwrap();
+        let names: Vec<&str> = seeded.iter().map(|seed| seed.name.as_str()).collect();
+        assert_eq!(names, vec!["roles", "users"]);
+        assert_eq!(count(&pool, "roles").await, 1);
+        assert_eq!(count(&pool, "users").await, 1);
+        assert_eq!(get_applied_seeds(&connection).await.unwrap().len(), 2);
+
+        assert!(run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failing_seed_leaves_no_rows_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        write_seed(
+            dir.path(),
+            "20240101000000-broken.sql",
+            "INSERT INTO roles (name) VALUES ('admin');\nINSERT INTO missing_table (name) VALUES ('x');\n",
+        );
+        let seeders_dir = dir.path().to_str().unwrap();
+
+        let (pool, connection) = sqlite_connection().await;
+        assert!(run_seeds_with_connection(&connection, seeders_dir, Environment::Development)
+            .await
+            .is_err());
+        assert_eq!(count(&pool, "roles").await, 0);
+        assert!(get_applied_seeds(&connection).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generated_seeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let seeders_dir = dir.path().join("seeders");
+        let seeders_dir = seeders_dir.to_str().unwrap();
+
+        let seed = create_seed_file(seeders_dir, "demo_posts", Some(Environment::Development), SeedKind::Sql)
+            .unwrap();
+        assert_eq!(seed.name, "demo_posts");
+        assert_eq!(seed.path.parent().unwrap(), dir.path().join("seeders").join("dev"));
+        assert!(fs::read_to_string(&seed.path)
+            .unwrap()
+            .contains("in the development environment"));
+        assert!(create_seed_file(seeders_dir, "demo posts", None, SeedKind::Sql).is_err());
+
+        let cargo_toml = dir.path().join("Cargo.toml");
+        fs::write(&cargo_toml, "[package]\nname = \"blog\"\nversion = \"0.1.0\"\n").unwrap();
+        let cargo_toml = cargo_toml.to_str().unwrap();
+        let seed = Seed::from_file("./seeders/20240101000000-admin_user.rs", None).unwrap();
+        assert_eq!(seed.kind, SeedKind::Rust);
+        register_rust_seed(cargo_toml, &seed).unwrap();
+        register_rust_seed(cargo_toml, &seed).unwrap();
+
+        let contents = fs::read_to_string(cargo_toml).unwrap();
+        assert!(contents.starts_with("[package]\ndefault-run = \"blog\"\nname = \"blog\"\n"));
+        assert_eq!(contents.matches("default-run").count(), 1);
+        assert_eq!(contents.matches("[[bin]]").count(), 1);
+        assert!(contents.contains(
+            "[[bin]]\nname = \"seed-20240101000000-admin_user\"\npath = \"seeders/20240101000000-admin_user.rs\"\n"
+        ));
+    }
+}
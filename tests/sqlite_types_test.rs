@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use rustyroad::database::migrations::sqlite_column;
+    use rustyroad::database::{
+        sqlite_affinity, DataTypeCategory, DatabaseType, DatabaseTypeTrait, SqliteDatabaseType,
+        SqliteTypes, TypesForDatabase,
+    };
+    use sqlx::sqlite::SqlitePool;
+
+    #[test]
+    fn test_affinity_follows_the_declared_type() {
+        assert_eq!(sqlite_affinity("INTEGER"), SqliteTypes::Integer);
+        assert_eq!(sqlite_affinity("tinyint"), SqliteTypes::Integer);
+        assert_eq!(sqlite_affinity("NVARCHAR(100)"), SqliteTypes::Text);
+        assert_eq!(sqlite_affinity("CLOB"), SqliteTypes::Text);
+        assert_eq!(sqlite_affinity(""), SqliteTypes::Blob);
+        assert_eq!(sqlite_affinity("DOUBLE PRECISION"), SqliteTypes::Real);
+        assert_eq!(sqlite_affinity("DECIMAL(10,5)"), SqliteTypes::Numeric);
+        // INT wins over the later rules, like in SQLite
+        assert_eq!(sqlite_affinity("FLOATING POINT"), SqliteTypes::Integer);
+
+        assert_eq!(SqliteTypes::Boolean.affinity(), SqliteTypes::Numeric);
+        assert_eq!(SqliteTypes::DateTime.affinity(), SqliteTypes::Numeric);
+        assert_eq!(SqliteTypes::Blob.affinity(), SqliteTypes::Blob);
+    }
+
+    #[test]
+    fn test_categories_offer_sqlite_types() {
+        let types = SqliteDatabaseType
+            .get_database_types(&TypesForDatabase::new(), &DataTypeCategory::Numeric);
+        assert_eq!(
+            types[0].sqlite.types["Numeric"],
+            vec![SqliteTypes::Integer, SqliteTypes::Real, SqliteTypes::Numeric]
+        );
+
+        let boolean = DataTypeCategory::Boolean.get_data_types_from_data_type_category(DatabaseType::Sqlite);
+        assert_eq!(
+            boolean.sqlite.types["Boolean"],
+            vec![SqliteTypes::Boolean, SqliteTypes::Integer]
+        );
+        assert_eq!(SqliteTypes::Boolean.category(), DataTypeCategory::Boolean);
+
+        // SQLite has no arrays
+        let array = DataTypeCategory::Array.get_data_types_from_data_type_category(DatabaseType::Sqlite);
+        assert!(array.sqlite.types.is_empty());
+        let types = SqliteDatabaseType.get_database_types(&array, &DataTypeCategory::Array);
+        assert!(types[0].sqlite.types["Array"].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generated_columns_decode_into_their_rust_types() {
+        let columns = [
+            sqlite_column("views", &SqliteTypes::Integer, false, "DEFAULT 0"),
+            sqlite_column("rating", &SqliteTypes::Real, true, ""),
+            sqlite_column("title", &SqliteTypes::Text, false, "UNIQUE"),
+            sqlite_column("published", &SqliteTypes::Boolean, false, ""),
+            sqlite_column("published_on", &SqliteTypes::Date, false, ""),
+            sqlite_column("starts_at", &SqliteTypes::Time, false, ""),
+            sqlite_column("created_at", &SqliteTypes::DateTime, false, ""),
+            sqlite_column("cover", &SqliteTypes::Blob, true, ""),
+        ];
+        assert_eq!(columns[0].0, "views INTEGER NOT NULL DEFAULT 0");
+        assert_eq!(columns[1].1, "    pub rating: Option<f64>,\n");
+        assert_eq!(columns[3].1, "    pub published: bool,\n");
+        assert_eq!(columns[6].1, "    pub created_at: NaiveDateTime,\n");
+
+        let definitions: Vec<&str> = columns.iter().map(|(column, _)| column.as_str()).collect();
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(&format!("CREATE TABLE posts ({});", definitions.join(", ")))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let published_on = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let starts_at = NaiveTime::from_hms_opt(13, 45, 0).unwrap();
+        let created_at = published_on.and_time(starts_at);
+        sqlx::query(
+            "INSERT INTO posts (title, published, published_on, starts_at, created_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind("Hello")
+        .bind(true)
+        .bind(published_on)
+        .bind(starts_at)
+        .bind(created_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        #[allow(clippy::type_complexity)]
+        let row: (i64, Option<f64>, String, bool, NaiveDate, NaiveTime, NaiveDateTime, Option<Vec<u8>>) =
+            sqlx::query_as(
+                "SELECT views, rating, title, published, published_on, starts_at, created_at, cover FROM posts",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            row,
+            (0, None, "Hello".to_string(), true, published_on, starts_at, created_at, None)
+        );
+    }
+}
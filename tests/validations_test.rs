@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod tests {
+    use rustyroad::database::{DatabaseError, DatabaseType, PoolConnection, ValidationErrors};
+    use rustyroad::writers::model_form_html_contents;
+    use rustyroad::writers::models::parse_create_tables;
+    use rustyroad::{Model, Validate};
+    use sqlx::sqlite::SqlitePool;
+    use tera::{Context, Tera};
+
+    #[derive(Debug, Clone, sqlx::FromRow, Model, Validate)]
+    #[table_name = "events"]
+    #[validate(custom = "ends_after_start")]
+    pub struct Event {
+        pub id: Option<i64>,
+        #[validate(presence, length(min = 3, max = 40))]
+        pub name: String,
+        #[validate(format = "^[a-z0-9-]+$", unique)]
+        pub slug: String,
+        #[validate(range(min = 1, max = 500))]
+        pub seats: i64,
+        #[validate(range(min = 0.0))]
+        pub price: Option<f64>,
+        #[validate(custom = "is_weekday")]
+        pub day: Option<String>,
+        pub starts_at: i64,
+        pub ends_at: i64,
+    }
+
+    fn is_weekday(day: &Option<String>) -> Result<(), String> {
+        match day.as_deref() {
+            Some("saturday" | "sunday") => Err("must be a weekday".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn ends_after_start(event: &Event, errors: &mut ValidationErrors) {
+        if event.ends_at <= event.starts_at {
+            errors.add("ends_at", "must be after the start");
+        }
+    }
+
+    fn event() -> Event {
+        Event {
+            id: None,
+            name: "RustConf".to_string(),
+            slug: "rustconf".to_string(),
+            seats: 100,
+            price: None,
+            day: Some("monday".to_string()),
+            starts_at: 9,
+            ends_at: 17,
+        }
+    }
+
+    async fn pool() -> PoolConnection {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                seats INTEGER NOT NULL,
+                price REAL,
+                day TEXT,
+                starts_at INTEGER NOT NULL,
+                ends_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        PoolConnection::Sqlite(pool)
+    }
+
+    async fn validation_errors(event: &Event, pool: &PoolConnection) -> ValidationErrors {
+        match event.validate(pool).await {
+            Err(DatabaseError::Validation(errors)) => errors,
+            result => panic!("expected validation errors, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_derived_rules() {
+        let pool = pool().await;
+        event().validate(&pool).await.unwrap();
+
+        let invalid = Event {
+            name: " ".to_string(),
+            slug: "Rust Conf".to_string(),
+            seats: 0,
+            price: Some(-1.5),
+            day: Some("sunday".to_string()),
+            starts_at: 17,
+            ends_at: 9,
+            ..event()
+        };
+        let errors = validation_errors(&invalid, &pool).await;
+        assert_eq!(errors.get("name"), ["can't be blank", "is too short (minimum is 3 characters)"]);
+        assert_eq!(errors.get("slug"), ["is invalid"]);
+        assert_eq!(errors.get("seats"), ["must be greater than or equal to 1"]);
+        assert_eq!(errors.get("price"), ["must be greater than or equal to 0"]);
+        assert_eq!(errors.get("day"), ["must be a weekday"]);
+        assert_eq!(errors.get("ends_at"), ["must be after the start"]);
+        assert!(errors.get("starts_at").is_empty());
+        assert_eq!(errors.full_messages()[0], "day must be a weekday");
+
+        let too_long = Event {
+            name: "x".repeat(41),
+            seats: 501,
+            ..event()
+        };
+        let errors = validation_errors(&too_long, &pool).await;
+        assert_eq!(errors.get("name"), ["is too long (maximum is 40 characters)"]);
+        assert_eq!(errors.get("seats"), ["must be less than or equal to 500"]);
+    }
+
+    #[tokio::test]
+    async fn test_uniqueness() {
+        let pool = pool().await;
+        let created = event().create(&pool).await.unwrap();
+
+        // The row itself doesn't take its value
+        created.validate(&pool).await.unwrap();
+        let errors = validation_errors(&event(), &pool).await;
+        assert_eq!(errors.get("slug"), ["has already been taken"]);
+        assert_eq!(errors.by_field().len(), 1);
+
+        let other = Event {
+            slug: "rustconf-eu".to_string(),
+            ..event()
+        };
+        other.validate(&pool).await.unwrap();
+    }
+
+    #[test]
+    fn test_errors_json() {
+        let mut errors = ValidationErrors::new();
+        assert!(errors.clone().into_result().is_ok());
+        errors.add("title", "can't be blank");
+        errors.add("title", "is too short (minimum is 3 characters)");
+        assert_eq!(
+            serde_json::to_value(&errors).unwrap(),
+            serde_json::json!({"errors": {"title": ["can't be blank", "is too short (minimum is 3 characters)"]}})
+        );
+        let err = errors.into_result().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation failed: title can't be blank, title is too short (minimum is 3 characters)"
+        );
+    }
+
+    #[test]
+    fn test_unique_constraints_of_migrations() {
+        let tables = parse_create_tables(
+            "CREATE TABLE users (
+                id SERIAL PRIMARY KEY,
+                email VARCHAR(255) NOT NULL UNIQUE,
+                first_name TEXT,
+                last_name TEXT,
+                UNIQUE (first_name, last_name)
+            );
+            CREATE UNIQUE INDEX index_users_on_handle ON users (\"handle\");",
+            &DatabaseType::Postgres,
+        )
+        .unwrap();
+        let indexes: Vec<(&str, Vec<&str>)> = tables[0]
+            .indexes
+            .iter()
+            .map(|index| (index.name.as_str(), index.columns.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            indexes,
+            vec![
+                ("index_users_on_first_name_and_last_name", vec!["first_name", "last_name"]),
+                ("index_users_on_email", vec!["email"]),
+                ("index_users_on_handle", vec!["handle"]),
+            ]
+        );
+        assert!(tables[0].indexes.iter().all(|index| index.unique));
+    }
+
+    #[test]
+    fn test_form_with_field_messages() {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            (
+                "base.html.tera",
+                "{% block title %}{% endblock title %}{% block head %}{% endblock head %}{% block content %}{% endblock content %}",
+            ),
+            ("pages/post_form.html.tera", model_form_html_contents("post").as_str()),
+        ])
+        .unwrap();
+
+        let mut errors = ValidationErrors::new();
+        errors.add("title", "can't be blank");
+        let mut context = Context::new();
+        context.insert("title", "Post");
+        context.insert("action", "/post");
+        context.insert(
+            "values",
+            &serde_json::json!({"id": 1, "title": "", "summary": "<b>bold</b>", "published_at": null}),
+        );
+        context.insert("errors", errors.by_field());
+        let html = tera.render("pages/post_form.html.tera", &context).unwrap();
+
+        assert!(html.contains("The post couldn't be saved."));
+        assert!(html.contains("<form class='space-y-6' action='/post' method='POST'>"));
+        assert!(html.contains("<p class='mt-2 text-sm text-red-600'>Title can&#x27;t be blank</p>"));
+        assert!(html.contains("value='&lt;b&gt;bold&lt;&#x2F;b&gt;'"));
+        assert!(html.contains("name='published_at' value=''"));
+        assert!(!html.contains("name='id'"));
+        assert_eq!(html.matches("ring-red-500").count(), 1);
+
+        // Without errors, the form has no messages
+        context.insert("errors", &ValidationErrors::new().by_field());
+        let html = tera.render("pages/post_form.html.tera", &context).unwrap();
+        assert!(!html.contains("couldn't be saved"));
+        assert!(!html.contains("text-red-600"));
+    }
+}